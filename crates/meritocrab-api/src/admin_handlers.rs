@@ -4,24 +4,29 @@ use axum::{
     http::StatusCode,
     response::{IntoResponse, Json, Response},
 };
-use meritocrab_core::{EvaluationStatus, credit::apply_credit};
+use meritocrab_core::{EvaluationStatus, RepoConfig};
 use meritocrab_db::{
-    contributors::{
-        count_contributors_by_repo, get_contributor_by_id, list_contributors_by_repo,
-        set_blacklisted, update_credit_score,
+    Contributor,
+    contributors::{count_contributors_by_repo, get_contributor_by_id, list_contributors_by_repo},
+    credit_events::{
+        NewCreditEvent, apply_credit_delta, count_events_by_repo, list_events_by_repo,
+        set_blacklisted_with_event, set_credit_score,
     },
-    credit_events::{count_events_by_repo, insert_credit_event, list_events_by_repo},
     evaluations::{
         approve_evaluation, get_evaluation, list_evaluations_by_repo_and_status,
         override_evaluation,
     },
+    jobs::{JobStatus, get_job, list_jobs_by_repo_and_status, requeue_dead_job},
+    repo_installations::get_repo_installation,
 };
 use serde::{Deserialize, Serialize};
 use tracing::{error, info};
 
 use crate::error::{ApiError, ApiResult};
+use crate::notifications::{Notification, NotificationEvent, notify, notify_credit_applied};
 use crate::oauth::GithubUser;
 use crate::state::AppState;
+use crate::user_resolver::resolve_contributor;
 
 /// Pagination query parameters
 #[derive(Debug, Deserialize)]
@@ -103,9 +108,25 @@ pub struct CreditEventResponse {
     pub credit_after: i32,
     pub llm_evaluation: Option<String>,
     pub maintainer_override: Option<String>,
+    pub reason: Option<String>,
+    pub pr_number: Option<i64>,
     pub created_at: String,
 }
 
+/// Background job response
+#[derive(Debug, Serialize)]
+pub struct JobResponse {
+    pub id: i64,
+    pub job_type: String,
+    pub payload: serde_json::Value,
+    pub status: String,
+    pub attempts: i32,
+    pub run_at: String,
+    pub last_error: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
 /// Override evaluation request
 #[derive(Debug, Deserialize)]
 pub struct OverrideRequest {
@@ -114,9 +135,15 @@ pub struct OverrideRequest {
 }
 
 /// Adjust credit request
+///
+/// Exactly one of `delta` (relative change) and `score` (absolute score, like
+/// `/credit set`) must be given.
 #[derive(Debug, Deserialize)]
 pub struct AdjustCreditRequest {
-    pub delta: i32,
+    #[serde(default)]
+    pub delta: Option<i32>,
+    #[serde(default)]
+    pub score: Option<i32>,
     pub reason: String,
 }
 
@@ -157,12 +184,19 @@ pub async fn list_evaluations(
     let total = evaluations.len() as i64; // For simplicity, we're not implementing count separately
 
     // Convert to response format
-    let data: Vec<EvaluationResponse> = evaluations
-        .into_iter()
-        .map(|eval| EvaluationResponse {
+    let mut data: Vec<EvaluationResponse> = Vec::with_capacity(evaluations.len());
+    for eval in evaluations {
+        let contributor_login = get_contributor_by_id(&state.db_pool, eval.contributor_id)
+            .await
+            .ok()
+            .flatten()
+            .and_then(|c| c.login)
+            .unwrap_or_else(|| format!("user-{}", eval.contributor_id));
+
+        data.push(EvaluationResponse {
             id: eval.id,
             contributor_id: eval.contributor_id,
-            contributor_login: format!("user-{}", eval.contributor_id), // TODO: fetch from GitHub API
+            contributor_login,
             repo_owner: eval.repo_owner,
             repo_name: eval.repo_name,
             llm_classification: eval.llm_classification,
@@ -170,8 +204,8 @@ pub async fn list_evaluations(
             proposed_delta: eval.proposed_delta,
             status: eval.status,
             created_at: eval.created_at.to_rfc3339(),
-        })
-        .collect();
+        });
+    }
 
     let total_pages = (total + pagination.per_page - 1) / pagination.per_page;
 
@@ -227,35 +261,26 @@ pub async fn approve_evaluation_handler(
             ))
         })?;
 
-    // Apply credit delta
-    let credit_before = contributor.credit_score;
-    let credit_after = apply_credit(credit_before, evaluation.proposed_delta);
-
-    // Update credit score
-    update_credit_score(&state.db_pool, contributor.id, credit_after)
-        .await
-        .map_err(|e| {
-            error!("Failed to update credit score: {}", e);
-            ApiError::InternalError(format!("Database error: {}", e))
-        })?;
+    // Changes made here auto-blacklist like any other
+    let repo_config = load_repo_config(&state, &owner, &repo).await?;
 
-    // Log credit event
-    insert_credit_event(
+    // Apply credit delta and log the event
+    apply_credit_delta(
         &state.db_pool,
         contributor.id,
-        "evaluation_approved",
         evaluation.proposed_delta,
-        credit_before,
-        credit_after,
-        Some(format!(
-            r#"{{"evaluation_id": "{}", "classification": "{}"}}"#,
-            evaluation.id, evaluation.llm_classification
-        )),
-        Some("false".to_string()), // maintainer_override = false
+        NewCreditEvent::new("evaluation_approved")
+            .with_llm_evaluation(format!(
+                r#"{{"evaluation_id": "{}", "classification": "{}"}}"#,
+                evaluation.id, evaluation.llm_classification
+            ))
+            .with_maintainer_override("false".to_string()) // maintainer_override = false
+            .with_source_id(evaluation.source_id.clone()),
+        Some(repo_config.blacklist_threshold),
     )
     .await
     .map_err(|e| {
-        error!("Failed to insert credit event: {}", e);
+        error!("Failed to apply credit: {}", e);
         ApiError::InternalError(format!("Database error: {}", e))
     })?;
 
@@ -319,35 +344,26 @@ pub async fn override_evaluation_handler(
             ))
         })?;
 
-    // Apply custom delta
-    let credit_before = contributor.credit_score;
-    let credit_after = apply_credit(credit_before, req.delta);
+    // Changes made here auto-blacklist like any other
+    let repo_config = load_repo_config(&state, &owner, &repo).await?;
 
-    // Update credit score
-    update_credit_score(&state.db_pool, contributor.id, credit_after)
-        .await
-        .map_err(|e| {
-            error!("Failed to update credit score: {}", e);
-            ApiError::InternalError(format!("Database error: {}", e))
-        })?;
-
-    // Log credit event with maintainer override
-    insert_credit_event(
+    // Apply custom delta and log the event with the maintainer override
+    apply_credit_delta(
         &state.db_pool,
         contributor.id,
-        "evaluation_overridden",
         req.delta,
-        credit_before,
-        credit_after,
-        Some(format!(
-            r#"{{"evaluation_id": "{}", "classification": "{}"}}"#,
-            evaluation.id, evaluation.llm_classification
-        )),
-        Some(req.reason.clone()),
+        NewCreditEvent::new("evaluation_overridden")
+            .with_llm_evaluation(format!(
+                r#"{{"evaluation_id": "{}", "classification": "{}"}}"#,
+                evaluation.id, evaluation.llm_classification
+            ))
+            .with_maintainer_override(req.reason.clone())
+            .with_source_id(evaluation.source_id.clone()),
+        Some(repo_config.blacklist_threshold),
     )
     .await
     .map_err(|e| {
-        error!("Failed to insert credit event: {}", e);
+        error!("Failed to apply credit: {}", e);
         ApiError::InternalError(format!("Database error: {}", e))
     })?;
 
@@ -367,6 +383,56 @@ pub async fn override_evaluation_handler(
     Ok((StatusCode::OK, "Evaluation overridden").into_response())
 }
 
+/// Load a repository's effective config as the installation it belongs to
+async fn load_repo_config(state: &AppState, owner: &str, repo: &str) -> ApiResult<RepoConfig> {
+    let installation_id = get_repo_installation(&state.db_pool, owner, repo)
+        .await
+        .map_err(|e| {
+            error!("Failed to get repo installation: {}", e);
+            ApiError::InternalError(format!("Database error: {}", e))
+        })?;
+    let state = state.for_installation(installation_id).await?;
+
+    Ok(state
+        .repo_config_loader
+        .get_config(&state.github_client, owner, repo)
+        .await)
+}
+
+/// Find a contributor by ID or `@username`
+///
+/// Numeric references are contributor IDs; anything else is resolved as a
+/// GitHub login. The caller still verifies the contributor's repository.
+async fn find_contributor(
+    state: &AppState,
+    owner: &str,
+    repo: &str,
+    reference: &str,
+) -> ApiResult<Contributor> {
+    let contributor = match reference.parse::<i64>() {
+        Ok(contributor_id) => get_contributor_by_id(&state.db_pool, contributor_id)
+            .await
+            .map_err(|e| {
+                error!("Failed to get contributor: {}", e);
+                ApiError::InternalError(format!("Database error: {}", e))
+            })?,
+        Err(_) => {
+            let state = state.for_installation(None).await?;
+            resolve_contributor(&state, reference, owner, repo).await?
+        }
+    };
+
+    contributor.ok_or_else(|| ApiError::NotFound(format!("Contributor not found: {}", reference)))
+}
+
+/// Contributor's login, or a placeholder for contributors never seen by login
+fn contributor_login(contributor: &Contributor) -> String {
+    contributor
+        .login
+        .clone()
+        .unwrap_or_else(|| format!("user-{}", contributor.github_user_id))
+}
+
 /// GET /api/repos/{owner}/{repo}/contributors
 /// List contributors with pagination
 pub async fn list_contributors(
@@ -400,7 +466,9 @@ pub async fn list_contributors(
         .map(|contrib| ContributorResponse {
             id: contrib.id,
             github_user_id: contrib.github_user_id,
-            username: format!("user-{}", contrib.github_user_id), // TODO: fetch from GitHub API
+            username: contrib
+                .login
+                .unwrap_or_else(|| format!("user-{}", contrib.github_user_id)),
             credit_score: contrib.credit_score,
             role: contrib.role,
             is_blacklisted: contrib.is_blacklisted,
@@ -423,56 +491,69 @@ pub async fn list_contributors(
 /// Manually adjust contributor credit
 pub async fn adjust_contributor_credit(
     State(state): State<AppState>,
-    Path((owner, repo, user_id)): Path<(String, String, i64)>,
+    Path((owner, repo, user_id)): Path<(String, String, String)>,
     Extension(_user): Extension<GithubUser>,
     Json(req): Json<AdjustCreditRequest>,
 ) -> ApiResult<Response> {
     // Get contributor
-    let contributor = get_contributor_by_id(&state.db_pool, user_id)
-        .await
-        .map_err(|e| {
-            error!("Failed to get contributor: {}", e);
-            ApiError::InternalError(format!("Database error: {}", e))
-        })?
-        .ok_or_else(|| ApiError::NotFound(format!("Contributor not found: {}", user_id)))?;
+    let contributor = find_contributor(&state, &owner, &repo, &user_id).await?;
 
     // Verify contributor belongs to this repo
     if contributor.repo_owner != owner || contributor.repo_name != repo {
         return Err(ApiError::NotFound("Contributor not found".to_string()));
     }
 
-    // Apply credit delta
-    let credit_before = contributor.credit_score;
-    let credit_after = apply_credit(credit_before, req.delta);
-
-    // Update credit score
-    update_credit_score(&state.db_pool, contributor.id, credit_after)
-        .await
-        .map_err(|e| {
-            error!("Failed to update credit score: {}", e);
-            ApiError::InternalError(format!("Database error: {}", e))
-        })?;
-
-    // Log credit event
-    insert_credit_event(
-        &state.db_pool,
-        contributor.id,
-        "manual_adjustment",
-        req.delta,
-        credit_before,
-        credit_after,
-        None,
-        Some(req.reason.clone()),
-    )
-    .await
+    // Changes made here auto-blacklist like any other
+    let repo_config = load_repo_config(&state, &owner, &repo).await?;
+
+    // Apply the change and log the event; a score is set against the
+    // contributor's current score, not the one read above
+    let applied = match (req.delta, req.score) {
+        (Some(delta), None) => {
+            apply_credit_delta(
+                &state.db_pool,
+                contributor.id,
+                delta,
+                NewCreditEvent::new("manual_adjustment")
+                    .with_maintainer_override(req.reason.clone()),
+                Some(repo_config.blacklist_threshold),
+            )
+            .await
+        }
+        (None, Some(score)) => {
+            set_credit_score(
+                &state.db_pool,
+                contributor.id,
+                score,
+                NewCreditEvent::new("manual_adjustment")
+                    .with_maintainer_override(format!("Set to {}: {}", score, req.reason)),
+                Some(repo_config.blacklist_threshold),
+            )
+            .await
+        }
+        _ => {
+            return Err(ApiError::BadRequest(
+                "Exactly one of delta and score is required".to_string(),
+            ));
+        }
+    }
     .map_err(|e| {
-        error!("Failed to insert credit event: {}", e);
+        error!("Failed to apply credit: {}", e);
         ApiError::InternalError(format!("Database error: {}", e))
     })?;
+    notify_credit_applied(
+        &state,
+        &owner,
+        &repo,
+        &contributor_login(&contributor),
+        contributor.github_user_id,
+        &applied,
+    )
+    .await;
 
     info!(
-        "Credit manually adjusted for contributor {} by maintainer: delta {} (reason: {})",
-        contributor.id, req.delta, req.reason
+        "Credit manually adjusted for contributor {} by maintainer: {} -> {} (reason: {})",
+        contributor.id, applied.event.credit_before, applied.event.credit_after, req.reason
     );
 
     Ok((StatusCode::OK, "Credit adjusted").into_response())
@@ -482,54 +563,36 @@ pub async fn adjust_contributor_credit(
 /// Toggle contributor blacklist status
 pub async fn toggle_contributor_blacklist(
     State(state): State<AppState>,
-    Path((owner, repo, user_id)): Path<(String, String, i64)>,
-    Extension(_user): Extension<GithubUser>,
+    Path((owner, repo, user_id)): Path<(String, String, String)>,
+    Extension(user): Extension<GithubUser>,
 ) -> ApiResult<Response> {
     // Get contributor
-    let contributor = get_contributor_by_id(&state.db_pool, user_id)
-        .await
-        .map_err(|e| {
-            error!("Failed to get contributor: {}", e);
-            ApiError::InternalError(format!("Database error: {}", e))
-        })?
-        .ok_or_else(|| ApiError::NotFound(format!("Contributor not found: {}", user_id)))?;
+    let contributor = find_contributor(&state, &owner, &repo, &user_id).await?;
 
     // Verify contributor belongs to this repo
     if contributor.repo_owner != owner || contributor.repo_name != repo {
         return Err(ApiError::NotFound("Contributor not found".to_string()));
     }
 
-    // Toggle blacklist status
+    // Toggle blacklist status and log the credit event together
     let new_status = !contributor.is_blacklisted;
-    set_blacklisted(&state.db_pool, contributor.id, new_status)
-        .await
-        .map_err(|e| {
-            error!("Failed to set blacklist status: {}", e);
-            ApiError::InternalError(format!("Database error: {}", e))
-        })?;
-
-    // Log credit event
     let event_type = if new_status {
         "blacklist_added"
     } else {
         "blacklist_removed"
     };
-    insert_credit_event(
+    set_blacklisted_with_event(
         &state.db_pool,
         contributor.id,
-        event_type,
-        0,
-        contributor.credit_score,
-        contributor.credit_score,
-        None,
-        Some(format!(
+        new_status,
+        NewCreditEvent::new(event_type).with_maintainer_override(format!(
             "Blacklist toggled by maintainer to: {}",
             new_status
         )),
     )
     .await
     .map_err(|e| {
-        error!("Failed to insert credit event: {}", e);
+        error!("Failed to set blacklist status: {}", e);
         ApiError::InternalError(format!("Database error: {}", e))
     })?;
 
@@ -538,6 +601,15 @@ pub async fn toggle_contributor_blacklist(
         contributor.id, new_status
     );
 
+    if new_status {
+        let event = NotificationEvent::BlacklistAdded {
+            login: contributor_login(&contributor),
+            github_user_id: contributor.github_user_id,
+            by: Some(user.login),
+        };
+        notify(&state, Notification::new(&owner, &repo, event)).await;
+    }
+
     Ok((
         StatusCode::OK,
         format!("Blacklist status set to: {}", new_status),
@@ -597,6 +669,8 @@ pub async fn list_credit_events(
             credit_after: event.credit_after,
             llm_evaluation: event.llm_evaluation,
             maintainer_override: event.maintainer_override,
+            reason: event.reason,
+            pr_number: event.pr_number,
             created_at: event.created_at.to_rfc3339(),
         })
         .collect();
@@ -611,3 +685,101 @@ pub async fn list_credit_events(
         total_pages,
     }))
 }
+
+/// GET /api/repos/{owner}/{repo}/jobs
+/// List background jobs by status (defaults to dead-lettered jobs)
+pub async fn list_jobs(
+    State(state): State<AppState>,
+    Path((owner, repo)): Path<(String, String)>,
+    Query(pagination): Query<PaginationQuery>,
+    Extension(_user): Extension<GithubUser>,
+) -> ApiResult<Json<PaginatedResponse<JobResponse>>> {
+    let status = match pagination.status.as_deref().unwrap_or("dead") {
+        "pending" => JobStatus::Pending,
+        "running" => JobStatus::Running,
+        "completed" => JobStatus::Completed,
+        _ => JobStatus::Dead,
+    };
+    let offset = (pagination.page - 1) * pagination.per_page;
+
+    let jobs = list_jobs_by_repo_and_status(
+        &state.db_pool,
+        &owner,
+        &repo,
+        status,
+        pagination.per_page,
+        offset,
+    )
+    .await
+    .map_err(|e| {
+        error!("Failed to list jobs: {}", e);
+        ApiError::InternalError(format!("Database error: {}", e))
+    })?;
+
+    let total = jobs.len() as i64;
+
+    let data: Vec<JobResponse> = jobs
+        .into_iter()
+        .map(|job| JobResponse {
+            id: job.id,
+            job_type: job.job_type,
+            payload: serde_json::from_str(&job.payload).unwrap_or(serde_json::Value::Null),
+            status: job.status,
+            attempts: job.attempts,
+            run_at: job.run_at.to_rfc3339(),
+            last_error: job.last_error,
+            created_at: job.created_at.to_rfc3339(),
+            updated_at: job.updated_at.to_rfc3339(),
+        })
+        .collect();
+
+    let total_pages = (total + pagination.per_page - 1) / pagination.per_page;
+
+    Ok(Json(PaginatedResponse {
+        data,
+        page: pagination.page,
+        per_page: pagination.per_page,
+        total,
+        total_pages,
+    }))
+}
+
+/// POST /api/repos/{owner}/{repo}/jobs/{id}/retry
+/// Put a dead-lettered job back on the queue
+pub async fn retry_dead_job(
+    State(state): State<AppState>,
+    Path((owner, repo, job_id)): Path<(String, String, i64)>,
+    Extension(_user): Extension<GithubUser>,
+) -> ApiResult<Response> {
+    let job = get_job(&state.db_pool, job_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to get job: {}", e);
+            ApiError::InternalError(format!("Database error: {}", e))
+        })?
+        .ok_or_else(|| ApiError::NotFound(format!("Job not found: {}", job_id)))?;
+
+    // Verify job belongs to this repo
+    if job.repo_owner != owner || job.repo_name != repo {
+        return Err(ApiError::NotFound("Job not found".to_string()));
+    }
+
+    let requeued = requeue_dead_job(&state.db_pool, job_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to requeue job: {}", e);
+            ApiError::InternalError(format!("Database error: {}", e))
+        })?;
+
+    if !requeued {
+        return Err(ApiError::BadRequest(format!(
+            "Job is not dead-lettered: {}",
+            job.status
+        )));
+    }
+
+    state.job_notify.notify_one();
+    info!("Dead-lettered job {} requeued by maintainer", job_id);
+
+    Ok((StatusCode::OK, "Job requeued").into_response())
+}
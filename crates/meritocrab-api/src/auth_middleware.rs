@@ -10,7 +10,7 @@ use tracing::{error, warn};
 
 use crate::error::ApiError;
 use crate::oauth::{GithubUser, get_session_user};
-use std::sync::Arc;
+use crate::state::AppState;
 
 /// Auth middleware that checks if user is authenticated
 pub async fn require_auth(session: Session, request: Request, next: Next) -> Response {
@@ -29,7 +29,7 @@ pub async fn require_auth(session: Session, request: Request, next: Next) -> Res
 
 /// Auth middleware that checks if user is a maintainer of the repo
 pub async fn require_maintainer(
-    State(github_client): State<Arc<GithubApiClient>>,
+    State(state): State<AppState>,
     session: Session,
     mut request: Request,
     next: Next,
@@ -54,7 +54,14 @@ pub async fn require_maintainer(
     };
 
     // Check if user is a maintainer of the repo
-    match check_user_is_maintainer(&github_client, &user, repo_owner, repo_name).await {
+    let state = match state.for_installation(None).await {
+        Ok(state) => state,
+        Err(e) => {
+            error!("Failed to get GitHub client: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Internal error").into_response();
+        }
+    };
+    match check_user_is_maintainer(&state.github_client, &user, repo_owner, repo_name).await {
         Ok(true) => {
             // User is a maintainer, store user in request extensions
             request.extensions_mut().insert(user);
@@ -1,5 +1,18 @@
-use lazy_static::lazy_static;
-use regex::Regex;
+use meritocrab_core::EventType;
+use meritocrab_db::models::{Contributor, CreditEvent};
+use std::fmt;
+
+/// Events listed by `/credit history` when no count is given
+pub const DEFAULT_HISTORY_LIMIT: u32 = 10;
+
+/// Most events `/credit history` lists
+pub const MAX_HISTORY_LIMIT: u32 = 50;
+
+/// Contributors listed by `/credit leaderboard`
+pub const LEADERBOARD_SIZE: usize = 10;
+
+/// Scored actions listed by `/credit me`
+pub const SELF_REPORT_EVENTS: usize = 5;
 
 /// Parsed /credit command
 #[derive(Debug, Clone, PartialEq)]
@@ -14,58 +27,517 @@ pub enum CreditCommand {
     },
     /// `/credit blacklist @username`
     Blacklist { username: String },
+    /// `/credit unblacklist @username`
+    Unblacklist { username: String },
+    /// `/credit set @username 120 "reason"`
+    Set {
+        username: String,
+        score: i32,
+        reason: String,
+    },
+    /// `/credit history @username [n]`
+    History { username: String, limit: u32 },
+    /// `/credit leaderboard`
+    Leaderboard,
+    /// `/credit help`
+    Help,
+    /// `/credit approve` (on a gated PR)
+    Approve,
+    /// `/credit me` (available to every commenter)
+    Me,
 }
 
-lazy_static! {
-    // Match: /credit check @username
-    static ref CHECK_REGEX: Regex = Regex::new(r#"(?m)^/credit\s+check\s+@(\w+)\s*$"#).unwrap();
+impl CreditCommand {
+    /// Subcommand name, as typed after `/credit`
+    pub fn name(&self) -> &'static str {
+        match self {
+            CreditCommand::Check { .. } => "check",
+            CreditCommand::Override { .. } => "override",
+            CreditCommand::Blacklist { .. } => "blacklist",
+            CreditCommand::Unblacklist { .. } => "unblacklist",
+            CreditCommand::Set { .. } => "set",
+            CreditCommand::History { .. } => "history",
+            CreditCommand::Leaderboard => "leaderboard",
+            CreditCommand::Help => "help",
+            CreditCommand::Approve => "approve",
+            CreditCommand::Me => "me",
+        }
+    }
+}
 
-    // Match: /credit override @username +10 "reason" or /credit override @username -20 "reason"
-    static ref OVERRIDE_REGEX: Regex = Regex::new(r#"(?m)^/credit\s+override\s+@(\w+)\s+([+-]\d+)\s+"([^"]+)"\s*$"#).unwrap();
+/// Error parsing a `/credit` command line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandError {
+    /// `/credit` without a subcommand
+    MissingSubcommand,
+    /// Subcommand that does not exist
+    UnknownSubcommand(String),
+    /// A required argument is missing
+    MissingArgument {
+        command: &'static str,
+        argument: &'static str,
+    },
+    /// An argument has the wrong form
+    InvalidArgument {
+        command: &'static str,
+        argument: &'static str,
+        value: String,
+    },
+    /// Arguments left over after a complete command
+    UnexpectedArgument {
+        command: &'static str,
+        value: String,
+    },
+    /// A quoted reason without its closing quote
+    UnterminatedQuote { command: &'static str },
+}
 
-    // Match: /credit blacklist @username
-    static ref BLACKLIST_REGEX: Regex = Regex::new(r#"(?m)^/credit\s+blacklist\s+@(\w+)\s*$"#).unwrap();
+impl CommandError {
+    /// Usage line of the subcommand the error is about, if it is known
+    pub fn usage(&self) -> Option<&'static str> {
+        match self {
+            CommandError::MissingSubcommand | CommandError::UnknownSubcommand(_) => None,
+            CommandError::MissingArgument { command, .. }
+            | CommandError::InvalidArgument { command, .. }
+            | CommandError::UnexpectedArgument { command, .. }
+            | CommandError::UnterminatedQuote { command } => usage(command),
+        }
+    }
 }
 
-/// Parse /credit command from comment body
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::MissingSubcommand => write!(f, "Missing subcommand"),
+            CommandError::UnknownSubcommand(name) => write!(f, "Unknown subcommand `{}`", name),
+            CommandError::MissingArgument { argument, .. } => {
+                write!(f, "Missing {}", argument)
+            }
+            CommandError::InvalidArgument {
+                argument, value, ..
+            } => write!(f, "Invalid {} `{}`", argument, value),
+            CommandError::UnexpectedArgument { value, .. } => {
+                write!(f, "Unexpected argument `{}`", value)
+            }
+            CommandError::UnterminatedQuote { .. } => write!(f, "Unterminated quote in reason"),
+        }
+    }
+}
+
+/// Usage line of a subcommand
+fn usage(command: &str) -> Option<&'static str> {
+    Some(match command {
+        "check" => "/credit check @user",
+        "history" => "/credit history @user [n]",
+        "leaderboard" => "/credit leaderboard",
+        "override" => "/credit override @user +10 \"reason\"",
+        "set" => "/credit set @user 120 \"reason\"",
+        "blacklist" => "/credit blacklist @user",
+        "unblacklist" => "/credit unblacklist @user",
+        "approve" => "/credit approve",
+        "help" => "/credit help",
+        "me" => "/credit me",
+        _ => return None,
+    })
+}
+
+/// Parse every /credit command in a comment body
 ///
-/// Returns Some(CreditCommand) if a valid /credit command is found, None otherwise.
-/// If multiple commands are present, only the first one is parsed.
-pub fn parse_credit_command(comment_body: &str) -> Option<CreditCommand> {
-    // Try to match /credit check @username
-    if let Some(captures) = CHECK_REGEX.captures(comment_body) {
-        let username = captures.get(1).unwrap().as_str().to_string();
-        return Some(CreditCommand::Check { username });
+/// A command is a line whose first word is `/credit`; lines inside fenced
+/// code blocks and `>` quoted replies are skipped, so quoting a command never
+/// runs it again. Returns one result per command line, in order, with lines
+/// that do not parse as [`CommandError`]s.
+pub fn parse_credit_commands(comment_body: &str) -> Vec<Result<CreditCommand, CommandError>> {
+    let mut commands = Vec::new();
+    // Opening fence character and length of the code block we are in
+    let mut fence: Option<(char, usize)> = None;
+
+    for line in comment_body.lines() {
+        let line = line.trim();
+
+        let fence_char = line.chars().next().filter(|c| *c == '`' || *c == '~');
+        if let Some(c) = fence_char {
+            let len = line.chars().take_while(|&ch| ch == c).count();
+            if len >= 3 {
+                match fence {
+                    None => fence = Some((c, len)),
+                    // Closing fences match the opening character and length
+                    // and carry no info string
+                    Some((open, open_len))
+                        if open == c && len >= open_len && line[len..].trim().is_empty() =>
+                    {
+                        fence = None
+                    }
+                    Some(_) => {}
+                }
+                continue;
+            }
+        }
+        if fence.is_some() || line.starts_with('>') {
+            continue;
+        }
+
+        let mut words = Words::new(line);
+        if !words
+            .next_word()
+            .is_some_and(|word| word.eq_ignore_ascii_case("/credit"))
+        {
+            continue;
+        }
+        commands.push(parse_command_line(words));
     }
 
-    // Try to match /credit override @username +10 "reason"
-    if let Some(captures) = OVERRIDE_REGEX.captures(comment_body) {
-        let username = captures.get(1).unwrap().as_str().to_string();
-        let delta_str = captures.get(2).unwrap().as_str();
-        let reason = captures.get(3).unwrap().as_str().to_string();
+    commands
+}
+
+/// Parse the first valid /credit command in a comment body
+///
+/// Returns None if the comment has no command or none of them parse.
+pub fn parse_credit_command(comment_body: &str) -> Option<CreditCommand> {
+    parse_credit_commands(comment_body)
+        .into_iter()
+        .find_map(Result::ok)
+}
 
-        // Parse delta (includes sign)
-        if let Ok(delta) = delta_str.parse::<i32>() {
-            return Some(CreditCommand::Override {
+/// Parse the words of a command line after `/credit`
+fn parse_command_line(mut words: Words<'_>) -> Result<CreditCommand, CommandError> {
+    let subcommand = words
+        .next_word()
+        .ok_or(CommandError::MissingSubcommand)?
+        .to_ascii_lowercase();
+
+    let command = match subcommand.as_str() {
+        "check" => CreditCommand::Check {
+            username: words.login("check")?,
+        },
+        "override" => CreditCommand::Override {
+            username: words.login("override")?,
+            delta: words.number("override", "delta")?,
+            reason: words.reason("override")?,
+        },
+        "set" => CreditCommand::Set {
+            username: words.login("set")?,
+            score: words.number("set", "score")?,
+            reason: words.reason("set")?,
+        },
+        "history" => {
+            let username = words.login("history")?;
+            let limit = match words.next_word() {
+                None => DEFAULT_HISTORY_LIMIT,
+                Some(n) if !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()) => {
+                    // Counts too large for u32 are clamped like any other large count
+                    n.parse::<u32>().unwrap_or(MAX_HISTORY_LIMIT)
+                }
+                Some(n) => {
+                    return Err(CommandError::InvalidArgument {
+                        command: "history",
+                        argument: "count",
+                        value: n.to_string(),
+                    });
+                }
+            };
+            CreditCommand::History {
                 username,
-                delta,
-                reason,
+                limit: limit.clamp(1, MAX_HISTORY_LIMIT),
+            }
+        }
+        "blacklist" => CreditCommand::Blacklist {
+            username: words.login("blacklist")?,
+        },
+        "unblacklist" => CreditCommand::Unblacklist {
+            username: words.login("unblacklist")?,
+        },
+        "leaderboard" => CreditCommand::Leaderboard,
+        "help" => CreditCommand::Help,
+        "approve" => CreditCommand::Approve,
+        "me" => CreditCommand::Me,
+        _ => return Err(CommandError::UnknownSubcommand(subcommand)),
+    };
+
+    words.finish(command)
+}
+
+/// Check a GitHub login (`@` optional), returning it without the `@`
+///
+/// Logins are 1-39 alphanumerics or hyphens, not starting with a hyphen; app
+/// accounts carry a `[bot]` suffix. Numeric GitHub user IDs pass as well.
+fn parse_login(word: &str) -> Option<String> {
+    let login = word.strip_prefix('@').unwrap_or(word);
+    let name = login.strip_suffix("[bot]").unwrap_or(login);
+    let valid = !name.is_empty()
+        && name.len() <= 39
+        && !name.starts_with('-')
+        && name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-');
+    valid.then(|| login.to_string())
+}
+
+/// Cursor over the whitespace-separated words of a command line
+struct Words<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Words<'a> {
+    fn new(line: &'a str) -> Self {
+        Self { rest: line }
+    }
+
+    fn next_word(&mut self) -> Option<&'a str> {
+        let trimmed = self.rest.trim_start();
+        if trimmed.is_empty() {
+            self.rest = trimmed;
+            return None;
+        }
+        let end = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+        let (word, rest) = trimmed.split_at(end);
+        self.rest = rest;
+        Some(word)
+    }
+
+    fn login(&mut self, command: &'static str) -> Result<String, CommandError> {
+        let word = self.next_word().ok_or(CommandError::MissingArgument {
+            command,
+            argument: "username",
+        })?;
+        parse_login(word).ok_or_else(|| CommandError::InvalidArgument {
+            command,
+            argument: "username",
+            value: word.to_string(),
+        })
+    }
+
+    fn number(
+        &mut self,
+        command: &'static str,
+        argument: &'static str,
+    ) -> Result<i32, CommandError> {
+        let word = self
+            .next_word()
+            .ok_or(CommandError::MissingArgument { command, argument })?;
+        word.parse::<i32>()
+            .map_err(|_| CommandError::InvalidArgument {
+                command,
+                argument,
+                value: word.to_string(),
+            })
+    }
+
+    /// The rest of the line: a single- or double-quoted string, or the
+    /// remaining words as-is
+    fn reason(&mut self, command: &'static str) -> Result<String, CommandError> {
+        let rest = self.rest.trim();
+        self.rest = "";
+
+        let reason = match rest.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                let inner = &rest[1..];
+                let end = inner
+                    .find(quote)
+                    .ok_or(CommandError::UnterminatedQuote { command })?;
+                let trailing = inner[end + 1..].trim();
+                if !trailing.is_empty() {
+                    return Err(CommandError::UnexpectedArgument {
+                        command,
+                        value: trailing.to_string(),
+                    });
+                }
+                inner[..end].trim()
+            }
+            _ => rest,
+        };
+
+        if reason.is_empty() {
+            return Err(CommandError::MissingArgument {
+                command,
+                argument: "reason",
             });
         }
+        Ok(reason.to_string())
     }
 
-    // Try to match /credit blacklist @username
-    if let Some(captures) = BLACKLIST_REGEX.captures(comment_body) {
-        let username = captures.get(1).unwrap().as_str().to_string();
-        return Some(CreditCommand::Blacklist { username });
+    /// Fail if arguments are left after a complete command
+    fn finish(self, command: CreditCommand) -> Result<CreditCommand, CommandError> {
+        let rest = self.rest.trim();
+        if rest.is_empty() {
+            Ok(command)
+        } else {
+            Err(CommandError::UnexpectedArgument {
+                command: command.name(),
+                value: rest.to_string(),
+            })
+        }
     }
+}
+
+/// Reply to `/credit help`
+pub const HELP_TEXT: &str = "**Credit Commands**
 
-    None
+Available to everyone:
+
+- `/credit me` — your own credit score and recent scored actions
+
+Available to maintainers of this repository:
+
+- `/credit check @user` — score, role, blacklist status and the last 5 events
+- `/credit history @user [n]` — the last `n` credit events (default 10, max 50)
+- `/credit leaderboard` — contributors with the highest credit
+- `/credit override @user +10 \"reason\"` — adjust credit by a delta
+- `/credit set @user 120 \"reason\"` — set credit to an absolute score
+- `/credit blacklist @user` — blacklist a contributor
+- `/credit unblacklist @user` — lift a blacklist
+- `/credit approve` — let this gated PR through without changing its author's credit
+- `/credit help` — show this message
+";
+
+/// Reply to a /credit command that did not parse
+///
+/// Shows the usage of the subcommand, or the full help when the subcommand
+/// itself is unknown.
+pub fn format_command_error(error: &CommandError) -> String {
+    match error.usage() {
+        Some(usage) => format!(
+            "Could not parse `/credit` command: {}.\n\nUsage: `{}`",
+            error, usage
+        ),
+        None => format!(
+            "Could not parse `/credit` command: {}.\n\n{}",
+            error, HELP_TEXT
+        ),
+    }
+}
+
+/// Format a credit delta with an explicit sign
+pub fn format_delta(delta: i32) -> String {
+    if delta >= 0 {
+        format!("+{}", delta)
+    } else {
+        delta.to_string()
+    }
+}
+
+/// Format credit events as a markdown list, newest first
+///
+/// Events with a maintainer reason (overrides, approvals, decay) show it
+/// after the timestamp.
+pub fn format_event_list(events: &[CreditEvent]) -> String {
+    if events.is_empty() {
+        return "_No credit events recorded._\n".to_string();
+    }
+
+    let mut list = String::new();
+    for event in events {
+        list.push_str(&format!(
+            "- `{}`: {} ({} -> {}) — {}",
+            event.event_type,
+            format_delta(event.delta),
+            event.credit_before,
+            event.credit_after,
+            event.created_at.format("%Y-%m-%d %H:%M UTC")
+        ));
+        if let Some(reason) = event.maintainer_override.as_ref().or(event.reason.as_ref()) {
+            list.push_str(&format!(" — {}", reason));
+        }
+        list.push('\n');
+    }
+    list
+}
+
+/// Reply to `/credit history`
+pub fn format_history(username: &str, events: &[CreditEvent]) -> String {
+    format!(
+        "**Credit History for @{}** (last {} events)\n\n{}",
+        username,
+        events.len(),
+        format_event_list(events)
+    )
+}
+
+/// Reply to `/credit me`
+///
+/// Only lists scored actions (PRs, comments, merges, reviews) without their
+/// reasons, so maintainer notes and blacklist changes never show up.
+pub fn format_self_report(
+    username: &str,
+    credit_score: i32,
+    pr_threshold: i32,
+    events: &[CreditEvent],
+) -> String {
+    let mut response = format!("**Your Credit, @{}**\n\n", username);
+    response.push_str(&format!("- Credit Score: **{}**\n", credit_score));
+    response.push_str(&format!("- PR Threshold: **{}**\n", pr_threshold));
+    if credit_score >= pr_threshold {
+        response.push_str(&format!(
+            "- You are **{}** above the PR threshold.\n",
+            credit_score - pr_threshold
+        ));
+    } else {
+        response.push_str(&format!(
+            "- You need **{}** more credit to reach the PR threshold.\n",
+            pr_threshold - credit_score
+        ));
+    }
+
+    response.push_str("\n**Recent Scored Actions:**\n\n");
+    let scored: Vec<_> = events
+        .iter()
+        .filter(|event| event.event_type.parse::<EventType>().is_ok())
+        .take(SELF_REPORT_EVENTS)
+        .collect();
+    if scored.is_empty() {
+        response.push_str("_No scored actions yet._\n");
+    }
+    for event in scored {
+        response.push_str(&format!(
+            "- `{}`{}: {} — {}\n",
+            event.event_type,
+            event
+                .pr_number
+                .map(|pr| format!(" on #{}", pr))
+                .unwrap_or_default(),
+            format_delta(event.delta),
+            event.created_at.format("%Y-%m-%d %H:%M UTC")
+        ));
+    }
+    response
+}
+
+/// Reply to `/credit leaderboard`
+///
+/// Expects contributors ordered by score; contributors whose login was never
+/// seen are listed by GitHub user ID.
+pub fn format_leaderboard(
+    repo_owner: &str,
+    repo_name: &str,
+    contributors: &[Contributor],
+) -> String {
+    let mut response = format!(
+        "**Credit Leaderboard for {}/{}**\n\n",
+        repo_owner, repo_name
+    );
+    if contributors.is_empty() {
+        response.push_str("_No contributors recorded._\n");
+        return response;
+    }
+
+    response.push_str("| # | Contributor | Credit |\n|---|---|---|\n");
+    for (rank, contributor) in contributors.iter().enumerate() {
+        let name = match &contributor.login {
+            Some(login) => format!("@{}", login),
+            None => format!("user {}", contributor.github_user_id),
+        };
+        response.push_str(&format!(
+            "| {} | {} | {} |\n",
+            rank + 1,
+            name,
+            contributor.credit_score
+        ));
+    }
+    response
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::{TimeZone, Utc};
 
     #[test]
     fn test_parse_check_command() {
@@ -91,6 +563,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_check_command_hyphenated_login() {
+        let comment = "/credit check @octo-cat";
+        let cmd = parse_credit_command(comment);
+        assert_eq!(
+            cmd,
+            Some(CreditCommand::Check {
+                username: "octo-cat".to_string()
+            })
+        );
+    }
+
     #[test]
     fn test_parse_override_positive() {
         let comment = r#"/credit override @user123 +10 "good first contribution""#;
@@ -131,6 +615,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_approve_command() {
+        assert_eq!(
+            parse_credit_command("/credit approve"),
+            Some(CreditCommand::Approve)
+        );
+        assert_eq!(
+            parse_credit_command("Looks good.\n/credit approve  \nThanks!"),
+            Some(CreditCommand::Approve)
+        );
+        assert_eq!(parse_credit_command("/credit approve @someone"), None);
+    }
+
     #[test]
     fn test_parse_no_command() {
         let comment = "This is just a regular comment";
@@ -174,4 +671,370 @@ Some context after"#;
             })
         );
     }
+
+    #[test]
+    fn test_parse_unblacklist_command() {
+        assert_eq!(
+            parse_credit_command("/credit unblacklist @octo-cat"),
+            Some(CreditCommand::Unblacklist {
+                username: "octo-cat".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_set_command() {
+        assert_eq!(
+            parse_credit_command(r#"/credit set @user123 120 "restored after review""#),
+            Some(CreditCommand::Set {
+                username: "user123".to_string(),
+                score: 120,
+                reason: "restored after review".to_string()
+            })
+        );
+        assert_eq!(
+            parse_credit_command(r#"/credit set @user123 -5 "spam""#),
+            Some(CreditCommand::Set {
+                username: "user123".to_string(),
+                score: -5,
+                reason: "spam".to_string()
+            })
+        );
+        // A reason is required
+        assert_eq!(parse_credit_command("/credit set @user123 120"), None);
+    }
+
+    #[test]
+    fn test_parse_history_command() {
+        assert_eq!(
+            parse_credit_command("/credit history @user123"),
+            Some(CreditCommand::History {
+                username: "user123".to_string(),
+                limit: DEFAULT_HISTORY_LIMIT
+            })
+        );
+        assert_eq!(
+            parse_credit_command("/credit history @user123 25"),
+            Some(CreditCommand::History {
+                username: "user123".to_string(),
+                limit: 25
+            })
+        );
+        assert_eq!(
+            parse_credit_command("/credit history @user123 99999999999"),
+            Some(CreditCommand::History {
+                username: "user123".to_string(),
+                limit: MAX_HISTORY_LIMIT
+            })
+        );
+        assert_eq!(
+            parse_credit_command("/credit history @user123 0"),
+            Some(CreditCommand::History {
+                username: "user123".to_string(),
+                limit: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_leaderboard_and_help() {
+        assert_eq!(
+            parse_credit_command("/credit leaderboard"),
+            Some(CreditCommand::Leaderboard)
+        );
+        assert_eq!(
+            parse_credit_command("/credit help"),
+            Some(CreditCommand::Help)
+        );
+    }
+
+    #[test]
+    fn test_parse_login_characters() {
+        for (comment, login) in [
+            ("/credit check @my-user", "my-user"),
+            ("/credit check @a", "a"),
+            ("/credit check @dependabot[bot]", "dependabot[bot]"),
+            ("/credit check 12345", "12345"),
+            ("/credit check OctoCat", "OctoCat"),
+        ] {
+            assert_eq!(
+                parse_credit_command(comment),
+                Some(CreditCommand::Check {
+                    username: login.to_string()
+                }),
+                "{}",
+                comment
+            );
+        }
+
+        for login in [
+            "@-leading",
+            "@under_score",
+            "@dot.ted",
+            "@",
+            &format!("@{}", "a".repeat(40)),
+        ] {
+            assert_eq!(
+                parse_credit_commands(&format!("/credit check {}", login)),
+                vec![Err(CommandError::InvalidArgument {
+                    command: "check",
+                    argument: "username",
+                    value: login.to_string()
+                })]
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_reason_forms() {
+        let expected = |reason: &str| {
+            Some(CreditCommand::Override {
+                username: "user".to_string(),
+                delta: 5,
+                reason: reason.to_string(),
+            })
+        };
+        assert_eq!(
+            parse_credit_command(r#"/credit override @user +5 'he said "thanks"'"#),
+            expected(r#"he said "thanks""#)
+        );
+        assert_eq!(
+            parse_credit_command("/credit override @user +5 great fix, thanks!"),
+            expected("great fix, thanks!")
+        );
+        assert_eq!(
+            parse_credit_command("/credit override @user 5 \"it's fine\""),
+            expected("it's fine")
+        );
+    }
+
+    #[test]
+    fn test_ignores_code_blocks_and_quotes() {
+        let comment = "> /credit blacklist @quoted\n\
+                       \n\
+                       ```\n\
+                       /credit blacklist @fenced\n\
+                       ```\n\
+                       ~~~~markdown\n\
+                       ~~~\n\
+                       /credit blacklist @still-fenced\n\
+                       ~~~~\n\
+                       Text mentioning /credit check @inline is not a command.\n\
+                       /credits check @typo\n";
+        assert_eq!(parse_credit_commands(comment), vec![]);
+
+        let comment = "```\n/credit check @fenced\n```\n/credit check @real";
+        assert_eq!(
+            parse_credit_commands(comment),
+            vec![Ok(CreditCommand::Check {
+                username: "real".to_string()
+            })]
+        );
+    }
+
+    #[test]
+    fn test_parse_multiple_commands() {
+        let comment = "Cleaning up after the spam wave:\n\
+                       /credit unblacklist @alice\n\
+                       /credit set @alice 100 \"false positive\"\n\
+                       /credit frobnicate\n\
+                       /Credit History @alice 3";
+        assert_eq!(
+            parse_credit_commands(comment),
+            vec![
+                Ok(CreditCommand::Unblacklist {
+                    username: "alice".to_string()
+                }),
+                Ok(CreditCommand::Set {
+                    username: "alice".to_string(),
+                    score: 100,
+                    reason: "false positive".to_string()
+                }),
+                Err(CommandError::UnknownSubcommand("frobnicate".to_string())),
+                Ok(CreditCommand::History {
+                    username: "alice".to_string(),
+                    limit: 3
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let cases = [
+            ("/credit", CommandError::MissingSubcommand),
+            (
+                "/credit check",
+                CommandError::MissingArgument {
+                    command: "check",
+                    argument: "username",
+                },
+            ),
+            (
+                "/credit override @user ten \"reason\"",
+                CommandError::InvalidArgument {
+                    command: "override",
+                    argument: "delta",
+                    value: "ten".to_string(),
+                },
+            ),
+            (
+                "/credit override @user +10",
+                CommandError::MissingArgument {
+                    command: "override",
+                    argument: "reason",
+                },
+            ),
+            (
+                "/credit set @user 120 \"unfinished",
+                CommandError::UnterminatedQuote { command: "set" },
+            ),
+            (
+                "/credit set @user 120 \"reason\" extra",
+                CommandError::UnexpectedArgument {
+                    command: "set",
+                    value: "extra".to_string(),
+                },
+            ),
+            (
+                "/credit history @user many",
+                CommandError::InvalidArgument {
+                    command: "history",
+                    argument: "count",
+                    value: "many".to_string(),
+                },
+            ),
+            (
+                "/credit approve @someone now",
+                CommandError::UnexpectedArgument {
+                    command: "approve",
+                    value: "@someone now".to_string(),
+                },
+            ),
+        ];
+        for (comment, error) in cases {
+            assert_eq!(
+                parse_credit_commands(comment),
+                vec![Err(error)],
+                "{}",
+                comment
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_command_error() {
+        let error = CommandError::MissingArgument {
+            command: "override",
+            argument: "reason",
+        };
+        assert_eq!(
+            format_command_error(&error),
+            "Could not parse `/credit` command: Missing reason.\n\n\
+             Usage: `/credit override @user +10 \"reason\"`"
+        );
+
+        let reply = format_command_error(&CommandError::UnknownSubcommand("nope".to_string()));
+        assert!(reply.starts_with("Could not parse `/credit` command: Unknown subcommand `nope`."));
+        assert!(reply.ends_with(HELP_TEXT));
+    }
+
+    fn event(event_type: &str, delta: i32, before: i32, reason: Option<&str>) -> CreditEvent {
+        CreditEvent {
+            id: 1,
+            contributor_id: 1,
+            event_type: event_type.to_string(),
+            delta,
+            credit_before: before,
+            credit_after: before + delta,
+            llm_evaluation: None,
+            maintainer_override: reason.map(str::to_string),
+            reason: None,
+            pr_number: None,
+            source_id: None,
+            created_at: Utc.with_ymd_and_hms(2026, 3, 1, 12, 30, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_format_history() {
+        let events = vec![
+            event("manual_adjustment", -20, 120, Some("spam PR")),
+            event("comment", 3, 117, None),
+        ];
+        assert_eq!(
+            format_history("octocat", &events),
+            "**Credit History for @octocat** (last 2 events)\n\n\
+             - `manual_adjustment`: -20 (120 -> 100) — 2026-03-01 12:30 UTC — spam PR\n\
+             - `comment`: +3 (117 -> 120) — 2026-03-01 12:30 UTC\n"
+        );
+        assert!(format_history("octocat", &[]).ends_with("_No credit events recorded._\n"));
+    }
+
+    #[test]
+    fn test_format_self_report() {
+        let mut merged = event("pr_merged", 20, 60, None);
+        merged.pr_number = Some(42);
+        let events = vec![
+            event(
+                "blacklist_removed",
+                0,
+                80,
+                Some("Manually unblacklisted by maintainer"),
+            ),
+            merged,
+            event("manual_adjustment", -30, 90, Some("spam")),
+            event("comment", 1, 59, Some("Daily credit cap of 10 reached")),
+        ];
+
+        let report = format_self_report("octocat", 40, 50, &events);
+        assert_eq!(
+            report,
+            "**Your Credit, @octocat**\n\n\
+             - Credit Score: **40**\n\
+             - PR Threshold: **50**\n\
+             - You need **10** more credit to reach the PR threshold.\n\n\
+             **Recent Scored Actions:**\n\n\
+             - `pr_merged` on #42: +20 — 2026-03-01 12:30 UTC\n\
+             - `comment`: +1 — 2026-03-01 12:30 UTC\n"
+        );
+
+        let report = format_self_report("octocat", 120, 50, &[]);
+        assert!(report.contains("You are **70** above the PR threshold."));
+        assert!(report.ends_with("_No scored actions yet._\n"));
+    }
+
+    #[test]
+    fn test_format_leaderboard() {
+        let now = Utc::now();
+        let contributor =
+            |github_user_id: i64, login: Option<&str>, credit_score: i32| Contributor {
+                id: github_user_id,
+                github_user_id,
+                login: login.map(str::to_string),
+                repo_owner: "owner".to_string(),
+                repo_name: "repo".to_string(),
+                credit_score,
+                role: None,
+                is_blacklisted: false,
+                created_at: now,
+                updated_at: now,
+            };
+
+        let leaderboard = format_leaderboard(
+            "owner",
+            "repo",
+            &[
+                contributor(1, Some("alice"), 180),
+                contributor(2, None, 120),
+            ],
+        );
+        assert_eq!(
+            leaderboard,
+            "**Credit Leaderboard for owner/repo**\n\n\
+             | # | Contributor | Credit |\n|---|---|---|\n\
+             | 1 | @alice | 180 |\n\
+             | 2 | user 2 | 120 |\n"
+        );
+    }
 }
@@ -0,0 +1,119 @@
+//! Credit decay for inactive contributors
+//!
+//! Runs periodically from the server. Each repository's `[decay]` policy is
+//! read from its `.meritocrab.toml`, fetched as the GitHub App installation
+//! the repository's webhooks last arrived from. Repositories without a
+//! `.meritocrab.toml` fall back to the server's default config, and those
+//! whose config has no `[decay]` section are skipped. A repository that fails
+//! is logged and skipped so it cannot hold up decay everywhere else.
+
+use crate::error::ApiResult;
+use crate::state::AppState;
+use chrono::{DateTime, Utc};
+use meritocrab_core::{DecayPolicy, RepoConfig, calculate_decay};
+use meritocrab_db::{
+    contributors::{list_decay_candidates, list_repos},
+    credit_events::{NewCreditEvent, apply_credit_change},
+    repo_installations::get_repo_installation,
+};
+use tracing::{error, info};
+
+/// Credit event type recorded for each decay step
+pub const DECAY_EVENT_TYPE: &str = "decay";
+
+/// Apply credit decay across every repository with a decay policy
+///
+/// Returns the number of contributors whose credit decayed.
+pub async fn apply_credit_decay(state: &AppState, now: DateTime<Utc>) -> ApiResult<usize> {
+    let mut decayed = 0;
+
+    for (repo_owner, repo_name) in list_repos(&state.db_pool).await? {
+        match decay_repo(state, &repo_owner, &repo_name, now).await {
+            Ok(count) => decayed += count,
+            Err(e) => error!(
+                "Failed to apply credit decay in {}/{}: {}",
+                repo_owner, repo_name, e
+            ),
+        }
+    }
+
+    Ok(decayed)
+}
+
+/// Apply credit decay in one repository, as its own installation
+async fn decay_repo(
+    state: &AppState,
+    repo_owner: &str,
+    repo_name: &str,
+    now: DateTime<Utc>,
+) -> ApiResult<usize> {
+    let installation_id = get_repo_installation(&state.db_pool, repo_owner, repo_name).await?;
+    let state = &state.for_installation(installation_id).await?;
+    let repo_config = state
+        .repo_config_loader
+        .get_config(&state.github_client, repo_owner, repo_name)
+        .await;
+    let Some(policy) = &repo_config.decay else {
+        return Ok(0);
+    };
+
+    decay_contributors(state, &repo_config, policy, repo_owner, repo_name, now).await
+}
+
+async fn decay_contributors(
+    state: &AppState,
+    repo_config: &RepoConfig,
+    policy: &DecayPolicy,
+    repo_owner: &str,
+    repo_name: &str,
+    now: DateTime<Utc>,
+) -> ApiResult<usize> {
+    let candidates = list_decay_candidates(
+        &state.db_pool,
+        repo_owner,
+        repo_name,
+        repo_config.starting_credit,
+    )
+    .await?;
+    let mut decayed = 0;
+
+    for candidate in candidates {
+        let reason = format!(
+            "Inactive since {}",
+            candidate.last_activity.format("%Y-%m-%d")
+        );
+        // Decay the score as it is under the row lock, not as it was listed
+        let applied = apply_credit_change(
+            &state.db_pool,
+            candidate.contributor.id,
+            |credit_score| {
+                calculate_decay(
+                    policy,
+                    credit_score,
+                    repo_config.starting_credit,
+                    candidate.last_activity,
+                    candidate.last_decay,
+                    now,
+                )
+            },
+            NewCreditEvent::new(DECAY_EVENT_TYPE).with_reason(reason),
+            Some(repo_config.blacklist_threshold),
+        )
+        .await?;
+        let Some(applied) = applied else {
+            continue;
+        };
+
+        info!(
+            "Decayed credit of user {} in {}/{}: {} -> {}",
+            candidate.contributor.github_user_id,
+            repo_owner,
+            repo_name,
+            applied.event.credit_before,
+            applied.event.credit_after
+        );
+        decayed += 1;
+    }
+
+    Ok(decayed)
+}
@@ -1,3 +1,4 @@
+use crate::notifications::NotificationError;
 use axum::{
     Json,
     http::StatusCode,
@@ -6,6 +7,7 @@ use axum::{
 use meritocrab_core::CoreError;
 use meritocrab_db::DbError;
 use meritocrab_github::GithubError;
+use meritocrab_llm::LlmError;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
@@ -21,6 +23,12 @@ pub enum ApiError {
     /// Core logic error
     Core(CoreError),
 
+    /// LLM evaluation error
+    Llm(LlmError),
+
+    /// Outbound notification delivery error
+    Notification(NotificationError),
+
     /// Invalid request payload
     InvalidPayload(String),
 
@@ -52,6 +60,8 @@ impl fmt::Display for ApiError {
             ApiError::Database(e) => write!(f, "Database error: {}", e),
             ApiError::Github(e) => write!(f, "GitHub error: {}", e),
             ApiError::Core(e) => write!(f, "Core error: {}", e),
+            ApiError::Llm(e) => write!(f, "LLM error: {}", e),
+            ApiError::Notification(e) => write!(f, "Notification error: {}", e),
             ApiError::InvalidPayload(msg) => write!(f, "Invalid payload: {}", msg),
             ApiError::InvalidSignature(msg) => write!(f, "Invalid signature: {}", msg),
             ApiError::Internal(msg) => write!(f, "Internal error: {}", msg),
@@ -91,6 +101,10 @@ impl IntoResponse for ApiError {
                 "core_error",
                 e.to_string(),
             ),
+            ApiError::Llm(e) => (StatusCode::BAD_GATEWAY, "llm_error", e.to_string()),
+            ApiError::Notification(e) => {
+                (StatusCode::BAD_GATEWAY, "notification_error", e.to_string())
+            }
             ApiError::InvalidPayload(msg) => {
                 (StatusCode::BAD_REQUEST, "invalid_payload", msg.clone())
             }
@@ -136,6 +150,18 @@ impl From<CoreError> for ApiError {
     }
 }
 
+impl From<LlmError> for ApiError {
+    fn from(e: LlmError) -> Self {
+        ApiError::Llm(e)
+    }
+}
+
+impl From<NotificationError> for ApiError {
+    fn from(e: NotificationError) -> Self {
+        ApiError::Notification(e)
+    }
+}
+
 impl From<serde_json::Error> for ApiError {
     fn from(e: serde_json::Error) -> Self {
         ApiError::InvalidPayload(format!("JSON parsing error: {}", e))
@@ -2,6 +2,7 @@ use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
 use serde::{Deserialize, Serialize};
 use std::time::Instant;
 
+use crate::rate_limit::{LimiterStatus, RateLimitStatus};
 use crate::state::AppState;
 
 /// Server start time (shared across all health checks)
@@ -31,6 +32,10 @@ pub struct HealthResponse {
     pub uptime_seconds: u64,
     pub database: DatabaseStatus,
     pub llm_provider: LlmProviderStatus,
+    /// `None` when rate limiting is disabled
+    pub rate_limits: Option<RateLimitStatus>,
+    /// `/credit me` quota (`None` when unlimited)
+    pub self_service_limit: Option<LimiterStatus>,
 }
 
 /// Database connectivity status
@@ -67,6 +72,14 @@ pub async fn health(State(state): State<AppState>) -> impl IntoResponse {
         uptime_seconds: get_uptime_seconds(),
         database: db_status,
         llm_provider: llm_status,
+        rate_limits: state
+            .rate_limiters
+            .as_ref()
+            .map(|limiters| limiters.status()),
+        self_service_limit: state
+            .self_service_limiter
+            .as_ref()
+            .map(|limiter| limiter.status()),
     };
 
     (StatusCode::OK, Json(response))
@@ -102,17 +115,19 @@ fn check_llm_status(state: &AppState) -> LlmProviderStatus {
 mod tests {
     use super::*;
     use crate::OAuthConfig;
+    use crate::rate_limit::{RateLimitConfig, RateLimiters, SelfServiceLimiter};
     use axum::extract::State;
     use meritocrab_core::RepoConfig;
-    use meritocrab_github::{
-        GithubApiClient, GithubAppAuth, InstallationTokenManager, WebhookSecret,
-    };
+    use meritocrab_github::{GithubApiClient, WebhookSecret};
     use meritocrab_llm::MockEvaluator;
     use sqlx::any::AnyPoolOptions;
     use std::sync::Arc;
 
     #[tokio::test]
     async fn test_health_endpoint() {
+        // Initialize rustls crypto provider for tests
+        let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+
         // Initialize server start time
         init_server_start_time();
 
@@ -126,12 +141,9 @@ mod tests {
             .await
             .expect("Failed to create test database");
 
-        // Create test GitHub client
-        let github_auth = GithubAppAuth::new(123456, "fake-private-key".to_string());
-        let mut token_manager = InstallationTokenManager::new(github_auth);
-        // Note: This will fail but we won't use GitHub in health check
-        let token = token_manager.get_token(123456).await.unwrap_or_default();
-        let github_client = GithubApiClient::new(token).expect("Failed to create GitHub client");
+        // Create test GitHub client (not used by the health check)
+        let github_client =
+            GithubApiClient::new("test-token".to_string()).expect("Failed to create GitHub client");
 
         // Create test state
         let app_state = AppState::new(
@@ -147,9 +159,25 @@ mod tests {
                 redirect_url: "http://localhost/callback".to_string(),
             },
             300,
+        )
+        .with_rate_limiters(RateLimiters::new(RateLimitConfig::default()).unwrap())
+        .with_self_service_limiter(
+            SelfServiceLimiter::new(RateLimitConfig::default().self_service).unwrap(),
         );
 
         let response = health(State(app_state)).await.into_response();
         assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let health: HealthResponse = serde_json::from_slice(&body).unwrap();
+        let rate_limits = health.rate_limits.expect("rate limits reported");
+        assert_eq!(rate_limits.webhook.tracked_keys, 0);
+        assert_eq!(rate_limits.admin.burst_size, 60);
+        let self_service = health
+            .self_service_limit
+            .expect("self-service limit reported");
+        assert_eq!(self_service.burst_size, 3);
     }
 }
@@ -0,0 +1,481 @@
+//! Durable background jobs
+//!
+//! LLM evaluations, shadow-blacklist PR closes and outbound notifications are
+//! persisted in the `jobs`
+//! table instead of living in bare `tokio::spawn` tasks, so a deploy or crash
+//! does not lose them. A worker claims due jobs under a lease, retries
+//! transient failures with exponential backoff, and moves jobs that keep
+//! failing to the `dead` status for maintainers to inspect.
+//!
+//! The lease is renewed while a job runs, and a worker that lost it anyway
+//! cannot record an outcome. Credit for a piece of GitHub content is applied
+//! at most once, so a job that does run twice cannot pay out twice.
+
+use crate::{
+    error::{ApiError, ApiResult},
+    notifications::{NotificationError, NotificationJob},
+    state::AppState,
+    thread_context::ThreadSource,
+    webhook_handler::{close_pr_with_message, evaluate_and_apply_credit},
+};
+use chrono::{DateTime, Utc};
+use meritocrab_core::EventType;
+use meritocrab_db::{
+    Job,
+    credit_events::is_pr_exempt,
+    jobs::{
+        claim_next_job, complete_job, count_unfinished_jobs, dead_letter_job, renew_job_lease,
+        retry_job,
+    },
+};
+use meritocrab_llm::{ContentType, LlmError};
+use serde::{Deserialize, Serialize};
+use std::{sync::Arc, time::Duration};
+use tokio::{sync::Semaphore, task::JoinHandle};
+use tracing::{error, info, warn};
+
+/// Comment posted when a blacklisted contributor's PR is closed
+///
+/// Deliberately generic: no mention of blacklist, credit, or spam.
+pub const SHADOW_CLOSE_MESSAGE: &str = "Thank you for your contribution. Unfortunately, we are unable to accept this pull request at this time.";
+
+/// Work carried by a job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JobPayload {
+    /// Evaluate content with the LLM and apply credit
+    Evaluate(EvaluationJob),
+    /// Close a pull request with the generic shadow-blacklist message
+    ClosePr(ClosePrJob),
+    /// Deliver a notification to one target
+    Notify(NotificationJob),
+}
+
+impl JobPayload {
+    /// Job type stored alongside the payload
+    pub fn job_type(&self) -> &'static str {
+        match self {
+            JobPayload::Evaluate(_) => "evaluate",
+            JobPayload::ClosePr(_) => "close_pr",
+            JobPayload::Notify(_) => "notify",
+        }
+    }
+
+    fn repo(&self) -> (&str, &str) {
+        match self {
+            JobPayload::Evaluate(job) => (&job.repo_owner, &job.repo_name),
+            JobPayload::ClosePr(job) => (&job.repo_owner, &job.repo_name),
+            JobPayload::Notify(job) => (&job.notification.repo_owner, &job.notification.repo_name),
+        }
+    }
+}
+
+/// LLM evaluation of a PR, issue, comment, or review
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvaluationJob {
+    pub contributor_id: i64,
+    pub user_id: i64,
+    pub username: String,
+    pub repo_owner: String,
+    pub repo_name: String,
+    pub event_type: EventType,
+    pub content_type: ContentType,
+    /// PR or issue number
+    pub pr_number: Option<i64>,
+    pub title: Option<String>,
+    pub body: String,
+    pub diff_summary: Option<String>,
+    pub thread_context: Option<String>,
+    /// Comment or review to fetch the thread of; replaces `thread_context`
+    /// when GitHub provides it
+    #[serde(default)]
+    pub thread: Option<ThreadSource>,
+    /// GitHub App installation the event came from (`None` for the default)
+    #[serde(default)]
+    pub installation_id: Option<i64>,
+    /// GitHub content the credit is for (e.g. `issue_comment:123`), linking
+    /// later edits and deletions back to it
+    #[serde(default)]
+    pub source_id: Option<String>,
+    /// Re-evaluation of edited content: only the difference to the credit
+    /// already earned for `source_id` is applied
+    #[serde(default)]
+    pub revision: bool,
+}
+
+/// Delayed close of a blacklisted contributor's PR
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClosePrJob {
+    pub repo_owner: String,
+    pub repo_name: String,
+    pub pr_number: u64,
+    pub username: String,
+    /// GitHub App installation the PR belongs to (`None` for the default)
+    #[serde(default)]
+    pub installation_id: Option<i64>,
+}
+
+/// Job worker configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JobQueueConfig {
+    /// Seconds between polls when no job wakes the worker
+    pub poll_interval_seconds: u64,
+    /// Seconds a claimed job is leased before another worker may take it over
+    pub lease_seconds: i64,
+    /// Attempts before a job is dead-lettered
+    pub max_attempts: i32,
+    /// Backoff after the first failed attempt, doubled on each retry
+    pub backoff_base_seconds: i64,
+    /// Upper bound on the backoff between attempts
+    pub backoff_max_seconds: i64,
+    /// Maximum number of jobs executed concurrently
+    pub max_concurrent_jobs: usize,
+}
+
+impl Default for JobQueueConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval_seconds: 5,
+            lease_seconds: 300,
+            max_attempts: 5,
+            backoff_base_seconds: 30,
+            backoff_max_seconds: 3600,
+            max_concurrent_jobs: 10,
+        }
+    }
+}
+
+impl JobQueueConfig {
+    /// Backoff before the next attempt, given the number of attempts made so far
+    pub fn backoff(&self, attempts: i32) -> chrono::Duration {
+        let exponent = attempts.saturating_sub(1).clamp(0, 30) as u32;
+        let seconds = self
+            .backoff_base_seconds
+            .saturating_mul(1i64 << exponent)
+            .min(self.backoff_max_seconds);
+        chrono::Duration::seconds(seconds)
+    }
+}
+
+/// Persist a job to run at `run_at` and wake the worker
+pub async fn enqueue(
+    state: &AppState,
+    payload: JobPayload,
+    run_at: DateTime<Utc>,
+) -> ApiResult<()> {
+    let payload_json = serde_json::to_string(&payload)
+        .map_err(|e| ApiError::Internal(format!("Failed to serialize job payload: {}", e)))?;
+    let (repo_owner, repo_name) = payload.repo();
+
+    let id = meritocrab_db::jobs::enqueue_job(
+        &state.db_pool,
+        payload.job_type(),
+        &payload_json,
+        repo_owner,
+        repo_name,
+        run_at,
+    )
+    .await?;
+
+    info!(
+        "Enqueued {} job {} for {}/{}",
+        payload.job_type(),
+        id,
+        repo_owner,
+        repo_name
+    );
+    state.job_notify.notify_one();
+
+    Ok(())
+}
+
+/// Persist a job unless one with the same `dedupe_key` was ever enqueued
+///
+/// Returns `false` when the key was taken and nothing was enqueued.
+pub async fn enqueue_once(
+    state: &AppState,
+    payload: JobPayload,
+    run_at: DateTime<Utc>,
+    dedupe_key: &str,
+) -> ApiResult<bool> {
+    let payload_json = serde_json::to_string(&payload)
+        .map_err(|e| ApiError::Internal(format!("Failed to serialize job payload: {}", e)))?;
+    let (repo_owner, repo_name) = payload.repo();
+
+    let Some(id) = meritocrab_db::jobs::enqueue_unique_job(
+        &state.db_pool,
+        payload.job_type(),
+        &payload_json,
+        repo_owner,
+        repo_name,
+        run_at,
+        dedupe_key,
+    )
+    .await?
+    else {
+        info!("Job {} already enqueued, skipping", dedupe_key);
+        return Ok(false);
+    };
+
+    info!(
+        "Enqueued {} job {} ({}) for {}/{}",
+        payload.job_type(),
+        id,
+        dedupe_key,
+        repo_owner,
+        repo_name
+    );
+    state.job_notify.notify_one();
+
+    Ok(true)
+}
+
+/// Whether a failed job should be retried rather than dead-lettered
+pub fn is_retryable(error: &ApiError) -> bool {
+    matches!(
+        error,
+        ApiError::Llm(LlmError::RateLimitError)
+            | ApiError::Llm(LlmError::NetworkError(_))
+            | ApiError::Notification(NotificationError::Transient(_))
+    )
+}
+
+/// Spawn the job worker
+///
+/// Jobs left pending or running by a previous process are picked up on the
+/// first poll (running jobs once their lease expires).
+pub fn spawn_job_worker(state: AppState, config: JobQueueConfig) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        match count_unfinished_jobs(&state.db_pool).await {
+            Ok(0) => {}
+            Ok(count) => info!("Resuming {} unfinished background jobs", count),
+            Err(e) => error!("Failed to count unfinished jobs: {}", e),
+        }
+
+        let slots = Arc::new(Semaphore::new(config.max_concurrent_jobs.max(1)));
+        let poll_interval = Duration::from_secs(config.poll_interval_seconds);
+        let lease = chrono::Duration::seconds(config.lease_seconds);
+
+        loop {
+            let permit = match slots.clone().acquire_owned().await {
+                Ok(permit) => permit,
+                Err(_) => return,
+            };
+
+            match claim_next_job(&state.db_pool, lease).await {
+                Ok(Some(job)) => {
+                    let state = state.clone();
+                    let config = config.clone();
+                    tokio::spawn(async move {
+                        run_job(&state, &config, job).await;
+                        drop(permit);
+                    });
+                }
+                Ok(None) => {
+                    drop(permit);
+                    tokio::select! {
+                        _ = state.job_notify.notified() => {}
+                        _ = tokio::time::sleep(poll_interval) => {}
+                    }
+                }
+                Err(e) => {
+                    drop(permit);
+                    error!("Failed to claim job: {}", e);
+                    tokio::time::sleep(poll_interval).await;
+                }
+            }
+        }
+    })
+}
+
+/// Execute a claimed job and record the outcome
+///
+/// The outcome is only recorded while this worker still holds the lease.
+async fn run_job(state: &AppState, config: &JobQueueConfig, job: Job) {
+    let heartbeat = tokio::spawn(renew_lease(
+        state.clone(),
+        config.lease_seconds,
+        job.clone(),
+    ));
+    let result = match serde_json::from_str::<JobPayload>(&job.payload) {
+        Ok(payload) => execute(state, payload).await,
+        Err(e) => Err(ApiError::InvalidPayload(format!(
+            "Undecodable job payload: {}",
+            e
+        ))),
+    };
+    heartbeat.abort();
+
+    let outcome = match result {
+        Ok(()) => complete_job(&state.db_pool, job.id, job.attempts).await,
+        Err(e) if is_retryable(&e) && job.attempts < config.max_attempts => {
+            let run_at = Utc::now() + config.backoff(job.attempts);
+            warn!(
+                "{} job {} failed (attempt {}/{}), retrying at {}: {}",
+                job.job_type, job.id, job.attempts, config.max_attempts, run_at, e
+            );
+            retry_job(&state.db_pool, job.id, job.attempts, run_at, &e.to_string()).await
+        }
+        Err(e) => {
+            error!(
+                "{} job {} failed after {} attempts, moving to dead-letter: {}",
+                job.job_type, job.id, job.attempts, e
+            );
+            dead_letter_job(&state.db_pool, job.id, job.attempts, &e.to_string()).await
+        }
+    };
+
+    match outcome {
+        Ok(true) => {}
+        Ok(false) => warn!(
+            "Lost the lease of {} job {} (attempt {}), outcome discarded",
+            job.job_type, job.id, job.attempts
+        ),
+        Err(e) => error!("Failed to record outcome of job {}: {}", job.id, e),
+    }
+}
+
+/// Keep renewing a running job's lease until the task is aborted
+///
+/// Renews every third of the lease, so a slow LLM call or GitHub fetch never
+/// lets the lease expire and another worker take the job over.
+async fn renew_lease(state: AppState, lease_seconds: i64, job: Job) {
+    let lease = chrono::Duration::seconds(lease_seconds);
+    let interval = Duration::from_secs((lease_seconds / 3).max(1) as u64);
+
+    loop {
+        tokio::time::sleep(interval).await;
+        match renew_job_lease(&state.db_pool, job.id, job.attempts, lease).await {
+            Ok(true) => {}
+            Ok(false) => {
+                warn!(
+                    "Lease of {} job {} (attempt {}) was lost",
+                    job.job_type, job.id, job.attempts
+                );
+                return;
+            }
+            Err(e) => warn!("Failed to renew lease of job {}: {}", job.id, e),
+        }
+    }
+}
+
+async fn execute(state: &AppState, payload: JobPayload) -> ApiResult<()> {
+    match payload {
+        JobPayload::Evaluate(job) => {
+            let state = &state.for_installation(job.installation_id).await?;
+            let repo_config = state
+                .repo_config_loader
+                .get_config(&state.github_client, &job.repo_owner, &job.repo_name)
+                .await;
+            evaluate_and_apply_credit(state, &repo_config, job).await
+        }
+        JobPayload::ClosePr(job) => {
+            let state = &state.for_installation(job.installation_id).await?;
+
+            // A maintainer approved the PR while the close was pending
+            if is_pr_exempt(
+                &state.db_pool,
+                &job.repo_owner,
+                &job.repo_name,
+                job.pr_number as i64,
+            )
+            .await?
+            {
+                info!(
+                    "PR #{} was approved by a maintainer, not closing",
+                    job.pr_number
+                );
+                return Ok(());
+            }
+
+            close_pr_with_message(
+                state,
+                &job.repo_owner,
+                &job.repo_name,
+                job.pr_number,
+                SHADOW_CLOSE_MESSAGE,
+            )
+            .await?;
+            info!(
+                "Closed blacklisted PR #{} for {}",
+                job.pr_number, job.username
+            );
+            Ok(())
+        }
+        JobPayload::Notify(job) => {
+            let notifier = state.notifier.as_ref().ok_or_else(|| {
+                NotificationError::Rejected("Notifications are not configured".to_string())
+            })?;
+            notifier.deliver(job.target, &job.notification).await?;
+            info!(
+                "Delivered {} notification {} to target {}",
+                job.notification.event.name(),
+                job.notification.id,
+                job.target
+            );
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_and_caps() {
+        let config = JobQueueConfig {
+            backoff_base_seconds: 30,
+            backoff_max_seconds: 200,
+            ..JobQueueConfig::default()
+        };
+
+        assert_eq!(config.backoff(1).num_seconds(), 30);
+        assert_eq!(config.backoff(2).num_seconds(), 60);
+        assert_eq!(config.backoff(3).num_seconds(), 120);
+        assert_eq!(config.backoff(4).num_seconds(), 200);
+        assert_eq!(config.backoff(50).num_seconds(), 200);
+    }
+
+    #[test]
+    fn test_retryable_errors() {
+        assert!(is_retryable(&ApiError::Llm(LlmError::RateLimitError)));
+        assert!(is_retryable(&ApiError::Llm(LlmError::NetworkError(
+            "timeout".to_string()
+        ))));
+        assert!(!is_retryable(&ApiError::Llm(LlmError::AuthError)));
+        assert!(!is_retryable(&ApiError::Internal("boom".to_string())));
+        assert!(is_retryable(&ApiError::Notification(
+            NotificationError::Transient("HTTP 503".to_string())
+        )));
+        assert!(!is_retryable(&ApiError::Notification(
+            NotificationError::Rejected("HTTP 404".to_string())
+        )));
+    }
+
+    #[test]
+    fn test_payload_roundtrip() {
+        let payload = JobPayload::ClosePr(ClosePrJob {
+            repo_owner: "owner".to_string(),
+            repo_name: "repo".to_string(),
+            pr_number: 7,
+            username: "someone".to_string(),
+            installation_id: Some(42),
+        });
+
+        let json = serde_json::to_string(&payload).unwrap();
+        assert!(json.contains("\"type\":\"close_pr\""));
+
+        let decoded: JobPayload = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.job_type(), "close_pr");
+
+        // Jobs enqueued before installations were tracked use the default
+        let legacy =
+            r#"{"type":"close_pr","repo_owner":"o","repo_name":"r","pr_number":1,"username":"u"}"#;
+        match serde_json::from_str::<JobPayload>(legacy).unwrap() {
+            JobPayload::ClosePr(job) => assert_eq!(job.installation_id, None),
+            _ => panic!("expected close_pr payload"),
+        }
+    }
+}
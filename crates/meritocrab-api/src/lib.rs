@@ -1,18 +1,30 @@
 pub mod admin_handlers;
 pub mod auth_middleware;
 pub mod credit_commands;
+pub mod decay;
 pub mod error;
 pub mod extractors;
 pub mod health;
+pub mod jobs;
+pub mod notifications;
 pub mod oauth;
 pub mod rate_limit;
 pub mod repo_config_loader;
+pub mod session_store;
 pub mod state;
+pub mod thread_context;
+pub mod user_resolver;
 pub mod webhook_handler;
 
 // Re-export commonly used types
+pub use decay::apply_credit_decay;
 pub use error::{ApiError, ApiResult, ErrorResponse};
 pub use extractors::VerifiedWebhookPayload;
 pub use health::{health, init_server_start_time};
+pub use jobs::{JobQueueConfig, spawn_job_worker};
+pub use notifications::{NotificationConfig, Notifier};
+pub use rate_limit::{RateLimitConfig, RateLimiters, SelfServiceLimiter};
+pub use session_store::DbSessionStore;
 pub use state::{AppState, OAuthConfig};
+pub use thread_context::ThreadContextConfig;
 pub use webhook_handler::handle_webhook;
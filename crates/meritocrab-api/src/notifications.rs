@@ -0,0 +1,520 @@
+//! Outbound notifications on credit and blacklist changes
+//!
+//! Notifications go to the configured targets: a generic JSON webhook signed
+//! with HMAC-SHA256, a Slack-compatible incoming webhook, or a Matrix room.
+//! Each delivery is a job in the durable queue, so a target that is down is
+//! retried with backoff and ends up dead-lettered instead of being dropped.
+
+use crate::{
+    error::{ApiError, ApiResult},
+    jobs::{JobPayload, enqueue},
+    state::AppState,
+};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use meritocrab_core::EvaluationStatus;
+use meritocrab_db::{
+    credit_events::AppliedCredit, evaluations::count_evaluations_by_repo_and_status,
+};
+use rand::Rng;
+use reqwest::{StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::Sha256;
+use std::{fmt, time::Duration};
+use tracing::{error, info};
+
+/// Header carrying the HMAC-SHA256 signature of generic webhook bodies
+pub const SIGNATURE_HEADER: &str = "X-Meritocrab-Signature-256";
+
+/// Header carrying the notification event name of generic webhook bodies
+pub const EVENT_HEADER: &str = "X-Meritocrab-Event";
+
+/// Header carrying the notification ID, stable across retries
+pub const DELIVERY_HEADER: &str = "X-Meritocrab-Delivery";
+
+/// Where notifications are delivered
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum NotificationTarget {
+    /// POST the notification as JSON, signed with `secret` when set
+    Webhook { url: String, secret: Option<String> },
+    /// POST a Slack incoming-webhook message
+    Slack { webhook_url: String },
+    /// Send an `m.text` message to a Matrix room
+    Matrix {
+        homeserver_url: String,
+        room_id: String,
+        access_token: String,
+    },
+}
+
+/// Notification configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationConfig {
+    /// Targets every notification is delivered to
+    pub targets: Vec<NotificationTarget>,
+    /// Notify when this many evaluations are pending review in a repository
+    pub pending_evaluations_threshold: Option<i64>,
+    /// Notify when a single credit change takes away at least this much credit
+    pub large_negative_delta: Option<i32>,
+    /// Seconds before a delivery attempt times out
+    pub timeout_seconds: u64,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            targets: Vec::new(),
+            pending_evaluations_threshold: None,
+            large_negative_delta: None,
+            timeout_seconds: 10,
+        }
+    }
+}
+
+/// What happened
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum NotificationEvent {
+    /// A credit change dropped the contributor to the blacklist threshold
+    AutoBlacklist {
+        login: String,
+        github_user_id: i64,
+        credit_score: i32,
+    },
+    /// A maintainer blacklisted the contributor
+    BlacklistAdded {
+        login: String,
+        github_user_id: i64,
+        /// Maintainer who did it, when known
+        by: Option<String>,
+    },
+    /// Evaluations waiting for maintainer review reached the threshold
+    PendingEvaluations { pending: i64 },
+    /// A single credit change took away a lot of credit
+    LargeNegativeDelta {
+        login: String,
+        github_user_id: i64,
+        event_type: String,
+        delta: i32,
+        credit_after: i32,
+    },
+}
+
+impl NotificationEvent {
+    /// Event name, as in the JSON body and the event header
+    pub fn name(&self) -> &'static str {
+        match self {
+            NotificationEvent::AutoBlacklist { .. } => "auto_blacklist",
+            NotificationEvent::BlacklistAdded { .. } => "blacklist_added",
+            NotificationEvent::PendingEvaluations { .. } => "pending_evaluations",
+            NotificationEvent::LargeNegativeDelta { .. } => "large_negative_delta",
+        }
+    }
+}
+
+/// A notification about one repository
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Notification {
+    /// Unique ID, stable across delivery retries
+    pub id: String,
+    pub repo_owner: String,
+    pub repo_name: String,
+    #[serde(flatten)]
+    pub event: NotificationEvent,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Notification {
+    pub fn new(repo_owner: &str, repo_name: &str, event: NotificationEvent) -> Self {
+        let created_at = Utc::now();
+        Self {
+            id: format!(
+                "{}-{:08x}",
+                created_at.timestamp_millis(),
+                rand::rng().random::<u32>()
+            ),
+            repo_owner: repo_owner.to_string(),
+            repo_name: repo_name.to_string(),
+            event,
+            created_at,
+        }
+    }
+
+    /// One-line summary for chat targets
+    pub fn text(&self) -> String {
+        let repo = format!("{}/{}", self.repo_owner, self.repo_name);
+        match &self.event {
+            NotificationEvent::AutoBlacklist {
+                login,
+                credit_score,
+                ..
+            } => format!(
+                "@{} was auto-blacklisted in {} (credit dropped to {})",
+                login, repo, credit_score
+            ),
+            NotificationEvent::BlacklistAdded { login, by, .. } => match by {
+                Some(by) => format!("@{} was blacklisted in {} by @{}", login, repo, by),
+                None => format!("@{} was blacklisted in {}", login, repo),
+            },
+            NotificationEvent::PendingEvaluations { pending } => {
+                format!("{} evaluations are pending review in {}", pending, repo)
+            }
+            NotificationEvent::LargeNegativeDelta {
+                login,
+                event_type,
+                delta,
+                credit_after,
+                ..
+            } => format!(
+                "@{} lost {} credit in {} ({}, now {})",
+                login, -delta, repo, event_type, credit_after
+            ),
+        }
+    }
+}
+
+/// Failed notification delivery
+#[derive(Debug)]
+pub enum NotificationError {
+    /// The target could not be reached or asked to retry later (429/5xx)
+    Transient(String),
+    /// The target refused the notification
+    Rejected(String),
+}
+
+impl fmt::Display for NotificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotificationError::Transient(msg) => write!(f, "Delivery failed: {}", msg),
+            NotificationError::Rejected(msg) => write!(f, "Delivery rejected: {}", msg),
+        }
+    }
+}
+
+/// Delivery of one notification to one configured target
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationJob {
+    /// Index into the configured targets
+    ///
+    /// Targets are referenced rather than copied, so their secrets never end
+    /// up in the jobs table.
+    pub target: usize,
+    pub notification: Notification,
+}
+
+/// Delivers notifications to the configured targets
+#[derive(Debug)]
+pub struct Notifier {
+    config: NotificationConfig,
+    client: reqwest::Client,
+}
+
+impl Notifier {
+    pub fn new(config: NotificationConfig) -> ApiResult<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_seconds))
+            .build()
+            .map_err(|e| ApiError::Internal(format!("Failed to build HTTP client: {}", e)))?;
+        Ok(Self { config, client })
+    }
+
+    pub fn config(&self) -> &NotificationConfig {
+        &self.config
+    }
+
+    /// Deliver a notification to the target at `index`
+    pub async fn deliver(
+        &self,
+        index: usize,
+        notification: &Notification,
+    ) -> Result<(), NotificationError> {
+        let Some(target) = self.config.targets.get(index) else {
+            return Err(NotificationError::Rejected(format!(
+                "No notification target {} configured",
+                index
+            )));
+        };
+
+        let request = match target {
+            NotificationTarget::Webhook { url, secret } => {
+                let body = serde_json::to_vec(notification)
+                    .map_err(|e| NotificationError::Rejected(e.to_string()))?;
+                let mut request = self
+                    .client
+                    .post(url)
+                    .header(reqwest::header::CONTENT_TYPE, "application/json")
+                    .header(EVENT_HEADER, notification.event.name())
+                    .header(DELIVERY_HEADER, &notification.id);
+                if let Some(secret) = secret {
+                    request = request.header(SIGNATURE_HEADER, sign(secret, &body));
+                }
+                request.body(body)
+            }
+            NotificationTarget::Slack { webhook_url } => self
+                .client
+                .post(webhook_url)
+                .json(&json!({ "text": notification.text() })),
+            NotificationTarget::Matrix {
+                homeserver_url,
+                room_id,
+                access_token,
+            } => {
+                // Reusing the notification ID as transaction ID makes retries idempotent
+                let url = matrix_send_url(homeserver_url, room_id, &notification.id)?;
+                self.client
+                    .put(url)
+                    .bearer_auth(access_token)
+                    .json(&json!({ "msgtype": "m.text", "body": notification.text() }))
+            }
+        };
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| NotificationError::Transient(e.to_string()))?;
+        let status = response.status();
+        if status.is_success() {
+            Ok(())
+        } else if status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+            Err(NotificationError::Transient(format!("HTTP {}", status)))
+        } else {
+            Err(NotificationError::Rejected(format!("HTTP {}", status)))
+        }
+    }
+}
+
+/// `sha256=<hex>` HMAC-SHA256 signature of a webhook body
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+fn matrix_send_url(
+    homeserver_url: &str,
+    room_id: &str,
+    txn_id: &str,
+) -> Result<Url, NotificationError> {
+    let mut url = Url::parse(homeserver_url)
+        .map_err(|e| NotificationError::Rejected(format!("Invalid homeserver URL: {}", e)))?;
+    url.path_segments_mut()
+        .map_err(|_| NotificationError::Rejected("Invalid homeserver URL".to_string()))?
+        .pop_if_empty()
+        .extend([
+            "_matrix",
+            "client",
+            "v3",
+            "rooms",
+            room_id,
+            "send",
+            "m.room.message",
+            txn_id,
+        ]);
+    Ok(url)
+}
+
+/// Queue a notification for delivery to every configured target
+///
+/// Never fails: a notification that cannot be queued is logged, so it does
+/// not abort the credit change that triggered it.
+pub async fn notify(state: &AppState, notification: Notification) {
+    let Some(notifier) = &state.notifier else {
+        return;
+    };
+
+    for target in 0..notifier.config.targets.len() {
+        let job = JobPayload::Notify(NotificationJob {
+            target,
+            notification: notification.clone(),
+        });
+        if let Err(e) = enqueue(state, job, Utc::now()).await {
+            error!(
+                "Failed to queue {} notification for {}/{}: {}",
+                notification.event.name(),
+                notification.repo_owner,
+                notification.repo_name,
+                e
+            );
+        }
+    }
+}
+
+/// Notify about an applied credit change that blacklisted the contributor or
+/// took away more than the configured amount
+pub async fn notify_credit_applied(
+    state: &AppState,
+    repo_owner: &str,
+    repo_name: &str,
+    login: &str,
+    github_user_id: i64,
+    applied: &AppliedCredit,
+) {
+    let Some(notifier) = &state.notifier else {
+        return;
+    };
+
+    if applied.auto_blacklisted {
+        let event = NotificationEvent::AutoBlacklist {
+            login: login.to_string(),
+            github_user_id,
+            credit_score: applied.event.credit_after,
+        };
+        notify(state, Notification::new(repo_owner, repo_name, event)).await;
+    }
+
+    if let Some(threshold) = notifier.config.large_negative_delta
+        && applied.event.delta <= -threshold.abs()
+    {
+        let event = NotificationEvent::LargeNegativeDelta {
+            login: login.to_string(),
+            github_user_id,
+            event_type: applied.event.event_type.clone(),
+            delta: applied.event.delta,
+            credit_after: applied.event.credit_after,
+        };
+        notify(state, Notification::new(repo_owner, repo_name, event)).await;
+    }
+}
+
+/// Notify when a new pending evaluation brings the review queue to the threshold
+///
+/// Fires once per crossing rather than for every evaluation above it.
+pub async fn notify_pending_evaluations(state: &AppState, repo_owner: &str, repo_name: &str) {
+    let Some(threshold) = state
+        .notifier
+        .as_ref()
+        .and_then(|notifier| notifier.config.pending_evaluations_threshold)
+    else {
+        return;
+    };
+
+    match count_evaluations_by_repo_and_status(
+        &state.db_pool,
+        repo_owner,
+        repo_name,
+        &EvaluationStatus::Pending,
+    )
+    .await
+    {
+        Ok(pending) if pending == threshold => {
+            info!(
+                "{} evaluations pending review in {}/{}",
+                pending, repo_owner, repo_name
+            );
+            let event = NotificationEvent::PendingEvaluations { pending };
+            notify(state, Notification::new(repo_owner, repo_name, event)).await;
+        }
+        Ok(_) => {}
+        Err(e) => error!(
+            "Failed to count pending evaluations for {}/{}: {}",
+            repo_owner, repo_name, e
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notification(event: NotificationEvent) -> Notification {
+        Notification {
+            id: "1-abc".to_string(),
+            repo_owner: "owner".to_string(),
+            repo_name: "repo".to_string(),
+            event,
+            created_at: DateTime::parse_from_rfc3339("2026-03-01T12:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+        }
+    }
+
+    #[test]
+    fn test_notification_json() {
+        let json = serde_json::to_value(notification(NotificationEvent::AutoBlacklist {
+            login: "spammer".to_string(),
+            github_user_id: 42,
+            credit_score: -5,
+        }))
+        .unwrap();
+
+        assert_eq!(
+            json,
+            json!({
+                "id": "1-abc",
+                "repo_owner": "owner",
+                "repo_name": "repo",
+                "event": "auto_blacklist",
+                "login": "spammer",
+                "github_user_id": 42,
+                "credit_score": -5,
+                "created_at": "2026-03-01T12:00:00Z"
+            })
+        );
+    }
+
+    #[test]
+    fn test_notification_text() {
+        let text = notification(NotificationEvent::LargeNegativeDelta {
+            login: "spammer".to_string(),
+            github_user_id: 42,
+            event_type: "pr_opened".to_string(),
+            delta: -25,
+            credit_after: 75,
+        })
+        .text();
+        assert_eq!(
+            text,
+            "@spammer lost 25 credit in owner/repo (pr_opened, now 75)"
+        );
+
+        let text = notification(NotificationEvent::PendingEvaluations { pending: 10 }).text();
+        assert_eq!(text, "10 evaluations are pending review in owner/repo");
+    }
+
+    #[test]
+    fn test_matrix_send_url_escapes_room_id() {
+        let url =
+            matrix_send_url("https://matrix.example.org/", "!room:example.org", "1-abc").unwrap();
+        assert_eq!(
+            url.as_str(),
+            "https://matrix.example.org/_matrix/client/v3/rooms/!room:example.org/send/m.room.message/1-abc"
+        );
+
+        let url = matrix_send_url("https://matrix.example.org", "#a b:example.org", "1").unwrap();
+        assert!(url.as_str().contains("/rooms/%23a%20b:example.org/send/"));
+    }
+
+    #[test]
+    fn test_target_config() {
+        let config: NotificationConfig = toml::from_str(
+            r#"
+            large_negative_delta = 20
+
+            [[targets]]
+            kind = "webhook"
+            url = "https://example.org/hook"
+            secret = "s3cret"
+
+            [[targets]]
+            kind = "matrix"
+            homeserver_url = "https://matrix.example.org"
+            room_id = "!room:example.org"
+            access_token = "token"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.targets.len(), 2);
+        assert_eq!(config.large_negative_delta, Some(20));
+        assert_eq!(config.pending_evaluations_threshold, None);
+        assert_eq!(config.timeout_seconds, 10);
+        assert!(matches!(
+            &config.targets[0],
+            NotificationTarget::Webhook { secret: Some(secret), .. } if secret == "s3cret"
+        ));
+    }
+}
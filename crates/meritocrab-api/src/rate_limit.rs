@@ -1,23 +1,429 @@
-// NOTE: Rate limiting using tower_governor is implemented but commented out due to
-// complex API changes in v0.8. For production, consider using a reverse proxy
-// (nginx, HAProxy) or API gateway (AWS API Gateway, Kong) for rate limiting.
-//
-// The webhook endpoint naturally has rate limiting from GitHub's webhook delivery mechanism.
-// Admin endpoints are protected by authentication which provides basic DoS protection.
-//
-// For a simple in-process solution, you could implement a custom middleware using
-// a DashMap<IpAddr, (Count, Instant)> to track requests per IP.
-
-/// Placeholder for webhook rate limiting
-///
-/// In production, use reverse proxy rate limiting or implement custom middleware
-pub fn webhook_rate_limiter() {
-    // No-op for now - rely on GitHub's webhook delivery rate and authentication
+//! Rate limiting for the webhook endpoint, the admin API and `/credit me`
+//!
+//! All limiters are keyed token buckets from `governor`: the webhook endpoint
+//! is keyed by source IP, the admin API by the authenticated GitHub user.
+//! HTTP requests over quota get `429 Too Many Requests` with `Retry-After`.
+//! `/credit me` replies are keyed by the commenting GitHub user and dropped
+//! when over quota, since any reply would add to the noise in the thread.
+//! That limiter is a separate [`SelfServiceLimiter`]: a reverse proxy can take
+//! over HTTP rate limiting, but cannot see `/credit me` comments.
+
+use crate::error::{ApiError, ApiResult, ErrorResponse};
+use crate::oauth::GithubUser;
+use axum::{
+    Json,
+    body::Body,
+    http::{HeaderValue, Request, Response, StatusCode, header::RETRY_AFTER},
+    response::IntoResponse,
+};
+use governor::{DefaultKeyedRateLimiter, Quota, RateLimiter, middleware::NoOpMiddleware};
+use serde::{Deserialize, Serialize};
+use std::{net::IpAddr, num::NonZeroU32, sync::Arc, time::Duration};
+use tower_governor::{
+    GovernorError, GovernorLayer,
+    governor::{GovernorConfig, GovernorConfigBuilder},
+    key_extractor::{KeyExtractor, PeerIpKeyExtractor, SmartIpKeyExtractor},
+};
+
+/// Token bucket quota
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuotaConfig {
+    /// Requests allowed in a burst before limiting starts
+    pub burst_size: u32,
+    /// Milliseconds after which one request of the burst is replenished
+    pub replenish_interval_ms: u64,
+}
+
+/// Rate limiter configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    /// Disable to rely on a reverse proxy for rate limiting HTTP requests
+    /// instead (`/credit me` stays limited)
+    pub enabled: bool,
+    /// Key webhook requests by `X-Forwarded-For` / `X-Real-IP` / `Forwarded`
+    ///
+    /// Only enable behind a reverse proxy that sets these headers, otherwise
+    /// clients can pick their own key.
+    pub trust_forwarded_headers: bool,
+    /// Quota per source IP on `/webhooks/github`
+    pub webhook: QuotaConfig,
+    /// Quota per GitHub user on the admin API
+    pub admin: QuotaConfig,
+    /// Quota per GitHub user for `/credit me` replies
+    pub self_service: QuotaConfig,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            trust_forwarded_headers: false,
+            webhook: QuotaConfig {
+                burst_size: 120,
+                replenish_interval_ms: 250,
+            },
+            admin: QuotaConfig {
+                burst_size: 60,
+                replenish_interval_ms: 500,
+            },
+            self_service: QuotaConfig {
+                burst_size: 3,
+                replenish_interval_ms: 600_000,
+            },
+        }
+    }
+}
+
+/// Keys webhook requests by source IP
+#[derive(Debug, Clone, Copy)]
+pub struct WebhookKeyExtractor {
+    trust_forwarded_headers: bool,
+}
+
+impl KeyExtractor for WebhookKeyExtractor {
+    type Key = IpAddr;
+
+    fn extract<T>(&self, req: &Request<T>) -> Result<Self::Key, GovernorError> {
+        if self.trust_forwarded_headers {
+            SmartIpKeyExtractor.extract(req)
+        } else {
+            PeerIpKeyExtractor.extract(req)
+        }
+    }
+}
+
+/// Keys admin requests by the GitHub user `require_maintainer` authenticated
+#[derive(Debug, Clone, Copy)]
+pub struct MaintainerKeyExtractor;
+
+impl KeyExtractor for MaintainerKeyExtractor {
+    type Key = i64;
+
+    fn extract<T>(&self, req: &Request<T>) -> Result<Self::Key, GovernorError> {
+        req.extensions()
+            .get::<GithubUser>()
+            .map(|user| user.id)
+            .ok_or(GovernorError::UnableToExtractKey)
+    }
+}
+
+/// Current state of one limiter, reported by `/health`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LimiterStatus {
+    pub burst_size: u32,
+    pub replenish_interval_ms: u64,
+    /// Keys (IPs or users) with recent requests
+    pub tracked_keys: usize,
+}
+
+/// Current state of all limiters, reported by `/health`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitStatus {
+    pub webhook: LimiterStatus,
+    pub admin: LimiterStatus,
+}
+
+/// Shared limiter state for the webhook and admin layers
+#[derive(Debug, Clone)]
+pub struct RateLimiters {
+    config: RateLimitConfig,
+    webhook: Arc<GovernorConfig<WebhookKeyExtractor, NoOpMiddleware>>,
+    admin: Arc<GovernorConfig<MaintainerKeyExtractor, NoOpMiddleware>>,
 }
 
-/// Placeholder for admin API rate limiting
+impl RateLimiters {
+    /// Build limiters from configuration
+    ///
+    /// Fails if a burst size or replenish interval is zero.
+    pub fn new(config: RateLimitConfig) -> ApiResult<Self> {
+        let webhook = build_config(
+            &config.webhook,
+            WebhookKeyExtractor {
+                trust_forwarded_headers: config.trust_forwarded_headers,
+            },
+        )
+        .ok_or_else(|| invalid_quota("webhook"))?;
+        let admin = build_config(&config.admin, MaintainerKeyExtractor)
+            .ok_or_else(|| invalid_quota("admin"))?;
+
+        Ok(Self {
+            config,
+            webhook: Arc::new(webhook),
+            admin: Arc::new(admin),
+        })
+    }
+
+    /// Layer for `/webhooks/github`, keyed by source IP
+    ///
+    /// Source IPs come from `ConnectInfo`, so the server must be started with
+    /// `into_make_service_with_connect_info::<SocketAddr>()`.
+    pub fn webhook_rate_limiter(&self) -> GovernorLayer<WebhookKeyExtractor, NoOpMiddleware, Body> {
+        GovernorLayer::new(self.webhook.clone()).error_handler(rate_limit_response)
+    }
+
+    /// Layer for the admin API, keyed by GitHub user
+    ///
+    /// Must run inside `require_maintainer`, which puts the user in the
+    /// request extensions.
+    pub fn admin_rate_limiter(
+        &self,
+    ) -> GovernorLayer<MaintainerKeyExtractor, NoOpMiddleware, Body> {
+        GovernorLayer::new(self.admin.clone()).error_handler(rate_limit_response)
+    }
+
+    /// Drop keys whose buckets have fully replenished
+    pub fn retain_recent(&self) {
+        self.webhook.limiter().retain_recent();
+        self.webhook.limiter().shrink_to_fit();
+        self.admin.limiter().retain_recent();
+        self.admin.limiter().shrink_to_fit();
+    }
+
+    /// Quotas and tracked keys of both limiters
+    pub fn status(&self) -> RateLimitStatus {
+        RateLimitStatus {
+            webhook: LimiterStatus {
+                burst_size: self.config.webhook.burst_size,
+                replenish_interval_ms: self.config.webhook.replenish_interval_ms,
+                tracked_keys: self.webhook.limiter().len(),
+            },
+            admin: LimiterStatus {
+                burst_size: self.config.admin.burst_size,
+                replenish_interval_ms: self.config.admin.replenish_interval_ms,
+                tracked_keys: self.admin.limiter().len(),
+            },
+        }
+    }
+}
+
+/// Per-user quota for `/credit me` replies
 ///
-/// In production, use reverse proxy rate limiting or implement custom middleware
-pub fn admin_rate_limiter() {
-    // No-op for now - admin endpoints are protected by OAuth authentication
+/// Independent of [`RateLimitConfig::enabled`], which only governs the HTTP
+/// layers.
+#[derive(Debug, Clone)]
+pub struct SelfServiceLimiter {
+    quota: QuotaConfig,
+    limiter: Arc<DefaultKeyedRateLimiter<i64>>,
+}
+
+impl SelfServiceLimiter {
+    /// Build the limiter from its quota
+    ///
+    /// Fails if the burst size or replenish interval is zero.
+    pub fn new(quota: QuotaConfig) -> ApiResult<Self> {
+        let limiter = build_quota(&quota).ok_or_else(|| invalid_quota("self_service"))?;
+
+        Ok(Self {
+            quota,
+            limiter: Arc::new(RateLimiter::keyed(limiter)),
+        })
+    }
+
+    /// Take one `/credit me` reply from the GitHub user's quota
+    ///
+    /// Returns false when the user is over quota.
+    pub fn check(&self, github_user_id: i64) -> bool {
+        self.limiter.check_key(&github_user_id).is_ok()
+    }
+
+    /// Drop keys whose buckets have fully replenished
+    pub fn retain_recent(&self) {
+        self.limiter.retain_recent();
+        self.limiter.shrink_to_fit();
+    }
+
+    /// Quota and tracked users
+    pub fn status(&self) -> LimiterStatus {
+        LimiterStatus {
+            burst_size: self.quota.burst_size,
+            replenish_interval_ms: self.quota.replenish_interval_ms,
+            tracked_keys: self.limiter.len(),
+        }
+    }
+}
+
+fn build_config<K: KeyExtractor>(
+    quota: &QuotaConfig,
+    key_extractor: K,
+) -> Option<GovernorConfig<K, NoOpMiddleware>> {
+    GovernorConfigBuilder::default()
+        .period(Duration::from_millis(quota.replenish_interval_ms))
+        .burst_size(quota.burst_size)
+        .key_extractor(key_extractor)
+        .finish()
+}
+
+fn build_quota(quota: &QuotaConfig) -> Option<Quota> {
+    let burst_size = NonZeroU32::new(quota.burst_size)?;
+    Quota::with_period(Duration::from_millis(quota.replenish_interval_ms))
+        .map(|quota| quota.allow_burst(burst_size))
+}
+
+fn invalid_quota(limiter: &str) -> ApiError {
+    ApiError::Internal(format!(
+        "Invalid {} rate limit: burst_size and replenish_interval_ms must be non-zero",
+        limiter
+    ))
+}
+
+/// Convert a limiter rejection into a JSON error response
+fn rate_limit_response(error: GovernorError) -> Response<Body> {
+    match error {
+        GovernorError::TooManyRequests { wait_time, .. } => {
+            // Sub-second waits round down to 0; never tell clients to retry immediately
+            let retry_after = wait_time.max(1);
+            let mut response = (
+                StatusCode::TOO_MANY_REQUESTS,
+                Json(ErrorResponse {
+                    error: "rate_limited".to_string(),
+                    message: format!("Too many requests, retry in {}s", retry_after),
+                }),
+            )
+                .into_response();
+            response
+                .headers_mut()
+                .insert(RETRY_AFTER, HeaderValue::from(retry_after));
+            response
+        }
+        GovernorError::UnableToExtractKey => {
+            ApiError::Internal("Unable to determine rate limit key".to_string()).into_response()
+        }
+        GovernorError::Other { code, msg, .. } => {
+            (code, msg.unwrap_or_else(|| "Rate limit error".to_string())).into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, extract::ConnectInfo, routing::post};
+    use std::net::SocketAddr;
+    use tower::ServiceExt;
+
+    fn limiters(burst_size: u32) -> RateLimiters {
+        let quota = QuotaConfig {
+            burst_size,
+            replenish_interval_ms: 60_000,
+        };
+        RateLimiters::new(RateLimitConfig {
+            webhook: quota.clone(),
+            admin: quota,
+            ..RateLimitConfig::default()
+        })
+        .unwrap()
+    }
+
+    fn webhook_request(ip: [u8; 4]) -> Request<Body> {
+        let mut request = Request::builder()
+            .method("POST")
+            .uri("/webhooks/github")
+            .body(Body::empty())
+            .unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from((ip, 443))));
+        request
+    }
+
+    #[test]
+    fn test_zero_quota_rejected() {
+        let config = RateLimitConfig {
+            admin: QuotaConfig {
+                burst_size: 0,
+                replenish_interval_ms: 1000,
+            },
+            ..RateLimitConfig::default()
+        };
+        assert!(RateLimiters::new(config).is_err());
+
+        let quota = QuotaConfig {
+            burst_size: 1,
+            replenish_interval_ms: 0,
+        };
+        assert!(SelfServiceLimiter::new(quota).is_err());
+    }
+
+    #[test]
+    fn test_self_service_limited_per_user() {
+        let limiter = SelfServiceLimiter::new(QuotaConfig {
+            burst_size: 2,
+            replenish_interval_ms: 60_000,
+        })
+        .unwrap();
+
+        assert!(limiter.check(1));
+        assert!(limiter.check(1));
+        assert!(!limiter.check(1));
+        assert!(limiter.check(2));
+        assert_eq!(limiter.status().tracked_keys, 2);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_limited_per_source_ip() {
+        let limiters = limiters(1);
+        let app = Router::new()
+            .route("/webhooks/github", post(|| async { "ok" }))
+            .layer(limiters.webhook_rate_limiter());
+
+        let response = app
+            .clone()
+            .oneshot(webhook_request([10, 0, 0, 1]))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app
+            .clone()
+            .oneshot(webhook_request([10, 0, 0, 1]))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        let retry_after: u64 = response.headers()[RETRY_AFTER]
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(retry_after >= 1);
+
+        // Another source has its own bucket
+        let response = app.oneshot(webhook_request([10, 0, 0, 2])).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let status = limiters.status();
+        assert_eq!(status.webhook.tracked_keys, 2);
+        assert_eq!(status.admin.tracked_keys, 0);
+    }
+
+    #[tokio::test]
+    async fn test_admin_limited_per_user() {
+        let limiters = limiters(1);
+        let app = Router::new()
+            .route("/api/repos/o/r/jobs", post(|| async { "ok" }))
+            .layer(limiters.admin_rate_limiter());
+
+        let request = |id: i64| {
+            let mut request = Request::builder()
+                .method("POST")
+                .uri("/api/repos/o/r/jobs")
+                .body(Body::empty())
+                .unwrap();
+            request.extensions_mut().insert(GithubUser {
+                id,
+                login: format!("user{}", id),
+                name: None,
+                email: None,
+            });
+            request
+        };
+
+        let response = app.clone().oneshot(request(1)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = app.clone().oneshot(request(1)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key(RETRY_AFTER));
+        let response = app.oneshot(request(2)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
 }
@@ -17,10 +17,12 @@ struct CachedConfig {
 /// Repository configuration loader with caching
 ///
 /// Fetches `.meritocrab.toml` from repository root via GitHub API
-/// and caches it with configurable TTL. Falls back to default config
-/// if file is missing or invalid.
+/// and caches it with configurable TTL. Falls back to the server-wide
+/// config if file is missing or invalid.
+///
+/// The loader does not own a GitHub client: each lookup uses the client of the
+/// installation the repository belongs to.
 pub struct RepoConfigLoader {
-    github_client: Arc<GithubApiClient>,
     cache: Arc<RwLock<HashMap<String, CachedConfig>>>,
     cache_ttl: Duration,
     default_config: RepoConfig,
@@ -30,14 +32,13 @@ impl RepoConfigLoader {
     /// Create new config loader
     ///
     /// # Arguments
-    /// * `github_client` - GitHub API client for fetching config files
     /// * `cache_ttl_seconds` - TTL for cached configs in seconds
-    pub fn new(github_client: Arc<GithubApiClient>, cache_ttl_seconds: u64) -> Self {
+    /// * `default_config` - Server-wide config used when a repo has no usable `.meritocrab.toml`
+    pub fn new(cache_ttl_seconds: u64, default_config: RepoConfig) -> Self {
         Self {
-            github_client,
             cache: Arc::new(RwLock::new(HashMap::new())),
             cache_ttl: Duration::from_secs(cache_ttl_seconds),
-            default_config: RepoConfig::default(),
+            default_config,
         }
     }
 
@@ -45,7 +46,12 @@ impl RepoConfigLoader {
     ///
     /// Checks cache first, then fetches from GitHub if cache miss or expired.
     /// Returns default config if file is missing or invalid.
-    pub async fn get_config(&self, repo_owner: &str, repo_name: &str) -> RepoConfig {
+    pub async fn get_config(
+        &self,
+        github_client: &GithubApiClient,
+        repo_owner: &str,
+        repo_name: &str,
+    ) -> RepoConfig {
         let cache_key = format!("{}/{}", repo_owner, repo_name);
 
         // Check cache
@@ -62,18 +68,13 @@ impl RepoConfigLoader {
         // Cache miss or expired, fetch from GitHub
         info!("Fetching .meritocrab.toml for {}/{}", repo_owner, repo_name);
 
-        match self.fetch_config_from_github(repo_owner, repo_name).await {
+        match self
+            .fetch_config_from_github(github_client, repo_owner, repo_name)
+            .await
+        {
             Ok(config) => {
-                // Update cache
-                let mut cache_guard = self.cache.write().await;
-                cache_guard.insert(
-                    cache_key.clone(),
-                    CachedConfig {
-                        config: config.clone(),
-                        fetched_at: Instant::now(),
-                    },
-                );
-                info!("Cached config for {}/{}", repo_owner, repo_name);
+                self.cache_config(repo_owner, repo_name, config.clone())
+                    .await;
                 config
             }
             Err(e) => {
@@ -89,12 +90,12 @@ impl RepoConfigLoader {
     /// Fetch .meritocrab.toml from GitHub repository
     async fn fetch_config_from_github(
         &self,
+        github_client: &GithubApiClient,
         repo_owner: &str,
         repo_name: &str,
     ) -> ApiResult<RepoConfig> {
         // Fetch file content from GitHub
-        let file_content = self
-            .github_client
+        let file_content = github_client
             .get_file_content(repo_owner, repo_name, ".meritocrab.toml")
             .await?;
 
@@ -119,8 +120,21 @@ impl RepoConfigLoader {
         Ok(config)
     }
 
+    /// Cache the configuration of a repository
+    pub(crate) async fn cache_config(&self, repo_owner: &str, repo_name: &str, config: RepoConfig) {
+        let cache_key = format!("{}/{}", repo_owner, repo_name);
+        let mut cache_guard = self.cache.write().await;
+        cache_guard.insert(
+            cache_key,
+            CachedConfig {
+                config,
+                fetched_at: Instant::now(),
+            },
+        );
+        info!("Cached config for {}/{}", repo_owner, repo_name);
+    }
+
     /// Clear cache for a specific repository
-    #[allow(dead_code)]
     pub async fn invalidate_cache(&self, repo_owner: &str, repo_name: &str) {
         let cache_key = format!("{}/{}", repo_owner, repo_name);
         let mut cache_guard = self.cache.write().await;
@@ -159,8 +173,8 @@ mod tests {
             GithubApiClient::new("test-token".to_string()).expect("Failed to create client"),
         );
 
-        let loader = RepoConfigLoader::new(github_client, 300);
-        let config = loader.get_config("owner", "repo").await;
+        let loader = RepoConfigLoader::new(300, RepoConfig::default());
+        let config = loader.get_config(&github_client, "owner", "repo").await;
 
         // Should return defaults since GitHub fetch will fail
         assert_eq!(config.starting_credit, 100);
@@ -168,6 +182,31 @@ mod tests {
         assert_eq!(config.blacklist_threshold, 0);
     }
 
+    #[tokio::test]
+    async fn test_loader_falls_back_to_server_config() {
+        // Initialize rustls crypto provider for tests
+        let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+
+        let github_client = Arc::new(
+            GithubApiClient::new("test-token".to_string()).expect("Failed to create client"),
+        );
+
+        let server_config = RepoConfig {
+            starting_credit: 150,
+            pr_threshold: 30,
+            blacklist_threshold: 5,
+            ..RepoConfig::default()
+        };
+
+        let loader = RepoConfigLoader::new(300, server_config);
+        let config = loader.get_config(&github_client, "owner", "repo").await;
+
+        // Should return the server-wide config, not the built-in defaults
+        assert_eq!(config.starting_credit, 150);
+        assert_eq!(config.pr_threshold, 30);
+        assert_eq!(config.blacklist_threshold, 5);
+    }
+
     #[tokio::test]
     async fn test_cache_ttl() {
         // Initialize rustls crypto provider for tests
@@ -178,15 +217,15 @@ mod tests {
         );
 
         // Very short TTL for testing
-        let loader = RepoConfigLoader::new(github_client, 1);
+        let loader = RepoConfigLoader::new(1, RepoConfig::default());
 
         // First fetch (cache miss, will fail and return defaults, NOT cached)
-        let config1 = loader.get_config("owner", "repo").await;
+        let config1 = loader.get_config(&github_client, "owner", "repo").await;
         // Cache is empty because fetch failed
         assert_eq!(loader.cache_size().await, 0);
 
         // Second fetch (cache miss again)
-        let config2 = loader.get_config("owner", "repo").await;
+        let config2 = loader.get_config(&github_client, "owner", "repo").await;
         assert_eq!(config1.starting_credit, config2.starting_credit);
 
         // Cache is still empty because fetches fail in tests
@@ -202,10 +241,10 @@ mod tests {
             GithubApiClient::new("test-token".to_string()).expect("Failed to create client"),
         );
 
-        let loader = RepoConfigLoader::new(github_client, 300);
+        let loader = RepoConfigLoader::new(300, RepoConfig::default());
 
         // Fetch config (will fail and not be cached)
-        let _config = loader.get_config("owner", "repo").await;
+        let _config = loader.get_config(&github_client, "owner", "repo").await;
         assert_eq!(loader.cache_size().await, 0);
 
         // Invalidate (cache is already empty, this is a no-op)
@@ -222,11 +261,11 @@ mod tests {
             GithubApiClient::new("test-token".to_string()).expect("Failed to create client"),
         );
 
-        let loader = RepoConfigLoader::new(github_client, 300);
+        let loader = RepoConfigLoader::new(300, RepoConfig::default());
 
         // Fetch multiple configs (will fail and not be cached)
-        let _config1 = loader.get_config("owner1", "repo1").await;
-        let _config2 = loader.get_config("owner2", "repo2").await;
+        let _config1 = loader.get_config(&github_client, "owner1", "repo1").await;
+        let _config2 = loader.get_config(&github_client, "owner2", "repo2").await;
         assert_eq!(loader.cache_size().await, 0);
 
         // Clear all (cache is already empty, this is a no-op)
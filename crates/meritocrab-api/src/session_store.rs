@@ -0,0 +1,151 @@
+//! Database-backed session store for the maintainer dashboard
+//!
+//! Sessions live in the application database, so they survive restarts and
+//! are shared by every replica behind a load balancer.
+//!
+//! This is a small `SessionStore` over `meritocrab_db::sessions` rather than
+//! `tower-sessions-sqlx-store`: that crate's stores take a concrete
+//! `SqlitePool` or `PgPool`, while the server shares one `Pool<Any>` across
+//! both backends. Keeping the `sessions` table in the versioned migrations
+//! also keeps its schema under the same `migrate` tooling as the rest.
+
+use chrono::DateTime;
+use meritocrab_db::sessions::{create_session, delete_session, load_session, save_session};
+use sqlx::{Any, Pool};
+use tower_sessions::{
+    SessionStore,
+    session::{Id, Record},
+    session_store,
+};
+
+/// `tower_sessions` store backed by the `sessions` table
+#[derive(Debug, Clone)]
+pub struct DbSessionStore {
+    db_pool: Pool<Any>,
+}
+
+impl DbSessionStore {
+    pub fn new(db_pool: Pool<Any>) -> Self {
+        Self { db_pool }
+    }
+}
+
+fn backend_error(e: meritocrab_db::DbError) -> session_store::Error {
+    session_store::Error::Backend(e.to_string())
+}
+
+fn encode(record: &Record) -> session_store::Result<(String, DateTime<chrono::Utc>)> {
+    let data =
+        serde_json::to_string(record).map_err(|e| session_store::Error::Encode(e.to_string()))?;
+    let expiry_date = DateTime::from_timestamp(
+        record.expiry_date.unix_timestamp(),
+        record.expiry_date.nanosecond(),
+    )
+    .ok_or_else(|| session_store::Error::Encode("Session expiry out of range".to_string()))?;
+
+    Ok((data, expiry_date))
+}
+
+#[async_trait::async_trait]
+impl SessionStore for DbSessionStore {
+    async fn create(&self, record: &mut Record) -> session_store::Result<()> {
+        // Pick a fresh ID on the (unlikely) event of a collision
+        loop {
+            let (data, expiry_date) = encode(record)?;
+            let created = create_session(&self.db_pool, &record.id.to_string(), &data, expiry_date)
+                .await
+                .map_err(backend_error)?;
+            if created {
+                return Ok(());
+            }
+            record.id = Id::default();
+        }
+    }
+
+    async fn save(&self, record: &Record) -> session_store::Result<()> {
+        let (data, expiry_date) = encode(record)?;
+        save_session(&self.db_pool, &record.id.to_string(), &data, expiry_date)
+            .await
+            .map_err(backend_error)
+    }
+
+    async fn load(&self, session_id: &Id) -> session_store::Result<Option<Record>> {
+        let Some(data) = load_session(&self.db_pool, &session_id.to_string())
+            .await
+            .map_err(backend_error)?
+        else {
+            return Ok(None);
+        };
+
+        let record =
+            serde_json::from_str(&data).map_err(|e| session_store::Error::Decode(e.to_string()))?;
+        Ok(Some(record))
+    }
+
+    async fn delete(&self, session_id: &Id) -> session_store::Result<()> {
+        delete_session(&self.db_pool, &session_id.to_string())
+            .await
+            .map_err(backend_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use meritocrab_db::run_migrations;
+    use sqlx::any::AnyPoolOptions;
+
+    async fn setup_test_store() -> DbSessionStore {
+        sqlx::any::install_default_drivers();
+
+        let pool = AnyPoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database pool");
+
+        run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        DbSessionStore::new(pool)
+    }
+
+    fn record(expires_in: tower_sessions::cookie::time::Duration) -> Record {
+        let mut data = std::collections::HashMap::new();
+        data.insert(
+            "user".to_string(),
+            serde_json::json!({ "login": "octocat" }),
+        );
+        Record {
+            id: Id::default(),
+            data,
+            expiry_date: tower_sessions::cookie::time::OffsetDateTime::now_utc() + expires_in,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_session_survives_new_store_instance() {
+        let store = setup_test_store().await;
+        let mut session = record(tower_sessions::cookie::time::Duration::hours(1));
+
+        store.create(&mut session).await.unwrap();
+
+        // A second store on the same database (another replica) sees it
+        let other = DbSessionStore::new(store.db_pool.clone());
+        let loaded = other.load(&session.id).await.unwrap().unwrap();
+        assert_eq!(loaded.data, session.data);
+
+        other.delete(&session.id).await.unwrap();
+        assert!(store.load(&session.id).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_expired_session_not_loaded() {
+        let store = setup_test_store().await;
+        let session = record(tower_sessions::cookie::time::Duration::minutes(-1));
+
+        store.save(&session).await.unwrap();
+        assert!(store.load(&session.id).await.unwrap().is_none());
+    }
+}
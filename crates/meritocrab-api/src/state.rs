@@ -1,12 +1,16 @@
+use crate::error::ApiResult;
+use crate::notifications::Notifier;
+use crate::rate_limit::{RateLimiters, SelfServiceLimiter};
 use crate::repo_config_loader::RepoConfigLoader;
+use crate::thread_context::ThreadContextConfig;
 use axum::extract::FromRef;
 use meritocrab_core::RepoConfig;
-use meritocrab_github::{GithubApiClient, WebhookSecret};
+use meritocrab_github::{GithubApiClient, InstallationClients, WebhookSecret};
 use meritocrab_llm::LlmEvaluator;
 use serde::{Deserialize, Serialize};
 use sqlx::{Any, Pool};
 use std::sync::Arc;
-use tokio::sync::Semaphore;
+use tokio::sync::{Notify, Semaphore};
 
 /// OAuth configuration
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -20,21 +24,33 @@ pub struct OAuthConfig {
 ///
 /// This is the DI root that contains all shared resources needed by handlers:
 /// - Database connection pool
-/// - GitHub API client
+/// - GitHub API client (and per-installation clients for GitHub App deployments)
 /// - Repository configuration
 /// - Webhook secret for HMAC verification
 /// - LLM evaluator for content quality assessment
 /// - Semaphore for limiting concurrent LLM evaluations
 /// - OAuth configuration for admin authentication
+/// - Notifier that wakes the background job worker
+/// - Rate limiters for the webhook endpoint, admin API and `/credit me`
+/// - Notifier for outbound credit and blacklist notifications
+/// - Budget for the thread context of comment and review evaluations
 #[derive(Clone)]
 pub struct AppState {
     /// Database connection pool
     pub db_pool: Pool<Any>,
 
     /// GitHub API client for operations like closing PRs
+    ///
+    /// Authenticated as `installation_id` when installation clients are configured.
     pub github_client: Arc<GithubApiClient>,
 
-    /// Repository credit configuration
+    /// Per-installation GitHub clients (`None` when using a single static token)
+    pub installation_clients: Option<Arc<InstallationClients>>,
+
+    /// GitHub App installation `github_client` is authenticated as
+    pub installation_id: Option<i64>,
+
+    /// Server-wide credit configuration (fallback when a repo has no `.meritocrab.toml`)
     pub repo_config: RepoConfig,
 
     /// Webhook secret for HMAC verification
@@ -51,6 +67,21 @@ pub struct AppState {
 
     /// Repository configuration loader with caching
     pub repo_config_loader: Arc<RepoConfigLoader>,
+
+    /// Wakes the job worker when a job is enqueued
+    pub job_notify: Arc<Notify>,
+
+    /// Webhook and admin API rate limiters (`None` when rate limiting is disabled)
+    pub rate_limiters: Option<RateLimiters>,
+
+    /// Per-user quota for `/credit me` replies (`None` leaves them unlimited)
+    pub self_service_limiter: Option<SelfServiceLimiter>,
+
+    /// Outbound notification targets (`None` when no targets are configured)
+    pub notifier: Option<Arc<Notifier>>,
+
+    /// How much of a comment's thread goes into its evaluation
+    pub thread_context_config: ThreadContextConfig,
 }
 
 impl AppState {
@@ -58,7 +89,7 @@ impl AppState {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         db_pool: Pool<Any>,
-        github_client: GithubApiClient,
+        github_client: impl Into<Arc<GithubApiClient>>,
         repo_config: RepoConfig,
         webhook_secret: WebhookSecret,
         llm_evaluator: Arc<dyn LlmEvaluator>,
@@ -66,25 +97,91 @@ impl AppState {
         oauth_config: OAuthConfig,
         config_cache_ttl_seconds: u64,
     ) -> Self {
-        let github_client_arc = Arc::new(github_client);
         let repo_config_loader = Arc::new(RepoConfigLoader::new(
-            github_client_arc.clone(),
             config_cache_ttl_seconds,
+            repo_config.clone(),
         ));
 
         Self {
             db_pool,
-            github_client: github_client_arc,
+            github_client: github_client.into(),
+            installation_clients: None,
+            installation_id: None,
             repo_config,
             webhook_secret,
             llm_evaluator,
             llm_semaphore: Arc::new(Semaphore::new(max_concurrent_llm_evals)),
             oauth_config,
             repo_config_loader,
+            job_notify: Arc::new(Notify::new()),
+            rate_limiters: None,
+            self_service_limiter: None,
+            notifier: None,
+            thread_context_config: ThreadContextConfig::default(),
         }
     }
 }
 
+impl AppState {
+    /// Authenticate GitHub calls per App installation
+    ///
+    /// `default_installation_id` is used for requests that carry no
+    /// installation, such as the admin API.
+    pub fn with_installation_clients(
+        mut self,
+        installation_clients: Arc<InstallationClients>,
+        default_installation_id: i64,
+    ) -> Self {
+        self.installation_clients = Some(installation_clients);
+        self.installation_id = Some(default_installation_id);
+        self
+    }
+
+    /// Report HTTP rate limiter state in `/health`
+    pub fn with_rate_limiters(mut self, rate_limiters: RateLimiters) -> Self {
+        self.rate_limiters = Some(rate_limiters);
+        self
+    }
+
+    /// Rate limit `/credit me` replies per GitHub user
+    pub fn with_self_service_limiter(mut self, limiter: SelfServiceLimiter) -> Self {
+        self.self_service_limiter = Some(limiter);
+        self
+    }
+
+    /// Deliver notifications on credit and blacklist changes
+    pub fn with_notifier(mut self, notifier: Notifier) -> Self {
+        self.notifier = Some(Arc::new(notifier));
+        self
+    }
+
+    /// Change how much of a comment's thread goes into its evaluation
+    pub fn with_thread_context_config(mut self, config: ThreadContextConfig) -> Self {
+        self.thread_context_config = config;
+        self
+    }
+
+    /// State whose GitHub client acts as the given installation
+    ///
+    /// Falls back to the default installation when `installation_id` is `None`.
+    /// Without installation clients the state is returned unchanged.
+    pub async fn for_installation(&self, installation_id: Option<i64>) -> ApiResult<AppState> {
+        let mut state = self.clone();
+
+        let (Some(clients), Some(installation_id)) = (
+            &self.installation_clients,
+            installation_id.or(self.installation_id),
+        ) else {
+            return Ok(state);
+        };
+
+        state.github_client = clients.client(installation_id).await?;
+        state.installation_id = Some(installation_id);
+
+        Ok(state)
+    }
+}
+
 /// Implement FromRef to allow VerifiedWebhook extractor to access WebhookSecret
 impl FromRef<AppState> for WebhookSecret {
     fn from_ref(state: &AppState) -> Self {
@@ -98,10 +195,3 @@ impl FromRef<AppState> for OAuthConfig {
         state.oauth_config.clone()
     }
 }
-
-/// Implement FromRef to allow auth middleware to access GithubApiClient
-impl FromRef<AppState> for Arc<GithubApiClient> {
-    fn from_ref(state: &AppState) -> Self {
-        state.github_client.clone()
-    }
-}
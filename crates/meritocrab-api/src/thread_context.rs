@@ -0,0 +1,421 @@
+//! Conversation context for comment and review evaluation
+//!
+//! A comment is hard to judge on its own: "+1, same issue here" and an answer
+//! to a maintainer's question look alike without the thread around them. The
+//! context builder fetches the issue or PR description, the comments before
+//! the evaluated one, and the diff hunks a review comments on, and fits them
+//! into a character budget for `EvalContext::thread_context`.
+
+use crate::state::AppState;
+use chrono::{DateTime, Utc};
+use meritocrab_github::{Comment, GithubApiClient, GithubResult};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+/// Smallest share of the budget worth spending on one more comment
+const MIN_COMMENT_CHARS: usize = 80;
+
+/// Longest heading of the previous comments section
+const COMMENTS_HEADING_CHARS: usize = 48;
+
+/// Marker for text cut to fit the budget
+const ELLIPSIS: &str = "…";
+
+/// Thread context configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThreadContextConfig {
+    /// Previous comments included at most
+    pub max_comments: usize,
+    /// Characters the whole context may use
+    pub max_chars: usize,
+    /// Seconds an inline review comment waits for the rest of its review
+    ///
+    /// A review's inline comments arrive as separate webhooks; the review is
+    /// evaluated once, this long after the first of them.
+    pub review_group_seconds: i64,
+}
+
+impl Default for ThreadContextConfig {
+    fn default() -> Self {
+        Self {
+            max_comments: 5,
+            max_chars: 4000,
+            review_group_seconds: 30,
+        }
+    }
+}
+
+/// The comment or review an evaluation is for
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ThreadSource {
+    /// Conversation comment on an issue or pull request
+    IssueComment { comment_id: i64 },
+    /// Pull request review, with its inline comments
+    Review {
+        review_id: i64,
+        state: String,
+        /// When the review was submitted; read back from GitHub if unknown
+        #[serde(default)]
+        submitted_at: Option<DateTime<Utc>>,
+    },
+}
+
+/// Issue or pull request conversation around the evaluated content
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Thread {
+    pub number: i64,
+    pub is_pull_request: bool,
+    pub title: String,
+    pub author: String,
+    pub description: String,
+    /// Review state (`approved`, `changes_requested`, ...) for reviews
+    pub review_state: Option<String>,
+    /// Comments before the evaluated content, oldest first
+    pub comments: Vec<ThreadComment>,
+    /// Diff hunks the evaluated content comments on
+    pub hunks: Vec<ThreadHunk>,
+}
+
+/// Earlier comment in the thread
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThreadComment {
+    pub author: String,
+    pub body: String,
+}
+
+/// Diff hunk an inline review comment is attached to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThreadHunk {
+    pub path: String,
+    pub diff_hunk: String,
+    /// The inline comment on the hunk
+    pub comment: String,
+}
+
+/// Fetch the thread of an issue or pull request from GitHub
+///
+/// Bot comments are left out. Only the latest `max_comments` comments before
+/// the evaluated content (the comment, or the review's submission) are kept.
+pub async fn fetch_thread(
+    github: &GithubApiClient,
+    repo_owner: &str,
+    repo_name: &str,
+    number: i64,
+    source: &ThreadSource,
+    max_comments: usize,
+) -> GithubResult<Thread> {
+    let issue = github
+        .get_issue(repo_owner, repo_name, number as u64)
+        .await?;
+    let comments = github
+        .list_latest_issue_comments(repo_owner, repo_name, number as u64, issue.comments)
+        .await?;
+
+    let (previous, review_state, hunks) = match source {
+        ThreadSource::IssueComment { comment_id } => {
+            let position = comments
+                .iter()
+                .position(|comment| comment.id == *comment_id)
+                .unwrap_or_else(|| {
+                    comments
+                        .iter()
+                        .take_while(|comment| comment.id < *comment_id)
+                        .count()
+                });
+            (&comments[..position], None, Vec::new())
+        }
+        ThreadSource::Review {
+            review_id,
+            state,
+            submitted_at,
+        } => {
+            let submitted_at = match submitted_at {
+                Some(submitted_at) => Some(*submitted_at),
+                None => {
+                    github
+                        .get_review(repo_owner, repo_name, number as u64, *review_id as u64)
+                        .await?
+                        .submitted_at
+                }
+            };
+            // Comments posted after the review are not what it responded to
+            let position = match submitted_at {
+                Some(submitted_at) => comments
+                    .iter()
+                    .position(|comment| {
+                        comment
+                            .created_at
+                            .is_some_and(|created_at| created_at >= submitted_at)
+                    })
+                    .unwrap_or(comments.len()),
+                None => comments.len(),
+            };
+            let hunks = github
+                .list_review_comments(repo_owner, repo_name, number as u64, *review_id as u64)
+                .await?
+                .into_iter()
+                .map(|comment| ThreadHunk {
+                    path: comment.path,
+                    diff_hunk: comment.diff_hunk,
+                    comment: comment.body,
+                })
+                .collect();
+            (&comments[..position], Some(state.clone()), hunks)
+        }
+    };
+
+    let mut previous: Vec<ThreadComment> = previous
+        .iter()
+        .rev()
+        .filter(|comment| !is_bot(comment))
+        .take(max_comments)
+        .map(|comment| ThreadComment {
+            author: comment.user.login.clone(),
+            body: comment.body.clone(),
+        })
+        .collect();
+    previous.reverse();
+
+    Ok(Thread {
+        number,
+        is_pull_request: issue.pull_request.is_some(),
+        title: issue.title,
+        author: issue.user.login,
+        description: issue.body.unwrap_or_default(),
+        review_state,
+        comments: previous,
+        hunks,
+    })
+}
+
+fn is_bot(comment: &Comment) -> bool {
+    comment.user.user_type.as_deref() == Some("Bot") || comment.user.login.ends_with("[bot]")
+}
+
+/// Render a thread within `max_chars` characters
+///
+/// The header is always kept. The description and the diff hunks may each
+/// use up to a third of the budget; the comments get the rest, newest first,
+/// and are shown oldest first. Hunks are cut from the top so the commented
+/// lines at their end stay visible.
+pub fn render_thread(thread: &Thread, max_chars: usize) -> String {
+    let kind = if thread.is_pull_request {
+        "PR"
+    } else {
+        "Issue"
+    };
+    let mut out = format!(
+        "{} #{} by @{}: {}\n",
+        kind, thread.number, thread.author, thread.title
+    );
+    if let Some(state) = &thread.review_state {
+        out.push_str(&format!("Review state: {}\n", state));
+    }
+    let mut remaining = max_chars.saturating_sub(char_len(&out));
+    let share = remaining / 3;
+
+    let description = thread.description.trim();
+    if !description.is_empty() {
+        let section = format!(
+            "\nDescription:\n{}\n",
+            truncate_end(description, share.saturating_sub(15))
+        );
+        remaining = remaining.saturating_sub(char_len(&section));
+        out.push_str(&section);
+    }
+
+    if !thread.hunks.is_empty() {
+        let per_hunk = share / thread.hunks.len();
+        for hunk in &thread.hunks {
+            let header = format!("\nInline comment on {}:\n", hunk.path);
+            let room = per_hunk.saturating_sub(char_len(&header) + 2);
+            let comment = truncate_end(hunk.comment.trim(), room / 3);
+            let diff = truncate_start(&hunk.diff_hunk, room.saturating_sub(char_len(&comment)));
+            let section = format!("{}{}\n> {}\n", header, diff, comment);
+            if char_len(&section) > remaining {
+                break;
+            }
+            remaining -= char_len(&section);
+            out.push_str(&section);
+        }
+    }
+
+    // Room for the "Previous comments (N earlier not shown):" heading
+    remaining = remaining.saturating_sub(COMMENTS_HEADING_CHARS);
+    let mut shown = Vec::new();
+    for comment in thread.comments.iter().rev() {
+        let prefix = format!("@{}: ", comment.author);
+        let room = remaining.saturating_sub(char_len(&prefix) + 1);
+        if room < MIN_COMMENT_CHARS.min(char_len(comment.body.trim())) || room == 0 {
+            break;
+        }
+        let line = format!("{}{}\n", prefix, truncate_end(comment.body.trim(), room));
+        remaining = remaining.saturating_sub(char_len(&line));
+        shown.push(line);
+    }
+    if !shown.is_empty() {
+        let omitted = thread.comments.len() - shown.len();
+        out.push_str("\nPrevious comments");
+        if omitted > 0 {
+            out.push_str(&format!(" ({} earlier not shown)", omitted));
+        }
+        out.push_str(":\n");
+        for line in shown.iter().rev() {
+            out.push_str(line);
+        }
+    }
+
+    out
+}
+
+/// Build the thread context for an evaluation
+///
+/// Returns `None` when GitHub cannot provide the thread; the caller keeps
+/// the context captured from the webhook instead.
+pub async fn build_thread_context(
+    state: &AppState,
+    repo_owner: &str,
+    repo_name: &str,
+    number: i64,
+    source: &ThreadSource,
+) -> Option<String> {
+    let config = &state.thread_context_config;
+    match fetch_thread(
+        &state.github_client,
+        repo_owner,
+        repo_name,
+        number,
+        source,
+        config.max_comments,
+    )
+    .await
+    {
+        Ok(thread) => Some(render_thread(&thread, config.max_chars)),
+        Err(e) => {
+            warn!(
+                "Failed to fetch thread of #{} in {}/{}: {}",
+                number, repo_owner, repo_name, e
+            );
+            None
+        }
+    }
+}
+
+fn char_len(text: &str) -> usize {
+    text.chars().count()
+}
+
+/// Keep the start of `text`, at most `max` characters
+fn truncate_end(text: &str, max: usize) -> String {
+    if char_len(text) <= max {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(max.saturating_sub(1)).collect();
+    format!("{}{}", kept, ELLIPSIS)
+}
+
+/// Keep the end of `text`, at most `max` characters, starting at a line
+fn truncate_start(text: &str, max: usize) -> String {
+    let length = char_len(text);
+    if length <= max {
+        return text.to_string();
+    }
+    let tail: String = text.chars().skip(length - max.saturating_sub(2)).collect();
+    let tail = match tail.split_once('\n') {
+        Some((_, whole_lines)) if !whole_lines.is_empty() => whole_lines,
+        _ => &tail,
+    };
+    format!("{}\n{}", ELLIPSIS, tail)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(author: &str, body: &str) -> ThreadComment {
+        ThreadComment {
+            author: author.to_string(),
+            body: body.to_string(),
+        }
+    }
+
+    fn thread() -> Thread {
+        Thread {
+            number: 7,
+            is_pull_request: true,
+            title: "Fix panic on empty config".to_string(),
+            author: "alice".to_string(),
+            description: "Loading an empty file panics.".to_string(),
+            ..Thread::default()
+        }
+    }
+
+    #[test]
+    fn test_render_small_thread() {
+        let thread = Thread {
+            comments: vec![
+                comment("bob", "Can you add a test?"),
+                comment("alice", "Done."),
+            ],
+            ..thread()
+        };
+
+        assert_eq!(
+            render_thread(&thread, 4000),
+            "PR #7 by @alice: Fix panic on empty config\n\
+             \nDescription:\nLoading an empty file panics.\n\
+             \nPrevious comments:\n@bob: Can you add a test?\n@alice: Done.\n"
+        );
+    }
+
+    #[test]
+    fn test_render_keeps_newest_comments_within_budget() {
+        let thread = Thread {
+            description: "x".repeat(5000),
+            comments: (0..20)
+                .map(|i| comment("user", &format!("comment {} {}", i, "y".repeat(150))))
+                .collect(),
+            ..thread()
+        };
+
+        let rendered = render_thread(&thread, 1000);
+        assert!(char_len(&rendered) <= 1000);
+        assert!(rendered.contains("Description:\nxxx"));
+        assert!(rendered.contains(&format!("x{}\n", ELLIPSIS)));
+        assert!(rendered.contains("comment 19 "));
+        assert!(!rendered.contains("comment 0 "));
+        assert!(rendered.contains("earlier not shown):\n"));
+    }
+
+    #[test]
+    fn test_render_review_hunks_keep_commented_lines() {
+        let diff_hunk = format!(
+            "@@ -1,40 +1,41 @@\n{}+    let config = load()?;",
+            " unchanged line\n".repeat(40)
+        );
+        let thread = Thread {
+            review_state: Some("changes_requested".to_string()),
+            hunks: vec![ThreadHunk {
+                path: "src/config.rs".to_string(),
+                diff_hunk,
+                comment: "This still panics when the file is missing.".to_string(),
+            }],
+            ..thread()
+        };
+
+        let rendered = render_thread(&thread, 900);
+        assert!(char_len(&rendered) <= 900);
+        assert!(rendered.contains("Review state: changes_requested\n"));
+        assert!(rendered.contains("Inline comment on src/config.rs:\n…\n"));
+        assert!(rendered.contains("+    let config = load()?;\n> This still panics"));
+        assert!(!rendered.contains("@@ -1,40"));
+    }
+
+    #[test]
+    fn test_truncate_is_char_safe() {
+        assert_eq!(truncate_end("héllo wörld", 5), "héll…");
+        assert_eq!(truncate_start("ab\ncd\nef", 6), "…\nef");
+    }
+}
@@ -0,0 +1,127 @@
+//! Resolve `@username` references to contributors
+//!
+//! Logins are recorded on `contributors` from webhook payloads. Users we have
+//! not seen yet (or who renamed since we last saw them) are looked up through
+//! the GitHub users API.
+
+use crate::{error::ApiResult, state::AppState};
+use meritocrab_db::{
+    Contributor,
+    contributors::{
+        find_user_id_by_login, get_contributor, lookup_or_create_contributor, update_login,
+    },
+};
+
+/// GitHub identity behind an `@username` reference
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedUser {
+    pub github_user_id: i64,
+    pub login: String,
+}
+
+/// Resolve an `@username` (or a numeric GitHub user ID) to a GitHub identity
+///
+/// Known logins are resolved from the database; anything else falls back to
+/// the GitHub users API. Logins may be all digits, so a numeric reference is
+/// only taken as a user ID when no user has it as their login. Returns `None`
+/// if no such user exists.
+pub async fn resolve_user(state: &AppState, reference: &str) -> ApiResult<Option<ResolvedUser>> {
+    let login = reference.trim().trim_start_matches('@');
+    if login.is_empty() {
+        return Ok(None);
+    }
+
+    if let Some(github_user_id) = find_user_id_by_login(&state.db_pool, login).await? {
+        return Ok(Some(ResolvedUser {
+            github_user_id,
+            login: login.to_string(),
+        }));
+    }
+
+    match state.github_client.get_user(login).await? {
+        Some(user) => {
+            // Catch renames of users we already track under an old login
+            update_login(&state.db_pool, user.id, &user.login).await?;
+            Ok(Some(ResolvedUser {
+                github_user_id: user.id,
+                login: user.login,
+            }))
+        }
+        // Numeric references are GitHub user IDs (kept for existing scripts)
+        None => Ok(login
+            .parse::<i64>()
+            .ok()
+            .map(|github_user_id| ResolvedUser {
+                github_user_id,
+                login: login.to_string(),
+            })),
+    }
+}
+
+/// Resolve an `@username` reference to an existing contributor in a repo
+pub async fn resolve_contributor(
+    state: &AppState,
+    reference: &str,
+    repo_owner: &str,
+    repo_name: &str,
+) -> ApiResult<Option<Contributor>> {
+    let Some(user) = resolve_user(state, reference).await? else {
+        return Ok(None);
+    };
+
+    Ok(get_contributor(&state.db_pool, user.github_user_id, repo_owner, repo_name).await?)
+}
+
+/// Resolve an `@username` reference to a contributor, creating them if needed
+///
+/// Used by maintainer actions that may target someone who has not contributed
+/// to the repository yet.
+pub async fn resolve_or_create_contributor(
+    state: &AppState,
+    reference: &str,
+    repo_owner: &str,
+    repo_name: &str,
+    starting_credit: i32,
+) -> ApiResult<Option<Contributor>> {
+    let Some(user) = resolve_user(state, reference).await? else {
+        return Ok(None);
+    };
+
+    let contributor = track_contributor(
+        state,
+        user.github_user_id,
+        &user.login,
+        repo_owner,
+        repo_name,
+        starting_credit,
+    )
+    .await?;
+
+    Ok(Some(contributor))
+}
+
+/// Look up or create a contributor and record their current login
+pub(crate) async fn track_contributor(
+    state: &AppState,
+    github_user_id: i64,
+    login: &str,
+    repo_owner: &str,
+    repo_name: &str,
+    starting_credit: i32,
+) -> ApiResult<Contributor> {
+    let mut contributor = lookup_or_create_contributor(
+        &state.db_pool,
+        github_user_id,
+        repo_owner,
+        repo_name,
+        starting_credit,
+    )
+    .await?;
+
+    if contributor.login.as_deref() != Some(login) {
+        update_login(&state.db_pool, github_user_id, login).await?;
+        contributor.login = Some(login.to_string());
+    }
+
+    Ok(contributor)
+}
@@ -1,34 +1,151 @@
-use crate::{error::ApiResult, extractors::VerifiedWebhookPayload, state::AppState};
-use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use crate::{
+    credit_commands::{
+        CommandError, CreditCommand, HELP_TEXT, LEADERBOARD_SIZE, format_command_error,
+        format_delta, format_event_list, format_history, format_leaderboard, format_self_report,
+        parse_credit_commands,
+    },
+    error::ApiResult,
+    extractors::VerifiedWebhookPayload,
+    jobs::{ClosePrJob, EvaluationJob, JobPayload, enqueue, enqueue_once},
+    notifications::{
+        Notification, NotificationEvent, notify, notify_credit_applied, notify_pending_evaluations,
+    },
+    state::AppState,
+    thread_context::{ThreadSource, build_thread_context},
+    user_resolver::{resolve_contributor, resolve_or_create_contributor, track_contributor},
+};
+use axum::{
+    Json,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+};
+use chrono::Utc;
 use meritocrab_core::{
-    EventType, GateResult, apply_credit, calculate_delta_with_config, check_blacklist,
-    check_pr_gate,
+    EarningLimits, EvaluationState, EvaluationStatus, EventType, GateResult, QualityLevel,
+    RecentCredit, RepoConfig, apply_earning_limits, calculate_delta_with_config, check_blacklist,
+    check_pr_gate_with_tiers, limits::EARNING_WINDOW_HOURS,
 };
 use meritocrab_db::{
-    contributors::{lookup_or_create_contributor, set_blacklisted, update_credit_score},
-    credit_events::insert_credit_event,
-    evaluations::insert_evaluation,
+    DbError,
+    contributors::{get_contributor, list_contributors_by_repo, update_login},
+    credit_events::{
+        NewCreditEvent, apply_credit_delta, get_pr_credit_event, is_pr_exempt,
+        list_events_by_source, list_events_with_origin_since, set_blacklisted_with_event,
+        set_credit_score,
+    },
+    evaluations::{
+        count_evaluations_by_source, get_evaluation_by_pr, insert_evaluation_with_source,
+    },
+    models::Contributor,
+    repo_installations::record_repo_installation,
+    webhook_deliveries::{delete_delivery, record_delivery},
+};
+use meritocrab_github::{
+    IssueCommentEvent, IssuesEvent, PullRequestEvent, PullRequestReviewCommentEvent,
+    PullRequestReviewEvent, PushEvent, User,
+};
+use meritocrab_llm::{
+    ContentType, DEFAULT_DIFF_TOKEN_BUDGET, DiffStats, EvalContext, FileDiff, budget_diff,
+    parse_unified_diff,
 };
-use meritocrab_github::{IssueCommentEvent, PullRequestEvent, PullRequestReviewEvent};
-use meritocrab_llm::{ContentType, EvalContext};
 use rand::Rng;
 use serde_json::Value;
-use std::time::Duration;
-use tracing::{error, info, warn};
+use tracing::{info, warn};
 
 /// Webhook handler for GitHub events
 ///
 /// This handler:
 /// 1. Verifies HMAC signature (handled by VerifiedWebhook extractor)
 /// 2. Parses the event payload
-/// 3. Processes pull_request, issue_comment, and pull_request_review events
-/// 4. Returns 200 OK immediately (async LLM processing happens in background)
+/// 3. Skips deliveries already processed (keyed on `X-GitHub-Delivery`)
+/// 4. Processes pull_request, issues, issue_comment, pull_request_review,
+///    pull_request_review_comment, and push events, including edits and
+///    deletions of scored content
+/// 5. Returns 200 OK immediately (async LLM processing happens in background)
 pub async fn handle_webhook(
     State(state): State<AppState>,
+    headers: HeaderMap,
     VerifiedWebhookPayload(body): VerifiedWebhookPayload,
 ) -> ApiResult<impl IntoResponse> {
+    let delivery_id = headers
+        .get("X-GitHub-Delivery")
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.to_string());
+    let event_name = headers.get("X-GitHub-Event").and_then(|v| v.to_str().ok());
+
+    // Short-circuit redeliveries before any credit or LLM work happens
+    if let Some(delivery_id) = &delivery_id {
+        if !record_delivery(&state.db_pool, delivery_id, event_name).await? {
+            info!("Duplicate webhook delivery {}, ignoring", delivery_id);
+            return Ok((
+                StatusCode::OK,
+                Json(serde_json::json!({
+                    "status": "duplicate",
+                    "message": "Delivery already processed"
+                })),
+            ));
+        }
+    }
+
+    let db_pool = state.db_pool.clone();
+    let result = dispatch_webhook(state, &body).await;
+
+    // Forget the delivery if processing failed so a redelivery can retry it
+    if result.is_err() {
+        if let Some(delivery_id) = &delivery_id {
+            if let Err(e) = delete_delivery(&db_pool, delivery_id).await {
+                warn!(
+                    "Failed to clear failed webhook delivery {}: {}",
+                    delivery_id, e
+                );
+            }
+        }
+    }
+
+    result
+}
+
+/// Route a verified webhook payload to the matching event processor
+async fn dispatch_webhook(state: AppState, body: &[u8]) -> ApiResult<(StatusCode, Json<Value>)> {
     // Parse the event payload
-    let payload: Value = serde_json::from_slice(&body)?;
+    let payload: Value = serde_json::from_slice(body)?;
+
+    // Act as the GitHub App installation that delivered the event
+    let installation_id = payload.pointer("/installation/id").and_then(|v| v.as_i64());
+    let state = state.for_installation(installation_id).await?;
+
+    // Remember the installation so background tasks like decay can act as it
+    if let (Some(installation_id), Some(repo_owner), Some(repo_name)) = (
+        installation_id,
+        payload
+            .pointer("/repository/owner/login")
+            .and_then(|v| v.as_str()),
+        payload.pointer("/repository/name").and_then(|v| v.as_str()),
+    ) {
+        record_repo_installation(&state.db_pool, repo_owner, repo_name, installation_id).await?;
+    }
+
+    // Keep stored logins current (GitHub users can rename their accounts)
+    if let (Some(sender_id), Some(sender_login)) = (
+        payload.pointer("/sender/id").and_then(|v| v.as_i64()),
+        payload.pointer("/sender/login").and_then(|v| v.as_str()),
+    ) {
+        update_login(&state.db_pool, sender_id, sender_login).await?;
+    }
+
+    // Handle push events (no action field; identified by ref + commits)
+    if payload.get("ref").is_some() && payload.get("commits").is_some() {
+        let event: PushEvent = serde_json::from_slice(body)?;
+        process_push(state, event).await;
+        return Ok((
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "status": "ok",
+                "message": "Push processed successfully"
+            })),
+        ));
+    }
 
     // Check event type and action
     if let Some(action) = payload.get("action").and_then(|v| v.as_str()) {
@@ -38,7 +155,7 @@ pub async fn handle_webhook(
             if let Some(_review) = payload.get("review") {
                 // This is a pull_request_review event
                 if action == "submitted" {
-                    let event: PullRequestReviewEvent = serde_json::from_slice(&body)?;
+                    let event: PullRequestReviewEvent = serde_json::from_slice(body)?;
                     process_pr_review_submitted(state, event).await?;
                     return Ok((
                         StatusCode::OK,
@@ -47,10 +164,53 @@ pub async fn handle_webhook(
                             "message": "Review processed successfully"
                         })),
                     ));
+                } else if action == "edited" {
+                    let event: PullRequestReviewEvent = serde_json::from_slice(body)?;
+                    process_pr_review_edited(state, event).await?;
+                    return Ok((
+                        StatusCode::OK,
+                        Json(serde_json::json!({
+                            "status": "ok",
+                            "message": "Review edit processed successfully"
+                        })),
+                    ));
+                }
+            } else if let Some(_comment) = payload.get("comment") {
+                // This is a pull_request_review_comment event (inline diff comment)
+                if action == "created" {
+                    let event: PullRequestReviewCommentEvent = serde_json::from_slice(body)?;
+                    process_pr_review_comment_created(state, event).await?;
+                    return Ok((
+                        StatusCode::OK,
+                        Json(serde_json::json!({
+                            "status": "ok",
+                            "message": "Review comment processed successfully"
+                        })),
+                    ));
+                } else if action == "edited" {
+                    let event: PullRequestReviewCommentEvent = serde_json::from_slice(body)?;
+                    process_pr_review_comment_edited(state, event).await?;
+                    return Ok((
+                        StatusCode::OK,
+                        Json(serde_json::json!({
+                            "status": "ok",
+                            "message": "Review comment edit processed successfully"
+                        })),
+                    ));
+                } else if action == "deleted" {
+                    let event: PullRequestReviewCommentEvent = serde_json::from_slice(body)?;
+                    process_pr_review_comment_deleted(state, event).await?;
+                    return Ok((
+                        StatusCode::OK,
+                        Json(serde_json::json!({
+                            "status": "ok",
+                            "message": "Review comment deletion processed successfully"
+                        })),
+                    ));
                 }
             } else if action == "opened" {
                 // This is a pull_request.opened event
-                let event: PullRequestEvent = serde_json::from_slice(&body)?;
+                let event: PullRequestEvent = serde_json::from_slice(body)?;
                 process_pr_opened(state, event).await?;
                 return Ok((
                     StatusCode::OK,
@@ -59,26 +219,98 @@ pub async fn handle_webhook(
                         "message": "PR processed successfully"
                     })),
                 ));
+            } else if action == "edited" {
+                // This is a pull_request.edited event (title, body or base)
+                let event: PullRequestEvent = serde_json::from_slice(body)?;
+                process_pr_edited(state, event).await?;
+                return Ok((
+                    StatusCode::OK,
+                    Json(serde_json::json!({
+                        "status": "ok",
+                        "message": "PR edit processed successfully"
+                    })),
+                ));
+            } else if action == "closed" {
+                // This is a pull_request.closed event; only merges earn credit
+                let event: PullRequestEvent = serde_json::from_slice(body)?;
+                if event.pull_request.merged == Some(true) {
+                    process_pr_merged(state, event).await?;
+                    return Ok((
+                        StatusCode::OK,
+                        Json(serde_json::json!({
+                            "status": "ok",
+                            "message": "Merged PR processed successfully"
+                        })),
+                    ));
+                }
             }
         }
 
-        // Handle issue_comment events
+        // Handle issue_comment events (on issues and pull requests)
         if let Some(_issue) = payload.get("issue") {
             if let Some(_comment) = payload.get("comment") {
                 if action == "created" {
-                    let event: IssueCommentEvent = serde_json::from_slice(&body)?;
-                    // Only process comments on pull requests
-                    if event.issue.pull_request.is_some() {
-                        process_comment_created(state, event).await?;
-                        return Ok((
-                            StatusCode::OK,
-                            Json(serde_json::json!({
-                                "status": "ok",
-                                "message": "Comment processed successfully"
-                            })),
-                        ));
-                    }
+                    let event: IssueCommentEvent = serde_json::from_slice(body)?;
+                    process_comment_created(state, event).await?;
+                    return Ok((
+                        StatusCode::OK,
+                        Json(serde_json::json!({
+                            "status": "ok",
+                            "message": "Comment processed successfully"
+                        })),
+                    ));
+                } else if action == "edited" {
+                    let event: IssueCommentEvent = serde_json::from_slice(body)?;
+                    process_comment_edited(state, event).await?;
+                    return Ok((
+                        StatusCode::OK,
+                        Json(serde_json::json!({
+                            "status": "ok",
+                            "message": "Comment edit processed successfully"
+                        })),
+                    ));
+                } else if action == "deleted" {
+                    let event: IssueCommentEvent = serde_json::from_slice(body)?;
+                    process_comment_deleted(state, event).await?;
+                    return Ok((
+                        StatusCode::OK,
+                        Json(serde_json::json!({
+                            "status": "ok",
+                            "message": "Comment deletion processed successfully"
+                        })),
+                    ));
                 }
+            } else if action == "opened" {
+                // This is an issues.opened event
+                let event: IssuesEvent = serde_json::from_slice(body)?;
+                process_issue_opened(state, event).await?;
+                return Ok((
+                    StatusCode::OK,
+                    Json(serde_json::json!({
+                        "status": "ok",
+                        "message": "Issue processed successfully"
+                    })),
+                ));
+            } else if action == "edited" {
+                let event: IssuesEvent = serde_json::from_slice(body)?;
+                process_issue_edited(state, event).await?;
+                return Ok((
+                    StatusCode::OK,
+                    Json(serde_json::json!({
+                        "status": "ok",
+                        "message": "Issue edit processed successfully"
+                    })),
+                ));
+            } else if action == "deleted" {
+                let event: IssuesEvent = serde_json::from_slice(body)?;
+                process_issue_deleted(state, event).await?;
+                return Ok((
+                    StatusCode::OK,
+                    Json(serde_json::json!({
+                        "status": "ok",
+                        "message": "Issue deletion processed successfully"
+                    })),
+                ));
             }
         }
     }
@@ -94,6 +326,26 @@ pub async fn handle_webhook(
     ))
 }
 
+/// Process a push event
+///
+/// Invalidates the cached `.meritocrab.toml` for the repository when a push to
+/// the default branch touches the config file, so the next event picks up the edit.
+async fn process_push(state: AppState, event: PushEvent) {
+    let repo_owner = &event.repository.owner.login;
+    let repo_name = &event.repository.name;
+
+    if event.is_default_branch() && event.touches(".meritocrab.toml") {
+        info!(
+            "Push to {} in {}/{} modified .meritocrab.toml, invalidating config cache",
+            event.git_ref, repo_owner, repo_name
+        );
+        state
+            .repo_config_loader
+            .invalidate_cache(repo_owner, repo_name)
+            .await;
+    }
+}
+
 /// Process a PR opened event
 async fn process_pr_opened(state: AppState, event: PullRequestEvent) -> ApiResult<()> {
     let user_id = event.pull_request.user.id;
@@ -133,13 +385,20 @@ async fn process_pr_opened(state: AppState, event: PullRequestEvent) -> ApiResul
         }
     }
 
+    // Resolve effective config for this repository
+    let repo_config = state
+        .repo_config_loader
+        .get_config(&state.github_client, repo_owner, repo_name)
+        .await;
+
     // Step 2: Lookup or create contributor
-    let contributor = lookup_or_create_contributor(
-        &state.db_pool,
+    let contributor = track_contributor(
+        &state,
         user_id,
+        username,
         repo_owner,
         repo_name,
-        state.repo_config.starting_credit,
+        repo_config.starting_credit,
     )
     .await?;
 
@@ -148,12 +407,13 @@ async fn process_pr_opened(state: AppState, event: PullRequestEvent) -> ApiResul
         username, contributor.credit_score
     );
 
+    // A maintainer let this PR through with /credit approve
+    let exempt = is_pr_exempt(&state.db_pool, repo_owner, repo_name, pr_number as i64).await?;
+
     // Step 3: Check if contributor is blacklisted (or check is_blacklisted field)
-    if contributor.is_blacklisted
-        || check_blacklist(
-            contributor.credit_score,
-            state.repo_config.blacklist_threshold,
-        )
+    if !exempt
+        && (contributor.is_blacklisted
+            || check_blacklist(contributor.credit_score, repo_config.blacklist_threshold))
     {
         warn!(
             "Contributor {} is blacklisted (credit: {}, is_blacklisted: {}), scheduling delayed PR close for #{}",
@@ -162,43 +422,114 @@ async fn process_pr_opened(state: AppState, event: PullRequestEvent) -> ApiResul
 
         // Shadow blacklist: schedule delayed PR close with randomized delay (30-120 seconds)
         schedule_delayed_pr_close(
-            state.clone(),
+            &state,
             repo_owner.to_string(),
             repo_name.to_string(),
             pr_number,
             username.to_string(),
-        );
+        )
+        .await?;
 
         // Return 200 OK immediately (delay happens in background)
         return Ok(());
     }
 
-    // Step 4: Check PR gate (credit threshold)
-    let gate_result = check_pr_gate(contributor.credit_score, state.repo_config.pr_threshold);
+    // Step 4: Check PR gate (credit threshold and graduated tiers below it)
+    let gate_result = if exempt {
+        GateResult::Allow
+    } else {
+        check_pr_gate_with_tiers(
+            contributor.credit_score,
+            repo_config.pr_threshold,
+            &repo_config.gate,
+        )
+    };
 
     match gate_result {
         GateResult::Allow => {
             info!(
                 "PR #{} allowed (credit: {} >= threshold: {}), spawning LLM evaluation",
-                pr_number, contributor.credit_score, state.repo_config.pr_threshold
+                pr_number, contributor.credit_score, repo_config.pr_threshold
+            );
+        }
+        GateResult::AllowWithLabel => {
+            info!(
+                "PR #{} allowed with label {:?} (credit: {} < threshold: {})",
+                pr_number,
+                repo_config.gate.label,
+                contributor.credit_score,
+                repo_config.pr_threshold
             );
 
-            // Step 5: Spawn async LLM evaluation
-            spawn_pr_evaluation(
-                state.clone(),
-                contributor.id,
-                user_id,
-                username.to_string(),
-                repo_owner.to_string(),
-                repo_name.to_string(),
-                event.pull_request.title,
-                event.pull_request.body.unwrap_or_default(),
+            // A missing label must not cost the PR its evaluation
+            if let Err(e) = state
+                .github_client
+                .add_labels(
+                    repo_owner,
+                    repo_name,
+                    pr_number,
+                    std::slice::from_ref(&repo_config.gate.label),
+                )
+                .await
+            {
+                warn!("Failed to label PR #{}: {}", pr_number, e);
+            }
+        }
+        GateResult::Draft => {
+            info!(
+                "PR #{} converted to draft (credit: {} < threshold: {})",
+                pr_number, contributor.credit_score, repo_config.pr_threshold
+            );
+
+            state
+                .github_client
+                .add_comment(
+                    repo_owner,
+                    repo_name,
+                    pr_number,
+                    &format!(
+                        "Your contribution score ({}) is below the required threshold ({}), so this PR has been converted to a draft. A maintainer can mark it ready for review.",
+                        contributor.credit_score, repo_config.pr_threshold
+                    ),
+                )
+                .await?;
+            // Retrying the webhook would only post the comment again
+            if let Err(e) = state
+                .github_client
+                .convert_to_draft(repo_owner, repo_name, pr_number)
+                .await
+            {
+                warn!("Failed to convert PR #{} to draft: {}", pr_number, e);
+            }
+
+            // Gated PRs are not scored, so they cannot earn pr_opened credit
+            return Ok(());
+        }
+        GateResult::RequireApproval => {
+            info!(
+                "PR #{} awaiting maintainer approval (credit: {} < threshold: {})",
+                pr_number, contributor.credit_score, repo_config.pr_threshold
             );
+
+            state
+                .github_client
+                .add_comment(
+                    repo_owner,
+                    repo_name,
+                    pr_number,
+                    &format!(
+                        "Your contribution score ({}) is below the required threshold ({}). A maintainer needs to approve this PR with `/credit approve` before it is reviewed.",
+                        contributor.credit_score, repo_config.pr_threshold
+                    ),
+                )
+                .await?;
+
+            return Ok(());
         }
         GateResult::Deny => {
             warn!(
                 "PR #{} denied (credit: {} < threshold: {}), closing",
-                pr_number, contributor.credit_score, state.repo_config.pr_threshold
+                pr_number, contributor.credit_score, repo_config.pr_threshold
             );
 
             close_pr_with_message(
@@ -208,97 +539,124 @@ async fn process_pr_opened(state: AppState, event: PullRequestEvent) -> ApiResul
                 pr_number,
                 &format!(
                     "Your contribution score ({}) is below the required threshold ({}). Please build your score through quality comments and reviews.",
-                    contributor.credit_score, state.repo_config.pr_threshold
+                    contributor.credit_score, repo_config.pr_threshold
                 ),
             )
             .await?;
+
+            return Ok(());
         }
     }
 
-    Ok(())
-}
-
-/// Helper to close PR and add comment
-async fn close_pr_with_message(
-    state: &AppState,
-    repo_owner: &str,
-    repo_name: &str,
-    pr_number: u64,
-    message: &str,
-) -> ApiResult<()> {
-    // Add comment first
-    state
-        .github_client
-        .add_comment(repo_owner, repo_name, pr_number, message)
-        .await?;
-
-    // Then close the PR
-    state
-        .github_client
-        .close_pull_request(repo_owner, repo_name, pr_number)
-        .await?;
+    // Step 5: Enqueue async LLM evaluation for PRs the gate let through
+    enqueue(
+        &state,
+        JobPayload::Evaluate(EvaluationJob {
+            contributor_id: contributor.id,
+            user_id,
+            username: username.to_string(),
+            repo_owner: repo_owner.to_string(),
+            repo_name: repo_name.to_string(),
+            event_type: EventType::PrOpened,
+            content_type: ContentType::PullRequest,
+            pr_number: Some(pr_number as i64),
+            title: Some(event.pull_request.title),
+            body: event.pull_request.body.unwrap_or_default(),
+            diff_summary: None,
+            thread_context: None,
+            thread: None,
+            installation_id: state.installation_id,
+            source_id: Some(format!("pull_request:{}", pr_number)),
+            revision: false,
+        }),
+        Utc::now(),
+    )
+    .await?;
 
-    info!("Closed PR #{} with message", pr_number);
     Ok(())
 }
 
-/// Schedule delayed PR close for shadow blacklist
+/// Process a pull request edited event
 ///
-/// This spawns a background task that waits a randomized delay (30-120 seconds)
-/// before closing the PR with a generic message. This makes the blacklist less
-/// obvious to bad actors.
-fn schedule_delayed_pr_close(
-    state: AppState,
-    repo_owner: String,
-    repo_name: String,
-    pr_number: u64,
-    username: String,
-) {
-    tokio::spawn(async move {
-        // Generate random delay between 30 and 120 seconds
-        let delay_secs = rand::rng().random_range(30..=120);
-        let delay = Duration::from_secs(delay_secs);
+/// A PR that earned credit and is edited after the edit grace window is
+/// re-evaluated, and only the difference to its earlier credit is applied.
+async fn process_pr_edited(state: AppState, event: PullRequestEvent) -> ApiResult<()> {
+    let user_id = event.pull_request.user.id;
+    let username = &event.pull_request.user.login;
+    let repo_owner = &event.repository.owner.login;
+    let repo_name = &event.repository.name;
+    let pr_number = event.pull_request.number;
+    let source_id = format!("pull_request:{}", pr_number);
+
+    // Resolve effective config for this repository
+    let repo_config = state
+        .repo_config_loader
+        .get_config(&state.github_client, repo_owner, repo_name)
+        .await;
 
+    if within_edit_grace(
+        event.pull_request.created_at,
+        event.pull_request.updated_at,
+        repo_config.edit_grace_seconds,
+    ) {
         info!(
-            "Scheduled PR #{} close for blacklisted user {} with delay of {} seconds",
-            pr_number, username, delay_secs
+            "PR #{} by {} edited within the grace window, keeping its credit",
+            pr_number, username
         );
+        return Ok(());
+    }
 
-        // Wait for the randomized delay
-        tokio::time::sleep(delay).await;
+    let Some(contributor) =
+        earned_credit_holder(&state, user_id, repo_owner, repo_name, &source_id).await?
+    else {
+        return Ok(());
+    };
 
-        // Close PR with generic message (no mention of blacklist/credit/spam)
-        let generic_message = "Thank you for your contribution. Unfortunately, we are unable to accept this pull request at this time.";
+    info!(
+        "Re-evaluating edited PR #{} by {} in {}/{}",
+        pr_number, username, repo_owner, repo_name
+    );
 
-        if let Err(e) =
-            close_pr_with_message(&state, &repo_owner, &repo_name, pr_number, generic_message).await
-        {
-            error!(
-                "Failed to close blacklisted PR #{} for {}: {}",
-                pr_number, username, e
-            );
-        } else {
-            info!(
-                "Successfully closed blacklisted PR #{} for {} after {} second delay",
-                pr_number, username, delay_secs
-            );
-        }
-    });
+    enqueue(
+        &state,
+        JobPayload::Evaluate(EvaluationJob {
+            contributor_id: contributor.id,
+            user_id,
+            username: username.to_string(),
+            repo_owner: repo_owner.to_string(),
+            repo_name: repo_name.to_string(),
+            event_type: EventType::PrOpened,
+            content_type: ContentType::PullRequest,
+            pr_number: Some(pr_number),
+            title: Some(event.pull_request.title),
+            body: event.pull_request.body.unwrap_or_default(),
+            diff_summary: None,
+            thread_context: None,
+            thread: None,
+            installation_id: state.installation_id,
+            source_id: Some(source_id),
+            revision: true,
+        }),
+        Utc::now(),
+    )
+    .await
 }
 
-/// Process a pull request review submitted event
-async fn process_pr_review_submitted(
-    state: AppState,
-    event: PullRequestReviewEvent,
-) -> ApiResult<()> {
-    let user_id = event.review.user.id;
-    let username = &event.review.user.login;
+/// Process a pull request closed event where the PR was merged
+///
+/// Looks up the quality level of the original `PrOpened` evaluation for this PR
+/// and applies the `pr_merged` delta for that level. The `pr_merged` event is
+/// unique per contributor and PR, so redelivered webhooks cannot pay out twice.
+async fn process_pr_merged(state: AppState, event: PullRequestEvent) -> ApiResult<()> {
+    let user_id = event.pull_request.user.id;
+    let username = &event.pull_request.user.login;
     let repo_owner = &event.repository.owner.login;
     let repo_name = &event.repository.name;
+    let pr_number = event.pull_request.number;
 
     info!(
-        "Processing review submitted by {} in {}/{}",
-        username, repo_owner, repo_name
+        "Processing merged PR #{} by {} in {}/{}",
+        pr_number, username, repo_owner, repo_name
     );
 
     // Check if user is a maintainer/collaborator (skip credit for privileged roles)
@@ -309,7 +667,7 @@ async fn process_pr_review_submitted(
     {
         Ok(role) if role.is_maintainer() || role.has_write_access() => {
             info!(
-                "User {} has privileged role {:?}, skipping credit for review",
+                "User {} has privileged role {:?}, skipping credit for merged PR",
                 username, role
             );
             return Ok(());
@@ -325,94 +683,432 @@ async fn process_pr_review_submitted(
         }
     }
 
+    // Resolve effective config for this repository
+    let repo_config = state
+        .repo_config_loader
+        .get_config(&state.github_client, repo_owner, repo_name)
+        .await;
+
     // Lookup or create contributor
-    let contributor = lookup_or_create_contributor(
-        &state.db_pool,
+    let contributor = track_contributor(
+        &state,
         user_id,
+        username,
         repo_owner,
         repo_name,
-        state.repo_config.starting_credit,
+        repo_config.starting_credit,
     )
     .await?;
 
     // Check if blacklisted (skip credit for blacklisted users)
-    if check_blacklist(
-        contributor.credit_score,
-        state.repo_config.blacklist_threshold,
-    ) {
+    if contributor.is_blacklisted
+        || check_blacklist(contributor.credit_score, repo_config.blacklist_threshold)
+    {
         info!(
-            "Contributor {} is blacklisted, skipping credit for review",
+            "Contributor {} is blacklisted, skipping credit for merged PR",
             username
         );
         return Ok(());
     }
 
-    // Reviews always grant +5 credit (no LLM evaluation needed)
-    let delta = 5;
-    let credit_before = contributor.credit_score;
-    let credit_after = apply_credit(credit_before, delta);
-
-    // Update contributor credit
-    update_credit_score(&state.db_pool, contributor.id, credit_after).await?;
+    // Skip if this merge has already been credited (webhook redelivery)
+    if get_pr_credit_event(&state.db_pool, contributor.id, "pr_merged", pr_number)
+        .await?
+        .is_some()
+    {
+        info!(
+            "Merged PR #{} in {}/{} already credited, ignoring",
+            pr_number, repo_owner, repo_name
+        );
+        return Ok(());
+    }
 
-    // Log credit event
-    insert_credit_event(
-        &state.db_pool,
+    let quality = lookup_pr_quality(&state, contributor.id, pr_number).await?;
+    let (delta, credit_event) = limit_earned_credit(
+        &state,
+        &repo_config,
         contributor.id,
-        "review_submitted",
-        delta,
-        credit_before,
-        credit_after,
-        None, // No LLM evaluation for reviews
-        None,
+        EventType::PrMerged,
+        calculate_delta_with_config(&repo_config, EventType::PrMerged, quality),
+        NewCreditEvent::new("pr_merged").with_pr_number(Some(pr_number)),
     )
     .await?;
 
+    // The unique index rejects concurrent duplicates without applying any credit
+    let applied =
+        match apply_credit_delta(&state.db_pool, contributor.id, delta, credit_event, None).await {
+            Ok(applied) => applied,
+            Err(DbError::DuplicateCreditEvent(..)) => {
+                info!(
+                    "Merged PR #{} in {}/{} already credited, ignoring",
+                    pr_number, repo_owner, repo_name
+                );
+                return Ok(());
+            }
+            Err(e) => return Err(e.into()),
+        };
+    let credit_after = applied.event.credit_after;
+
     info!(
-        "Granted +{} credit to {} for review (new score: {})",
-        delta, username, credit_after
+        "Applied {} credit to {} for merged PR #{} ({:?}, new score: {})",
+        delta, username, pr_number, quality, credit_after
     );
 
-    // Note: Reviews always have positive delta, so no auto-blacklist check needed
-
     Ok(())
 }
 
-/// Process an issue comment created event
-async fn process_comment_created(state: AppState, event: IssueCommentEvent) -> ApiResult<()> {
-    let user_id = event.comment.user.id;
-    let username = &event.comment.user.login;
-    let repo_owner = &event.repository.owner.login;
-    let repo_name = &event.repository.name;
-    let comment_body = &event.comment.body;
-    let issue_number = event.issue.number;
+/// Look up the quality level assigned to a PR when it was opened
+///
+/// Prefers the auto-applied `pr_opened` credit event, then any evaluation
+/// queued for maintainer review. Falls back to `Acceptable` when the PR was
+/// never evaluated, since a maintainer chose to merge it.
+async fn lookup_pr_quality(
+    state: &AppState,
+    contributor_id: i64,
+    pr_number: i64,
+) -> ApiResult<QualityLevel> {
+    if let Some(event) =
+        get_pr_credit_event(&state.db_pool, contributor_id, "pr_opened", pr_number).await?
+    {
+        let classification = event
+            .llm_evaluation
+            .as_deref()
+            .and_then(|json| serde_json::from_str::<Value>(json).ok())
+            .and_then(|v| v.get("classification").cloned())
+            .and_then(|v| serde_json::from_value::<QualityLevel>(v).ok());
+        if let Some(quality) = classification {
+            return Ok(quality);
+        }
+    }
 
-    info!(
-        "Processing comment by {} in {}/{} on PR #{}",
-        username, repo_owner, repo_name, issue_number
-    );
+    if let Some(evaluation) =
+        get_evaluation_by_pr(&state.db_pool, contributor_id, pr_number).await?
+    {
+        let quality = serde_json::from_value::<QualityLevel>(Value::String(
+            evaluation.llm_classification.to_lowercase(),
+        ));
+        if let Ok(quality) = quality {
+            return Ok(quality);
+        }
+    }
+
+    info!(
+        "No opening evaluation found for PR #{}, treating as acceptable",
+        pr_number
+    );
+    Ok(QualityLevel::Acceptable)
+}
+
+/// Helper to close PR and add comment
+pub(crate) async fn close_pr_with_message(
+    state: &AppState,
+    repo_owner: &str,
+    repo_name: &str,
+    pr_number: u64,
+    message: &str,
+) -> ApiResult<()> {
+    // Add comment first
+    state
+        .github_client
+        .add_comment(repo_owner, repo_name, pr_number, message)
+        .await?;
+
+    // Then close the PR
+    state
+        .github_client
+        .close_pull_request(repo_owner, repo_name, pr_number)
+        .await?;
+
+    info!("Closed PR #{} with message", pr_number);
+    Ok(())
+}
+
+/// Schedule delayed PR close for shadow blacklist
+///
+/// This enqueues a job that runs after a randomized delay (30-120 seconds) and
+/// closes the PR with a generic message. This makes the blacklist less obvious
+/// to bad actors.
+async fn schedule_delayed_pr_close(
+    state: &AppState,
+    repo_owner: String,
+    repo_name: String,
+    pr_number: u64,
+    username: String,
+) -> ApiResult<()> {
+    // Generate random delay between 30 and 120 seconds
+    let delay_secs = rand::rng().random_range(30..=120);
+
+    info!(
+        "Scheduled PR #{} close for blacklisted user {} with delay of {} seconds",
+        pr_number, username, delay_secs
+    );
+
+    enqueue(
+        state,
+        JobPayload::ClosePr(ClosePrJob {
+            repo_owner,
+            repo_name,
+            pr_number,
+            username,
+            installation_id: state.installation_id,
+        }),
+        Utc::now() + chrono::Duration::seconds(delay_secs),
+    )
+    .await
+}
+
+/// Process a pull request review submitted event
+async fn process_pr_review_submitted(
+    state: AppState,
+    event: PullRequestReviewEvent,
+) -> ApiResult<()> {
+    let user_id = event.review.user.id;
+    let username = &event.review.user.login;
+    let repo_owner = &event.repository.owner.login;
+    let repo_name = &event.repository.name;
+    let pr_number = event.pull_request.number;
+
+    info!(
+        "Processing review submitted by {} in {}/{}",
+        username, repo_owner, repo_name
+    );
 
-    // STEP 1: Check if comment contains /credit command
-    use crate::credit_commands::parse_credit_command;
-    if let Some(command) = parse_credit_command(comment_body) {
+    // Check if user is a maintainer/collaborator (skip credit for privileged roles)
+    match state
+        .github_client
+        .check_collaborator_role(repo_owner, repo_name, username)
+        .await
+    {
+        Ok(role) if role.is_maintainer() || role.has_write_access() => {
+            info!(
+                "User {} has privileged role {:?}, skipping credit for review",
+                username, role
+            );
+            return Ok(());
+        }
+        Ok(_) => {
+            // User is not privileged, proceed with credit grant
+        }
+        Err(e) => {
+            warn!(
+                "Failed to check collaborator role for {}: {}. Proceeding with credit grant.",
+                username, e
+            );
+        }
+    }
+
+    // Resolve effective config for this repository
+    let repo_config = state
+        .repo_config_loader
+        .get_config(&state.github_client, repo_owner, repo_name)
+        .await;
+
+    // Lookup or create contributor
+    let contributor = track_contributor(
+        &state,
+        user_id,
+        username,
+        repo_owner,
+        repo_name,
+        repo_config.starting_credit,
+    )
+    .await?;
+
+    // Check if blacklisted (skip credit for blacklisted users)
+    if contributor.is_blacklisted
+        || check_blacklist(contributor.credit_score, repo_config.blacklist_threshold)
+    {
         info!(
-            "Detected /credit command from {} in {}/{}: {:?}",
-            username, repo_owner, repo_name, command
+            "Contributor {} is blacklisted, skipping credit for review",
+            username
         );
+        return Ok(());
+    }
 
-        // Process /credit command (requires maintainer role)
-        return process_credit_command(
-            state,
-            repo_owner.to_string(),
-            repo_name.to_string(),
-            username.to_string(),
-            issue_number as u64,
-            command,
-        )
+    let review_body = event.review.body.unwrap_or_default();
+
+    // Optional rule: empty reviews (no body, no inline comments) earn nothing
+    if repo_config.zero_credit_for_empty_reviews && review_body.trim().is_empty() {
+        match state
+            .github_client
+            .count_review_comments(
+                repo_owner,
+                repo_name,
+                pr_number as u64,
+                event.review.id as u64,
+            )
+            .await
+        {
+            Ok(0) => {
+                info!(
+                    "Review by {} on PR #{} has no body or inline comments, granting zero credit",
+                    username, pr_number
+                );
+
+                apply_credit_delta(
+                    &state.db_pool,
+                    contributor.id,
+                    0,
+                    NewCreditEvent::new("review_submitted")
+                        .with_reason("Empty review (no body or inline comments)".to_string())
+                        .with_pr_number(Some(pr_number))
+                        .with_source_id(Some(format!("review:{}", event.review.id))),
+                    None,
+                )
+                .await?;
+
+                return Ok(());
+            }
+            Ok(_) => {
+                // Inline comments carry the review, proceed with evaluation
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to count inline comments for review by {}: {}. Proceeding with evaluation.",
+                    username, e
+                );
+            }
+        }
+    }
+
+    // Enqueue async LLM evaluation with the PR as context
+    let pr_context = format!(
+        "Review state: {}\nPR title: {}\n\n{}",
+        event.review.state,
+        event.pull_request.title,
+        event.pull_request.body.unwrap_or_default()
+    );
+
+    // The review is complete once submitted, so it is evaluated right away
+    enqueue_review_evaluation(
+        &state,
+        EvaluationJob {
+            contributor_id: contributor.id,
+            user_id,
+            username: username.to_string(),
+            repo_owner: repo_owner.to_string(),
+            repo_name: repo_name.to_string(),
+            event_type: EventType::ReviewSubmitted,
+            content_type: ContentType::Review,
+            pr_number: Some(pr_number),
+            title: None,
+            body: review_body,
+            diff_summary: None,
+            thread_context: Some(pr_context),
+            thread: Some(ThreadSource::Review {
+                review_id: event.review.id,
+                state: event.review.state.clone(),
+                submitted_at: event.review.submitted_at,
+            }),
+            installation_id: state.installation_id,
+            source_id: Some(format!("review:{}", event.review.id)),
+            revision: false,
+        },
+        event.review.id,
+        Utc::now(),
+    )
+    .await
+}
+
+/// Process a review edited event
+///
+/// A review whose body changes after the edit grace window is re-evaluated,
+/// and only the difference to the credit it already earned is applied.
+async fn process_pr_review_edited(state: AppState, event: PullRequestReviewEvent) -> ApiResult<()> {
+    let user_id = event.review.user.id;
+    let username = &event.review.user.login;
+    let repo_owner = &event.repository.owner.login;
+    let repo_name = &event.repository.name;
+    let pr_number = event.pull_request.number;
+    let source_id = format!("review:{}", event.review.id);
+
+    // Resolve effective config for this repository
+    let repo_config = state
+        .repo_config_loader
+        .get_config(&state.github_client, repo_owner, repo_name)
         .await;
+
+    // Reviews carry no edit time; the webhook arrives as the edit is made
+    if within_edit_grace(
+        event.review.submitted_at,
+        Some(Utc::now()),
+        repo_config.edit_grace_seconds,
+    ) {
+        info!(
+            "Review {} by {} edited within the grace window, keeping its credit",
+            event.review.id, username
+        );
+        return Ok(());
     }
 
-    // STEP 2: Check if user is a maintainer/collaborator (skip credit for privileged roles)
+    let Some(contributor) =
+        earned_credit_holder(&state, user_id, repo_owner, repo_name, &source_id).await?
+    else {
+        return Ok(());
+    };
+
+    info!(
+        "Re-evaluating edited review {} by {} on PR #{}",
+        event.review.id, username, pr_number
+    );
+
+    let pr_context = format!(
+        "Review state: {}\nPR title: {}\n\n{}",
+        event.review.state,
+        event.pull_request.title,
+        event.pull_request.body.unwrap_or_default()
+    );
+
+    enqueue(
+        &state,
+        JobPayload::Evaluate(EvaluationJob {
+            contributor_id: contributor.id,
+            user_id,
+            username: username.to_string(),
+            repo_owner: repo_owner.to_string(),
+            repo_name: repo_name.to_string(),
+            event_type: EventType::ReviewSubmitted,
+            content_type: ContentType::Review,
+            pr_number: Some(pr_number),
+            title: None,
+            body: event.review.body.unwrap_or_default(),
+            diff_summary: None,
+            thread_context: Some(pr_context),
+            thread: Some(ThreadSource::Review {
+                review_id: event.review.id,
+                state: event.review.state.clone(),
+                submitted_at: event.review.submitted_at,
+            }),
+            installation_id: state.installation_id,
+            source_id: Some(source_id),
+            revision: true,
+        }),
+        Utc::now(),
+    )
+    .await
+}
+
+/// Process an inline review comment created event
+///
+/// Inline comments are scored as part of their review: the first event of a
+/// review schedules its evaluation after `review_group_seconds`, and the job
+/// reads the review and all of its inline comments back from GitHub.
+async fn process_pr_review_comment_created(
+    state: AppState,
+    event: PullRequestReviewCommentEvent,
+) -> ApiResult<()> {
+    let user_id = event.comment.user.id;
+    let username = &event.comment.user.login;
+    let repo_owner = &event.repository.owner.login;
+    let repo_name = &event.repository.name;
+    let pr_number = event.pull_request.number;
+
+    info!(
+        "Processing inline review comment by {} in {}/{} on PR #{}",
+        username, repo_owner, repo_name, pr_number
+    );
+
+    // Check if user is a maintainer/collaborator (skip credit for privileged roles)
     match state
         .github_client
         .check_collaborator_role(repo_owner, repo_name, username)
@@ -420,7 +1116,7 @@ async fn process_comment_created(state: AppState, event: IssueCommentEvent) -> A
     {
         Ok(role) if role.is_maintainer() || role.has_write_access() => {
             info!(
-                "User {} has privileged role {:?}, skipping credit for comment",
+                "User {} has privileged role {:?}, skipping credit for review comment",
                 username, role
             );
             return Ok(());
@@ -434,116 +1130,1249 @@ async fn process_comment_created(state: AppState, event: IssueCommentEvent) -> A
                 username, e
             );
         }
-    }
+    }
+
+    // Resolve effective config for this repository
+    let repo_config = state
+        .repo_config_loader
+        .get_config(&state.github_client, repo_owner, repo_name)
+        .await;
+
+    // Lookup or create contributor
+    let contributor = track_contributor(
+        &state,
+        user_id,
+        username,
+        repo_owner,
+        repo_name,
+        repo_config.starting_credit,
+    )
+    .await?;
+
+    // Check if blacklisted (skip credit for blacklisted users)
+    if contributor.is_blacklisted
+        || check_blacklist(contributor.credit_score, repo_config.blacklist_threshold)
+    {
+        info!(
+            "Contributor {} is blacklisted, skipping credit for review comment",
+            username
+        );
+        return Ok(());
+    }
+
+    // The commented hunk, in case the review cannot be read back
+    let hunk_context = format!(
+        "PR title: {}\n\nInline comment on {}:\n{}\n> {}",
+        event.pull_request.title, event.comment.path, event.comment.diff_hunk, event.comment.body
+    );
+    let job = EvaluationJob {
+        contributor_id: contributor.id,
+        user_id,
+        username: username.to_string(),
+        repo_owner: repo_owner.to_string(),
+        repo_name: repo_name.to_string(),
+        event_type: EventType::ReviewSubmitted,
+        content_type: ContentType::Review,
+        pr_number: Some(pr_number),
+        title: None,
+        body: String::new(),
+        diff_summary: None,
+        thread_context: Some(hunk_context),
+        thread: None,
+        installation_id: state.installation_id,
+        source_id: Some(format!("review_comment:{}", event.comment.id)),
+        revision: false,
+    };
+
+    match event.comment.pull_request_review_id {
+        Some(review_id) => {
+            // Review body and state are read back when the job runs
+            let job = EvaluationJob {
+                thread: Some(ThreadSource::Review {
+                    review_id,
+                    state: "commented".to_string(),
+                    submitted_at: None,
+                }),
+                source_id: Some(format!("review:{}", review_id)),
+                ..job
+            };
+            let run_at = Utc::now()
+                + chrono::Duration::seconds(state.thread_context_config.review_group_seconds);
+            enqueue_review_evaluation(&state, job, review_id, run_at).await
+        }
+        None => {
+            // Not part of a review: evaluate the comment alone on its hunk
+            let job = EvaluationJob {
+                body: event.comment.body.clone(),
+                ..job
+            };
+            enqueue(&state, JobPayload::Evaluate(job), Utc::now()).await
+        }
+    }
+}
+
+/// Process an inline review comment edited event
+///
+/// Inline comments earn credit as part of their review, so an edit after the
+/// edit grace window re-evaluates the whole review. Comments posted outside a
+/// review are re-evaluated alone.
+async fn process_pr_review_comment_edited(
+    state: AppState,
+    event: PullRequestReviewCommentEvent,
+) -> ApiResult<()> {
+    // Resolve effective config for this repository
+    let repo_config = state
+        .repo_config_loader
+        .get_config(
+            &state.github_client,
+            &event.repository.owner.login,
+            &event.repository.name,
+        )
+        .await;
+
+    if within_edit_grace(
+        event.comment.created_at,
+        event.comment.updated_at,
+        repo_config.edit_grace_seconds,
+    ) {
+        info!(
+            "Review comment {} by {} edited within the grace window, keeping its credit",
+            event.comment.id, event.comment.user.login
+        );
+        return Ok(());
+    }
+
+    revise_review_comment(state, event).await
+}
+
+/// Process an inline review comment deleted event
+///
+/// The comment's review is re-evaluated without it. Credit earned by a
+/// comment posted outside a review is taken back with a `reversal` event.
+async fn process_pr_review_comment_deleted(
+    state: AppState,
+    event: PullRequestReviewCommentEvent,
+) -> ApiResult<()> {
+    if event.comment.pull_request_review_id.is_some() {
+        return revise_review_comment(state, event).await;
+    }
+
+    reverse_earned_credit(
+        &state,
+        &event.comment.user,
+        &event.repository.owner.login,
+        &event.repository.name,
+        format!("review_comment:{}", event.comment.id),
+        event.pull_request.number,
+        format!("Review comment {} deleted", event.comment.id),
+    )
+    .await
+}
+
+/// Re-evaluate the credit an inline review comment contributed to
+async fn revise_review_comment(
+    state: AppState,
+    event: PullRequestReviewCommentEvent,
+) -> ApiResult<()> {
+    let user_id = event.comment.user.id;
+    let username = &event.comment.user.login;
+    let repo_owner = &event.repository.owner.login;
+    let repo_name = &event.repository.name;
+    let pr_number = event.pull_request.number;
+    let source_id = match event.comment.pull_request_review_id {
+        Some(review_id) => format!("review:{}", review_id),
+        None => format!("review_comment:{}", event.comment.id),
+    };
+
+    let Some(contributor) =
+        earned_credit_holder(&state, user_id, repo_owner, repo_name, &source_id).await?
+    else {
+        return Ok(());
+    };
+
+    info!(
+        "Re-evaluating {} by {} on PR #{} after a change to review comment {}",
+        source_id, username, pr_number, event.comment.id
+    );
+
+    let hunk_context = format!(
+        "PR title: {}\n\nInline comment on {}:\n{}\n> {}",
+        event.pull_request.title, event.comment.path, event.comment.diff_hunk, event.comment.body
+    );
+    let job = EvaluationJob {
+        contributor_id: contributor.id,
+        user_id,
+        username: username.to_string(),
+        repo_owner: repo_owner.to_string(),
+        repo_name: repo_name.to_string(),
+        event_type: EventType::ReviewSubmitted,
+        content_type: ContentType::Review,
+        pr_number: Some(pr_number),
+        title: None,
+        body: event.comment.body.clone(),
+        diff_summary: None,
+        thread_context: Some(hunk_context),
+        thread: None,
+        installation_id: state.installation_id,
+        source_id: Some(source_id),
+        revision: true,
+    };
+
+    // A review's body, state and remaining comments are read back when the job runs
+    let job = match event.comment.pull_request_review_id {
+        Some(review_id) => EvaluationJob {
+            body: String::new(),
+            thread: Some(ThreadSource::Review {
+                review_id,
+                state: "commented".to_string(),
+                submitted_at: None,
+            }),
+            ..job
+        },
+        None => job,
+    };
+
+    enqueue(&state, JobPayload::Evaluate(job), Utc::now()).await
+}
+
+/// Enqueue the single evaluation of a review at `run_at`
+///
+/// The review and each of its inline comments arrive as separate webhooks;
+/// whichever is processed first enqueues the evaluation and the others find
+/// it already taken.
+async fn enqueue_review_evaluation(
+    state: &AppState,
+    job: EvaluationJob,
+    review_id: i64,
+    run_at: chrono::DateTime<Utc>,
+) -> ApiResult<()> {
+    let dedupe_key = format!("review:{}/{}:{}", job.repo_owner, job.repo_name, review_id);
+    enqueue_once(state, JobPayload::Evaluate(job), run_at, &dedupe_key).await?;
+    Ok(())
+}
+
+/// Process an issue opened event
+///
+/// Issues are only scored in repositories that configure `issue_opened`
+/// deltas. Unlike PRs, issues are never gated or closed.
+async fn process_issue_opened(state: AppState, event: IssuesEvent) -> ApiResult<()> {
+    let user_id = event.issue.user.id;
+    let username = &event.issue.user.login;
+    let repo_owner = &event.repository.owner.login;
+    let repo_name = &event.repository.name;
+    let issue_number = event.issue.number;
+
+    // Resolve effective config for this repository
+    let repo_config = state
+        .repo_config_loader
+        .get_config(&state.github_client, repo_owner, repo_name)
+        .await;
+
+    if !repo_config.scores(EventType::IssueOpened) {
+        info!(
+            "Issue scoring is disabled for {}/{}, ignoring issue #{}",
+            repo_owner, repo_name, issue_number
+        );
+        return Ok(());
+    }
+
+    info!(
+        "Processing issue #{} opened by {} in {}/{}",
+        issue_number, username, repo_owner, repo_name
+    );
+
+    // STEP 1: Check if user is a maintainer/collaborator (skip credit for privileged roles)
+    match state
+        .github_client
+        .check_collaborator_role(repo_owner, repo_name, username)
+        .await
+    {
+        Ok(role) if role.is_maintainer() || role.has_write_access() => {
+            info!(
+                "User {} has privileged role {:?}, skipping credit for issue",
+                username, role
+            );
+            return Ok(());
+        }
+        Ok(_) => {
+            // User is not privileged, proceed with credit evaluation
+        }
+        Err(e) => {
+            warn!(
+                "Failed to check collaborator role for {}: {}. Proceeding with credit evaluation.",
+                username, e
+            );
+        }
+    }
+
+    // STEP 2: Lookup or create contributor
+    let contributor = track_contributor(
+        &state,
+        user_id,
+        username,
+        repo_owner,
+        repo_name,
+        repo_config.starting_credit,
+    )
+    .await?;
+
+    // STEP 3: Check if blacklisted (issue stays open but no credit earned)
+    if contributor.is_blacklisted
+        || check_blacklist(contributor.credit_score, repo_config.blacklist_threshold)
+    {
+        info!(
+            "Contributor {} is blacklisted, skipping credit for issue",
+            username
+        );
+        return Ok(());
+    }
+
+    // STEP 4: Enqueue async LLM evaluation for the issue
+    enqueue(
+        &state,
+        JobPayload::Evaluate(EvaluationJob {
+            contributor_id: contributor.id,
+            user_id,
+            username: username.to_string(),
+            repo_owner: repo_owner.to_string(),
+            repo_name: repo_name.to_string(),
+            event_type: EventType::IssueOpened,
+            content_type: ContentType::Issue,
+            pr_number: Some(issue_number),
+            title: Some(event.issue.title),
+            body: event.issue.body.unwrap_or_default(),
+            diff_summary: None,
+            thread_context: None,
+            thread: None,
+            installation_id: state.installation_id,
+            source_id: Some(format!("issue:{}", issue_number)),
+            revision: false,
+        }),
+        Utc::now(),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Process an issue edited event
+///
+/// An issue that earned credit and is edited after the edit grace window is
+/// re-evaluated, and only the difference to its earlier credit is applied.
+async fn process_issue_edited(state: AppState, event: IssuesEvent) -> ApiResult<()> {
+    let user_id = event.issue.user.id;
+    let username = &event.issue.user.login;
+    let repo_owner = &event.repository.owner.login;
+    let repo_name = &event.repository.name;
+    let issue_number = event.issue.number;
+    let source_id = format!("issue:{}", issue_number);
+
+    // Resolve effective config for this repository
+    let repo_config = state
+        .repo_config_loader
+        .get_config(&state.github_client, repo_owner, repo_name)
+        .await;
+
+    if within_edit_grace(
+        event.issue.created_at,
+        event.issue.updated_at,
+        repo_config.edit_grace_seconds,
+    ) {
+        info!(
+            "Issue #{} by {} edited within the grace window, keeping its credit",
+            issue_number, username
+        );
+        return Ok(());
+    }
+
+    let Some(contributor) =
+        earned_credit_holder(&state, user_id, repo_owner, repo_name, &source_id).await?
+    else {
+        return Ok(());
+    };
+
+    info!(
+        "Re-evaluating edited issue #{} by {} in {}/{}",
+        issue_number, username, repo_owner, repo_name
+    );
+
+    enqueue(
+        &state,
+        JobPayload::Evaluate(EvaluationJob {
+            contributor_id: contributor.id,
+            user_id,
+            username: username.to_string(),
+            repo_owner: repo_owner.to_string(),
+            repo_name: repo_name.to_string(),
+            event_type: EventType::IssueOpened,
+            content_type: ContentType::Issue,
+            pr_number: Some(issue_number),
+            title: Some(event.issue.title),
+            body: event.issue.body.unwrap_or_default(),
+            diff_summary: None,
+            thread_context: None,
+            thread: None,
+            installation_id: state.installation_id,
+            source_id: Some(source_id),
+            revision: true,
+        }),
+        Utc::now(),
+    )
+    .await
+}
+
+/// Process an issue deleted event
+///
+/// Credit the issue earned is taken back with a `reversal` event.
+async fn process_issue_deleted(state: AppState, event: IssuesEvent) -> ApiResult<()> {
+    reverse_earned_credit(
+        &state,
+        &event.issue.user,
+        &event.repository.owner.login,
+        &event.repository.name,
+        format!("issue:{}", event.issue.number),
+        event.issue.number,
+        format!("Issue #{} deleted", event.issue.number),
+    )
+    .await
+}
+
+/// Process a comment created on a pull request or an issue
+async fn process_comment_created(state: AppState, event: IssueCommentEvent) -> ApiResult<()> {
+    let user_id = event.comment.user.id;
+    let username = &event.comment.user.login;
+    let repo_owner = &event.repository.owner.login;
+    let repo_name = &event.repository.name;
+    let comment_body = &event.comment.body;
+    let issue_number = event.issue.number;
+    let is_pull_request = event.issue.pull_request.is_some();
+
+    info!(
+        "Processing comment by {} in {}/{} on {} #{}",
+        username,
+        repo_owner,
+        repo_name,
+        if is_pull_request { "PR" } else { "issue" },
+        issue_number
+    );
+
+    // STEP 1: Check if comment contains /credit commands
+    let commands = parse_credit_commands(comment_body);
+    if !commands.is_empty() {
+        if !is_pull_request {
+            info!(
+                "Ignoring /credit command from {} on issue #{} in {}/{}",
+                username, issue_number, repo_owner, repo_name
+            );
+            return Ok(());
+        }
+
+        info!(
+            "Detected {} /credit command(s) from {} in {}/{}: {:?}",
+            commands.len(),
+            username,
+            repo_owner,
+            repo_name,
+            commands
+        );
+
+        // Process /credit commands (all but `/credit me` require maintainer role)
+        return process_credit_commands(
+            state,
+            repo_owner.to_string(),
+            repo_name.to_string(),
+            event.comment.user.clone(),
+            issue_number as u64,
+            event.issue.user.clone(),
+            commands,
+        )
+        .await;
+    }
+
+    // Resolve effective config for this repository
+    let repo_config = state
+        .repo_config_loader
+        .get_config(&state.github_client, repo_owner, repo_name)
+        .await;
+
+    // Comments on issues only earn credit where issue scoring is enabled
+    let event_type = if is_pull_request {
+        EventType::Comment
+    } else {
+        EventType::IssueComment
+    };
+    if !repo_config.scores(event_type) {
+        info!(
+            "Issue comment scoring is disabled for {}/{}, ignoring comment",
+            repo_owner, repo_name
+        );
+        return Ok(());
+    }
+
+    // STEP 2: Check if user is a maintainer/collaborator (skip credit for privileged roles)
+    match state
+        .github_client
+        .check_collaborator_role(repo_owner, repo_name, username)
+        .await
+    {
+        Ok(role) if role.is_maintainer() || role.has_write_access() => {
+            info!(
+                "User {} has privileged role {:?}, skipping credit for comment",
+                username, role
+            );
+            return Ok(());
+        }
+        Ok(_) => {
+            // User is not privileged, proceed with credit evaluation
+        }
+        Err(e) => {
+            warn!(
+                "Failed to check collaborator role for {}: {}. Proceeding with credit evaluation.",
+                username, e
+            );
+        }
+    }
+
+    // STEP 3: Lookup or create contributor
+    let contributor = track_contributor(
+        &state,
+        user_id,
+        username,
+        repo_owner,
+        repo_name,
+        repo_config.starting_credit,
+    )
+    .await?;
+
+    // STEP 4: Check if blacklisted (comment stays but no credit earned)
+    if contributor.is_blacklisted
+        || check_blacklist(contributor.credit_score, repo_config.blacklist_threshold)
+    {
+        info!(
+            "Contributor {} is blacklisted, skipping credit for comment",
+            username
+        );
+        return Ok(());
+    }
+
+    // STEP 5: Enqueue async LLM evaluation for the comment
+    enqueue(
+        &state,
+        JobPayload::Evaluate(EvaluationJob {
+            contributor_id: contributor.id,
+            user_id,
+            username: username.to_string(),
+            repo_owner: repo_owner.to_string(),
+            repo_name: repo_name.to_string(),
+            event_type,
+            content_type: ContentType::Comment,
+            pr_number: Some(issue_number),
+            title: None,
+            body: comment_body.clone(),
+            diff_summary: None,
+            thread_context: Some(event.issue.title),
+            thread: Some(ThreadSource::IssueComment {
+                comment_id: event.comment.id,
+            }),
+            installation_id: state.installation_id,
+            source_id: Some(format!("issue_comment:{}", event.comment.id)),
+            revision: false,
+        }),
+        Utc::now(),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Process a comment edited on a pull request or an issue
+///
+/// A comment that earned credit and is edited after the edit grace window is
+/// re-evaluated, and only the difference to the credit it already earned is
+/// applied. Comments that never moved the score are left alone.
+async fn process_comment_edited(state: AppState, event: IssueCommentEvent) -> ApiResult<()> {
+    let user_id = event.comment.user.id;
+    let username = &event.comment.user.login;
+    let repo_owner = &event.repository.owner.login;
+    let repo_name = &event.repository.name;
+    let issue_number = event.issue.number;
+    let source_id = format!("issue_comment:{}", event.comment.id);
+
+    // Resolve effective config for this repository
+    let repo_config = state
+        .repo_config_loader
+        .get_config(&state.github_client, repo_owner, repo_name)
+        .await;
+
+    // Typo fixes right after posting keep the credit of the original
+    if within_edit_grace(
+        event.comment.created_at,
+        event.comment.updated_at,
+        repo_config.edit_grace_seconds,
+    ) {
+        info!(
+            "Comment {} by {} edited within the grace window, keeping its credit",
+            event.comment.id, username
+        );
+        return Ok(());
+    }
+
+    let Some(contributor) =
+        earned_credit_holder(&state, user_id, repo_owner, repo_name, &source_id).await?
+    else {
+        return Ok(());
+    };
+
+    info!(
+        "Re-evaluating edited comment {} by {} in {}/{}",
+        event.comment.id, username, repo_owner, repo_name
+    );
+
+    let event_type = if event.issue.pull_request.is_some() {
+        EventType::Comment
+    } else {
+        EventType::IssueComment
+    };
+
+    enqueue(
+        &state,
+        JobPayload::Evaluate(EvaluationJob {
+            contributor_id: contributor.id,
+            user_id,
+            username: username.to_string(),
+            repo_owner: repo_owner.to_string(),
+            repo_name: repo_name.to_string(),
+            event_type,
+            content_type: ContentType::Comment,
+            pr_number: Some(issue_number),
+            title: None,
+            body: event.comment.body.clone(),
+            diff_summary: None,
+            thread_context: Some(event.issue.title),
+            thread: Some(ThreadSource::IssueComment {
+                comment_id: event.comment.id,
+            }),
+            installation_id: state.installation_id,
+            source_id: Some(source_id),
+            revision: true,
+        }),
+        Utc::now(),
+    )
+    .await
+}
+
+/// Process a comment deleted from a pull request or an issue
+///
+/// Credit the comment earned is taken back with a `reversal` event. Deleting
+/// a comment that cost credit does not win it back, and evaluations of the
+/// comment still pending review are left for maintainers to decide.
+async fn process_comment_deleted(state: AppState, event: IssueCommentEvent) -> ApiResult<()> {
+    reverse_earned_credit(
+        &state,
+        &event.comment.user,
+        &event.repository.owner.login,
+        &event.repository.name,
+        format!("issue_comment:{}", event.comment.id),
+        event.issue.number,
+        format!("Comment {} deleted", event.comment.id),
+    )
+    .await
+}
+
+/// Take back the credit a piece of deleted GitHub content earned
+///
+/// Content that cost credit keeps its penalty.
+async fn reverse_earned_credit(
+    state: &AppState,
+    author: &User,
+    repo_owner: &str,
+    repo_name: &str,
+    source_id: String,
+    pr_number: i64,
+    reason: String,
+) -> ApiResult<()> {
+    let Some(contributor) =
+        get_contributor(&state.db_pool, author.id, repo_owner, repo_name).await?
+    else {
+        return Ok(());
+    };
+
+    let earned: i32 = list_events_by_source(&state.db_pool, contributor.id, &source_id)
+        .await?
+        .iter()
+        .map(|event| event.delta)
+        .sum();
+    if earned <= 0 {
+        return Ok(());
+    }
+
+    // Resolve effective config for this repository
+    let repo_config = state
+        .repo_config_loader
+        .get_config(&state.github_client, repo_owner, repo_name)
+        .await;
+
+    let applied = apply_credit_delta(
+        &state.db_pool,
+        contributor.id,
+        -earned,
+        NewCreditEvent::new("reversal")
+            .with_reason(reason)
+            .with_pr_number(Some(pr_number))
+            .with_source_id(Some(source_id.clone())),
+        Some(repo_config.blacklist_threshold),
+    )
+    .await?;
+
+    info!(
+        "Reversed {} credit of deleted {} by {} (new score: {})",
+        earned, source_id, author.login, applied.event.credit_after
+    );
+    notify_credit_applied(
+        state,
+        repo_owner,
+        repo_name,
+        &author.login,
+        author.id,
+        &applied,
+    )
+    .await;
+
+    Ok(())
+}
+
+/// Whether an edit came soon enough after posting to count as a typo fix
+fn within_edit_grace(
+    posted_at: Option<chrono::DateTime<Utc>>,
+    edited_at: Option<chrono::DateTime<Utc>>,
+    grace_seconds: i64,
+) -> bool {
+    match (posted_at, edited_at) {
+        (Some(posted_at), Some(edited_at)) => {
+            edited_at - posted_at <= chrono::Duration::seconds(grace_seconds)
+        }
+        _ => false,
+    }
+}
+
+/// Look up the contributor holding credit for a piece of GitHub content
+///
+/// Returns `None` when the content never moved their score, e.g. because it
+/// was posted by a maintainer or its evaluation is still pending.
+async fn earned_credit_holder(
+    state: &AppState,
+    user_id: i64,
+    repo_owner: &str,
+    repo_name: &str,
+    source_id: &str,
+) -> ApiResult<Option<Contributor>> {
+    let Some(contributor) = get_contributor(&state.db_pool, user_id, repo_owner, repo_name).await?
+    else {
+        return Ok(None);
+    };
+
+    if list_events_by_source(&state.db_pool, contributor.id, source_id)
+        .await?
+        .is_empty()
+    {
+        info!("{} earned no credit, ignoring its edit", source_id);
+        return Ok(None);
+    }
+
+    Ok(Some(contributor))
+}
+
+/// Process the /credit commands of a comment, in order
+///
+/// Maintainers may run every command, and commands that did not parse are
+/// answered with their usage. Other commenters may only run `/credit me`;
+/// everything else they write is silently ignored.
+async fn process_credit_commands(
+    state: AppState,
+    repo_owner: String,
+    repo_name: String,
+    commenter: User,
+    issue_number: u64,
+    pr_author: User,
+    commands: Vec<Result<CreditCommand, CommandError>>,
+) -> ApiResult<()> {
+    // Check if commenter is a maintainer
+    let is_maintainer = match state
+        .github_client
+        .check_collaborator_role(&repo_owner, &repo_name, &commenter.login)
+        .await
+    {
+        Ok(role) if role.is_maintainer() => true,
+        Ok(_) => false,
+        Err(e) => {
+            warn!(
+                "Failed to check collaborator role for {}: {}. Treating as non-maintainer.",
+                commenter.login, e
+            );
+            false
+        }
+    };
+
+    // Non-maintainers: answer `/credit me` (once), silently ignore the rest
+    if !is_maintainer {
+        if commands
+            .iter()
+            .any(|command| matches!(command, Ok(CreditCommand::Me)))
+        {
+            return handle_credit_me(state, repo_owner, repo_name, issue_number, commenter).await;
+        }
+        info!(
+            "User {} is not a maintainer of {}/{}. Silently ignoring /credit command.",
+            commenter.login, repo_owner, repo_name
+        );
+        return Ok(());
+    }
+
+    info!(
+        "Processing /credit commands from maintainer {} in {}/{}",
+        commenter.login, repo_owner, repo_name
+    );
+
+    for command in commands {
+        match command {
+            Ok(command) => {
+                process_credit_command(
+                    state.clone(),
+                    repo_owner.clone(),
+                    repo_name.clone(),
+                    commenter.clone(),
+                    issue_number,
+                    pr_author.clone(),
+                    command,
+                )
+                .await?
+            }
+            Err(error) => {
+                info!(
+                    "Invalid /credit command from {} in {}/{}: {}",
+                    commenter.login, repo_owner, repo_name, error
+                );
+                state
+                    .github_client
+                    .add_comment(
+                        &repo_owner,
+                        &repo_name,
+                        issue_number,
+                        &format_command_error(&error),
+                    )
+                    .await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Process a single /credit command from a maintainer
+async fn process_credit_command(
+    state: AppState,
+    repo_owner: String,
+    repo_name: String,
+    commenter: User,
+    issue_number: u64,
+    pr_author: User,
+    command: CreditCommand,
+) -> ApiResult<()> {
+    match command {
+        CreditCommand::Check { username } => {
+            handle_credit_check(state, repo_owner, repo_name, issue_number, username).await
+        }
+        CreditCommand::Override {
+            username,
+            delta,
+            reason,
+        } => {
+            handle_credit_override(
+                state,
+                repo_owner,
+                repo_name,
+                issue_number,
+                username,
+                delta,
+                reason,
+            )
+            .await
+        }
+        CreditCommand::Blacklist { username } => {
+            handle_credit_blacklist(
+                state,
+                repo_owner,
+                repo_name,
+                issue_number,
+                username,
+                commenter.login,
+            )
+            .await
+        }
+        CreditCommand::Unblacklist { username } => {
+            handle_credit_unblacklist(state, repo_owner, repo_name, issue_number, username).await
+        }
+        CreditCommand::Set {
+            username,
+            score,
+            reason,
+        } => {
+            handle_credit_set(
+                state,
+                repo_owner,
+                repo_name,
+                issue_number,
+                username,
+                score,
+                reason,
+            )
+            .await
+        }
+        CreditCommand::History { username, limit } => {
+            handle_credit_history(state, repo_owner, repo_name, issue_number, username, limit).await
+        }
+        CreditCommand::Leaderboard => {
+            handle_credit_leaderboard(state, repo_owner, repo_name, issue_number).await
+        }
+        CreditCommand::Help => {
+            state
+                .github_client
+                .add_comment(&repo_owner, &repo_name, issue_number, HELP_TEXT)
+                .await?;
+            Ok(())
+        }
+        CreditCommand::Approve => {
+            handle_credit_approve(
+                state,
+                repo_owner,
+                repo_name,
+                issue_number,
+                pr_author,
+                commenter.login,
+            )
+            .await
+        }
+        CreditCommand::Me => {
+            handle_credit_me(state, repo_owner, repo_name, issue_number, commenter).await
+        }
+    }
+}
+
+/// Handle /credit check @username command
+async fn handle_credit_check(
+    state: AppState,
+    repo_owner: String,
+    repo_name: String,
+    issue_number: u64,
+    target_username: String,
+) -> ApiResult<()> {
+    info!(
+        "Checking credit for {} in {}/{}",
+        target_username, repo_owner, repo_name
+    );
+
+    let contributor_opt =
+        resolve_contributor(&state, &target_username, &repo_owner, &repo_name).await?;
+
+    let contributor = match contributor_opt {
+        Some(c) => c,
+        None => {
+            let response = format!(
+                "Contributor @{} not found in {}/{}.",
+                target_username, repo_owner, repo_name
+            );
+            state
+                .github_client
+                .add_comment(&repo_owner, &repo_name, issue_number, &response)
+                .await?;
+            return Ok(());
+        }
+    };
+
+    // Get last 5 credit events
+    let events = meritocrab_db::credit_events::list_events_by_contributor(
+        &state.db_pool,
+        contributor.id,
+        5,
+        0,
+    )
+    .await?;
+
+    // Format response
+    let mut response = format!("**Credit Report for @{}**\n\n", target_username);
+    response.push_str(&format!(
+        "- Credit Score: **{}**\n",
+        contributor.credit_score
+    ));
+    response.push_str(&format!(
+        "- Role: {}\n",
+        contributor.role.as_deref().unwrap_or("contributor")
+    ));
+    response.push_str(&format!(
+        "- Blacklisted: {}\n",
+        if contributor.is_blacklisted {
+            "Yes"
+        } else {
+            "No"
+        }
+    ));
+    response.push_str("\n**Recent Credit History (last 5 events):**\n\n");
+    response.push_str(&format_event_list(&events));
+
+    // Reply with credit report
+    state
+        .github_client
+        .add_comment(&repo_owner, &repo_name, issue_number, &response)
+        .await?;
+
+    info!(
+        "Replied with credit report for {} in {}/{}",
+        target_username, repo_owner, repo_name
+    );
+    Ok(())
+}
+
+/// Handle /credit override @username +10 "reason" command
+async fn handle_credit_override(
+    state: AppState,
+    repo_owner: String,
+    repo_name: String,
+    issue_number: u64,
+    target_username: String,
+    delta: i32,
+    reason: String,
+) -> ApiResult<()> {
+    info!(
+        "Overriding credit for {} in {}/{}: delta={}, reason={}",
+        target_username, repo_owner, repo_name, delta, reason
+    );
+
+    // Resolve effective config for this repository
+    let repo_config = state
+        .repo_config_loader
+        .get_config(&state.github_client, &repo_owner, &repo_name)
+        .await;
+
+    // Maintainers may act on users who have not contributed here yet
+    let contributor_opt = resolve_or_create_contributor(
+        &state,
+        &target_username,
+        &repo_owner,
+        &repo_name,
+        repo_config.starting_credit,
+    )
+    .await?;
+
+    let contributor = match contributor_opt {
+        Some(c) => c,
+        None => {
+            let response = format!("GitHub user @{} not found.", target_username);
+            state
+                .github_client
+                .add_comment(&repo_owner, &repo_name, issue_number, &response)
+                .await?;
+            return Ok(());
+        }
+    };
+
+    // Apply credit adjustment (auto-blacklists if credit drops to the threshold)
+    let applied = apply_credit_delta(
+        &state.db_pool,
+        contributor.id,
+        delta,
+        NewCreditEvent::new("manual_adjustment").with_maintainer_override(reason.clone()),
+        Some(repo_config.blacklist_threshold),
+    )
+    .await?;
+    let credit_before = applied.event.credit_before;
+    let credit_after = applied.event.credit_after;
+
+    info!(
+        "Applied credit override for {}: {} -> {} (delta: {})",
+        target_username, credit_before, credit_after, delta
+    );
+
+    if applied.auto_blacklisted {
+        warn!(
+            "Auto-blacklisted user {} due to credit override (credit dropped to {})",
+            target_username, credit_after
+        );
+    }
+    notify_credit_applied(
+        &state,
+        &repo_owner,
+        &repo_name,
+        &target_username,
+        contributor.github_user_id,
+        &applied,
+    )
+    .await;
+
+    // Reply with confirmation
+    let response = format!(
+        "Credit adjusted for @{}: **{} → {}** (delta: {})\n\nReason: {}",
+        target_username,
+        credit_before,
+        credit_after,
+        format_delta(delta),
+        reason
+    );
+
+    state
+        .github_client
+        .add_comment(&repo_owner, &repo_name, issue_number, &response)
+        .await?;
+
+    info!(
+        "Replied with credit override confirmation for {} in {}/{}",
+        target_username, repo_owner, repo_name
+    );
+    Ok(())
+}
+
+/// Handle /credit blacklist @username command
+async fn handle_credit_blacklist(
+    state: AppState,
+    repo_owner: String,
+    repo_name: String,
+    issue_number: u64,
+    target_username: String,
+    maintainer_username: String,
+) -> ApiResult<()> {
+    info!(
+        "Blacklisting user {} in {}/{}",
+        target_username, repo_owner, repo_name
+    );
+
+    // Resolve effective config for this repository
+    let repo_config = state
+        .repo_config_loader
+        .get_config(&state.github_client, &repo_owner, &repo_name)
+        .await;
+
+    // Maintainers may act on users who have not contributed here yet
+    let contributor_opt = resolve_or_create_contributor(
+        &state,
+        &target_username,
+        &repo_owner,
+        &repo_name,
+        repo_config.starting_credit,
+    )
+    .await?;
+
+    let contributor = match contributor_opt {
+        Some(c) => c,
+        None => {
+            let response = format!("GitHub user @{} not found.", target_username);
+            state
+                .github_client
+                .add_comment(&repo_owner, &repo_name, issue_number, &response)
+                .await?;
+            return Ok(());
+        }
+    };
 
-    // STEP 3: Lookup or create contributor
-    let contributor = lookup_or_create_contributor(
+    // Set blacklist flag and log the event together
+    set_blacklisted_with_event(
         &state.db_pool,
-        user_id,
-        repo_owner,
-        repo_name,
-        state.repo_config.starting_credit,
+        contributor.id,
+        true,
+        NewCreditEvent::new("blacklist_added")
+            .with_maintainer_override("Manually blacklisted by maintainer".to_string()),
     )
     .await?;
 
-    // STEP 4: Check if blacklisted (comment stays but no credit earned)
-    if check_blacklist(
-        contributor.credit_score,
-        state.repo_config.blacklist_threshold,
-    ) {
-        info!(
-            "Contributor {} is blacklisted, skipping credit for comment",
-            username
-        );
-        return Ok(());
-    }
-
-    // STEP 5: Spawn async LLM evaluation for the comment
-    spawn_comment_evaluation(
-        state.clone(),
-        contributor.id,
-        user_id,
-        username.to_string(),
-        repo_owner.to_string(),
-        repo_name.to_string(),
-        comment_body.clone(),
-        event.issue.title,
+    info!(
+        "Blacklisted user {} in {}/{}",
+        target_username, repo_owner, repo_name
     );
 
+    let event = NotificationEvent::BlacklistAdded {
+        login: target_username.clone(),
+        github_user_id: contributor.github_user_id,
+        by: Some(maintainer_username),
+    };
+    notify(&state, Notification::new(&repo_owner, &repo_name, event)).await;
+
+    // Reply with vague confirmation (as per spec)
+    let response = "User status updated.";
+
+    state
+        .github_client
+        .add_comment(&repo_owner, &repo_name, issue_number, response)
+        .await?;
+
+    info!(
+        "Replied with blacklist confirmation for {} in {}/{}",
+        target_username, repo_owner, repo_name
+    );
     Ok(())
 }
 
-/// Process /credit command from maintainer
-async fn process_credit_command(
+/// Handle /credit me command
+///
+/// Replies with the commenter's own standing. Replies are rate limited per
+/// GitHub user; over quota the command is dropped without a reply.
+async fn handle_credit_me(
     state: AppState,
     repo_owner: String,
     repo_name: String,
-    commenter_username: String,
     issue_number: u64,
-    command: crate::credit_commands::CreditCommand,
+    commenter: User,
 ) -> ApiResult<()> {
-    use crate::credit_commands::CreditCommand;
-
-    // Check if commenter is a maintainer
-    let is_maintainer = match state
-        .github_client
-        .check_collaborator_role(&repo_owner, &repo_name, &commenter_username)
-        .await
+    if let Some(limiter) = &state.self_service_limiter
+        && !limiter.check(commenter.id)
     {
-        Ok(role) if role.is_maintainer() => true,
-        Ok(_) => false,
-        Err(e) => {
-            warn!(
-                "Failed to check collaborator role for {}: {}. Treating as non-maintainer.",
-                commenter_username, e
-            );
-            false
-        }
-    };
-
-    // Non-maintainers: silently ignore command
-    if !is_maintainer {
         info!(
-            "User {} is not a maintainer of {}/{}. Silently ignoring /credit command.",
-            commenter_username, repo_owner, repo_name
+            "Rate limited /credit me from {} in {}/{}",
+            commenter.login, repo_owner, repo_name
         );
         return Ok(());
     }
 
-    info!(
-        "Processing /credit command from maintainer {} in {}/{}",
-        commenter_username, repo_owner, repo_name
-    );
+    // Resolve effective config for this repository
+    let repo_config = state
+        .repo_config_loader
+        .get_config(&state.github_client, &repo_owner, &repo_name)
+        .await;
 
-    // Process command based on type
-    match command {
-        CreditCommand::Check { username } => {
-            handle_credit_check(state, repo_owner, repo_name, issue_number, username).await
-        }
-        CreditCommand::Override {
-            username,
-            delta,
-            reason,
-        } => {
-            handle_credit_override(
-                state,
-                repo_owner,
-                repo_name,
-                issue_number,
-                username,
-                delta,
-                reason,
+    // Commenters the bot has not scored yet stand at the starting credit
+    let contributor =
+        get_contributor(&state.db_pool, commenter.id, &repo_owner, &repo_name).await?;
+    let (credit_score, events) = match contributor {
+        Some(contributor) => {
+            let events = meritocrab_db::credit_events::list_events_by_contributor(
+                &state.db_pool,
+                contributor.id,
+                50,
+                0,
             )
-            .await
-        }
-        CreditCommand::Blacklist { username } => {
-            handle_credit_blacklist(state, repo_owner, repo_name, issue_number, username).await
+            .await?;
+            (contributor.credit_score, events)
         }
-    }
+        None => (repo_config.starting_credit, Vec::new()),
+    };
+
+    let response = format_self_report(
+        &commenter.login,
+        credit_score,
+        repo_config.pr_threshold,
+        &events,
+    );
+    state
+        .github_client
+        .add_comment(&repo_owner, &repo_name, issue_number, &response)
+        .await?;
+
+    info!(
+        "Replied to /credit me from {} in {}/{}",
+        commenter.login, repo_owner, repo_name
+    );
+    Ok(())
 }
 
-/// Handle /credit check @username command
-async fn handle_credit_check(
+/// Handle /credit unblacklist @username command
+async fn handle_credit_unblacklist(
     state: AppState,
     repo_owner: String,
     repo_name: String,
@@ -551,41 +2380,12 @@ async fn handle_credit_check(
     target_username: String,
 ) -> ApiResult<()> {
     info!(
-        "Checking credit for {} in {}/{}",
+        "Lifting blacklist for {} in {}/{}",
         target_username, repo_owner, repo_name
     );
 
-    // Get GitHub user ID by username (we need to look up in database or use GitHub API)
-    // For simplicity, we'll try to find the contributor by username pattern
-    // This is a limitation - we should ideally query GitHub API to get user ID
-    // For now, we'll search contributors by repo and match username pattern
-
-    // Try to find contributor by matching github_user_id with expected username pattern
-    // Note: This is not ideal, but we don't have a username field in the database
-    // We'll need to query GitHub API to get the actual username for each user_id
-    // For now, we'll use a simplified approach: query by github_user_id if username is numeric
-
-    let contributor_opt = if let Ok(github_user_id) = target_username.parse::<i64>() {
-        meritocrab_db::contributors::get_contributor(
-            &state.db_pool,
-            github_user_id,
-            &repo_owner,
-            &repo_name,
-        )
-        .await?
-    } else {
-        // We don't have username stored, so we can't look up by username
-        // Return error message
-        let response = format!(
-            "Unable to find contributor @{}. Note: Use GitHub user ID instead of username for now.",
-            target_username
-        );
-        state
-            .github_client
-            .add_comment(&repo_owner, &repo_name, issue_number, &response)
-            .await?;
-        return Ok(());
-    };
+    let contributor_opt =
+        resolve_contributor(&state, &target_username, &repo_owner, &repo_name).await?;
 
     let contributor = match contributor_opt {
         Some(c) => c,
@@ -602,110 +2402,75 @@ async fn handle_credit_check(
         }
     };
 
-    // Get last 5 credit events
-    let events = meritocrab_db::credit_events::list_events_by_contributor(
-        &state.db_pool,
-        contributor.id,
-        5,
-        0,
-    )
-    .await?;
-
-    // Format response
-    let mut response = format!("**Credit Report for @{}**\n\n", target_username);
-    response.push_str(&format!(
-        "- Credit Score: **{}**\n",
-        contributor.credit_score
-    ));
-    response.push_str(&format!(
-        "- Role: {}\n",
-        contributor.role.as_deref().unwrap_or("contributor")
-    ));
-    response.push_str(&format!(
-        "- Blacklisted: {}\n",
-        if contributor.is_blacklisted {
-            "Yes"
-        } else {
-            "No"
-        }
-    ));
-    response.push_str("\n**Recent Credit History (last 5 events):**\n\n");
+    if contributor.is_blacklisted {
+        set_blacklisted_with_event(
+            &state.db_pool,
+            contributor.id,
+            false,
+            NewCreditEvent::new("blacklist_removed")
+                .with_maintainer_override("Manually unblacklisted by maintainer".to_string()),
+        )
+        .await?;
 
-    if events.is_empty() {
-        response.push_str("_No credit events recorded._\n");
-    } else {
-        for event in events {
-            response.push_str(&format!(
-                "- `{}`: {} ({} -> {}) — {}\n",
-                event.event_type,
-                if event.delta >= 0 {
-                    format!("+{}", event.delta)
-                } else {
-                    event.delta.to_string()
-                },
-                event.credit_before,
-                event.credit_after,
-                event.created_at.format("%Y-%m-%d %H:%M UTC")
-            ));
-        }
+        info!(
+            "Unblacklisted user {} in {}/{}",
+            target_username, repo_owner, repo_name
+        );
     }
 
-    // Reply with credit report
+    // Same vague confirmation as /credit blacklist, so the reply does not
+    // reveal whether the user was blacklisted
     state
         .github_client
-        .add_comment(&repo_owner, &repo_name, issue_number, &response)
+        .add_comment(
+            &repo_owner,
+            &repo_name,
+            issue_number,
+            "User status updated.",
+        )
         .await?;
 
-    info!(
-        "Replied with credit report for {} in {}/{}",
-        target_username, repo_owner, repo_name
-    );
     Ok(())
 }
 
-/// Handle /credit override @username +10 "reason" command
-async fn handle_credit_override(
+/// Handle /credit set @username 120 "reason" command
+///
+/// Recorded as a `manual_adjustment` by the difference to the current score,
+/// so the audit log stays a sequence of deltas.
+async fn handle_credit_set(
     state: AppState,
     repo_owner: String,
     repo_name: String,
     issue_number: u64,
     target_username: String,
-    delta: i32,
+    score: i32,
     reason: String,
 ) -> ApiResult<()> {
     info!(
-        "Overriding credit for {} in {}/{}: delta={}, reason={}",
-        target_username, repo_owner, repo_name, delta, reason
+        "Setting credit for {} in {}/{}: score={}, reason={}",
+        target_username, repo_owner, repo_name, score, reason
     );
 
-    // Look up contributor (same limitation as credit check)
-    let contributor_opt = if let Ok(github_user_id) = target_username.parse::<i64>() {
-        meritocrab_db::contributors::get_contributor(
-            &state.db_pool,
-            github_user_id,
-            &repo_owner,
-            &repo_name,
-        )
-        .await?
-    } else {
-        let response = format!(
-            "Unable to find contributor @{}. Note: Use GitHub user ID instead of username for now.",
-            target_username
-        );
-        state
-            .github_client
-            .add_comment(&repo_owner, &repo_name, issue_number, &response)
-            .await?;
-        return Ok(());
-    };
+    // Resolve effective config for this repository
+    let repo_config = state
+        .repo_config_loader
+        .get_config(&state.github_client, &repo_owner, &repo_name)
+        .await;
+
+    // Maintainers may act on users who have not contributed here yet
+    let contributor_opt = resolve_or_create_contributor(
+        &state,
+        &target_username,
+        &repo_owner,
+        &repo_name,
+        repo_config.starting_credit,
+    )
+    .await?;
 
     let contributor = match contributor_opt {
         Some(c) => c,
         None => {
-            let response = format!(
-                "Contributor @{} not found in {}/{}.",
-                target_username, repo_owner, repo_name
-            );
+            let response = format!("GitHub user @{} not found.", target_username);
             state
                 .github_client
                 .add_comment(&repo_owner, &repo_name, issue_number, &response)
@@ -714,253 +2479,410 @@ async fn handle_credit_override(
         }
     };
 
-    // Apply credit adjustment
-    let credit_before = contributor.credit_score;
-    let credit_after = apply_credit(credit_before, delta);
-
-    // Update credit score
-    update_credit_score(&state.db_pool, contributor.id, credit_after).await?;
-
-    // Log credit event with maintainer override
-    insert_credit_event(
+    let applied = set_credit_score(
         &state.db_pool,
         contributor.id,
-        "manual_adjustment",
-        delta,
-        credit_before,
-        credit_after,
-        None,
-        Some(reason.clone()),
+        score,
+        NewCreditEvent::new("manual_adjustment")
+            .with_maintainer_override(format!("Set to {}: {}", score, reason)),
+        Some(repo_config.blacklist_threshold),
     )
     .await?;
+    let credit_before = applied.event.credit_before;
+    let credit_after = applied.event.credit_after;
 
-    info!(
-        "Applied credit override for {}: {} -> {} (delta: {})",
-        target_username, credit_before, credit_after, delta
-    );
-
-    // Check if auto-blacklist should trigger
-    if credit_after <= state.repo_config.blacklist_threshold
-        && credit_before > state.repo_config.blacklist_threshold
-    {
+    if applied.auto_blacklisted {
         warn!(
-            "Auto-blacklisting user {} due to credit override (credit dropped to {})",
+            "Auto-blacklisted user {} due to credit set (credit set to {})",
             target_username, credit_after
         );
-
-        set_blacklisted(&state.db_pool, contributor.id, true).await?;
-
-        insert_credit_event(
-            &state.db_pool,
-            contributor.id,
-            "auto_blacklist",
-            0,
-            credit_after,
-            credit_after,
-            None,
-            Some(format!(
-                "Auto-blacklisted due to credit dropping to {}",
-                credit_after
-            )),
-        )
-        .await?;
     }
+    notify_credit_applied(
+        &state,
+        &repo_owner,
+        &repo_name,
+        &target_username,
+        contributor.github_user_id,
+        &applied,
+    )
+    .await;
 
-    // Reply with confirmation
     let response = format!(
-        "Credit adjusted for @{}: **{} → {}** (delta: {})\n\nReason: {}",
+        "Credit set for @{}: **{} → {}** (delta: {})\n\nReason: {}",
         target_username,
         credit_before,
         credit_after,
-        if delta >= 0 {
-            format!("+{}", delta)
-        } else {
-            delta.to_string()
-        },
+        format_delta(credit_after - credit_before),
         reason
     );
+    state
+        .github_client
+        .add_comment(&repo_owner, &repo_name, issue_number, &response)
+        .await?;
+
+    info!(
+        "Set credit for {} in {}/{}: {} -> {}",
+        target_username, repo_owner, repo_name, credit_before, credit_after
+    );
+    Ok(())
+}
+
+/// Handle /credit history @username [n] command
+async fn handle_credit_history(
+    state: AppState,
+    repo_owner: String,
+    repo_name: String,
+    issue_number: u64,
+    target_username: String,
+    limit: u32,
+) -> ApiResult<()> {
+    let contributor_opt =
+        resolve_contributor(&state, &target_username, &repo_owner, &repo_name).await?;
+
+    let response = match contributor_opt {
+        Some(contributor) => {
+            let events = meritocrab_db::credit_events::list_events_by_contributor(
+                &state.db_pool,
+                contributor.id,
+                limit as i64,
+                0,
+            )
+            .await?;
+            format_history(&target_username, &events)
+        }
+        None => format!(
+            "Contributor @{} not found in {}/{}.",
+            target_username, repo_owner, repo_name
+        ),
+    };
+
+    state
+        .github_client
+        .add_comment(&repo_owner, &repo_name, issue_number, &response)
+        .await?;
+    Ok(())
+}
+
+/// Handle /credit leaderboard command
+///
+/// Blacklisted contributors are left out, since the reply is public.
+async fn handle_credit_leaderboard(
+    state: AppState,
+    repo_owner: String,
+    repo_name: String,
+    issue_number: u64,
+) -> ApiResult<()> {
+    // Over-fetch so blacklisted contributors near the top do not shorten the list
+    let contributors = list_contributors_by_repo(
+        &state.db_pool,
+        &repo_owner,
+        &repo_name,
+        (LEADERBOARD_SIZE * 5) as i64,
+        0,
+    )
+    .await?;
+    let top: Vec<_> = contributors
+        .into_iter()
+        .filter(|contributor| !contributor.is_blacklisted)
+        .take(LEADERBOARD_SIZE)
+        .collect();
 
+    let response = format_leaderboard(&repo_owner, &repo_name, &top);
     state
         .github_client
         .add_comment(&repo_owner, &repo_name, issue_number, &response)
         .await?;
-
-    info!(
-        "Replied with credit override confirmation for {} in {}/{}",
-        target_username, repo_owner, repo_name
-    );
     Ok(())
 }
 
-/// Handle /credit blacklist @username command
-async fn handle_credit_blacklist(
+/// Handle /credit approve command
+///
+/// Lets a single gated PR through without changing its author's score: a
+/// draft is marked ready for review, a closed PR is reopened, and the
+/// evaluation the gate skipped is enqueued. An `exemption` event is recorded
+/// for the PR, which also keeps the gate and pending closes from closing it
+/// again.
+async fn handle_credit_approve(
     state: AppState,
     repo_owner: String,
     repo_name: String,
-    issue_number: u64,
-    target_username: String,
+    pr_number: u64,
+    pr_author: User,
+    maintainer_username: String,
 ) -> ApiResult<()> {
     info!(
-        "Blacklisting user {} in {}/{}",
-        target_username, repo_owner, repo_name
+        "Approving PR #{} by {} in {}/{}",
+        pr_number, pr_author.login, repo_owner, repo_name
     );
 
-    // Look up contributor (same limitation as credit check)
-    let contributor_opt = if let Ok(github_user_id) = target_username.parse::<i64>() {
-        meritocrab_db::contributors::get_contributor(
-            &state.db_pool,
-            github_user_id,
-            &repo_owner,
-            &repo_name,
-        )
-        .await?
-    } else {
-        let response = format!(
-            "Unable to find contributor @{}. Note: Use GitHub user ID instead of username for now.",
-            target_username
-        );
-        state
-            .github_client
-            .add_comment(&repo_owner, &repo_name, issue_number, &response)
-            .await?;
-        return Ok(());
-    };
-
-    let contributor = match contributor_opt {
-        Some(c) => c,
-        None => {
-            let response = format!(
-                "Contributor @{} not found in {}/{}.",
-                target_username, repo_owner, repo_name
-            );
-            state
-                .github_client
-                .add_comment(&repo_owner, &repo_name, issue_number, &response)
-                .await?;
-            return Ok(());
-        }
-    };
+    // Resolve effective config for this repository
+    let repo_config = state
+        .repo_config_loader
+        .get_config(&state.github_client, &repo_owner, &repo_name)
+        .await;
 
-    // Set blacklist flag
-    set_blacklisted(&state.db_pool, contributor.id, true).await?;
+    let contributor = track_contributor(
+        &state,
+        pr_author.id,
+        &pr_author.login,
+        &repo_owner,
+        &repo_name,
+        repo_config.starting_credit,
+    )
+    .await?;
 
-    // Log blacklist event
-    insert_credit_event(
+    // Record the exemption first, so a close racing with the reopen skips this PR
+    match apply_credit_delta(
         &state.db_pool,
         contributor.id,
-        "blacklist_added",
         0,
-        contributor.credit_score,
-        contributor.credit_score,
+        NewCreditEvent::new("exemption")
+            .with_pr_number(Some(pr_number as i64))
+            .with_maintainer_override(format!("Approved by @{}", maintainer_username)),
         None,
-        Some("Manually blacklisted by maintainer".to_string()),
     )
-    .await?;
+    .await
+    {
+        Ok(_) => {}
+        Err(DbError::DuplicateCreditEvent(..)) => {
+            info!(
+                "PR #{} in {}/{} already approved, approving again",
+                pr_number, repo_owner, repo_name
+            );
+        }
+        Err(e) => return Err(e.into()),
+    }
 
-    info!(
-        "Blacklisted user {} in {}/{}",
-        target_username, repo_owner, repo_name
-    );
+    // Undo whatever the gate did to the PR
+    let pull_request = state
+        .github_client
+        .get_pull_request(&repo_owner, &repo_name, pr_number)
+        .await?;
+    if pull_request.state == "closed" && pull_request.merged != Some(true) {
+        state
+            .github_client
+            .reopen_pull_request(&repo_owner, &repo_name, pr_number)
+            .await?;
+    }
+    if pull_request.draft {
+        state
+            .github_client
+            .mark_ready_for_review(&repo_owner, &repo_name, pr_number)
+            .await?;
+    }
 
-    // Reply with vague confirmation (as per spec)
-    let response = "User status updated.";
+    // Gated PRs were not scored; evaluate this one now unless it already was
+    let source_id = format!("pull_request:{}", pr_number);
+    if list_events_by_source(&state.db_pool, contributor.id, &source_id)
+        .await?
+        .is_empty()
+    {
+        let dedupe_key = format!("approved_pr:{}/{}:{}", repo_owner, repo_name, pr_number);
+        enqueue_once(
+            &state,
+            JobPayload::Evaluate(EvaluationJob {
+                contributor_id: contributor.id,
+                user_id: pr_author.id,
+                username: pr_author.login.clone(),
+                repo_owner: repo_owner.clone(),
+                repo_name: repo_name.clone(),
+                event_type: EventType::PrOpened,
+                content_type: ContentType::PullRequest,
+                pr_number: Some(pr_number as i64),
+                title: Some(pull_request.title),
+                body: pull_request.body.unwrap_or_default(),
+                diff_summary: None,
+                thread_context: None,
+                thread: None,
+                installation_id: state.installation_id,
+                source_id: Some(source_id),
+                revision: false,
+            }),
+            Utc::now(),
+            &dedupe_key,
+        )
+        .await?;
+    }
 
+    let response = format!(
+        "PR #{} approved by @{}. The credit score of @{} is unchanged (**{}**).",
+        pr_number, maintainer_username, pr_author.login, contributor.credit_score
+    );
     state
         .github_client
-        .add_comment(&repo_owner, &repo_name, issue_number, response)
+        .add_comment(&repo_owner, &repo_name, pr_number, &response)
         .await?;
 
     info!(
-        "Replied with blacklist confirmation for {} in {}/{}",
-        target_username, repo_owner, repo_name
+        "Approved PR #{} by {} in {}/{}",
+        pr_number, pr_author.login, repo_owner, repo_name
     );
     Ok(())
 }
 
-/// Spawn async PR evaluation task
-#[allow(clippy::too_many_arguments)]
-fn spawn_pr_evaluation(
-    state: AppState,
+/// Apply the repo's earning limits to credit a contributor is about to earn
+///
+/// Returns the delta to apply and the event to record it with. When a limit
+/// reduced the delta, the reason is recorded on the event, so capped credit
+/// still shows up in the audit log (with `delta = 0` when fully capped).
+async fn limit_earned_credit(
+    state: &AppState,
+    repo_config: &RepoConfig,
     contributor_id: i64,
-    user_id: i64,
-    username: String,
-    repo_owner: String,
-    repo_name: String,
-    pr_title: String,
-    pr_body: String,
-) {
-    tokio::spawn(async move {
-        if let Err(e) = evaluate_and_apply_credit(
-            state,
-            contributor_id,
-            user_id,
-            username,
-            repo_owner,
-            repo_name,
-            EventType::PrOpened,
-            ContentType::PullRequest,
-            Some(pr_title.clone()),
-            pr_body.clone(),
-            None,
-            None,
-        )
-        .await
-        {
-            error!("Failed to evaluate PR: {}", e);
+    event_type: EventType,
+    delta: i32,
+    event: NewCreditEvent,
+) -> ApiResult<(i32, NewCreditEvent)> {
+    if delta <= 0 || repo_config.earning_limits == EarningLimits::default() {
+        return Ok((delta, event));
+    }
+
+    let since = Utc::now() - chrono::Duration::hours(EARNING_WINDOW_HOURS);
+    let mut history = Vec::new();
+    for recent in list_events_with_origin_since(&state.db_pool, contributor_id, since).await? {
+        // Edit adjustments count as credit of the content they adjusted
+        let recent_type = match recent.event.event_type.as_str() {
+            "edit_adjustment" => recent
+                .original_event_type
+                .as_deref()
+                .and_then(|original_type| original_type.parse().ok()),
+            recent_type => recent_type.parse().ok(),
+        };
+        let recent = recent.event;
+        if let Some(event_type) = recent_type {
+            history.push(RecentCredit {
+                event_type,
+                delta: recent.delta,
+                pr_number: recent.pr_number,
+                created_at: recent.created_at,
+            });
+        }
+    }
+
+    let limited = apply_earning_limits(
+        &repo_config.earning_limits,
+        event_type,
+        delta,
+        event.pr_number,
+        &history,
+        Utc::now(),
+    );
+
+    match limited.capped_by {
+        Some(cap) => {
+            info!(
+                "Limited {} credit for contributor {} from {} to {}: {}",
+                event_type.as_str(),
+                contributor_id,
+                delta,
+                limited.delta,
+                cap
+            );
+            Ok((limited.delta, event.with_reason(cap.to_string())))
         }
-    });
+        None => Ok((limited.delta, event)),
+    }
 }
 
-/// Spawn async comment evaluation task
-#[allow(clippy::too_many_arguments)]
-fn spawn_comment_evaluation(
-    state: AppState,
-    contributor_id: i64,
-    user_id: i64,
-    username: String,
-    repo_owner: String,
-    repo_name: String,
-    comment_body: String,
-    thread_context: String,
-) {
-    tokio::spawn(async move {
-        if let Err(e) = evaluate_and_apply_credit(
-            state,
-            contributor_id,
-            user_id,
-            username,
-            repo_owner,
-            repo_name,
-            EventType::Comment,
-            ContentType::Comment,
-            None,
-            comment_body.clone(),
-            None,
-            Some(thread_context),
-        )
+/// Fetch a PR's change stats and its diff fitted into the token budget
+///
+/// The unified diff is used when GitHub serves it; PRs too large for that
+/// fall back to the per-file patches. Returns `None` when neither can be
+/// fetched, in which case the PR is evaluated on its title and body alone.
+async fn fetch_pr_diff(
+    state: &AppState,
+    repo_owner: &str,
+    repo_name: &str,
+    pr_number: u64,
+) -> Option<(String, String)> {
+    let github = &state.github_client;
+    let stats = match github
+        .get_pull_request_stats(repo_owner, repo_name, pr_number)
         .await
-        {
-            error!("Failed to evaluate comment: {}", e);
+    {
+        Ok(stats) => stats,
+        Err(e) => {
+            warn!("Evaluating PR #{} without its diff: {}", pr_number, e);
+            return None;
+        }
+    };
+
+    let files = match github
+        .get_pull_request_diff(repo_owner, repo_name, pr_number)
+        .await
+    {
+        Ok(diff) => parse_unified_diff(&diff),
+        Err(e) => {
+            info!("Using file list for PR #{}: {}", pr_number, e);
+            match github
+                .list_pull_request_files(repo_owner, repo_name, pr_number)
+                .await
+            {
+                Ok(files) => files
+                    .into_iter()
+                    .map(|file| FileDiff {
+                        path: file.filename,
+                        additions: file.additions,
+                        deletions: file.deletions,
+                        patch: file.patch,
+                    })
+                    .collect(),
+                Err(e) => {
+                    warn!("Evaluating PR #{} without its diff: {}", pr_number, e);
+                    return None;
+                }
+            }
         }
-    });
+    };
+
+    let stats = DiffStats {
+        additions: stats.additions,
+        deletions: stats.deletions,
+        changed_files: stats.changed_files,
+    };
+    Some((
+        stats.to_string(),
+        budget_diff(&files, DEFAULT_DIFF_TOKEN_BUDGET),
+    ))
 }
 
 /// Evaluate content and apply credit based on confidence
-#[allow(clippy::too_many_arguments)]
-async fn evaluate_and_apply_credit(
-    state: AppState,
-    contributor_id: i64,
-    user_id: i64,
-    username: String,
-    repo_owner: String,
-    repo_name: String,
-    event_type: EventType,
-    content_type: ContentType,
-    title: Option<String>,
-    body: String,
-    diff_summary: Option<String>,
-    thread_context: Option<String>,
+pub(crate) async fn evaluate_and_apply_credit(
+    state: &AppState,
+    repo_config: &RepoConfig,
+    job: EvaluationJob,
 ) -> ApiResult<()> {
+    let EvaluationJob {
+        contributor_id,
+        user_id,
+        username,
+        repo_owner,
+        repo_name,
+        event_type,
+        content_type,
+        pr_number,
+        title,
+        body,
+        diff_summary,
+        thread_context,
+        thread,
+        source_id,
+        revision,
+        ..
+    } = job;
+
+    // A job run again after losing its lease finds its content already handled
+    if let (false, Some(source_id)) = (revision, &source_id) {
+        if !list_events_by_source(&state.db_pool, contributor_id, source_id)
+            .await?
+            .is_empty()
+            || count_evaluations_by_source(&state.db_pool, contributor_id, source_id).await? > 0
+        {
+            info!("{} was already evaluated, skipping", source_id);
+            return Ok(());
+        }
+    }
+
     // Acquire semaphore permit to limit concurrent evaluations
     let _permit = state.llm_semaphore.acquire().await.map_err(|e| {
         crate::error::ApiError::Internal(format!("Failed to acquire semaphore: {}", e))
@@ -972,12 +2894,74 @@ async fn evaluate_and_apply_credit(
             ContentType::PullRequest => "PR",
             ContentType::Comment => "comment",
             ContentType::Review => "review",
+            ContentType::Issue => "issue",
         },
         username,
         repo_owner,
         repo_name
     );
 
+    // PRs are judged on their diff, fitted into the token budget
+    let (diff_summary, diff) = match (content_type, pr_number, diff_summary) {
+        (ContentType::PullRequest, Some(pr_number), None) => {
+            match fetch_pr_diff(state, &repo_owner, &repo_name, pr_number as u64).await {
+                Some((stats, diff)) => (Some(stats), Some(diff)),
+                None => (None, None),
+            }
+        }
+        (_, _, diff_summary) => (diff_summary, None),
+    };
+
+    // Reviews scheduled by an inline comment do not know the review body and
+    // state yet; read them back from GitHub
+    let (body, thread) = match (thread, pr_number) {
+        (
+            Some(ThreadSource::Review {
+                review_id,
+                state: review_state,
+                submitted_at,
+            }),
+            Some(number),
+        ) if body.trim().is_empty() => {
+            match state
+                .github_client
+                .get_review(&repo_owner, &repo_name, number as u64, review_id as u64)
+                .await
+            {
+                Ok(review) => (
+                    review.body.unwrap_or_default(),
+                    Some(ThreadSource::Review {
+                        review_id,
+                        state: review.state,
+                        submitted_at: review.submitted_at,
+                    }),
+                ),
+                Err(e) => {
+                    warn!("Failed to read back review {}: {}", review_id, e);
+                    (
+                        body,
+                        Some(ThreadSource::Review {
+                            review_id,
+                            state: review_state,
+                            submitted_at,
+                        }),
+                    )
+                }
+            }
+        }
+        (thread, _) => (body, thread),
+    };
+
+    // Comments and reviews are judged with their conversation around them
+    let thread_context = match (&thread, pr_number) {
+        (Some(source), Some(number)) => {
+            build_thread_context(state, &repo_owner, &repo_name, number, source)
+                .await
+                .or(thread_context)
+        }
+        _ => thread_context,
+    };
+
     // Create evaluation context
     let context = EvalContext {
         content_type,
@@ -988,11 +2972,8 @@ async fn evaluate_and_apply_credit(
     };
 
     // Perform LLM evaluation
-    let evaluation = state
-        .llm_evaluator
-        .evaluate(&body, &context)
-        .await
-        .map_err(|e| crate::error::ApiError::Internal(format!("LLM evaluation failed: {}", e)))?;
+    let content = diff.as_deref().unwrap_or(&body);
+    let evaluation = state.llm_evaluator.evaluate(content, &context).await?;
 
     info!(
         "LLM evaluation for {}: {:?} (confidence: {})",
@@ -1000,115 +2981,122 @@ async fn evaluate_and_apply_credit(
     );
 
     // Calculate credit delta
-    let delta =
-        calculate_delta_with_config(&state.repo_config, event_type, evaluation.classification);
+    let delta = calculate_delta_with_config(repo_config, event_type, evaluation.classification);
+
+    // Edited content only moves the score by the difference to its earlier
+    // credit, recorded as an adjustment
+    let (delta, credit_event_type) = match (revision, &source_id) {
+        (true, Some(source_id)) => {
+            let events = list_events_by_source(&state.db_pool, contributor_id, source_id).await?;
+            if events.is_empty() {
+                info!("{} holds no credit, skipping its re-evaluation", source_id);
+                return Ok(());
+            }
+            let earned: i32 = events.iter().map(|event| event.delta).sum();
+            if delta == earned {
+                info!(
+                    "Edited {} still earns {} credit, nothing to adjust",
+                    source_id, earned
+                );
+                return Ok(());
+            }
+            (delta - earned, "edit_adjustment")
+        }
+        _ => (delta, event_type.as_str()),
+    };
 
     // Serialize LLM evaluation to JSON string
     let llm_eval_json_str = serde_json::to_string(&evaluation).map_err(|e| {
         crate::error::ApiError::Internal(format!("Failed to serialize LLM evaluation: {}", e))
     })?;
 
-    // Get current contributor state
-    let contributor = meritocrab_db::contributors::get_contributor(
-        &state.db_pool,
+    // Auto-apply or queue for review according to the repo's policy
+    let eval_id = format!(
+        "eval-{}-{}-{}",
         user_id,
-        &repo_owner,
-        &repo_name,
+        repo_name,
+        chrono::Utc::now().timestamp()
+    );
+    let decision = EvaluationState::new(
+        eval_id,
+        user_id,
+        format!("{:?}", evaluation.classification),
+        evaluation.confidence,
+        delta,
     )
-    .await?
-    .ok_or_else(|| crate::error::ApiError::Internal("Contributor not found".to_string()))?;
+    .decide(repo_config.auto_apply_threshold(event_type, delta));
 
-    let credit_before = contributor.credit_score;
-
-    // Check confidence threshold
-    if evaluation.confidence >= 0.85 {
-        // High confidence: apply credit automatically
-        let credit_after = apply_credit(credit_before, delta);
-
-        // Update contributor credit
-        update_credit_score(&state.db_pool, contributor_id, credit_after).await?;
-
-        // Log credit event with LLM evaluation
-        insert_credit_event(
-            &state.db_pool,
-            contributor_id,
-            match event_type {
-                EventType::PrOpened => "pr_opened",
-                EventType::Comment => "comment",
-                EventType::PrMerged => "pr_merged",
-                EventType::ReviewSubmitted => "review_submitted",
-            },
-            delta,
-            credit_before,
-            credit_after,
-            Some(llm_eval_json_str),
-            None,
-        )
-        .await?;
+    match (&decision.status, decision.get_final_delta()) {
+        (EvaluationStatus::AutoApplied, Some(final_delta)) => {
+            let (final_delta, credit_event) = limit_earned_credit(
+                state,
+                repo_config,
+                contributor_id,
+                event_type,
+                final_delta,
+                NewCreditEvent::new(credit_event_type)
+                    .with_llm_evaluation(llm_eval_json_str)
+                    .with_pr_number(pr_number)
+                    .with_source_id(source_id),
+            )
+            .await?;
 
-        info!(
-            "Applied {} credit to {} (confidence {:.2}, new score: {})",
-            delta, username, evaluation.confidence, credit_after
-        );
+            // Apply credit automatically, auto-blacklisting if credit drops
+            // to the threshold
+            let applied = match apply_credit_delta(
+                &state.db_pool,
+                contributor_id,
+                final_delta,
+                credit_event,
+                Some(repo_config.blacklist_threshold),
+            )
+            .await
+            {
+                Ok(applied) => applied,
+                Err(DbError::DuplicateSourceCredit(_, source_id)) => {
+                    info!("{} was credited concurrently, skipping", source_id);
+                    return Ok(());
+                }
+                Err(e) => return Err(e.into()),
+            };
+            let credit_after = applied.event.credit_after;
 
-        // Auto-blacklist if credit drops to 0 or below
-        if credit_after <= state.repo_config.blacklist_threshold
-            && credit_before > state.repo_config.blacklist_threshold
-        {
-            warn!(
-                "Auto-blacklisting user {} (credit dropped to {})",
-                username, credit_after
+            info!(
+                "Applied {} credit to {} (confidence {:.2}, new score: {})",
+                final_delta, username, decision.confidence, credit_after
             );
 
-            // Set blacklist flag
-            set_blacklisted(&state.db_pool, contributor_id, true).await?;
-
-            // Log auto-blacklist event
-            insert_credit_event(
+            if applied.auto_blacklisted {
+                warn!(
+                    "Auto-blacklisted user {} (credit dropped to {})",
+                    username, credit_after
+                );
+            }
+            notify_credit_applied(state, &repo_owner, &repo_name, &username, user_id, &applied)
+                .await;
+        }
+        _ => {
+            // Below the threshold or always reviewed: create pending evaluation
+            insert_evaluation_with_source(
                 &state.db_pool,
+                decision.id.clone(),
                 contributor_id,
-                "auto_blacklist",
-                0, // No delta for blacklist event
-                credit_after,
-                credit_after,
-                None,
-                Some(format!(
-                    "Auto-blacklisted due to credit dropping to {}",
-                    credit_after
-                )),
+                &repo_owner,
+                &repo_name,
+                decision.llm_classification,
+                decision.confidence,
+                decision.proposed_delta,
+                pr_number,
+                source_id,
             )
             .await?;
 
             info!(
-                "Successfully auto-blacklisted user {} (credit: {})",
-                username, credit_after
+                "Created pending evaluation {} for {} (confidence {:.2}, proposed delta: {})",
+                decision.id, username, decision.confidence, delta
             );
+            notify_pending_evaluations(state, &repo_owner, &repo_name).await;
         }
-    } else {
-        // Low confidence: create pending evaluation
-        let eval_id = format!(
-            "eval-{}-{}-{}",
-            user_id,
-            repo_name,
-            chrono::Utc::now().timestamp()
-        );
-
-        insert_evaluation(
-            &state.db_pool,
-            eval_id.clone(),
-            contributor_id,
-            &repo_owner,
-            &repo_name,
-            format!("{:?}", evaluation.classification),
-            evaluation.confidence,
-            delta,
-        )
-        .await?;
-
-        info!(
-            "Created pending evaluation {} for {} (confidence {:.2}, proposed delta: {})",
-            eval_id, username, evaluation.confidence, delta
-        );
     }
 
     Ok(())
@@ -1119,6 +3107,7 @@ mod tests {
     use super::*;
     use crate::{error::ApiError, state::AppState};
     use meritocrab_core::RepoConfig;
+    use meritocrab_db::run_migrations;
     use meritocrab_github::{GithubApiClient, WebhookSecret};
     use sqlx::any::AnyPoolOptions;
     use std::sync::Arc;
@@ -1141,12 +3130,9 @@ mod tests {
             .expect("Failed to enable foreign keys");
 
         // Run migrations
-        sqlx::query(include_str!(
-            "../../meritocrab-db/migrations/001_initial.sql"
-        ))
-        .execute(&pool)
-        .await
-        .expect("Failed to run migrations");
+        run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
 
         // Create mock GitHub client (will need to be updated with actual mock)
         let github_client = create_mock_github_client();
@@ -1225,11 +3211,60 @@ mod tests {
         let body = b"{invalid json}";
 
         let webhook_payload = VerifiedWebhookPayload(body.to_vec());
-        let result = handle_webhook(State(state), webhook_payload).await;
+        let result = handle_webhook(State(state), HeaderMap::new(), webhook_payload).await;
 
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_webhook_handler_push_event() {
+        let state = setup_test_state().await;
+        state
+            .repo_config_loader
+            .cache_config("owner", "repo", RepoConfig::default())
+            .await;
+        assert_eq!(state.repo_config_loader.cache_size().await, 1);
+
+        let payload = serde_json::json!({
+            "ref": "refs/heads/main",
+            "commits": [
+                {
+                    "id": "abc123",
+                    "added": [],
+                    "removed": [],
+                    "modified": [".meritocrab.toml"]
+                }
+            ],
+            "repository": {
+                "id": 1,
+                "name": "repo",
+                "full_name": "owner/repo",
+                "owner": {
+                    "id": 1,
+                    "login": "owner"
+                },
+                "default_branch": "main"
+            },
+            "sender": {
+                "id": 1,
+                "login": "owner"
+            }
+        });
+
+        let body = serde_json::to_vec(&payload).unwrap();
+        let result = handle_webhook(
+            State(state.clone()),
+            HeaderMap::new(),
+            VerifiedWebhookPayload(body),
+        )
+        .await;
+
+        assert!(result.is_ok());
+
+        // The push touched .meritocrab.toml on the default branch
+        assert_eq!(state.repo_config_loader.cache_size().await, 0);
+    }
+
     #[test]
     fn test_error_conversion() {
         let json_err = serde_json::from_str::<serde_json::Value>("{invalid}").unwrap_err();
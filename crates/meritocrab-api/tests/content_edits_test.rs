@@ -0,0 +1,690 @@
+/// Integration tests for adjusting credit when scored content is edited or deleted
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+    routing::get,
+};
+use hmac::{Hmac, Mac};
+use meritocrab_api::{AppState, JobQueueConfig, OAuthConfig, handle_webhook, spawn_job_worker};
+use meritocrab_core::{QualityLevel, RepoConfig, config::ScoringDelta};
+use meritocrab_db::{
+    contributors::get_contributor, credit_events::list_events_by_source, run_migrations,
+};
+use meritocrab_github::{GithubApiClient, WebhookSecret};
+use meritocrab_llm::{EvalContext, Evaluation, LlmError, LlmEvaluator};
+use serde_json::json;
+use sha2::Sha256;
+use sqlx::any::AnyPoolOptions;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::time::{Duration, sleep};
+use tower::ServiceExt;
+
+/// Evaluator that classifies sales pitches as spam and everything else as high quality
+#[derive(Default)]
+struct KeywordEvaluator {
+    calls: AtomicUsize,
+}
+
+#[async_trait::async_trait]
+impl LlmEvaluator for KeywordEvaluator {
+    async fn evaluate(
+        &self,
+        content: &str,
+        _context: &EvalContext,
+    ) -> Result<Evaluation, LlmError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        let classification = if content.contains("buy now") {
+            QualityLevel::Spam
+        } else {
+            QualityLevel::High
+        };
+        Ok(Evaluation::new(
+            classification,
+            0.95,
+            "Keyword classification".to_string(),
+        ))
+    }
+
+    fn provider_name(&self) -> String {
+        "test_keyword".to_string()
+    }
+}
+
+/// Serve a GitHub API stand-in with PR 7, no comments on it, and review 30
+/// whose body has since been edited into spam
+async fn spawn_mock_github() -> GithubApiClient {
+    let app = Router::new()
+        .route(
+            "/repos/{owner}/{repo}/issues/{number}",
+            get(|| async {
+                axum::Json(json!({
+                    "number": 7,
+                    "title": "Fix panic on empty config",
+                    "body": "Loading an empty config file panics.",
+                    "user": { "id": 1, "login": "alice", "type": "User" },
+                    "pull_request": { "url": "https://api.github.com/repos/owner/repo/pulls/7" }
+                }))
+            }),
+        )
+        .route(
+            "/repos/{owner}/{repo}/issues/{number}/comments",
+            get(|| async { axum::Json(json!([])) }),
+        )
+        .route(
+            "/repos/{owner}/{repo}/pulls/{number}/reviews/{review_id}",
+            get(|| async {
+                axum::Json(json!({
+                    "id": 30,
+                    "body": "Nice work, buy now at example.com",
+                    "user": { "id": 12345, "login": "testuser" },
+                    "state": "COMMENTED",
+                    "html_url": "https://github.com/owner/repo/pull/7#pullrequestreview-30"
+                }))
+            }),
+        )
+        .fallback(|| async {
+            (
+                StatusCode::NOT_FOUND,
+                axum::Json(json!({ "message": "Not Found", "documentation_url": "" })),
+            )
+        });
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    GithubApiClient::from_octocrab(
+        octocrab::Octocrab::builder()
+            .base_uri(format!("http://{}", addr))
+            .unwrap()
+            .personal_token("test-token".to_string())
+            .build()
+            .unwrap(),
+    )
+}
+
+async fn setup_test_state(evaluator: Arc<KeywordEvaluator>) -> AppState {
+    setup_test_state_with_config(evaluator, RepoConfig::default()).await
+}
+
+async fn setup_test_state_with_config(
+    evaluator: Arc<KeywordEvaluator>,
+    repo_config: RepoConfig,
+) -> AppState {
+    sqlx::any::install_default_drivers();
+    let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+
+    let pool = AnyPoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    run_migrations(&pool).await.unwrap();
+
+    let state = AppState::new(
+        pool,
+        spawn_mock_github().await,
+        repo_config,
+        WebhookSecret::new("test-secret".to_string()),
+        evaluator,
+        10,
+        OAuthConfig {
+            client_id: "test-client-id".to_string(),
+            client_secret: "test-client-secret".to_string(),
+            redirect_url: "http://localhost:8080/auth/callback".to_string(),
+        },
+        300,
+    );
+    spawn_job_worker(state.clone(), JobQueueConfig::default());
+    state
+}
+
+async fn send_webhook(state: AppState, payload: serde_json::Value) -> StatusCode {
+    let body = serde_json::to_vec(&payload).unwrap();
+    let mut mac = Hmac::<Sha256>::new_from_slice(b"test-secret").unwrap();
+    mac.update(&body);
+    let signature = format!("sha256={}", hex::encode(mac.finalize().into_bytes()));
+
+    Router::new()
+        .route("/webhooks/github", axum::routing::post(handle_webhook))
+        .with_state(state)
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/webhooks/github")
+                .header("Content-Type", "application/json")
+                .header("X-GitHub-Event", "issue_comment")
+                .header("X-Hub-Signature-256", signature)
+                .body(Body::from(body))
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+        .status()
+}
+
+/// Comment 20 on PR 7, posted at 10:00 and last updated at `updated_at`
+fn comment_payload(action: &str, body: &str, updated_at: &str) -> serde_json::Value {
+    numbered_comment_payload(20, action, body, updated_at)
+}
+
+/// Comment `comment_id` on PR 7, posted at 10:00 and last updated at `updated_at`
+fn numbered_comment_payload(
+    comment_id: i64,
+    action: &str,
+    body: &str,
+    updated_at: &str,
+) -> serde_json::Value {
+    json!({
+        "action": action,
+        "issue": {
+            "number": 7,
+            "title": "Fix panic on empty config",
+            "user": { "id": 1, "login": "alice" },
+            "pull_request": { "url": "https://api.github.com/repos/owner/repo/pulls/7" }
+        },
+        "comment": {
+            "id": comment_id,
+            "body": body,
+            "user": { "id": 12345, "login": "testuser" },
+            "html_url": format!("https://github.com/owner/repo/pull/7#issuecomment-{}", comment_id),
+            "created_at": "2026-01-01T10:00:00Z",
+            "updated_at": updated_at
+        },
+        "repository": {
+            "id": 1,
+            "name": "repo",
+            "full_name": "owner/repo",
+            "owner": { "id": 1, "login": "owner" }
+        },
+        "sender": { "id": 12345, "login": "testuser" }
+    })
+}
+
+fn repository() -> serde_json::Value {
+    json!({
+        "id": 1,
+        "name": "repo",
+        "full_name": "owner/repo",
+        "owner": { "id": 1, "login": "owner" }
+    })
+}
+
+/// PR 7 by the test user, opened at 10:00 and last updated at `updated_at`
+fn pr_payload(action: &str, body: &str, updated_at: &str) -> serde_json::Value {
+    json!({
+        "action": action,
+        "number": 7,
+        "pull_request": {
+            "number": 7,
+            "title": "Fix panic on empty config",
+            "body": body,
+            "user": { "id": 12345, "login": "testuser" },
+            "state": "open",
+            "merged": false,
+            "html_url": "https://github.com/owner/repo/pull/7",
+            "created_at": "2026-01-01T10:00:00Z",
+            "updated_at": updated_at
+        },
+        "repository": repository(),
+        "sender": { "id": 12345, "login": "testuser" }
+    })
+}
+
+/// Issue 8 by the test user, opened at 10:00 and last updated at `updated_at`
+fn issue_payload(action: &str, body: &str, updated_at: &str) -> serde_json::Value {
+    json!({
+        "action": action,
+        "issue": {
+            "number": 8,
+            "title": "Crash when the config file is missing",
+            "body": body,
+            "user": { "id": 12345, "login": "testuser" },
+            "created_at": "2026-01-01T10:00:00Z",
+            "updated_at": updated_at
+        },
+        "repository": repository(),
+        "sender": { "id": 12345, "login": "testuser" }
+    })
+}
+
+/// Review 30 on PR 7, submitted at 10:00
+fn review_payload(body: &str) -> serde_json::Value {
+    json!({
+        "action": "submitted",
+        "review": {
+            "id": 30,
+            "body": body,
+            "user": { "id": 12345, "login": "testuser" },
+            "state": "commented",
+            "html_url": "https://github.com/owner/repo/pull/7#pullrequestreview-30",
+            "submitted_at": "2026-01-01T10:00:00Z"
+        },
+        "pull_request": {
+            "number": 7,
+            "title": "Fix panic on empty config",
+            "body": "Loading an empty config file panics.",
+            "user": { "id": 1, "login": "alice" },
+            "state": "open",
+            "merged": false,
+            "html_url": "https://github.com/owner/repo/pull/7"
+        },
+        "repository": repository(),
+        "sender": { "id": 12345, "login": "testuser" }
+    })
+}
+
+/// Inline comment 40 on PR 7, posted at 10:00 (in review 30 if given) and
+/// last updated at `updated_at`
+fn review_comment_payload(
+    action: &str,
+    review_id: Option<i64>,
+    body: &str,
+    updated_at: &str,
+) -> serde_json::Value {
+    json!({
+        "action": action,
+        "comment": {
+            "id": 40,
+            "body": body,
+            "path": "src/config.rs",
+            "diff_hunk": "@@ -1,3 +1,4 @@\n fn load() {\n+    parse()",
+            "user": { "id": 12345, "login": "testuser" },
+            "pull_request_review_id": review_id,
+            "created_at": "2026-01-01T10:00:00Z",
+            "updated_at": updated_at
+        },
+        "pull_request": {
+            "number": 7,
+            "title": "Fix panic on empty config",
+            "body": "Loading an empty config file panics.",
+            "user": { "id": 1, "login": "alice" },
+            "state": "open",
+            "merged": false,
+            "html_url": "https://github.com/owner/repo/pull/7"
+        },
+        "repository": repository(),
+        "sender": { "id": 12345, "login": "testuser" }
+    })
+}
+
+/// Credit events the test user holds for one piece of content
+async fn source_events(state: &AppState, source_id: &str) -> Vec<(String, i32)> {
+    let contributor = get_contributor(&state.db_pool, 12345, "owner", "repo")
+        .await
+        .unwrap()
+        .unwrap();
+    list_events_by_source(&state.db_pool, contributor.id, source_id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|event| (event.event_type, event.delta))
+        .collect()
+}
+
+async fn send_and_settle(state: &AppState, payload: serde_json::Value) {
+    assert_eq!(send_webhook(state.clone(), payload).await, StatusCode::OK);
+    sleep(Duration::from_millis(300)).await;
+}
+
+async fn credit_score(state: &AppState) -> i32 {
+    get_contributor(&state.db_pool, 12345, "owner", "repo")
+        .await
+        .unwrap()
+        .unwrap()
+        .credit_score
+}
+
+#[tokio::test]
+async fn test_edit_into_spam_applies_difference() {
+    let evaluator = Arc::new(KeywordEvaluator::default());
+    let state = setup_test_state(evaluator.clone()).await;
+
+    let created = comment_payload(
+        "created",
+        "This also fails with a missing file.",
+        "2026-01-01T10:00:00Z",
+    );
+    send_and_settle(&state, created).await;
+    assert_eq!(credit_score(&state).await, 103);
+
+    let edited = comment_payload(
+        "edited",
+        "Great tool, buy now at example.com",
+        "2026-01-01T11:00:00Z",
+    );
+    send_and_settle(&state, edited).await;
+
+    // The comment now scores as spam (-10), so 13 is taken from the +3 earned
+    assert_eq!(credit_score(&state).await, 90);
+    let contributor = get_contributor(&state.db_pool, 12345, "owner", "repo")
+        .await
+        .unwrap()
+        .unwrap();
+    let events = list_events_by_source(&state.db_pool, contributor.id, "issue_comment:20")
+        .await
+        .unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].event_type, "comment");
+    assert_eq!(events[0].delta, 3);
+    assert_eq!(events[1].event_type, "edit_adjustment");
+    assert_eq!(events[1].delta, -13);
+    assert_eq!(events[1].pr_number, Some(7));
+}
+
+#[tokio::test]
+async fn test_edit_within_grace_window_ignored() {
+    let evaluator = Arc::new(KeywordEvaluator::default());
+    let state = setup_test_state(evaluator.clone()).await;
+
+    let created = comment_payload(
+        "created",
+        "This also fails with a missing file.",
+        "2026-01-01T10:00:00Z",
+    );
+    send_and_settle(&state, created).await;
+
+    let edited = comment_payload(
+        "edited",
+        "Great tool, buy now at example.com",
+        "2026-01-01T10:00:30Z",
+    );
+    send_and_settle(&state, edited).await;
+
+    assert_eq!(evaluator.calls.load(Ordering::SeqCst), 1);
+    assert_eq!(credit_score(&state).await, 103);
+}
+
+#[tokio::test]
+async fn test_deleted_comment_credit_reversed() {
+    let evaluator = Arc::new(KeywordEvaluator::default());
+    let state = setup_test_state(evaluator.clone()).await;
+
+    let created = comment_payload(
+        "created",
+        "This also fails with a missing file.",
+        "2026-01-01T10:00:00Z",
+    );
+    send_and_settle(&state, created).await;
+    assert_eq!(credit_score(&state).await, 103);
+
+    let deleted = comment_payload(
+        "deleted",
+        "This also fails with a missing file.",
+        "2026-01-01T10:00:00Z",
+    );
+    send_and_settle(&state, deleted.clone()).await;
+    assert_eq!(credit_score(&state).await, 100);
+
+    // A repeated delivery finds nothing left to reverse
+    send_and_settle(&state, deleted).await;
+    assert_eq!(credit_score(&state).await, 100);
+
+    let contributor = get_contributor(&state.db_pool, 12345, "owner", "repo")
+        .await
+        .unwrap()
+        .unwrap();
+    let events = list_events_by_source(&state.db_pool, contributor.id, "issue_comment:20")
+        .await
+        .unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[1].event_type, "reversal");
+    assert_eq!(events[1].delta, -3);
+}
+
+#[tokio::test]
+async fn test_deleting_penalized_comment_keeps_penalty() {
+    let evaluator = Arc::new(KeywordEvaluator::default());
+    let state = setup_test_state(evaluator.clone()).await;
+
+    let created = comment_payload(
+        "created",
+        "Great tool, buy now at example.com",
+        "2026-01-01T10:00:00Z",
+    );
+    send_and_settle(&state, created).await;
+    assert_eq!(credit_score(&state).await, 90);
+
+    let deleted = comment_payload(
+        "deleted",
+        "Great tool, buy now at example.com",
+        "2026-01-01T10:00:00Z",
+    );
+    send_and_settle(&state, deleted).await;
+    assert_eq!(credit_score(&state).await, 90);
+}
+
+#[tokio::test]
+async fn test_edit_upgrade_counts_toward_daily_cap() {
+    let evaluator = Arc::new(KeywordEvaluator::default());
+    let mut repo_config = RepoConfig::default();
+    repo_config.earning_limits.daily_cap.comment = Some(5);
+    let state = setup_test_state_with_config(evaluator, repo_config).await;
+
+    // A spam comment (-10) and a high-quality one (+3)
+    let spam = numbered_comment_payload(
+        20,
+        "created",
+        "Great tool, buy now at example.com",
+        "2026-01-01T10:00:00Z",
+    );
+    send_and_settle(&state, spam).await;
+    let good = numbered_comment_payload(
+        21,
+        "created",
+        "This also fails with a missing file.",
+        "2026-01-01T10:00:00Z",
+    );
+    send_and_settle(&state, good).await;
+    assert_eq!(credit_score(&state).await, 93);
+
+    // Editing the spam into a good comment would earn +13; only 2 remain
+    let edited = numbered_comment_payload(
+        20,
+        "edited",
+        "It also fails when the file is missing.",
+        "2026-01-01T11:00:00Z",
+    );
+    send_and_settle(&state, edited).await;
+    assert_eq!(credit_score(&state).await, 95);
+
+    let contributor = get_contributor(&state.db_pool, 12345, "owner", "repo")
+        .await
+        .unwrap()
+        .unwrap();
+    let events = list_events_by_source(&state.db_pool, contributor.id, "issue_comment:20")
+        .await
+        .unwrap();
+    assert_eq!(events[1].event_type, "edit_adjustment");
+    assert_eq!(events[1].delta, 2);
+    assert_eq!(
+        events[1].reason.as_deref(),
+        Some("Daily credit cap of 5 reached")
+    );
+
+    // The upgrade used up the cap, so the next comment earns nothing
+    let next = numbered_comment_payload(
+        22,
+        "created",
+        "The same happens with an unreadable file.",
+        "2026-01-01T12:00:00Z",
+    );
+    send_and_settle(&state, next).await;
+    assert_eq!(credit_score(&state).await, 95);
+}
+
+#[tokio::test]
+async fn test_pr_edit_applies_difference() {
+    let evaluator = Arc::new(KeywordEvaluator::default());
+    let state = setup_test_state(evaluator.clone()).await;
+
+    let opened = pr_payload(
+        "opened",
+        "Loading an empty config file panics.",
+        "2026-01-01T10:00:00Z",
+    );
+    send_and_settle(&state, opened).await;
+    assert_eq!(credit_score(&state).await, 115);
+
+    let edited = pr_payload(
+        "edited",
+        "Great tool, buy now at example.com",
+        "2026-01-01T11:00:00Z",
+    );
+    send_and_settle(&state, edited).await;
+
+    // The PR now scores as spam (-25), so 40 is taken from the +15 earned
+    assert_eq!(credit_score(&state).await, 75);
+    assert_eq!(
+        source_events(&state, "pull_request:7").await,
+        vec![
+            ("pr_opened".to_string(), 15),
+            ("edit_adjustment".to_string(), -40)
+        ]
+    );
+}
+
+fn issue_scoring_config() -> RepoConfig {
+    RepoConfig {
+        issue_opened: Some(ScoringDelta {
+            spam: -5,
+            low: -1,
+            acceptable: 2,
+            high: 4,
+        }),
+        ..RepoConfig::default()
+    }
+}
+
+#[tokio::test]
+async fn test_issue_edit_applies_difference() {
+    let evaluator = Arc::new(KeywordEvaluator::default());
+    let state = setup_test_state_with_config(evaluator.clone(), issue_scoring_config()).await;
+
+    let opened = issue_payload(
+        "opened",
+        "Starting without a config file crashes.",
+        "2026-01-01T10:00:00Z",
+    );
+    send_and_settle(&state, opened).await;
+    assert_eq!(credit_score(&state).await, 104);
+
+    // Edits within the grace window are not re-evaluated
+    let typo_fix = issue_payload(
+        "edited",
+        "Great tool, buy now at example.com",
+        "2026-01-01T10:00:30Z",
+    );
+    send_and_settle(&state, typo_fix).await;
+    assert_eq!(evaluator.calls.load(Ordering::SeqCst), 1);
+
+    let edited = issue_payload(
+        "edited",
+        "Great tool, buy now at example.com",
+        "2026-01-01T11:00:00Z",
+    );
+    send_and_settle(&state, edited).await;
+    assert_eq!(credit_score(&state).await, 95);
+    assert_eq!(
+        source_events(&state, "issue:8").await,
+        vec![
+            ("issue_opened".to_string(), 4),
+            ("edit_adjustment".to_string(), -9)
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_deleted_issue_credit_reversed() {
+    let evaluator = Arc::new(KeywordEvaluator::default());
+    let state = setup_test_state_with_config(evaluator, issue_scoring_config()).await;
+
+    let opened = issue_payload(
+        "opened",
+        "Starting without a config file crashes.",
+        "2026-01-01T10:00:00Z",
+    );
+    send_and_settle(&state, opened).await;
+    assert_eq!(credit_score(&state).await, 104);
+
+    let deleted = issue_payload(
+        "deleted",
+        "Starting without a config file crashes.",
+        "2026-01-01T10:00:00Z",
+    );
+    send_and_settle(&state, deleted).await;
+    assert_eq!(credit_score(&state).await, 100);
+    assert_eq!(
+        source_events(&state, "issue:8").await,
+        vec![
+            ("issue_opened".to_string(), 4),
+            ("reversal".to_string(), -4)
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_review_comment_edit_reevaluates_review() {
+    let evaluator = Arc::new(KeywordEvaluator::default());
+    let state = setup_test_state(evaluator).await;
+
+    send_and_settle(
+        &state,
+        review_payload("Looks good, but check the error path."),
+    )
+    .await;
+    assert_eq!(credit_score(&state).await, 105);
+
+    // The review read back from GitHub has been edited into spam (0)
+    let edited = review_comment_payload(
+        "edited",
+        Some(30),
+        "Great tool, buy now at example.com",
+        "2026-01-01T11:00:00Z",
+    );
+    send_and_settle(&state, edited).await;
+    assert_eq!(credit_score(&state).await, 100);
+    assert_eq!(
+        source_events(&state, "review:30").await,
+        vec![
+            ("review_submitted".to_string(), 5),
+            ("edit_adjustment".to_string(), -5)
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_deleted_review_comment_credit_reversed() {
+    let evaluator = Arc::new(KeywordEvaluator::default());
+    let state = setup_test_state(evaluator).await;
+
+    // An inline comment outside any review is scored on its own
+    let created = review_comment_payload(
+        "created",
+        None,
+        "This unwrap panics on an empty file.",
+        "2026-01-01T10:00:00Z",
+    );
+    send_and_settle(&state, created).await;
+    assert_eq!(credit_score(&state).await, 105);
+
+    let deleted = review_comment_payload(
+        "deleted",
+        None,
+        "This unwrap panics on an empty file.",
+        "2026-01-01T10:00:00Z",
+    );
+    send_and_settle(&state, deleted).await;
+    assert_eq!(credit_score(&state).await, 100);
+    assert_eq!(
+        source_events(&state, "review_comment:40").await,
+        vec![
+            ("review_submitted".to_string(), 5),
+            ("reversal".to_string(), -5)
+        ]
+    );
+}
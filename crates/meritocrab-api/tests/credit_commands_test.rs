@@ -1,8 +1,33 @@
-use meritocrab_api::{AppState, OAuthConfig, credit_commands::*};
+use axum::{
+    Router,
+    body::Body,
+    extract::Path,
+    http::{Request, StatusCode},
+};
+use hmac::{Hmac, Mac};
+use meritocrab_api::{
+    AppState, OAuthConfig, SelfServiceLimiter,
+    credit_commands::*,
+    handle_webhook,
+    rate_limit::QuotaConfig,
+    user_resolver::{resolve_contributor, resolve_or_create_contributor, resolve_user},
+};
 use meritocrab_core::RepoConfig;
+use meritocrab_db::{
+    contributors::{create_contributor, get_contributor, set_blacklisted, update_login},
+    credit_events::{insert_credit_event_with_pr, list_events_by_contributor},
+    run_migrations,
+};
 use meritocrab_github::{GithubApiClient, WebhookSecret};
+use serde_json::json;
+use sha2::Sha256;
 use sqlx::any::AnyPoolOptions;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use tower::ServiceExt;
+
+type HmacSha256 = Hmac<Sha256>;
+
+type Comments = Arc<Mutex<Vec<String>>>;
 
 fn test_oauth_config() -> OAuthConfig {
     OAuthConfig {
@@ -13,6 +38,17 @@ fn test_oauth_config() -> OAuthConfig {
 }
 
 async fn setup_test_state() -> AppState {
+    // Initialize rustls for GitHub client
+    let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+
+    // Create mock GitHub client
+    let github_client =
+        GithubApiClient::new("test-token".to_string()).expect("Failed to create GitHub client");
+
+    setup_test_state_with_github(github_client).await
+}
+
+async fn setup_test_state_with_github(github_client: GithubApiClient) -> AppState {
     // Install SQLite driver
     sqlx::any::install_default_drivers();
 
@@ -30,19 +66,9 @@ async fn setup_test_state() -> AppState {
         .expect("Failed to enable foreign keys");
 
     // Run migrations
-    sqlx::query(include_str!(
-        "../../meritocrab-db/migrations/001_initial.sql"
-    ))
-    .execute(&pool)
-    .await
-    .expect("Failed to run migrations");
-
-    // Initialize rustls for GitHub client
-    let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
-
-    // Create mock GitHub client
-    let github_client =
-        GithubApiClient::new("test-token".to_string()).expect("Failed to create GitHub client");
+    run_migrations(&pool)
+        .await
+        .expect("Failed to run migrations");
 
     // Create mock LLM evaluator
     let llm_evaluator = Arc::new(meritocrab_llm::MockEvaluator::new());
@@ -62,6 +88,171 @@ async fn setup_test_state() -> AppState {
     )
 }
 
+/// Start a local server that mimics the GitHub users API
+///
+/// Knows `NewComer` (ID 999) and the all-digit login `2024` (ID 777); every
+/// other login is a 404.
+async fn spawn_mock_github() -> GithubApiClient {
+    let app = Router::new().route(
+        "/users/{login}",
+        axum::routing::get(|Path(login): Path<String>| async move {
+            if login.eq_ignore_ascii_case("newcomer") {
+                (
+                    StatusCode::OK,
+                    axum::Json(json!({ "id": 999, "login": "NewComer", "type": "User" })),
+                )
+            } else if login == "2024" {
+                (
+                    StatusCode::OK,
+                    axum::Json(json!({ "id": 777, "login": "2024", "type": "User" })),
+                )
+            } else {
+                (
+                    StatusCode::NOT_FOUND,
+                    axum::Json(json!({ "message": "Not Found", "documentation_url": "" })),
+                )
+            }
+        }),
+    );
+
+    let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let octocrab = octocrab::Octocrab::builder()
+        .base_uri(format!("http://{}", addr))
+        .unwrap()
+        .personal_token("test-token".to_string())
+        .build()
+        .unwrap();
+    GithubApiClient::from_octocrab(octocrab)
+}
+
+/// GitHub user object with every field octocrab requires
+fn github_user() -> serde_json::Value {
+    let url = "https://api.github.com/users/meritocrab";
+    json!({
+        "login": "meritocrab", "id": 1, "node_id": "U_1",
+        "avatar_url": url, "gravatar_id": "", "url": url, "html_url": url,
+        "followers_url": url, "following_url": url, "gists_url": url,
+        "starred_url": url, "subscriptions_url": url, "organizations_url": url,
+        "repos_url": url, "events_url": url, "received_events_url": url,
+        "type": "Bot", "site_admin": false
+    })
+}
+
+/// Start a local server that answers the calls maintainer commands make
+///
+/// Knows `maintainer` as an admin of `owner/repo` and records the body of
+/// every comment posted on issue 1; everything else is a 404.
+async fn spawn_commenting_github() -> (GithubApiClient, Comments) {
+    let comments: Comments = Arc::new(Mutex::new(Vec::new()));
+    let recorded = comments.clone();
+
+    let app = Router::new()
+        .route(
+            "/repos/owner/repo/collaborators/maintainer/permission",
+            axum::routing::get(|| async {
+                let mut user = github_user();
+                user["permissions"] = json!({ "admin": true, "push": true, "pull": true });
+                axum::Json(json!({ "permission": "admin", "role_name": "admin", "user": user }))
+            }),
+        )
+        .route(
+            "/repos/owner/repo/issues/1/comments",
+            axum::routing::post(move |axum::Json(body): axum::Json<serde_json::Value>| {
+                let recorded = recorded.clone();
+                async move {
+                    recorded
+                        .lock()
+                        .unwrap()
+                        .push(body["body"].as_str().unwrap_or_default().to_string());
+                    axum::Json(json!({
+                        "id": 1,
+                        "node_id": "IC_1",
+                        "url": "https://api.github.com/repos/owner/repo/issues/comments/1",
+                        "html_url": "https://github.com/owner/repo/pull/1#issuecomment-1",
+                        "body": body["body"],
+                        "user": github_user(),
+                        "created_at": "2026-01-01T00:00:00Z"
+                    }))
+                }
+            }),
+        )
+        .fallback(|| async {
+            (
+                StatusCode::NOT_FOUND,
+                axum::Json(json!({ "message": "Not Found", "documentation_url": "" })),
+            )
+        });
+
+    let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let octocrab = octocrab::Octocrab::builder()
+        .base_uri(format!("http://{}", addr))
+        .unwrap()
+        .personal_token("test-token".to_string())
+        .build()
+        .unwrap();
+    (GithubApiClient::from_octocrab(octocrab), comments)
+}
+
+/// Send a signed `issue_comment` webhook for a comment on PR #1 of owner/repo
+async fn comment(state: AppState, login: &str, body: &str) -> StatusCode {
+    let payload = json!({
+        "action": "created",
+        "issue": {
+            "number": 1,
+            "title": "Test PR",
+            "user": { "id": 12345, "login": "testuser" },
+            "pull_request": { "url": "https://api.github.com/repos/owner/repo/pulls/1" }
+        },
+        "comment": {
+            "id": 1,
+            "body": body,
+            "user": { "id": 999, "login": login },
+            "html_url": "https://github.com/owner/repo/pull/1#issuecomment-1"
+        },
+        "repository": {
+            "id": 1,
+            "name": "repo",
+            "full_name": "owner/repo",
+            "owner": { "id": 1, "login": "owner" }
+        },
+        "sender": { "id": 999, "login": login }
+    });
+
+    let body = serde_json::to_vec(&payload).unwrap();
+    let mut mac = HmacSha256::new_from_slice(b"test-secret").unwrap();
+    mac.update(&body);
+    let signature = format!("sha256={}", hex::encode(mac.finalize().into_bytes()));
+
+    let app = Router::new()
+        .route("/webhooks/github", axum::routing::post(handle_webhook))
+        .with_state(state);
+    app.oneshot(
+        Request::builder()
+            .method("POST")
+            .uri("/webhooks/github")
+            .header("Content-Type", "application/json")
+            .header("X-GitHub-Event", "issue_comment")
+            .header("X-Hub-Signature-256", signature)
+            .body(Body::from(body))
+            .unwrap(),
+    )
+    .await
+    .unwrap()
+    .status()
+}
+
 #[tokio::test]
 async fn test_parse_credit_check_command() {
     let comment = "/credit check @user123";
@@ -145,7 +336,7 @@ async fn test_repo_config_loader_returns_defaults() {
     // Get config for a non-existent repo (should return defaults)
     let config = state
         .repo_config_loader
-        .get_config("test-owner", "test-repo")
+        .get_config(&state.github_client, "test-owner", "test-repo")
         .await;
 
     // Verify defaults
@@ -361,3 +552,263 @@ async fn test_list_credit_events_for_contributor() {
     assert_eq!(events[0].delta, 4);
     assert_eq!(events[4].delta, 0);
 }
+
+#[tokio::test]
+async fn test_resolve_user_by_stored_login() {
+    let state = setup_test_state_with_github(spawn_mock_github().await).await;
+
+    let contributor = create_contributor(&state.db_pool, 12345, "test-owner", "test-repo", 100)
+        .await
+        .unwrap();
+    update_login(&state.db_pool, 12345, "octo-cat")
+        .await
+        .unwrap();
+
+    // Case-insensitive, with or without the leading @
+    let resolved = resolve_contributor(&state, "@Octo-Cat", "test-owner", "test-repo")
+        .await
+        .unwrap()
+        .expect("Contributor should resolve by login");
+    assert_eq!(resolved.id, contributor.id);
+    assert_eq!(resolved.login.as_deref(), Some("octo-cat"));
+
+    // Numeric references that are nobody's login are GitHub user IDs
+    let resolved = resolve_contributor(&state, "12345", "test-owner", "test-repo")
+        .await
+        .unwrap()
+        .expect("Contributor should resolve by ID");
+    assert_eq!(resolved.id, contributor.id);
+}
+
+#[tokio::test]
+async fn test_resolve_all_digit_login_before_user_id() {
+    let state = setup_test_state_with_github(spawn_mock_github().await).await;
+
+    let user = resolve_user(&state, "@2024")
+        .await
+        .unwrap()
+        .expect("All-digit login should resolve");
+    assert_eq!(user.github_user_id, 777);
+    assert_eq!(user.login, "2024");
+}
+
+#[tokio::test]
+async fn test_resolve_unseen_user_via_github_api() {
+    let state = setup_test_state_with_github(spawn_mock_github().await).await;
+
+    let user = resolve_user(&state, "@newcomer")
+        .await
+        .unwrap()
+        .expect("User should resolve through the GitHub API");
+    assert_eq!(user.github_user_id, 999);
+    assert_eq!(user.login, "NewComer");
+
+    // Not a contributor yet
+    assert!(
+        resolve_contributor(&state, "@newcomer", "test-owner", "test-repo")
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    // Maintainer actions create the contributor with their login
+    let contributor =
+        resolve_or_create_contributor(&state, "@newcomer", "test-owner", "test-repo", 100)
+            .await
+            .unwrap()
+            .expect("Contributor should be created");
+    assert_eq!(contributor.github_user_id, 999);
+    assert_eq!(contributor.login.as_deref(), Some("NewComer"));
+    assert_eq!(contributor.credit_score, 100);
+
+    // Unknown GitHub users do not resolve
+    assert!(resolve_user(&state, "@ghost").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_resolve_user_after_rename() {
+    let state = setup_test_state_with_github(spawn_mock_github().await).await;
+
+    // We saw user 999 under an old login
+    create_contributor(&state.db_pool, 999, "test-owner", "test-repo", 100)
+        .await
+        .unwrap();
+    update_login(&state.db_pool, 999, "old-name").await.unwrap();
+
+    // The new login is unknown locally, so the API lookup records the rename
+    let contributor = resolve_contributor(&state, "@NewComer", "test-owner", "test-repo")
+        .await
+        .unwrap()
+        .expect("Renamed contributor should resolve");
+    assert_eq!(contributor.github_user_id, 999);
+    assert_eq!(contributor.login.as_deref(), Some("NewComer"));
+}
+
+#[tokio::test]
+async fn test_maintainer_commands_reply_in_thread() {
+    let (github_client, comments) = spawn_commenting_github().await;
+    let state = setup_test_state_with_github(github_client).await;
+
+    let alice = create_contributor(&state.db_pool, 12345, "owner", "repo", 100)
+        .await
+        .unwrap();
+    update_login(&state.db_pool, 12345, "alice").await.unwrap();
+    let bob = create_contributor(&state.db_pool, 23456, "owner", "repo", 150)
+        .await
+        .unwrap();
+    update_login(&state.db_pool, 23456, "bob").await.unwrap();
+    set_blacklisted(&state.db_pool, bob.id, true).await.unwrap();
+
+    // Non-maintainers are silently ignored
+    comment(state.clone(), "testuser", "/credit help").await;
+    assert!(comments.lock().unwrap().is_empty());
+
+    comment(state.clone(), "maintainer", "/credit help").await;
+    comment(
+        state.clone(),
+        "maintainer",
+        r#"/credit set @alice 120 "restored after review""#,
+    )
+    .await;
+    comment(state.clone(), "maintainer", "/credit history @alice 5").await;
+    comment(state.clone(), "maintainer", "/credit leaderboard").await;
+    comment(state.clone(), "maintainer", "/credit unblacklist @bob").await;
+    comment(state.clone(), "maintainer", "/credit history @ghost").await;
+
+    let comments = comments.lock().unwrap().clone();
+    assert_eq!(comments.len(), 6);
+    assert_eq!(comments[0], HELP_TEXT);
+    assert_eq!(
+        comments[1],
+        "Credit set for @alice: **100 → 120** (delta: +20)\n\nReason: restored after review"
+    );
+    assert!(comments[2].starts_with("**Credit History for @alice** (last 1 events)"));
+    assert!(comments[2].contains("`manual_adjustment`: +20 (100 -> 120)"));
+    assert!(comments[2].contains("Set to 120: restored after review"));
+    // Blacklisted contributors stay off the public leaderboard
+    assert!(comments[3].contains("| 1 | @alice | 120 |"));
+    assert!(!comments[3].contains("@bob"));
+    assert_eq!(comments[4], "User status updated.");
+    assert_eq!(comments[5], "Contributor @ghost not found in owner/repo.");
+
+    let alice = get_contributor(&state.db_pool, alice.github_user_id, "owner", "repo")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(alice.credit_score, 120);
+
+    let bob = get_contributor(&state.db_pool, bob.github_user_id, "owner", "repo")
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!bob.is_blacklisted);
+    let events = list_events_by_contributor(&state.db_pool, bob.id, 10, 0)
+        .await
+        .unwrap();
+    assert_eq!(events[0].event_type, "blacklist_removed");
+}
+
+#[tokio::test]
+async fn test_invalid_command_replies_with_usage() {
+    let (github_client, comments) = spawn_commenting_github().await;
+    let state = setup_test_state_with_github(github_client).await;
+    create_contributor(&state.db_pool, 12345, "owner", "repo", 100)
+        .await
+        .unwrap();
+    update_login(&state.db_pool, 12345, "my-user")
+        .await
+        .unwrap();
+
+    // Invalid commands from non-maintainers get no reply either
+    comment(state.clone(), "testuser", "/credit override @my-user +10").await;
+    assert!(comments.lock().unwrap().is_empty());
+
+    let body = "Two fixes:\n\
+                /credit override @my-user +10\n\
+                /credit override @my-user +10 'first-time contributor'\n\
+                > /credit blacklist @my-user";
+    comment(state.clone(), "maintainer", body).await;
+
+    let comments = comments.lock().unwrap().clone();
+    assert_eq!(
+        comments,
+        vec![
+            "Could not parse `/credit` command: Missing reason.\n\n\
+             Usage: `/credit override @user +10 \"reason\"`"
+                .to_string(),
+            "Credit adjusted for @my-user: **100 → 110** (delta: +10)\n\n\
+             Reason: first-time contributor"
+                .to_string(),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_credit_me_for_contributors() {
+    let (github_client, comments) = spawn_commenting_github().await;
+    // No HTTP rate limiters are set up, as when a proxy handles them
+    let limiter = SelfServiceLimiter::new(QuotaConfig {
+        burst_size: 2,
+        replenish_interval_ms: 600_000,
+    })
+    .unwrap();
+    let state = setup_test_state_with_github(github_client)
+        .await
+        .with_self_service_limiter(limiter);
+
+    // The commenter (ID 999) is shadow-blacklisted after a spam PR
+    let contributor = create_contributor(&state.db_pool, 999, "owner", "repo", 30)
+        .await
+        .unwrap();
+    insert_credit_event_with_pr(
+        &state.db_pool,
+        contributor.id,
+        "pr_opened",
+        -25,
+        55,
+        30,
+        None,
+        None,
+        Some(7),
+    )
+    .await
+    .unwrap();
+    meritocrab_db::credit_events::insert_credit_event(
+        &state.db_pool,
+        contributor.id,
+        "blacklist_added",
+        0,
+        30,
+        30,
+        None,
+        Some("Manually blacklisted by maintainer".to_string()),
+    )
+    .await
+    .unwrap();
+    set_blacklisted(&state.db_pool, contributor.id, true)
+        .await
+        .unwrap();
+
+    // Other commands stay maintainer-only, even next to `/credit me`
+    comment(
+        state.clone(),
+        "testuser",
+        "/credit check @someone\n/credit me\n/credit me",
+    )
+    .await;
+
+    let replies = comments.lock().unwrap().clone();
+    assert_eq!(replies.len(), 1);
+    let report = &replies[0];
+    assert!(report.starts_with("**Your Credit, @testuser**"));
+    assert!(report.contains("- Credit Score: **30**"));
+    assert!(report.contains("- PR Threshold: **50**"));
+    assert!(report.contains("You need **20** more credit to reach the PR threshold."));
+    assert!(report.contains("- `pr_opened` on #7: -25"));
+    assert!(!report.to_lowercase().contains("blacklist"));
+
+    // One more reply fits the quota, then further requests are dropped
+    comment(state.clone(), "testuser", "/credit me").await;
+    comment(state.clone(), "testuser", "/credit me").await;
+    assert_eq!(comments.lock().unwrap().len(), 2);
+}
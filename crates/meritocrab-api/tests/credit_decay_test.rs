@@ -0,0 +1,296 @@
+/// Integration tests for credit decay of inactive contributors
+use axum::{Router, http::StatusCode};
+use chrono::{Duration, Utc};
+use meritocrab_api::{AppState, OAuthConfig, apply_credit_decay};
+use meritocrab_core::{DecayPolicy, QualityLevel, RepoConfig};
+use meritocrab_db::{
+    contributors::{create_contributor, get_contributor, update_credit_score},
+    credit_events::list_events_by_contributor,
+    repo_installations::record_repo_installation,
+    run_migrations,
+};
+use meritocrab_github::{GithubApiClient, GithubAppAuth, InstallationClients, WebhookSecret};
+use meritocrab_llm::MockEvaluator;
+use serde_json::json;
+use sqlx::any::AnyPoolOptions;
+use std::sync::{Arc, Mutex};
+
+/// Serve a GitHub API stand-in where every route is a 404, so repositories
+/// fall back to the default config
+async fn spawn_mock_github() -> String {
+    let app = Router::new().fallback(|| async {
+        (
+            StatusCode::NOT_FOUND,
+            axum::Json(json!({ "message": "Not Found", "documentation_url": "" })),
+        )
+    });
+
+    let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    format!("http://{}", addr)
+}
+
+/// Serve a GitHub App stand-in that issues `ghs_<id>` installation tokens
+/// (except for installation 13, which fails) and records each other
+/// request's path and token before answering 404
+async fn spawn_mock_github_app() -> (String, Arc<Mutex<Vec<String>>>) {
+    use axum::{extract::Path, http::HeaderMap};
+
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let recorded = requests.clone();
+    let app = Router::new()
+        .route(
+            "/app/installations/{id}/access_tokens",
+            axum::routing::post(|Path(id): Path<i64>| async move {
+                if id == 13 {
+                    return (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        axum::Json(json!({ "message": "Server Error", "documentation_url": "" })),
+                    );
+                }
+                let expires_at = Utc::now() + Duration::hours(1);
+                (
+                    StatusCode::CREATED,
+                    axum::Json(json!({
+                        "token": format!("ghs_{}", id),
+                        "expires_at": expires_at.to_rfc3339(),
+                        "permissions": {}
+                    })),
+                )
+            }),
+        )
+        .fallback(move |uri: axum::http::Uri, headers: HeaderMap| {
+            let recorded = recorded.clone();
+            async move {
+                let token = headers
+                    .get("authorization")
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.strip_prefix("Bearer "))
+                    .unwrap_or_default()
+                    .to_string();
+                recorded
+                    .lock()
+                    .unwrap()
+                    .push(format!("{} {}", uri.path(), token));
+                (
+                    StatusCode::NOT_FOUND,
+                    axum::Json(json!({ "message": "Not Found", "documentation_url": "" })),
+                )
+            }
+        });
+
+    let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    (format!("http://{}", addr), requests)
+}
+
+async fn setup_test_state(repo_config: RepoConfig) -> AppState {
+    sqlx::any::install_default_drivers();
+
+    let pool = AnyPoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .expect("Failed to create test database pool");
+    run_migrations(&pool)
+        .await
+        .expect("Failed to run migrations");
+
+    let github_client = GithubApiClient::from_octocrab(
+        octocrab::Octocrab::builder()
+            .base_uri(spawn_mock_github().await)
+            .unwrap()
+            .personal_token("test-token".to_string())
+            .build()
+            .unwrap(),
+    );
+
+    AppState::new(
+        pool,
+        github_client,
+        repo_config,
+        WebhookSecret::new("test-secret".to_string()),
+        Arc::new(MockEvaluator::with_default(QualityLevel::Acceptable)),
+        10,
+        OAuthConfig {
+            client_id: "test-client-id".to_string(),
+            client_secret: "test-client-secret".to_string(),
+            redirect_url: "http://localhost:8080/auth/callback".to_string(),
+        },
+        300,
+    )
+}
+
+/// Create a contributor with the given score, first seen `days_ago`
+async fn seed_contributor(state: &AppState, github_user_id: i64, score: i32, days_ago: i64) {
+    let contributor = create_contributor(&state.db_pool, github_user_id, "owner", "repo", 100)
+        .await
+        .unwrap();
+    update_credit_score(&state.db_pool, contributor.id, score)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE contributors SET created_at = ? WHERE id = ?")
+        .bind((Utc::now() - Duration::days(days_ago)).to_rfc3339())
+        .bind(contributor.id)
+        .execute(&state.db_pool)
+        .await
+        .unwrap();
+}
+
+async fn score(state: &AppState, github_user_id: i64) -> i32 {
+    get_contributor(&state.db_pool, github_user_id, "owner", "repo")
+        .await
+        .unwrap()
+        .unwrap()
+        .credit_score
+}
+
+#[tokio::test]
+async fn test_decay_records_events_and_is_idempotent() {
+    let repo_config = RepoConfig {
+        decay: Some(DecayPolicy::Linear {
+            after_inactive_days: 30,
+            points_per_day: 1.0,
+        }),
+        ..RepoConfig::default()
+    };
+    let state = setup_test_state(repo_config).await;
+
+    seed_contributor(&state, 1, 150, 40).await; // 10 days past the grace period
+    seed_contributor(&state, 2, 150, 5).await; // still active
+    seed_contributor(&state, 3, 50, 400).await; // below starting credit
+
+    let now = Utc::now();
+    assert_eq!(apply_credit_decay(&state, now).await.unwrap(), 1);
+    assert_eq!(score(&state, 1).await, 140);
+    assert_eq!(score(&state, 2).await, 150);
+    assert_eq!(score(&state, 3).await, 50);
+
+    let contributor = get_contributor(&state.db_pool, 1, "owner", "repo")
+        .await
+        .unwrap()
+        .unwrap();
+    let events = list_events_by_contributor(&state.db_pool, contributor.id, 10, 0)
+        .await
+        .unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].event_type, "decay");
+    assert_eq!(events[0].delta, -10);
+    assert!(
+        events[0]
+            .reason
+            .as_deref()
+            .unwrap()
+            .starts_with("Inactive since")
+    );
+
+    // Running again right away finds nothing new to decay
+    assert_eq!(apply_credit_decay(&state, Utc::now()).await.unwrap(), 0);
+    assert_eq!(score(&state, 1).await, 140);
+
+    // Two days later, only those two days decay
+    let later = Utc::now() + Duration::days(2);
+    assert_eq!(apply_credit_decay(&state, later).await.unwrap(), 1);
+    assert_eq!(score(&state, 1).await, 138);
+}
+
+#[tokio::test]
+async fn test_no_decay_without_policy() {
+    let state = setup_test_state(RepoConfig::default()).await;
+    seed_contributor(&state, 1, 150, 400).await;
+
+    assert_eq!(apply_credit_decay(&state, Utc::now()).await.unwrap(), 0);
+    assert_eq!(score(&state, 1).await, 150);
+}
+
+#[tokio::test]
+async fn test_decay_loads_config_as_repo_installation() {
+    let repo_config = RepoConfig {
+        decay: Some(DecayPolicy::Linear {
+            after_inactive_days: 30,
+            points_per_day: 1.0,
+        }),
+        ..RepoConfig::default()
+    };
+    let (api_url, requests) = spawn_mock_github_app().await;
+    let auth = GithubAppAuth::new(
+        1,
+        include_str!("../../meritocrab-github/tests/fixtures/test-app-key.pem").to_string(),
+    );
+    let clients = Arc::new(InstallationClients::with_api_url(auth, &api_url));
+    let state = setup_test_state(repo_config)
+        .await
+        .with_installation_clients(clients, 1);
+
+    seed_contributor(&state, 1, 150, 40).await;
+    record_repo_installation(&state.db_pool, "owner", "repo", 7)
+        .await
+        .unwrap();
+
+    // The repo has no .meritocrab.toml, so the server config's policy applies
+    assert_eq!(apply_credit_decay(&state, Utc::now()).await.unwrap(), 1);
+    assert_eq!(score(&state, 1).await, 140);
+
+    // The config was fetched with the repo's own installation token
+    let requests = requests.lock().unwrap().clone();
+    assert!(
+        requests
+            .iter()
+            .any(|r| r.starts_with("/repos/owner/repo/") && r.ends_with(" ghs_7")),
+        "unexpected requests: {:?}",
+        requests
+    );
+    assert!(
+        !requests.iter().any(|r| r.ends_with(" ghs_1")),
+        "unexpected requests: {:?}",
+        requests
+    );
+}
+
+#[tokio::test]
+async fn test_failing_repo_does_not_stop_decay_elsewhere() {
+    let repo_config = RepoConfig {
+        decay: Some(DecayPolicy::Linear {
+            after_inactive_days: 30,
+            points_per_day: 1.0,
+        }),
+        ..RepoConfig::default()
+    };
+    let (api_url, _requests) = spawn_mock_github_app().await;
+    let auth = GithubAppAuth::new(
+        1,
+        include_str!("../../meritocrab-github/tests/fixtures/test-app-key.pem").to_string(),
+    );
+    let clients = Arc::new(InstallationClients::with_api_url(auth, &api_url));
+    let state = setup_test_state(repo_config)
+        .await
+        .with_installation_clients(clients, 1);
+
+    // owner/broken sorts first, and its installation cannot get a token
+    let broken = create_contributor(&state.db_pool, 2, "owner", "broken", 100)
+        .await
+        .unwrap();
+    update_credit_score(&state.db_pool, broken.id, 150)
+        .await
+        .unwrap();
+    record_repo_installation(&state.db_pool, "owner", "broken", 13)
+        .await
+        .unwrap();
+    seed_contributor(&state, 1, 150, 40).await;
+    record_repo_installation(&state.db_pool, "owner", "repo", 7)
+        .await
+        .unwrap();
+
+    assert_eq!(apply_credit_decay(&state, Utc::now()).await.unwrap(), 1);
+    assert_eq!(score(&state, 1).await, 140);
+}
@@ -7,12 +7,12 @@ use axum::{
 use hmac::{Hmac, Mac};
 use meritocrab_api::{AppState, OAuthConfig, handle_webhook, health};
 use meritocrab_core::RepoConfig;
-use meritocrab_db::contributors::get_contributor;
-use meritocrab_github::{GithubApiClient, WebhookSecret};
+use meritocrab_db::{contributors::get_contributor, run_migrations};
+use meritocrab_github::{GithubApiClient, GithubAppAuth, InstallationClients, WebhookSecret};
 use serde_json::json;
 use sha2::Sha256;
 use sqlx::any::AnyPoolOptions;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use tower::ServiceExt;
 
 type HmacSha256 = Hmac<Sha256>;
@@ -43,12 +43,9 @@ async fn setup_test_state() -> AppState {
         .expect("Failed to enable foreign keys");
 
     // Run migrations
-    sqlx::query(include_str!(
-        "../../meritocrab-db/migrations/001_initial.sql"
-    ))
-    .execute(&pool)
-    .await
-    .expect("Failed to run migrations");
+    run_migrations(&pool)
+        .await
+        .expect("Failed to run migrations");
 
     // Initialize rustls for GitHub client
     let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
@@ -236,6 +233,7 @@ async fn test_webhook_pr_opened_new_contributor() {
         .expect("Contributor should exist");
 
     assert_eq!(contributor.github_user_id, 12345);
+    assert_eq!(contributor.login.as_deref(), Some("testuser"));
     assert_eq!(contributor.credit_score, 100); // Default starting credit
     assert_eq!(contributor.repo_owner, "owner");
     assert_eq!(contributor.repo_name, "repo");
@@ -299,3 +297,206 @@ async fn test_webhook_pr_opened_not_processed() {
 
     assert_eq!(response.status(), StatusCode::OK);
 }
+
+#[tokio::test]
+async fn test_webhook_duplicate_delivery_short_circuits() {
+    let state = setup_test_state().await;
+    let db_pool = state.db_pool.clone();
+    let app = create_app(state);
+
+    let payload = json!({
+        "action": "opened",
+        "number": 1,
+        "pull_request": {
+            "number": 1,
+            "title": "Test PR",
+            "body": "Test body",
+            "user": {
+                "id": 12345,
+                "login": "testuser"
+            },
+            "state": "open",
+            "merged": false,
+            "html_url": "https://github.com/owner/repo/pull/1"
+        },
+        "repository": {
+            "id": 1,
+            "name": "repo",
+            "full_name": "owner/repo",
+            "owner": {
+                "id": 1,
+                "login": "owner"
+            }
+        },
+        "sender": {
+            "id": 12345,
+            "login": "testuser"
+        }
+    });
+
+    let body = serde_json::to_vec(&payload).unwrap();
+    let signature = compute_signature(&body, "test-secret");
+
+    let mut statuses = Vec::new();
+    for _ in 0..2 {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/webhooks/github")
+                    .header("Content-Type", "application/json")
+                    .header("X-Hub-Signature-256", signature.clone())
+                    .header("X-GitHub-Event", "pull_request")
+                    .header("X-GitHub-Delivery", "72d3162e-cc78-11e3-81ab-4c9367dc0958")
+                    .body(Body::from(body.clone()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        statuses.push(json["status"].as_str().unwrap_or_default().to_string());
+    }
+
+    assert_ne!(statuses[0], "duplicate");
+    assert_eq!(statuses[1], "duplicate");
+
+    // Only one delivery row is recorded
+    let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM webhook_deliveries")
+        .fetch_one(&db_pool)
+        .await
+        .unwrap();
+    assert_eq!(count.0, 1);
+}
+
+/// Start a mock GitHub that issues `ghs_<installation>` tokens
+///
+/// Every other request is answered with 404 and recorded as
+/// `"<path> <bearer token>"`.
+async fn spawn_mock_github_app() -> (String, Arc<Mutex<Vec<String>>>) {
+    use axum::{extract::Path, http::HeaderMap};
+
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let recorded = requests.clone();
+    let app = Router::new()
+        .route(
+            "/app/installations/{id}/access_tokens",
+            axum::routing::post(|Path(id): Path<i64>| async move {
+                let expires_at = chrono::Utc::now() + chrono::Duration::hours(1);
+                (
+                    StatusCode::CREATED,
+                    axum::Json(json!({
+                        "token": format!("ghs_{}", id),
+                        "expires_at": expires_at.to_rfc3339(),
+                        "permissions": {}
+                    })),
+                )
+            }),
+        )
+        .fallback(move |uri: axum::http::Uri, headers: HeaderMap| {
+            let recorded = recorded.clone();
+            async move {
+                let token = headers
+                    .get("authorization")
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.strip_prefix("Bearer "))
+                    .unwrap_or_default()
+                    .to_string();
+                recorded
+                    .lock()
+                    .unwrap()
+                    .push(format!("{} {}", uri.path(), token));
+                (
+                    StatusCode::NOT_FOUND,
+                    axum::Json(json!({ "message": "Not Found", "documentation_url": "" })),
+                )
+            }
+        });
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    (format!("http://{}", addr), requests)
+}
+
+#[tokio::test]
+async fn test_webhook_uses_installation_client() {
+    let (api_url, requests) = spawn_mock_github_app().await;
+    let auth = GithubAppAuth::new(
+        1,
+        include_str!("../../meritocrab-github/tests/fixtures/test-app-key.pem").to_string(),
+    );
+    let clients = Arc::new(InstallationClients::with_api_url(auth, &api_url));
+    let state = setup_test_state()
+        .await
+        .with_installation_clients(clients, 1);
+    let app = create_app(state.clone());
+
+    // PRs opened in two orgs, each with its own installation
+    for (pr_number, owner, installation_id) in [(1, "org-a", 7), (2, "org-b", 8)] {
+        let payload = json!({
+            "action": "opened",
+            "number": pr_number,
+            "pull_request": {
+                "number": pr_number,
+                "title": "Test PR",
+                "body": "Test body",
+                "user": { "id": 12345, "login": "testuser" },
+                "state": "open",
+                "merged": false,
+                "html_url": format!("https://github.com/{}/repo/pull/{}", owner, pr_number)
+            },
+            "repository": {
+                "id": pr_number,
+                "name": "repo",
+                "full_name": format!("{}/repo", owner),
+                "owner": { "id": 1, "login": owner }
+            },
+            "installation": { "id": installation_id },
+            "sender": { "id": 12345, "login": "testuser" }
+        });
+
+        let body = serde_json::to_vec(&payload).unwrap();
+        let signature = compute_signature(&body, "test-secret");
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/webhooks/github")
+                    .header("Content-Type", "application/json")
+                    .header("X-Hub-Signature-256", signature)
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    // The collaborator lookup for each repo used that org's installation token
+    let requests = requests.lock().unwrap().clone();
+    assert!(
+        requests.contains(&"/repos/org-a/repo/collaborators/testuser/permission ghs_7".to_string()),
+        "unexpected requests: {:?}",
+        requests
+    );
+    assert!(
+        requests.contains(&"/repos/org-b/repo/collaborators/testuser/permission ghs_8".to_string()),
+        "unexpected requests: {:?}",
+        requests
+    );
+
+    // Requests without an installation fall back to the default one
+    let default_state = state.for_installation(None).await.unwrap();
+    assert_eq!(default_state.installation_id, Some(1));
+}
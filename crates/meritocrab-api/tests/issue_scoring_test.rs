@@ -0,0 +1,349 @@
+/// Integration tests for scoring issues and comments on issues
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+    routing::get,
+};
+use hmac::{Hmac, Mac};
+use meritocrab_api::{AppState, JobQueueConfig, OAuthConfig, handle_webhook, spawn_job_worker};
+use meritocrab_core::{QualityLevel, RepoConfig, config::ScoringDelta};
+use meritocrab_db::{
+    contributors::{create_contributor, get_contributor, set_blacklisted},
+    credit_events::list_events_by_contributor,
+    run_migrations,
+};
+use meritocrab_github::{GithubApiClient, WebhookSecret};
+use meritocrab_llm::{ContentType, EvalContext, Evaluation, LlmError, LlmEvaluator};
+use serde_json::json;
+use sha2::Sha256;
+use sqlx::any::AnyPoolOptions;
+use std::sync::{Arc, Mutex};
+use tokio::time::{Duration, sleep};
+use tower::ServiceExt;
+
+/// Evaluator that records what it was asked to evaluate
+#[derive(Default)]
+struct RecordingEvaluator {
+    contexts: Mutex<Vec<EvalContext>>,
+}
+
+#[async_trait::async_trait]
+impl LlmEvaluator for RecordingEvaluator {
+    async fn evaluate(
+        &self,
+        _content: &str,
+        context: &EvalContext,
+    ) -> Result<Evaluation, LlmError> {
+        self.contexts.lock().unwrap().push(context.clone());
+        Ok(Evaluation::new(
+            QualityLevel::High,
+            0.95,
+            "Clear reproduction steps".to_string(),
+        ))
+    }
+
+    fn provider_name(&self) -> String {
+        "test_recording".to_string()
+    }
+}
+
+/// Serve a GitHub API stand-in with issue 7 and no comments on it
+async fn spawn_mock_github() -> GithubApiClient {
+    let app = Router::new()
+        .route(
+            "/repos/{owner}/{repo}/issues/{number}",
+            get(|| async {
+                axum::Json(json!({
+                    "number": 7,
+                    "title": "Crash on empty config",
+                    "body": "Loading an empty config file panics.",
+                    "user": { "id": 1, "login": "alice", "type": "User" }
+                }))
+            }),
+        )
+        .route(
+            "/repos/{owner}/{repo}/issues/{number}/comments",
+            get(|| async { axum::Json(json!([])) }),
+        )
+        .fallback(|| async {
+            (
+                StatusCode::NOT_FOUND,
+                axum::Json(json!({ "message": "Not Found", "documentation_url": "" })),
+            )
+        });
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    GithubApiClient::from_octocrab(
+        octocrab::Octocrab::builder()
+            .base_uri(format!("http://{}", addr))
+            .unwrap()
+            .personal_token("test-token".to_string())
+            .build()
+            .unwrap(),
+    )
+}
+
+/// Repo config with issue and issue comment scoring enabled
+fn issue_scoring_config() -> RepoConfig {
+    RepoConfig {
+        issue_opened: Some(ScoringDelta {
+            spam: -10,
+            low: 0,
+            acceptable: 2,
+            high: 5,
+        }),
+        issue_comment: Some(ScoringDelta {
+            spam: -10,
+            low: -2,
+            acceptable: 1,
+            high: 3,
+        }),
+        ..RepoConfig::default()
+    }
+}
+
+async fn setup_test_state(evaluator: Arc<RecordingEvaluator>, config: RepoConfig) -> AppState {
+    sqlx::any::install_default_drivers();
+    let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+
+    let pool = AnyPoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    run_migrations(&pool).await.unwrap();
+
+    let state = AppState::new(
+        pool,
+        spawn_mock_github().await,
+        config,
+        WebhookSecret::new("test-secret".to_string()),
+        evaluator,
+        10,
+        OAuthConfig {
+            client_id: "test-client-id".to_string(),
+            client_secret: "test-client-secret".to_string(),
+            redirect_url: "http://localhost:8080/auth/callback".to_string(),
+        },
+        300,
+    );
+    spawn_job_worker(state.clone(), JobQueueConfig::default());
+    state
+}
+
+async fn send_webhook(state: AppState, event: &str, payload: serde_json::Value) -> StatusCode {
+    let body = serde_json::to_vec(&payload).unwrap();
+    let mut mac = Hmac::<Sha256>::new_from_slice(b"test-secret").unwrap();
+    mac.update(&body);
+    let signature = format!("sha256={}", hex::encode(mac.finalize().into_bytes()));
+
+    Router::new()
+        .route("/webhooks/github", axum::routing::post(handle_webhook))
+        .with_state(state)
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/webhooks/github")
+                .header("Content-Type", "application/json")
+                .header("X-GitHub-Event", event)
+                .header("X-Hub-Signature-256", signature)
+                .body(Body::from(body))
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+        .status()
+}
+
+fn issue_opened_payload() -> serde_json::Value {
+    json!({
+        "action": "opened",
+        "issue": {
+            "number": 7,
+            "title": "Crash on empty config",
+            "body": "Steps to reproduce: load an empty config file.",
+            "user": { "id": 12345, "login": "testuser" }
+        },
+        "repository": repository(),
+        "sender": { "id": 12345, "login": "testuser" }
+    })
+}
+
+fn issue_comment_payload(body: &str) -> serde_json::Value {
+    json!({
+        "action": "created",
+        "issue": {
+            "number": 7,
+            "title": "Crash on empty config",
+            "user": { "id": 1, "login": "alice" }
+        },
+        "comment": {
+            "id": 20,
+            "body": body,
+            "user": { "id": 12345, "login": "testuser" },
+            "html_url": "https://github.com/owner/repo/issues/7#issuecomment-20"
+        },
+        "repository": repository(),
+        "sender": { "id": 12345, "login": "testuser" }
+    })
+}
+
+fn repository() -> serde_json::Value {
+    json!({
+        "id": 1,
+        "name": "repo",
+        "full_name": "owner/repo",
+        "owner": { "id": 1, "login": "owner" }
+    })
+}
+
+#[tokio::test]
+async fn test_issue_opened_scored_when_enabled() {
+    let evaluator = Arc::new(RecordingEvaluator::default());
+    let state = setup_test_state(evaluator.clone(), issue_scoring_config()).await;
+
+    assert_eq!(
+        send_webhook(state.clone(), "issues", issue_opened_payload()).await,
+        StatusCode::OK
+    );
+    sleep(Duration::from_millis(300)).await;
+
+    {
+        let contexts = evaluator.contexts.lock().unwrap();
+        assert_eq!(contexts.len(), 1);
+        assert_eq!(contexts[0].content_type, ContentType::Issue);
+        assert_eq!(contexts[0].title.as_deref(), Some("Crash on empty config"));
+    }
+
+    let contributor = get_contributor(&state.db_pool, 12345, "owner", "repo")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(contributor.credit_score, 105);
+    let events = list_events_by_contributor(&state.db_pool, contributor.id, 10, 0)
+        .await
+        .unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].event_type, "issue_opened");
+    assert_eq!(events[0].pr_number, Some(7));
+}
+
+#[tokio::test]
+async fn test_issue_comment_scored_with_issue_thread() {
+    let evaluator = Arc::new(RecordingEvaluator::default());
+    let state = setup_test_state(evaluator.clone(), issue_scoring_config()).await;
+
+    let payload = issue_comment_payload("It also panics when the file is missing.");
+    assert_eq!(
+        send_webhook(state.clone(), "issue_comment", payload).await,
+        StatusCode::OK
+    );
+    sleep(Duration::from_millis(300)).await;
+
+    {
+        let contexts = evaluator.contexts.lock().unwrap();
+        assert_eq!(contexts.len(), 1);
+        assert_eq!(contexts[0].content_type, ContentType::Comment);
+        assert!(
+            contexts[0]
+                .thread_context
+                .as_deref()
+                .unwrap()
+                .starts_with("Issue #7 by @alice: Crash on empty config\n")
+        );
+    }
+
+    let contributor = get_contributor(&state.db_pool, 12345, "owner", "repo")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(contributor.credit_score, 103);
+    let events = list_events_by_contributor(&state.db_pool, contributor.id, 10, 0)
+        .await
+        .unwrap();
+    assert_eq!(events[0].event_type, "issue_comment");
+}
+
+#[tokio::test]
+async fn test_issue_scoring_off_by_default() {
+    let evaluator = Arc::new(RecordingEvaluator::default());
+    let state = setup_test_state(evaluator.clone(), RepoConfig::default()).await;
+
+    assert_eq!(
+        send_webhook(state.clone(), "issues", issue_opened_payload()).await,
+        StatusCode::OK
+    );
+    let payload = issue_comment_payload("It also panics when the file is missing.");
+    assert_eq!(
+        send_webhook(state.clone(), "issue_comment", payload).await,
+        StatusCode::OK
+    );
+    sleep(Duration::from_millis(300)).await;
+
+    assert!(evaluator.contexts.lock().unwrap().is_empty());
+    assert!(
+        get_contributor(&state.db_pool, 12345, "owner", "repo")
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn test_credit_command_on_issue_ignored() {
+    let evaluator = Arc::new(RecordingEvaluator::default());
+    let state = setup_test_state(evaluator.clone(), issue_scoring_config()).await;
+
+    let payload = issue_comment_payload("/credit me");
+    assert_eq!(
+        send_webhook(state.clone(), "issue_comment", payload).await,
+        StatusCode::OK
+    );
+    sleep(Duration::from_millis(300)).await;
+
+    assert!(evaluator.contexts.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_manually_blacklisted_user_earns_nothing_on_issues() {
+    let evaluator = Arc::new(RecordingEvaluator::default());
+    let state = setup_test_state(evaluator.clone(), issue_scoring_config()).await;
+
+    // Blacklisted by a maintainer despite a score well above the threshold
+    let contributor = create_contributor(&state.db_pool, 12345, "owner", "repo", 150)
+        .await
+        .unwrap();
+    set_blacklisted(&state.db_pool, contributor.id, true)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        send_webhook(state.clone(), "issues", issue_opened_payload()).await,
+        StatusCode::OK
+    );
+    let payload = issue_comment_payload("It also panics when the file is missing.");
+    assert_eq!(
+        send_webhook(state.clone(), "issue_comment", payload).await,
+        StatusCode::OK
+    );
+    sleep(Duration::from_millis(300)).await;
+
+    assert!(evaluator.contexts.lock().unwrap().is_empty());
+    let contributor = get_contributor(&state.db_pool, 12345, "owner", "repo")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(contributor.credit_score, 150);
+    assert!(
+        list_events_by_contributor(&state.db_pool, contributor.id, 10, 0)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
@@ -0,0 +1,313 @@
+/// Integration tests for the durable background job queue
+use axum::{extract::State, http::HeaderMap};
+use chrono::Utc;
+use meritocrab_api::{
+    AppState, JobQueueConfig, OAuthConfig, VerifiedWebhookPayload, handle_webhook,
+    jobs::{EvaluationJob, JobPayload, enqueue},
+    spawn_job_worker,
+};
+use meritocrab_core::{EventType, RepoConfig, config::QualityLevel};
+use meritocrab_db::{
+    contributors::{create_contributor, get_contributor, set_blacklisted},
+    jobs::{JobStatus, claim_next_job, enqueue_job, get_job, list_jobs_by_repo_and_status},
+    run_migrations,
+};
+use meritocrab_github::{GithubApiClient, WebhookSecret};
+use meritocrab_llm::{ContentType, EvalContext, Evaluation, LlmError, LlmEvaluator};
+use serde_json::json;
+use sqlx::any::AnyPoolOptions;
+use std::sync::Arc;
+use tokio::time::{Duration, sleep};
+
+fn test_oauth_config() -> OAuthConfig {
+    OAuthConfig {
+        client_id: "test-client-id".to_string(),
+        client_secret: "test-client-secret".to_string(),
+        redirect_url: "http://localhost:8080/auth/callback".to_string(),
+    }
+}
+
+/// Evaluator that always fails with the given error
+struct FailingEvaluator {
+    retryable: bool,
+}
+
+#[async_trait::async_trait]
+impl LlmEvaluator for FailingEvaluator {
+    async fn evaluate(
+        &self,
+        _content: &str,
+        _context: &EvalContext,
+    ) -> Result<Evaluation, LlmError> {
+        if self.retryable {
+            Err(LlmError::RateLimitError)
+        } else {
+            Err(LlmError::AuthError)
+        }
+    }
+
+    fn provider_name(&self) -> String {
+        "test_failing".to_string()
+    }
+}
+
+/// Evaluator that always succeeds with high confidence
+struct HighQualityEvaluator;
+
+#[async_trait::async_trait]
+impl LlmEvaluator for HighQualityEvaluator {
+    async fn evaluate(
+        &self,
+        _content: &str,
+        _context: &EvalContext,
+    ) -> Result<Evaluation, LlmError> {
+        Ok(Evaluation::new(
+            QualityLevel::High,
+            0.95,
+            "High quality".to_string(),
+        ))
+    }
+
+    fn provider_name(&self) -> String {
+        "test_high".to_string()
+    }
+}
+
+async fn setup_test_state(evaluator: Arc<dyn LlmEvaluator>) -> AppState {
+    sqlx::any::install_default_drivers();
+
+    let pool = AnyPoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .expect("Failed to create test database pool");
+
+    sqlx::query("PRAGMA foreign_keys = ON")
+        .execute(&pool)
+        .await
+        .expect("Failed to enable foreign keys");
+
+    run_migrations(&pool)
+        .await
+        .expect("Failed to run migrations");
+
+    let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+    let github_client =
+        GithubApiClient::new("test-token".to_string()).expect("Failed to create GitHub client");
+    let webhook_secret = WebhookSecret::new("test-secret".to_string());
+
+    AppState::new(
+        pool,
+        github_client,
+        RepoConfig::default(),
+        webhook_secret,
+        evaluator,
+        10,
+        test_oauth_config(),
+        300,
+    )
+}
+
+fn evaluation_job(contributor_id: i64) -> JobPayload {
+    JobPayload::Evaluate(EvaluationJob {
+        contributor_id,
+        user_id: 12345,
+        username: "testuser".to_string(),
+        repo_owner: "owner".to_string(),
+        repo_name: "repo".to_string(),
+        event_type: EventType::PrOpened,
+        content_type: ContentType::PullRequest,
+        pr_number: Some(1),
+        title: Some("Test PR".to_string()),
+        body: "Test body".to_string(),
+        diff_summary: None,
+        thread_context: None,
+        thread: None,
+        installation_id: None,
+        source_id: None,
+        revision: false,
+    })
+}
+
+#[tokio::test]
+async fn test_rate_limited_job_is_retried_with_backoff() {
+    let state = setup_test_state(Arc::new(FailingEvaluator { retryable: true })).await;
+    let contributor = create_contributor(&state.db_pool, 12345, "owner", "repo", 100)
+        .await
+        .unwrap();
+
+    spawn_job_worker(state.clone(), JobQueueConfig::default());
+    enqueue(&state, evaluation_job(contributor.id), Utc::now())
+        .await
+        .unwrap();
+    sleep(Duration::from_millis(200)).await;
+
+    let jobs =
+        list_jobs_by_repo_and_status(&state.db_pool, "owner", "repo", JobStatus::Pending, 10, 0)
+            .await
+            .unwrap();
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0].attempts, 1);
+    assert!(
+        jobs[0]
+            .last_error
+            .as_deref()
+            .unwrap()
+            .contains("Rate limit")
+    );
+    // Rescheduled with backoff, not immediately
+    assert!(jobs[0].run_at > Utc::now());
+}
+
+#[tokio::test]
+async fn test_exhausted_retries_move_job_to_dead_letter() {
+    let state = setup_test_state(Arc::new(FailingEvaluator { retryable: true })).await;
+    let contributor = create_contributor(&state.db_pool, 12345, "owner", "repo", 100)
+        .await
+        .unwrap();
+
+    let config = JobQueueConfig {
+        max_attempts: 1,
+        ..JobQueueConfig::default()
+    };
+    spawn_job_worker(state.clone(), config);
+    enqueue(&state, evaluation_job(contributor.id), Utc::now())
+        .await
+        .unwrap();
+    sleep(Duration::from_millis(200)).await;
+
+    let dead =
+        list_jobs_by_repo_and_status(&state.db_pool, "owner", "repo", JobStatus::Dead, 10, 0)
+            .await
+            .unwrap();
+    assert_eq!(dead.len(), 1);
+    assert_eq!(dead[0].job_type, "evaluate");
+}
+
+#[tokio::test]
+async fn test_permanent_failure_is_dead_lettered_immediately() {
+    let state = setup_test_state(Arc::new(FailingEvaluator { retryable: false })).await;
+    let contributor = create_contributor(&state.db_pool, 12345, "owner", "repo", 100)
+        .await
+        .unwrap();
+
+    spawn_job_worker(state.clone(), JobQueueConfig::default());
+    enqueue(&state, evaluation_job(contributor.id), Utc::now())
+        .await
+        .unwrap();
+    sleep(Duration::from_millis(200)).await;
+
+    let dead =
+        list_jobs_by_repo_and_status(&state.db_pool, "owner", "repo", JobStatus::Dead, 10, 0)
+            .await
+            .unwrap();
+    assert_eq!(dead.len(), 1);
+    assert_eq!(dead[0].attempts, 1);
+    assert!(
+        dead[0]
+            .last_error
+            .as_deref()
+            .unwrap()
+            .contains("authentication")
+    );
+}
+
+#[tokio::test]
+async fn test_worker_resumes_jobs_from_previous_run() {
+    let state = setup_test_state(Arc::new(HighQualityEvaluator)).await;
+    let contributor = create_contributor(&state.db_pool, 12345, "owner", "repo", 100)
+        .await
+        .unwrap();
+
+    // A job left pending and a job whose worker died mid-lease
+    let payload = serde_json::to_string(&evaluation_job(contributor.id)).unwrap();
+    let pending_id = enqueue_job(
+        &state.db_pool,
+        "evaluate",
+        &payload,
+        "owner",
+        "repo",
+        Utc::now(),
+    )
+    .await
+    .unwrap();
+    let crashed_id = enqueue_job(
+        &state.db_pool,
+        "evaluate",
+        &payload,
+        "owner",
+        "repo",
+        Utc::now(),
+    )
+    .await
+    .unwrap();
+    claim_next_job(&state.db_pool, chrono::Duration::seconds(-1))
+        .await
+        .unwrap();
+
+    spawn_job_worker(state.clone(), JobQueueConfig::default());
+    sleep(Duration::from_millis(300)).await;
+
+    for id in [pending_id, crashed_id] {
+        let job = get_job(&state.db_pool, id).await.unwrap().unwrap();
+        assert_eq!(job.status, "completed");
+    }
+
+    // Both evaluations applied +15 (high quality PR)
+    let contributor = get_contributor(&state.db_pool, 12345, "owner", "repo")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(contributor.credit_score, 130);
+}
+
+#[tokio::test]
+async fn test_blacklisted_pr_enqueues_delayed_close() {
+    let state = setup_test_state(Arc::new(HighQualityEvaluator)).await;
+    let contributor = create_contributor(&state.db_pool, 12345, "owner", "repo", 100)
+        .await
+        .unwrap();
+    set_blacklisted(&state.db_pool, contributor.id, true)
+        .await
+        .unwrap();
+
+    let payload = json!({
+        "action": "opened",
+        "number": 7,
+        "pull_request": {
+            "number": 7,
+            "title": "Test PR",
+            "body": "Test body",
+            "user": { "id": 12345, "login": "testuser" },
+            "state": "open",
+            "merged": false,
+            "html_url": "https://github.com/owner/repo/pull/7"
+        },
+        "repository": {
+            "id": 1,
+            "name": "repo",
+            "full_name": "owner/repo",
+            "owner": { "id": 1, "login": "owner" }
+        },
+        "sender": { "id": 12345, "login": "testuser" }
+    });
+
+    let before = Utc::now();
+    handle_webhook(
+        State(state.clone()),
+        HeaderMap::new(),
+        VerifiedWebhookPayload(serde_json::to_vec(&payload).unwrap()),
+    )
+    .await
+    .unwrap();
+
+    let jobs =
+        list_jobs_by_repo_and_status(&state.db_pool, "owner", "repo", JobStatus::Pending, 10, 0)
+            .await
+            .unwrap();
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0].job_type, "close_pr");
+
+    let delay = (jobs[0].run_at - before).num_seconds();
+    assert!((29..=121).contains(&delay), "unexpected delay {}", delay);
+}
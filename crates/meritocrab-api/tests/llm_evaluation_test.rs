@@ -3,13 +3,16 @@ use axum::{
     Router,
     body::Body,
     http::{Request, StatusCode},
+    response::IntoResponse,
 };
 use hmac::{Hmac, Mac};
-use meritocrab_api::{AppState, OAuthConfig, handle_webhook, health};
+use meritocrab_api::{
+    AppState, JobQueueConfig, OAuthConfig, handle_webhook, health, spawn_job_worker,
+};
 use meritocrab_core::{QualityLevel, RepoConfig};
 use meritocrab_db::{
     contributors::get_contributor, credit_events::list_events_by_contributor,
-    evaluations::list_evaluations_by_repo_and_status,
+    evaluations::list_evaluations_by_repo_and_status, run_migrations,
 };
 use meritocrab_github::{GithubApiClient, WebhookSecret};
 use meritocrab_llm::MockEvaluator;
@@ -31,6 +34,21 @@ fn test_oauth_config() -> OAuthConfig {
 type HmacSha256 = Hmac<Sha256>;
 
 async fn setup_test_state_with_evaluator(evaluator: MockEvaluator) -> AppState {
+    // Initialize rustls for GitHub client
+    let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+
+    // Create mock GitHub client
+    let github_client =
+        GithubApiClient::new("test-token".to_string()).expect("Failed to create GitHub client");
+
+    setup_test_state_with_config(evaluator, github_client, RepoConfig::default()).await
+}
+
+async fn setup_test_state_with_config(
+    evaluator: MockEvaluator,
+    github_client: GithubApiClient,
+    repo_config: RepoConfig,
+) -> AppState {
     // Install SQLite driver
     sqlx::any::install_default_drivers();
 
@@ -48,24 +66,13 @@ async fn setup_test_state_with_evaluator(evaluator: MockEvaluator) -> AppState {
         .expect("Failed to enable foreign keys");
 
     // Run migrations
-    sqlx::query(include_str!(
-        "../../meritocrab-db/migrations/001_initial.sql"
-    ))
-    .execute(&pool)
-    .await
-    .expect("Failed to run migrations");
-
-    // Initialize rustls for GitHub client
-    let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
-
-    // Create mock GitHub client
-    let github_client =
-        GithubApiClient::new("test-token".to_string()).expect("Failed to create GitHub client");
+    run_migrations(&pool)
+        .await
+        .expect("Failed to run migrations");
 
     let webhook_secret = WebhookSecret::new("test-secret".to_string());
-    let repo_config = RepoConfig::default();
 
-    AppState::new(
+    let state = AppState::new(
         pool,
         github_client,
         repo_config,
@@ -74,7 +81,12 @@ async fn setup_test_state_with_evaluator(evaluator: MockEvaluator) -> AppState {
         10,
         test_oauth_config(),
         300,
-    )
+    );
+
+    // Evaluations run on the background job worker
+    spawn_job_worker(state.clone(), JobQueueConfig::default());
+
+    state
 }
 
 fn compute_signature(body: &[u8], secret: &str) -> String {
@@ -84,6 +96,32 @@ fn compute_signature(body: &[u8], secret: &str) -> String {
     format!("sha256={}", hex::encode(result.into_bytes()))
 }
 
+/// Serve a minimal GitHub API stand-in on a local port
+///
+/// Every review has no inline comments; every other route is a 404.
+async fn spawn_mock_github() -> String {
+    let app = Router::new()
+        .route(
+            "/repos/{owner}/{repo}/pulls/{pr}/reviews/{id}/comments",
+            axum::routing::get(|| async { axum::Json(json!([])) }),
+        )
+        .fallback(|| async {
+            (
+                StatusCode::NOT_FOUND,
+                axum::Json(json!({ "message": "Not Found", "documentation_url": "" })),
+            )
+        });
+
+    let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    format!("http://{}", addr)
+}
+
 fn create_app(state: AppState) -> Router {
     Router::new()
         .route("/health", axum::routing::get(health))
@@ -175,7 +213,7 @@ async fn test_pr_opened_high_confidence_applies_credit() {
 
     // Verify LLM evaluation JSON contains expected fields
     let llm_eval: serde_json::Value =
-        serde_json::from_str(&event.llm_evaluation.as_ref().unwrap()).unwrap();
+        serde_json::from_str(event.llm_evaluation.as_ref().unwrap()).unwrap();
     // Check the classification - serde_json represents enums as strings
     assert!(llm_eval["classification"].is_string());
     assert!(llm_eval["confidence"].as_f64().unwrap() >= 0.85);
@@ -221,17 +259,12 @@ async fn test_pr_opened_low_confidence_creates_pending_evaluation() {
             .execute(&pool)
             .await
             .unwrap();
-        sqlx::query(include_str!(
-            "../../meritocrab-db/migrations/001_initial.sql"
-        ))
-        .execute(&pool)
-        .await
-        .unwrap();
+        run_migrations(&pool).await.unwrap();
         let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
         let github_client = GithubApiClient::new("test-token".to_string()).unwrap();
         let webhook_secret = WebhookSecret::new("test-secret".to_string());
         let repo_config = RepoConfig::default();
-        AppState::new(
+        let state = AppState::new(
             pool,
             github_client,
             repo_config,
@@ -240,7 +273,9 @@ async fn test_pr_opened_low_confidence_creates_pending_evaluation() {
             10,
             test_oauth_config(),
             300,
-        )
+        );
+        spawn_job_worker(state.clone(), JobQueueConfig::default());
+        state
     };
 
     let db_pool = state.db_pool.clone();
@@ -400,36 +435,129 @@ async fn test_comment_created_applies_credit() {
 }
 
 #[tokio::test]
-async fn test_review_submitted_grants_fixed_credit() {
-    let evaluator = MockEvaluator::new(); // Doesn't matter, reviews don't use LLM
+async fn test_review_submitted_evaluated_by_llm() {
+    let evaluator = MockEvaluator::with_default(QualityLevel::High);
+    let state = setup_test_state_with_evaluator(evaluator).await;
+    let db_pool = state.db_pool.clone();
+
+    let status = send_webhook(
+        state,
+        &review_payload("Thorough review of the parser changes"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Wait for async LLM evaluation to complete
+    sleep(Duration::from_millis(100)).await;
+
+    // Starting credit (100) + high quality review delta (+5) = 105
+    let contributor = get_contributor(&db_pool, 12345, "owner", "repo")
+        .await
+        .unwrap()
+        .expect("Contributor should exist");
+
+    assert_eq!(contributor.credit_score, 105);
+
+    // Verify event logged with the LLM evaluation and PR link
+    let events = list_events_by_contributor(&db_pool, contributor.id, 10, 0)
+        .await
+        .unwrap();
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].event_type, "review_submitted");
+    assert_eq!(events[0].delta, 5);
+    assert_eq!(events[0].pr_number, Some(123));
+    assert!(events[0].llm_evaluation.is_some());
+}
+
+#[tokio::test]
+async fn test_review_submitted_uses_review_delta_config() {
+    // Low quality reviews earn the configured low delta (0 by default)
+    let evaluator = MockEvaluator::with_default(QualityLevel::Low);
+    let state = setup_test_state_with_evaluator(evaluator).await;
+    let db_pool = state.db_pool.clone();
+
+    let status = send_webhook(state, &review_payload("LGTM")).await;
+    assert_eq!(status, StatusCode::OK);
+
+    sleep(Duration::from_millis(100)).await;
+
+    let contributor = get_contributor(&db_pool, 12345, "owner", "repo")
+        .await
+        .unwrap()
+        .expect("Contributor should exist");
+
+    assert_eq!(contributor.credit_score, 100);
+}
+
+#[tokio::test]
+async fn test_empty_review_earns_zero_when_enabled() {
+    let github_uri = spawn_mock_github().await;
+    let github_client = GithubApiClient::from_octocrab(
+        octocrab::Octocrab::builder()
+            .base_uri(github_uri)
+            .unwrap()
+            .personal_token("test-token".to_string())
+            .build()
+            .unwrap(),
+    );
+
+    let repo_config = RepoConfig {
+        zero_credit_for_empty_reviews: true,
+        ..RepoConfig::default()
+    };
+    let state = setup_test_state_with_config(
+        MockEvaluator::with_default(QualityLevel::High),
+        github_client,
+        repo_config,
+    )
+    .await;
+    let db_pool = state.db_pool.clone();
+
+    let status = send_webhook(state, &review_payload("")).await;
+    assert_eq!(status, StatusCode::OK);
+
+    sleep(Duration::from_millis(100)).await;
+
+    let contributor = get_contributor(&db_pool, 12345, "owner", "repo")
+        .await
+        .unwrap()
+        .expect("Contributor should exist");
+
+    // No LLM evaluation, no credit
+    assert_eq!(contributor.credit_score, 100);
+
+    let events = list_events_by_contributor(&db_pool, contributor.id, 10, 0)
+        .await
+        .unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].event_type, "review_submitted");
+    assert_eq!(events[0].delta, 0);
+    assert!(events[0].llm_evaluation.is_none());
+}
+
+#[tokio::test]
+async fn test_spam_pr_deducts_credit() {
+    let evaluator = MockEvaluator::with_default(QualityLevel::Spam);
     let state = setup_test_state_with_evaluator(evaluator).await;
     let db_pool = state.db_pool.clone();
 
     let app = create_app(state);
 
     let payload = json!({
-        "action": "submitted",
-        "review": {
-            "id": 789,
-            "body": "LGTM",
+        "action": "opened",
+        "number": 1,
+        "pull_request": {
+            "number": 1,
+            "title": "Buy now! Click here for free money!",
+            "body": "spam spam spam",
             "user": {
                 "id": 12345,
                 "login": "testuser"
             },
-            "state": "approved",
-            "html_url": "https://github.com/owner/repo/pull/123#pullrequestreview-789"
-        },
-        "pull_request": {
-            "number": 123,
-            "title": "Test PR",
-            "body": null,
-            "user": {
-                "id": 1,
-                "login": "owner"
-            },
             "state": "open",
             "merged": false,
-            "html_url": "https://github.com/owner/repo/pull/123"
+            "html_url": "https://github.com/owner/repo/pull/1"
         },
         "repository": {
             "id": 1,
@@ -464,48 +592,75 @@ async fn test_review_submitted_grants_fixed_credit() {
 
     assert_eq!(response.status(), StatusCode::OK);
 
-    // No need to wait - reviews apply credit synchronously
+    // Wait for async LLM evaluation
+    sleep(Duration::from_millis(100)).await;
 
-    // Verify credit was applied immediately: starting 100 + review (+5) = 105
+    // Verify negative credit was applied: starting 100 + spam PR (-25) = 75
     let contributor = get_contributor(&db_pool, 12345, "owner", "repo")
         .await
         .unwrap()
         .expect("Contributor should exist");
 
-    assert_eq!(contributor.credit_score, 105);
-
-    // Verify event logged
-    let events = list_events_by_contributor(&db_pool, contributor.id, 10, 0)
-        .await
-        .unwrap();
-
-    assert_eq!(events.len(), 1);
-    assert_eq!(events[0].event_type, "review_submitted");
-    assert_eq!(events[0].delta, 5);
-    assert!(events[0].llm_evaluation.is_none()); // No LLM for reviews
+    assert_eq!(contributor.credit_score, 75);
 }
 
 #[tokio::test]
-async fn test_spam_pr_deducts_credit() {
-    let evaluator = MockEvaluator::with_default(QualityLevel::Spam);
-    let state = setup_test_state_with_evaluator(evaluator).await;
+async fn test_large_penalty_queued_for_review_when_configured() {
+    let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+    let github_client =
+        GithubApiClient::new("test-token".to_string()).expect("Failed to create GitHub client");
+
+    let mut repo_config = RepoConfig::default();
+    repo_config.auto_apply.positive_confidence = Some(0.8);
+    repo_config.auto_apply.review_negative_below = Some(-10);
+    let state = setup_test_state_with_config(
+        MockEvaluator::with_default(QualityLevel::Spam),
+        github_client,
+        repo_config,
+    )
+    .await;
     let db_pool = state.db_pool.clone();
 
-    let app = create_app(state);
+    let status = send_webhook(state, &pr_payload("opened", false)).await;
+    assert_eq!(status, StatusCode::OK);
 
-    let payload = json!({
-        "action": "opened",
+    sleep(Duration::from_millis(100)).await;
+
+    // High confidence, but a -25 penalty always goes to a maintainer
+    let contributor = get_contributor(&db_pool, 12345, "owner", "repo")
+        .await
+        .unwrap()
+        .expect("Contributor should exist");
+    assert_eq!(contributor.credit_score, 100);
+
+    let pending = list_evaluations_by_repo_and_status(
+        &db_pool,
+        "owner",
+        "repo",
+        &meritocrab_core::EvaluationStatus::Pending,
+        10,
+        0,
+    )
+    .await
+    .unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].proposed_delta, -25);
+}
+
+fn pr_payload(action: &str, merged: bool) -> serde_json::Value {
+    json!({
+        "action": action,
         "number": 1,
         "pull_request": {
             "number": 1,
-            "title": "Buy now! Click here for free money!",
-            "body": "spam spam spam",
+            "title": "Implements comprehensive feature with tests",
+            "body": "This is a high quality PR with comprehensive implementation",
             "user": {
                 "id": 12345,
                 "login": "testuser"
             },
-            "state": "open",
-            "merged": false,
+            "state": if action == "opened" { "open" } else { "closed" },
+            "merged": merged,
             "html_url": "https://github.com/owner/repo/pull/1"
         },
         "repository": {
@@ -521,12 +676,14 @@ async fn test_spam_pr_deducts_credit() {
             "id": 12345,
             "login": "testuser"
         }
-    });
+    })
+}
 
-    let body = serde_json::to_vec(&payload).unwrap();
+async fn send_webhook(state: AppState, payload: &serde_json::Value) -> StatusCode {
+    let body = serde_json::to_vec(payload).unwrap();
     let signature = compute_signature(&body, "test-secret");
 
-    let response = app
+    create_app(state)
         .oneshot(
             Request::builder()
                 .method("POST")
@@ -536,19 +693,323 @@ async fn test_spam_pr_deducts_credit() {
                 .body(Body::from(body))
                 .unwrap(),
         )
+        .await
+        .unwrap()
+        .status()
+}
+
+#[tokio::test]
+async fn test_pr_merged_applies_bonus_once() {
+    let evaluator = MockEvaluator::with_default(QualityLevel::High);
+    let state = setup_test_state_with_evaluator(evaluator).await;
+    let db_pool = state.db_pool.clone();
+
+    // Open the PR and let the evaluation complete
+    let status = send_webhook(state.clone(), &pr_payload("opened", false)).await;
+    assert_eq!(status, StatusCode::OK);
+    sleep(Duration::from_millis(100)).await;
+
+    // Deliver the merge twice (simulated redelivery)
+    let status = send_webhook(state.clone(), &pr_payload("closed", true)).await;
+    assert_eq!(status, StatusCode::OK);
+    let status = send_webhook(state.clone(), &pr_payload("closed", true)).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let contributor = get_contributor(&db_pool, 12345, "owner", "repo")
+        .await
+        .unwrap()
+        .expect("Contributor should exist");
+
+    // Starting credit (100) + high quality PR (+15) + merge bonus for high (+20) = 135
+    assert_eq!(contributor.credit_score, 135);
+
+    let events = list_events_by_contributor(&db_pool, contributor.id, 10, 0)
         .await
         .unwrap();
+    let merged: Vec<_> = events
+        .iter()
+        .filter(|e| e.event_type == "pr_merged")
+        .collect();
+    assert_eq!(merged.len(), 1);
+    assert_eq!(merged[0].delta, 20);
+    assert_eq!(merged[0].pr_number, Some(1));
+}
 
-    assert_eq!(response.status(), StatusCode::OK);
+#[tokio::test]
+async fn test_pr_closed_without_merge_grants_nothing() {
+    let evaluator = MockEvaluator::with_default(QualityLevel::High);
+    let state = setup_test_state_with_evaluator(evaluator).await;
+    let db_pool = state.db_pool.clone();
 
-    // Wait for async LLM evaluation
+    let status = send_webhook(state.clone(), &pr_payload("opened", false)).await;
+    assert_eq!(status, StatusCode::OK);
     sleep(Duration::from_millis(100)).await;
 
-    // Verify negative credit was applied: starting 100 + spam PR (-25) = 75
+    let status = send_webhook(state.clone(), &pr_payload("closed", false)).await;
+    assert_eq!(status, StatusCode::OK);
+
     let contributor = get_contributor(&db_pool, 12345, "owner", "repo")
         .await
         .unwrap()
         .expect("Contributor should exist");
 
+    // Only the PR opened delta applies
+    assert_eq!(contributor.credit_score, 115);
+}
+
+fn review_payload(review_body: &str) -> serde_json::Value {
+    json!({
+        "action": "submitted",
+        "review": {
+            "id": 789,
+            "body": review_body,
+            "user": {
+                "id": 12345,
+                "login": "testuser"
+            },
+            "state": "approved",
+            "html_url": "https://github.com/owner/repo/pull/123#pullrequestreview-789"
+        },
+        "pull_request": {
+            "number": 123,
+            "title": "Test PR",
+            "body": null,
+            "user": {
+                "id": 1,
+                "login": "owner"
+            },
+            "state": "open",
+            "merged": false,
+            "html_url": "https://github.com/owner/repo/pull/123"
+        },
+        "repository": {
+            "id": 1,
+            "name": "repo",
+            "full_name": "owner/repo",
+            "owner": {
+                "id": 1,
+                "login": "owner"
+            }
+        },
+        "sender": {
+            "id": 12345,
+            "login": "testuser"
+        }
+    })
+}
+
+fn comment_payload(comment_id: i64, pr_number: i64) -> serde_json::Value {
+    json!({
+        "action": "created",
+        "issue": {
+            "number": pr_number,
+            "title": "Test PR",
+            "user": { "id": 1, "login": "owner" },
+            "pull_request": {
+                "url": format!("https://api.github.com/repos/owner/repo/pulls/{}", pr_number)
+            }
+        },
+        "comment": {
+            "id": comment_id,
+            "body": "Another helpful comment",
+            "user": { "id": 12345, "login": "testuser" },
+            "html_url": format!("https://github.com/owner/repo/issues/{}#issuecomment-{}", pr_number, comment_id)
+        },
+        "repository": {
+            "id": 1,
+            "name": "repo",
+            "full_name": "owner/repo",
+            "owner": { "id": 1, "login": "owner" }
+        },
+        "sender": { "id": 12345, "login": "testuser" }
+    })
+}
+
+#[tokio::test]
+async fn test_comment_farming_capped_by_earning_limits() {
+    let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+    let github_client =
+        GithubApiClient::new("test-token".to_string()).expect("Failed to create GitHub client");
+
+    let mut repo_config = RepoConfig::default();
+    repo_config.earning_limits.daily_cap.comment = Some(5);
+    let state = setup_test_state_with_config(
+        MockEvaluator::with_default(QualityLevel::High),
+        github_client,
+        repo_config,
+    )
+    .await;
+    let db_pool = state.db_pool.clone();
+
+    // Three high-quality comments (+3 each) against a daily cap of 5
+    for (comment_id, pr_number) in [(1, 10), (2, 11), (3, 12)] {
+        let status = send_webhook(state.clone(), &comment_payload(comment_id, pr_number)).await;
+        assert_eq!(status, StatusCode::OK);
+        sleep(Duration::from_millis(100)).await;
+    }
+
+    let contributor = get_contributor(&db_pool, 12345, "owner", "repo")
+        .await
+        .unwrap()
+        .expect("Contributor should exist");
+    assert_eq!(contributor.credit_score, 105);
+
+    // Capped events are still recorded, with the reason
+    let events = list_events_by_contributor(&db_pool, contributor.id, 10, 0)
+        .await
+        .unwrap();
+    let mut deltas: Vec<i32> = events.iter().map(|e| e.delta).collect();
+    deltas.sort();
+    assert_eq!(deltas, vec![0, 2, 3]);
+    let capped = events.iter().find(|e| e.delta == 0).unwrap();
+    assert_eq!(
+        capped.reason.as_deref(),
+        Some("Daily credit cap of 5 reached")
+    );
+}
+
+/// Serve a GitHub API stand-in for PR 1's stats, diff and file list
+///
+/// With `serve_diff` false the diff is refused like GitHub does for very
+/// large PRs, so only the file list is available.
+async fn spawn_diff_github(serve_diff: bool) -> GithubApiClient {
+    let app = Router::new()
+        .route(
+            "/repos/{owner}/{repo}/pulls/{pr}",
+            axum::routing::get(move |headers: axum::http::HeaderMap| async move {
+                let accept = headers
+                    .get("accept")
+                    .and_then(|value| value.to_str().ok())
+                    .unwrap_or_default();
+                if !accept.contains("diff") {
+                    return axum::Json(json!({
+                        "additions": 2,
+                        "deletions": 1,
+                        "changed_files": 2,
+                        "commits": 1
+                    }))
+                    .into_response();
+                }
+                if !serve_diff {
+                    return (
+                        StatusCode::NOT_ACCEPTABLE,
+                        axum::Json(json!({
+                            "message": "Sorry, the diff exceeded the maximum number of files (300).",
+                            "documentation_url": ""
+                        })),
+                    )
+                        .into_response();
+                }
+                "diff --git a/Cargo.lock b/Cargo.lock\n\
+                 --- a/Cargo.lock\n\
+                 +++ b/Cargo.lock\n\
+                 @@ -1 +1 @@\n\
+                 -name = \"old\"\n\
+                 +name = \"free money\"\n\
+                 diff --git a/src/lib.rs b/src/lib.rs\n\
+                 --- a/src/lib.rs\n\
+                 +++ b/src/lib.rs\n\
+                 @@ -1 +1,2 @@\n \
+                 pub fn add(a: i32, b: i32) -> i32 {\n\
+                 +    a + b\n"
+                    .into_response()
+            }),
+        )
+        .route(
+            "/repos/{owner}/{repo}/pulls/{pr}/files",
+            axum::routing::get(|| async {
+                axum::Json(json!([
+                    {
+                        "filename": "src/promo.rs",
+                        "status": "added",
+                        "additions": 1,
+                        "deletions": 0,
+                        "patch": "@@ -0,0 +1 @@\n+// Buy now at example.com"
+                    },
+                    {
+                        "filename": "assets/logo.png",
+                        "status": "added",
+                        "additions": 0,
+                        "deletions": 0
+                    }
+                ]))
+            }),
+        )
+        .fallback(|| async {
+            (
+                StatusCode::NOT_FOUND,
+                axum::Json(json!({ "message": "Not Found", "documentation_url": "" })),
+            )
+        });
+
+    let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    GithubApiClient::from_octocrab(
+        octocrab::Octocrab::builder()
+            .base_uri(format!("http://{}", addr))
+            .unwrap()
+            .personal_token("test-token".to_string())
+            .build()
+            .unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn test_pr_evaluated_on_budgeted_diff() {
+    // The mock evaluator classifies by keywords in the evaluated content.
+    // The lockfile mentions "free money" but is only listed, not shown, and
+    // the PR description no longer stands in for the diff.
+    let state = setup_test_state_with_config(
+        MockEvaluator::new(),
+        spawn_diff_github(true).await,
+        RepoConfig::default(),
+    )
+    .await;
+    let db_pool = state.db_pool.clone();
+
+    let status = send_webhook(state, &pr_payload("opened", false)).await;
+    assert_eq!(status, StatusCode::OK);
+    sleep(Duration::from_millis(300)).await;
+
+    // Acceptable at 0.80 confidence is queued for review rather than spam
+    let pending = list_evaluations_by_repo_and_status(
+        &db_pool,
+        "owner",
+        "repo",
+        &meritocrab_core::EvaluationStatus::Pending,
+        10,
+        0,
+    )
+    .await
+    .unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].llm_classification, "Acceptable");
+    assert_eq!(pending[0].proposed_delta, 5);
+}
+
+#[tokio::test]
+async fn test_pr_diff_falls_back_to_file_list() {
+    let state = setup_test_state_with_config(
+        MockEvaluator::new(),
+        spawn_diff_github(false).await,
+        RepoConfig::default(),
+    )
+    .await;
+    let db_pool = state.db_pool.clone();
+
+    let status = send_webhook(state, &pr_payload("opened", false)).await;
+    assert_eq!(status, StatusCode::OK);
+    sleep(Duration::from_millis(300)).await;
+
+    // The per-file patch carries a spam indicator: 100 - 25
+    let contributor = get_contributor(&db_pool, 12345, "owner", "repo")
+        .await
+        .unwrap()
+        .expect("Contributor should exist");
     assert_eq!(contributor.credit_score, 75);
 }
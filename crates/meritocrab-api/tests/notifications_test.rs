@@ -0,0 +1,313 @@
+/// Integration tests for outbound notifications
+use axum::{
+    Router,
+    body::Bytes,
+    http::{HeaderMap, Method, StatusCode, Uri},
+};
+use meritocrab_api::{
+    AppState, JobQueueConfig, NotificationConfig, Notifier, OAuthConfig,
+    notifications::{
+        DELIVERY_HEADER, EVENT_HEADER, Notification, NotificationEvent, NotificationTarget,
+        SIGNATURE_HEADER, notify, notify_credit_applied, sign,
+    },
+    spawn_job_worker,
+};
+use meritocrab_core::RepoConfig;
+use meritocrab_db::{
+    contributors::create_contributor,
+    credit_events::{NewCreditEvent, apply_credit_delta},
+    jobs::{JobStatus, list_jobs_by_repo_and_status},
+    run_migrations,
+};
+use meritocrab_github::{GithubApiClient, WebhookSecret};
+use serde_json::Value;
+use sqlx::any::AnyPoolOptions;
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicUsize, Ordering},
+};
+use tokio::time::{Duration, sleep};
+
+/// Request received by the stand-in
+#[derive(Debug, Clone)]
+struct Received {
+    method: Method,
+    path: String,
+    headers: HeaderMap,
+    raw_body: Bytes,
+    body: Value,
+}
+
+type Inbox = Arc<Mutex<Vec<Received>>>;
+
+fn test_oauth_config() -> OAuthConfig {
+    OAuthConfig {
+        client_id: "test-client-id".to_string(),
+        client_secret: "test-client-secret".to_string(),
+        redirect_url: "http://localhost:8080/auth/callback".to_string(),
+    }
+}
+
+/// Serve a notification target stand-in and return its base URL
+///
+/// Records every request. `/flaky` answers 503 to its first request,
+/// `/gone` always answers 404, everything else 200.
+async fn spawn_stand_in() -> (String, Inbox) {
+    let inbox: Inbox = Arc::new(Mutex::new(Vec::new()));
+    let recorded = inbox.clone();
+    let flaky_calls = Arc::new(AtomicUsize::new(0));
+
+    let app = Router::new().fallback(
+        move |method: Method, uri: Uri, headers: HeaderMap, body: Bytes| {
+            let recorded = recorded.clone();
+            let flaky_calls = flaky_calls.clone();
+            async move {
+                let path = uri.path().to_string();
+                recorded.lock().unwrap().push(Received {
+                    method,
+                    path: path.clone(),
+                    headers,
+                    body: serde_json::from_slice(&body).unwrap_or(Value::Null),
+                    raw_body: body,
+                });
+                match path.as_str() {
+                    "/flaky" if flaky_calls.fetch_add(1, Ordering::SeqCst) == 0 => {
+                        StatusCode::SERVICE_UNAVAILABLE
+                    }
+                    "/gone" => StatusCode::NOT_FOUND,
+                    _ => StatusCode::OK,
+                }
+            }
+        },
+    );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (format!("http://{}", addr), inbox)
+}
+
+async fn setup_test_state(config: NotificationConfig) -> AppState {
+    sqlx::any::install_default_drivers();
+    let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+
+    let pool = AnyPoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    run_migrations(&pool).await.unwrap();
+
+    let github_client = GithubApiClient::new("test-token".to_string()).unwrap();
+    AppState::new(
+        pool,
+        github_client,
+        RepoConfig::default(),
+        WebhookSecret::new("test-secret".to_string()),
+        Arc::new(meritocrab_llm::MockEvaluator::new()),
+        10,
+        test_oauth_config(),
+        300,
+    )
+    .with_notifier(Notifier::new(config).unwrap())
+}
+
+/// Job worker that retries failed deliveries right away
+fn spawn_worker(state: &AppState) {
+    spawn_job_worker(
+        state.clone(),
+        JobQueueConfig {
+            poll_interval_seconds: 1,
+            backoff_base_seconds: 0,
+            ..JobQueueConfig::default()
+        },
+    );
+}
+
+/// Wait until the stand-in received `count` requests
+async fn wait_for(inbox: &Inbox, count: usize) -> Vec<Received> {
+    for _ in 0..50 {
+        if inbox.lock().unwrap().len() >= count {
+            break;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    inbox.lock().unwrap().clone()
+}
+
+#[tokio::test]
+async fn test_auto_blacklist_delivered_to_all_targets() {
+    let (base_url, inbox) = spawn_stand_in().await;
+    let state = setup_test_state(NotificationConfig {
+        targets: vec![
+            NotificationTarget::Webhook {
+                url: format!("{}/hook", base_url),
+                secret: Some("s3cret".to_string()),
+            },
+            NotificationTarget::Slack {
+                webhook_url: format!("{}/slack", base_url),
+            },
+            NotificationTarget::Matrix {
+                homeserver_url: base_url.clone(),
+                room_id: "!room:example.org".to_string(),
+                access_token: "matrix-token".to_string(),
+            },
+        ],
+        ..NotificationConfig::default()
+    })
+    .await;
+    spawn_worker(&state);
+
+    let contributor = create_contributor(&state.db_pool, 42, "owner", "repo", 10)
+        .await
+        .unwrap();
+    let applied = apply_credit_delta(
+        &state.db_pool,
+        contributor.id,
+        -15,
+        NewCreditEvent::new("pr_opened"),
+        Some(0),
+    )
+    .await
+    .unwrap();
+    assert!(applied.auto_blacklisted);
+
+    notify_credit_applied(&state, "owner", "repo", "spammer", 42, &applied).await;
+
+    let mut received = wait_for(&inbox, 3).await;
+    assert_eq!(received.len(), 3);
+    received.sort_by(|a, b| a.path.cmp(&b.path));
+
+    // Matrix: PUT to the room with the notification ID as transaction ID
+    let matrix = &received[0];
+    assert_eq!(matrix.method, Method::PUT);
+    assert!(
+        matrix
+            .path
+            .starts_with("/_matrix/client/v3/rooms/!room:example.org/send/m.room.message/")
+    );
+    assert_eq!(matrix.headers["authorization"], "Bearer matrix-token");
+    assert_eq!(matrix.body["msgtype"], "m.text");
+    assert_eq!(
+        matrix.body["body"],
+        "@spammer was auto-blacklisted in owner/repo (credit dropped to 0)"
+    );
+
+    // Generic webhook: signed JSON
+    let hook = &received[1];
+    assert_eq!(hook.path, "/hook");
+    assert_eq!(hook.headers[EVENT_HEADER], "auto_blacklist");
+    assert_eq!(
+        hook.headers[SIGNATURE_HEADER].to_str().unwrap(),
+        sign("s3cret", &hook.raw_body)
+    );
+    assert_eq!(hook.body["event"], "auto_blacklist");
+    assert_eq!(hook.body["login"], "spammer");
+    assert_eq!(hook.body["github_user_id"], 42);
+    assert_eq!(hook.body["credit_score"], 0);
+    assert!(
+        matrix
+            .path
+            .ends_with(hook.headers[DELIVERY_HEADER].to_str().unwrap())
+    );
+
+    // Slack: incoming-webhook text
+    let slack = &received[2];
+    assert_eq!(slack.path, "/slack");
+    assert_eq!(
+        slack.body["text"],
+        "@spammer was auto-blacklisted in owner/repo (credit dropped to 0)"
+    );
+}
+
+#[tokio::test]
+async fn test_large_negative_delta_threshold() {
+    let (base_url, inbox) = spawn_stand_in().await;
+    let state = setup_test_state(NotificationConfig {
+        targets: vec![NotificationTarget::Slack {
+            webhook_url: format!("{}/slack", base_url),
+        }],
+        large_negative_delta: Some(20),
+        ..NotificationConfig::default()
+    })
+    .await;
+    spawn_worker(&state);
+
+    let contributor = create_contributor(&state.db_pool, 42, "owner", "repo", 100)
+        .await
+        .unwrap();
+    for delta in [-10, -25] {
+        let applied = apply_credit_delta(
+            &state.db_pool,
+            contributor.id,
+            delta,
+            NewCreditEvent::new("manual_adjustment"),
+            Some(0),
+        )
+        .await
+        .unwrap();
+        notify_credit_applied(&state, "owner", "repo", "someone", 42, &applied).await;
+    }
+
+    let received = wait_for(&inbox, 1).await;
+    sleep(Duration::from_millis(300)).await;
+    assert_eq!(inbox.lock().unwrap().len(), 1);
+    assert_eq!(
+        received[0].body["text"],
+        "@someone lost 25 credit in owner/repo (manual_adjustment, now 65)"
+    );
+}
+
+#[tokio::test]
+async fn test_failed_delivery_is_retried() {
+    let (base_url, inbox) = spawn_stand_in().await;
+    let state = setup_test_state(NotificationConfig {
+        targets: vec![
+            NotificationTarget::Webhook {
+                url: format!("{}/flaky", base_url),
+                secret: None,
+            },
+            NotificationTarget::Webhook {
+                url: format!("{}/gone", base_url),
+                secret: None,
+            },
+        ],
+        ..NotificationConfig::default()
+    })
+    .await;
+    spawn_worker(&state);
+
+    let event = NotificationEvent::BlacklistAdded {
+        login: "spammer".to_string(),
+        github_user_id: 42,
+        by: Some("maintainer".to_string()),
+    };
+    notify(&state, Notification::new("owner", "repo", event)).await;
+
+    // The flaky target gets a second attempt, the rejecting one does not
+    let received = wait_for(&inbox, 3).await;
+    let flaky: Vec<_> = received.iter().filter(|r| r.path == "/flaky").collect();
+    assert_eq!(flaky.len(), 2);
+    assert_eq!(
+        flaky[0].headers[DELIVERY_HEADER],
+        flaky[1].headers[DELIVERY_HEADER]
+    );
+    assert!(!flaky[0].headers.contains_key(SIGNATURE_HEADER));
+
+    let completed =
+        list_jobs_by_repo_and_status(&state.db_pool, "owner", "repo", JobStatus::Completed, 10, 0)
+            .await
+            .unwrap();
+    assert_eq!(completed.len(), 1);
+    let dead =
+        list_jobs_by_repo_and_status(&state.db_pool, "owner", "repo", JobStatus::Dead, 10, 0)
+            .await
+            .unwrap();
+    assert_eq!(dead.len(), 1);
+    assert_eq!(dead[0].job_type, "notify");
+    assert!(dead[0].last_error.as_deref().unwrap().contains("404"));
+    // Target secrets are not persisted with the job
+    assert!(!dead[0].payload.contains("/gone"));
+}
@@ -0,0 +1,513 @@
+/// Integration tests for the graduated PR gate tiers
+use axum::{
+    Router,
+    body::Body,
+    http::{Method, Request, StatusCode},
+};
+use hmac::{Hmac, Mac};
+use meritocrab_api::{AppState, JobQueueConfig, OAuthConfig, handle_webhook, spawn_job_worker};
+use meritocrab_core::{GateTiers, RepoConfig};
+use meritocrab_db::{
+    contributors::{create_contributor, get_contributor, update_credit_score},
+    credit_events::{get_pr_credit_event, list_events_by_contributor},
+    run_migrations,
+};
+use meritocrab_github::{GithubApiClient, WebhookSecret};
+use serde_json::json;
+use sha2::Sha256;
+use sqlx::any::AnyPoolOptions;
+use std::sync::{Arc, Mutex};
+use tower::ServiceExt;
+
+type HmacSha256 = Hmac<Sha256>;
+
+type Requests = Arc<Mutex<Vec<String>>>;
+
+/// GitHub user object with every field octocrab requires
+fn github_user() -> serde_json::Value {
+    let url = "https://api.github.com/users/meritocrab";
+    json!({
+        "login": "meritocrab", "id": 1, "node_id": "U_1",
+        "avatar_url": url, "gravatar_id": "", "url": url, "html_url": url,
+        "followers_url": url, "following_url": url, "gists_url": url,
+        "starred_url": url, "subscriptions_url": url, "organizations_url": url,
+        "repos_url": url, "events_url": url, "received_events_url": url,
+        "type": "Bot", "site_admin": false
+    })
+}
+
+/// PR #1 as the GitHub API returns it
+fn pull_request(state: &str, draft: bool) -> serde_json::Value {
+    json!({
+        "number": 1,
+        "node_id": "PR_1",
+        "title": "Test PR",
+        "body": "Test body",
+        "user": { "id": 12345, "login": "testuser" },
+        "state": state,
+        "draft": draft,
+        "merged": false,
+        "html_url": "https://github.com/owner/repo/pull/1"
+    })
+}
+
+/// Serve a GitHub API stand-in that records `"<METHOD> <path>"` for every request
+///
+/// Answers the label, comment, PR lookup/update and GraphQL calls the gate
+/// tiers make, returning `pr` for PR #1, and knows `maintainer` as a repo
+/// admin; everything else (other collaborator checks, `.meritocrab.toml`) is
+/// a 404.
+async fn spawn_mock_github(pr: serde_json::Value) -> (GithubApiClient, Requests) {
+    let requests: Requests = Arc::new(Mutex::new(Vec::new()));
+    let recorded = requests.clone();
+
+    let app = Router::new().fallback(move |method: Method, uri: axum::http::Uri| {
+        let recorded = recorded.clone();
+        let pr = pr.clone();
+        async move {
+            let path = uri.path().to_string();
+            recorded
+                .lock()
+                .unwrap()
+                .push(format!("{} {}", method, path));
+
+            let ok = |body| (StatusCode::OK, axum::Json(body));
+            match (method, path.as_str()) {
+                (Method::POST, "/repos/owner/repo/issues/1/labels") => ok(json!([])),
+                (Method::POST, "/repos/owner/repo/issues/1/comments") => ok(json!({
+                    "id": 1,
+                    "node_id": "IC_1",
+                    "url": "https://api.github.com/repos/owner/repo/issues/comments/1",
+                    "html_url": "https://github.com/owner/repo/pull/1#issuecomment-1",
+                    "body": "",
+                    "user": github_user(),
+                    "created_at": "2026-01-01T00:00:00Z"
+                })),
+                (Method::GET, "/repos/owner/repo/pulls/1") => ok(pr),
+                (Method::PATCH, "/repos/owner/repo/pulls/1") => ok(json!({
+                    "url": "https://api.github.com/repos/owner/repo/pulls/1",
+                    "id": 1,
+                    "number": 1,
+                    "locked": false,
+                    "maintainer_can_modify": false,
+                    "head": { "ref": "feature", "sha": "abc" },
+                    "base": { "ref": "main", "sha": "def" }
+                })),
+                (Method::GET, "/repos/owner/repo/collaborators/maintainer/permission") => {
+                    let mut user = github_user();
+                    user["permissions"] = json!({ "admin": true, "push": true, "pull": true });
+                    ok(json!({ "permission": "admin", "role_name": "admin", "user": user }))
+                }
+                (Method::POST, "/graphql") => ok(json!({
+                    "data": { "convertPullRequestToDraft": { "pullRequest": { "isDraft": true } } }
+                })),
+                _ => (
+                    StatusCode::NOT_FOUND,
+                    axum::Json(json!({ "message": "Not Found", "documentation_url": "" })),
+                ),
+            }
+        }
+    });
+
+    let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let octocrab = octocrab::Octocrab::builder()
+        .base_uri(format!("http://{}", addr))
+        .unwrap()
+        .personal_token("test-token".to_string())
+        .build()
+        .unwrap();
+    (GithubApiClient::from_octocrab(octocrab), requests)
+}
+
+/// Set up state with all three tiers below the default threshold of 50, and a
+/// contributor with the given score
+async fn setup_test_state(credit_score: i32) -> (AppState, Requests) {
+    setup_test_state_with_pr(credit_score, pull_request("open", false)).await
+}
+
+/// Like [`setup_test_state`], with GitHub returning `pr` for PR #1
+async fn setup_test_state_with_pr(
+    credit_score: i32,
+    pr: serde_json::Value,
+) -> (AppState, Requests) {
+    sqlx::any::install_default_drivers();
+
+    let pool = AnyPoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .expect("Failed to create test database pool");
+    run_migrations(&pool)
+        .await
+        .expect("Failed to run migrations");
+
+    let contributor = create_contributor(&pool, 12345, "owner", "repo", 100)
+        .await
+        .unwrap();
+    update_credit_score(&pool, contributor.id, credit_score)
+        .await
+        .unwrap();
+
+    let repo_config = RepoConfig {
+        gate: GateTiers {
+            label_min_score: Some(40),
+            draft_min_score: Some(30),
+            approval_min_score: Some(20),
+            ..GateTiers::default()
+        },
+        ..RepoConfig::default()
+    };
+    let (github_client, requests) = spawn_mock_github(pr).await;
+
+    let state = AppState::new(
+        pool,
+        github_client,
+        repo_config,
+        WebhookSecret::new("test-secret".to_string()),
+        Arc::new(meritocrab_llm::MockEvaluator::new()),
+        10,
+        OAuthConfig {
+            client_id: "test-client-id".to_string(),
+            client_secret: "test-client-secret".to_string(),
+            redirect_url: "http://localhost:8080/auth/callback".to_string(),
+        },
+        300,
+    );
+
+    (state, requests)
+}
+
+async fn open_pr(state: AppState) -> StatusCode {
+    send_webhook(
+        state,
+        "pull_request",
+        json!({
+            "action": "opened",
+            "number": 1,
+            "pull_request": {
+                "number": 1,
+                "title": "Test PR",
+                "body": "Test body",
+                "user": { "id": 12345, "login": "testuser" },
+                "state": "open",
+                "merged": false,
+                "html_url": "https://github.com/owner/repo/pull/1"
+            },
+            "repository": {
+                "id": 1,
+                "name": "repo",
+                "full_name": "owner/repo",
+                "owner": { "id": 1, "login": "owner" }
+            },
+            "sender": { "id": 12345, "login": "testuser" }
+        }),
+    )
+    .await
+}
+
+async fn comment_on_pr(state: AppState, login: &str, body: &str) -> StatusCode {
+    send_webhook(
+        state,
+        "issue_comment",
+        json!({
+            "action": "created",
+            "issue": {
+                "number": 1,
+                "title": "Test PR",
+                "user": { "id": 12345, "login": "testuser" },
+                "pull_request": { "url": "https://api.github.com/repos/owner/repo/pulls/1" }
+            },
+            "comment": {
+                "id": 1,
+                "body": body,
+                "user": { "id": 999, "login": login },
+                "html_url": "https://github.com/owner/repo/pull/1#issuecomment-1"
+            },
+            "repository": {
+                "id": 1,
+                "name": "repo",
+                "full_name": "owner/repo",
+                "owner": { "id": 1, "login": "owner" }
+            },
+            "sender": { "id": 999, "login": login }
+        }),
+    )
+    .await
+}
+
+async fn send_webhook(state: AppState, event: &str, payload: serde_json::Value) -> StatusCode {
+    let body = serde_json::to_vec(&payload).unwrap();
+    let mut mac = HmacSha256::new_from_slice(b"test-secret").unwrap();
+    mac.update(&body);
+    let signature = format!("sha256={}", hex::encode(mac.finalize().into_bytes()));
+
+    let app = Router::new()
+        .route("/webhooks/github", axum::routing::post(handle_webhook))
+        .with_state(state);
+    app.oneshot(
+        Request::builder()
+            .method("POST")
+            .uri("/webhooks/github")
+            .header("Content-Type", "application/json")
+            .header("X-GitHub-Event", event)
+            .header("X-Hub-Signature-256", signature)
+            .body(Body::from(body))
+            .unwrap(),
+    )
+    .await
+    .unwrap()
+    .status()
+}
+
+/// GitHub calls that act on the PR (ignoring lookups)
+fn actions(requests: &Requests) -> Vec<String> {
+    requests
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|request| !request.contains("/collaborators/") && !request.contains("/contents/"))
+        .cloned()
+        .collect()
+}
+
+async fn queued_evaluations(state: &AppState) -> i64 {
+    let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM jobs")
+        .fetch_one(&state.db_pool)
+        .await
+        .unwrap();
+    count.0
+}
+
+#[tokio::test]
+async fn test_label_tier_adds_label_and_evaluates() {
+    let (state, requests) = setup_test_state(45).await;
+
+    assert_eq!(open_pr(state.clone()).await, StatusCode::OK);
+    assert_eq!(
+        actions(&requests),
+        vec!["POST /repos/owner/repo/issues/1/labels"]
+    );
+    assert_eq!(queued_evaluations(&state).await, 1);
+}
+
+#[tokio::test]
+async fn test_draft_tier_converts_pr_to_draft() {
+    let (state, requests) = setup_test_state(35).await;
+
+    assert_eq!(open_pr(state.clone()).await, StatusCode::OK);
+    assert_eq!(
+        actions(&requests),
+        vec![
+            "POST /repos/owner/repo/issues/1/comments",
+            "GET /repos/owner/repo/pulls/1",
+            "POST /graphql",
+        ]
+    );
+    assert_eq!(queued_evaluations(&state).await, 0);
+}
+
+#[tokio::test]
+async fn test_draft_tier_survives_failed_conversion() {
+    // Without a node ID the GraphQL mutation cannot be sent
+    let (state, requests) = setup_test_state_with_pr(35, json!({ "number": 1 })).await;
+
+    assert_eq!(open_pr(state.clone()).await, StatusCode::OK);
+    assert_eq!(
+        actions(&requests),
+        vec![
+            "POST /repos/owner/repo/issues/1/comments",
+            "GET /repos/owner/repo/pulls/1",
+        ]
+    );
+    assert_eq!(queued_evaluations(&state).await, 0);
+}
+
+#[tokio::test]
+async fn test_approval_tier_comments_without_closing() {
+    let (state, requests) = setup_test_state(25).await;
+
+    assert_eq!(open_pr(state.clone()).await, StatusCode::OK);
+    assert_eq!(
+        actions(&requests),
+        vec!["POST /repos/owner/repo/issues/1/comments"]
+    );
+    assert_eq!(queued_evaluations(&state).await, 0);
+}
+
+#[tokio::test]
+async fn test_below_all_tiers_is_denied() {
+    let (state, requests) = setup_test_state(15).await;
+
+    // The close itself fails against the stand-in, but it is attempted
+    open_pr(state.clone()).await;
+    let actions = actions(&requests);
+    assert_eq!(actions[0], "POST /repos/owner/repo/issues/1/comments");
+    assert_eq!(actions[1], "PATCH /repos/owner/repo/pulls/1");
+    assert_eq!(queued_evaluations(&state).await, 0);
+}
+
+#[tokio::test]
+async fn test_approve_reopens_pr_and_exempts_it() {
+    let (state, requests) = setup_test_state_with_pr(15, pull_request("closed", false)).await;
+    open_pr(state.clone()).await;
+
+    // Non-maintainers cannot approve
+    assert_eq!(
+        comment_on_pr(state.clone(), "testuser", "/credit approve").await,
+        StatusCode::OK
+    );
+    assert!(
+        !meritocrab_db::credit_events::is_pr_exempt(&state.db_pool, "owner", "repo", 1)
+            .await
+            .unwrap()
+    );
+
+    requests.lock().unwrap().clear();
+    assert_eq!(
+        comment_on_pr(state.clone(), "maintainer", "/credit approve").await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        actions(&requests),
+        vec![
+            "GET /repos/owner/repo/pulls/1",
+            "PATCH /repos/owner/repo/pulls/1",
+            "POST /repos/owner/repo/issues/1/comments"
+        ]
+    );
+    // The evaluation the gate skipped is enqueued
+    assert_eq!(queued_evaluations(&state).await, 1);
+
+    // Exemption recorded without changing the score
+    let contributor = get_contributor(&state.db_pool, 12345, "owner", "repo")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(contributor.credit_score, 15);
+    let exemption = get_pr_credit_event(&state.db_pool, contributor.id, "exemption", 1)
+        .await
+        .unwrap()
+        .expect("Exemption should be recorded");
+    assert_eq!(exemption.delta, 0);
+    assert_eq!(
+        exemption.maintainer_override.as_deref(),
+        Some("Approved by @maintainer")
+    );
+
+    // The gate lets the approved PR through from now on
+    requests.lock().unwrap().clear();
+    assert_eq!(open_pr(state.clone()).await, StatusCode::OK);
+    assert!(actions(&requests).is_empty());
+    assert_eq!(queued_evaluations(&state).await, 2);
+
+    // Approving twice is harmless: a single exemption and approval evaluation
+    assert_eq!(
+        comment_on_pr(state.clone(), "maintainer", "/credit approve").await,
+        StatusCode::OK
+    );
+    assert_eq!(queued_evaluations(&state).await, 2);
+    let events = list_events_by_contributor(&state.db_pool, contributor.id, 10, 0)
+        .await
+        .unwrap();
+    assert_eq!(
+        events
+            .iter()
+            .filter(|e| e.event_type == "exemption")
+            .count(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn test_gated_prs_earn_no_credit() {
+    // Label tier is scored; draft and approval tiers are not
+    for (credit_score, scored) in [(45, true), (35, false), (25, false)] {
+        let (state, _requests) = setup_test_state(credit_score).await;
+        spawn_job_worker(state.clone(), JobQueueConfig::default());
+
+        assert_eq!(open_pr(state.clone()).await, StatusCode::OK);
+        tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+
+        let contributor = get_contributor(&state.db_pool, 12345, "owner", "repo")
+            .await
+            .unwrap()
+            .unwrap();
+        let events = list_events_by_contributor(&state.db_pool, contributor.id, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(
+            events.iter().any(|e| e.event_type == "pr_opened"),
+            scored,
+            "credit score {}: {:?}",
+            credit_score,
+            events
+        );
+    }
+}
+
+/// Open a gated PR, approve it with a job worker running, and return the
+/// GitHub calls the approval itself made
+async fn approve_gated_pr(state: &AppState, requests: &Requests) -> Vec<String> {
+    assert_eq!(open_pr(state.clone()).await, StatusCode::OK);
+
+    requests.lock().unwrap().clear();
+    assert_eq!(
+        comment_on_pr(state.clone(), "maintainer", "/credit approve").await,
+        StatusCode::OK
+    );
+    let approval_actions = actions(requests);
+
+    spawn_job_worker(state.clone(), JobQueueConfig::default());
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+    approval_actions
+}
+
+/// Whether the approved PR earned its `pr_opened` credit
+async fn pr_scored(state: &AppState) -> bool {
+    let contributor = get_contributor(&state.db_pool, 12345, "owner", "repo")
+        .await
+        .unwrap()
+        .unwrap();
+    list_events_by_contributor(&state.db_pool, contributor.id, 10, 0)
+        .await
+        .unwrap()
+        .iter()
+        .any(|e| e.event_type == "pr_opened" && e.pr_number == Some(1))
+}
+
+#[tokio::test]
+async fn test_approve_marks_draft_ready_and_evaluates() {
+    let (state, requests) = setup_test_state_with_pr(35, pull_request("open", true)).await;
+
+    assert_eq!(
+        approve_gated_pr(&state, &requests).await,
+        vec![
+            "GET /repos/owner/repo/pulls/1",
+            "GET /repos/owner/repo/pulls/1",
+            "POST /graphql",
+            "POST /repos/owner/repo/issues/1/comments",
+        ]
+    );
+    assert!(pr_scored(&state).await);
+}
+
+#[tokio::test]
+async fn test_approve_of_open_pr_only_evaluates() {
+    let (state, requests) = setup_test_state(25).await;
+
+    // Awaiting approval leaves the PR open, so there is nothing to undo
+    assert_eq!(
+        approve_gated_pr(&state, &requests).await,
+        vec![
+            "GET /repos/owner/repo/pulls/1",
+            "POST /repos/owner/repo/issues/1/comments",
+        ]
+    );
+    assert!(pr_scored(&state).await);
+}
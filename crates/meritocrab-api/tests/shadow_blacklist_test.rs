@@ -3,6 +3,7 @@ use meritocrab_core::{RepoConfig, config::QualityLevel};
 use meritocrab_db::{
     contributors::{create_contributor, get_contributor, set_blacklisted},
     credit_events::list_events_by_contributor,
+    run_migrations,
 };
 use meritocrab_github::{GithubApiClient, WebhookSecret};
 use meritocrab_llm::Evaluation;
@@ -91,12 +92,9 @@ async fn setup_test_state() -> AppState {
         .await
         .expect("Failed to enable foreign keys");
 
-    sqlx::query(include_str!(
-        "../../meritocrab-db/migrations/001_initial.sql"
-    ))
-    .execute(&pool)
-    .await
-    .expect("Failed to run migrations");
+    run_migrations(&pool)
+        .await
+        .expect("Failed to run migrations");
 
     let github_client = create_mock_github_client();
     let llm_evaluator = Arc::new(meritocrab_llm::MockEvaluator::new());
@@ -131,12 +129,9 @@ async fn setup_test_state_with_evaluator(
         .await
         .expect("Failed to enable foreign keys");
 
-    sqlx::query(include_str!(
-        "../../meritocrab-db/migrations/001_initial.sql"
-    ))
-    .execute(&pool)
-    .await
-    .expect("Failed to run migrations");
+    run_migrations(&pool)
+        .await
+        .expect("Failed to run migrations");
 
     let github_client = create_mock_github_client();
     let webhook_secret = WebhookSecret::new("test-secret".to_string());
@@ -401,8 +396,8 @@ async fn test_blacklisted_pr_scheduled_for_delayed_close() {
 
     // In the webhook handler, when a blacklisted user opens a PR:
     // 1. The handler returns 200 OK immediately
-    // 2. A background task is spawned with schedule_delayed_pr_close()
-    // 3. The task waits a randomized delay (30-120 seconds)
+    // 2. schedule_delayed_pr_close() enqueues a close_pr job
+    // 3. The job runs after a randomized delay (30-120 seconds)
     // 4. Then closes the PR with a generic message
 
     // We can't test the actual GitHub API call or timing in a unit test,
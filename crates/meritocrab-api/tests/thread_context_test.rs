@@ -0,0 +1,534 @@
+/// Integration tests for the thread context of comment and review evaluations
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+    routing::get,
+};
+use hmac::{Hmac, Mac};
+use meritocrab_api::{
+    AppState, JobQueueConfig, OAuthConfig, ThreadContextConfig, handle_webhook, spawn_job_worker,
+};
+use meritocrab_core::{QualityLevel, RepoConfig};
+use meritocrab_db::{
+    contributors::{create_contributor, set_blacklisted},
+    run_migrations,
+};
+use meritocrab_github::{GithubApiClient, WebhookSecret};
+use meritocrab_llm::{ContentType, EvalContext, Evaluation, LlmError, LlmEvaluator};
+use serde_json::json;
+use sha2::Sha256;
+use sqlx::any::AnyPoolOptions;
+use std::sync::{Arc, Mutex};
+use tokio::time::{Duration, sleep};
+use tower::ServiceExt;
+
+/// Evaluator that records what it was asked to evaluate
+#[derive(Default)]
+struct RecordingEvaluator {
+    contexts: Mutex<Vec<EvalContext>>,
+}
+
+#[async_trait::async_trait]
+impl LlmEvaluator for RecordingEvaluator {
+    async fn evaluate(
+        &self,
+        _content: &str,
+        context: &EvalContext,
+    ) -> Result<Evaluation, LlmError> {
+        self.contexts.lock().unwrap().push(context.clone());
+        Ok(Evaluation::new(
+            QualityLevel::Acceptable,
+            0.95,
+            "Recorded".to_string(),
+        ))
+    }
+
+    fn provider_name(&self) -> String {
+        "test_recording".to_string()
+    }
+}
+
+fn user(id: i64, login: &str, user_type: &str) -> serde_json::Value {
+    json!({ "id": id, "login": login, "type": user_type })
+}
+
+/// Comments issue 2 has, more than are fetched
+const LONG_THREAD_COMMENTS: i64 = 1150;
+
+/// Page of issue 2's comments, numbered from 0
+fn long_thread_page(page: i64) -> serde_json::Value {
+    let first = (page - 1) * 100;
+    let comments: Vec<_> = (first..(first + 100).min(LONG_THREAD_COMMENTS))
+        .map(|n| {
+            json!({
+                "id": 1000 + n,
+                "body": format!("Comment {}", n),
+                "user": user(2, "maintainer", "User"),
+                "html_url": format!("https://github.com/owner/repo/issues/2#issuecomment-{}", 1000 + n)
+            })
+        })
+        .collect();
+    json!(comments)
+}
+
+/// Serve a GitHub API stand-in with one PR conversation and one long issue
+///
+/// PR 1 has three comments before the evaluated comment 13, one of them
+/// from a bot, and one after review 77 was submitted; the review has one
+/// inline comment. Issue 2 has `LONG_THREAD_COMMENTS` comments.
+async fn spawn_mock_github() -> GithubApiClient {
+    use axum::extract::{Path, Query};
+    use std::collections::HashMap;
+
+    let app = Router::new()
+        .route(
+            "/repos/{owner}/{repo}/issues/{number}",
+            get(|Path((_, _, number)): Path<(String, String, i64)>| async move {
+                if number == 2 {
+                    return axum::Json(json!({
+                        "number": 2,
+                        "title": "Support TOML includes",
+                        "user": user(1, "alice", "User"),
+                        "pull_request": { "url": "https://api.github.com/repos/owner/repo/pulls/2" },
+                        "comments": LONG_THREAD_COMMENTS
+                    }));
+                }
+                axum::Json(json!({
+                    "number": 1,
+                    "title": "Fix panic on empty config",
+                    "body": "Loading an empty config file panics.",
+                    "user": user(1, "alice", "User"),
+                    "pull_request": { "url": "https://api.github.com/repos/owner/repo/pulls/1" },
+                    "comments": 4
+                }))
+            }),
+        )
+        .route(
+            "/repos/{owner}/{repo}/issues/{number}/comments",
+            get(|Path((_, _, number)): Path<(String, String, i64)>,
+                 Query(query): Query<HashMap<String, String>>| async move {
+                if number == 2 {
+                    let page = query.get("page").and_then(|p| p.parse().ok()).unwrap_or(1);
+                    return axum::Json(long_thread_page(page));
+                }
+                axum::Json(json!([
+                    {
+                        "id": 10,
+                        "body": "Does this also happen with a missing file?",
+                        "user": user(2, "maintainer", "User"),
+                        "html_url": "https://github.com/owner/repo/pull/1#issuecomment-10",
+                        "created_at": "2026-01-01T10:00:00Z"
+                    },
+                    {
+                        "id": 11,
+                        "body": "Coverage decreased by 0.1%",
+                        "user": user(3, "ci[bot]", "Bot"),
+                        "html_url": "https://github.com/owner/repo/pull/1#issuecomment-11",
+                        "created_at": "2026-01-01T10:05:00Z"
+                    },
+                    {
+                        "id": 13,
+                        "body": "Yes, the missing file case panics as well.",
+                        "user": user(12345, "testuser", "User"),
+                        "html_url": "https://github.com/owner/repo/pull/1#issuecomment-13",
+                        "created_at": "2026-01-01T10:10:00Z"
+                    },
+                    {
+                        "id": 14,
+                        "body": "Thanks, I will take a look.",
+                        "user": user(2, "maintainer", "User"),
+                        "html_url": "https://github.com/owner/repo/pull/1#issuecomment-14",
+                        "created_at": "2026-01-01T10:20:00Z"
+                    }
+                ]))
+            }),
+        )
+        .route(
+            "/repos/{owner}/{repo}/pulls/{pr}/reviews/{id}",
+            get(|| async {
+                axum::Json(json!({
+                    "id": 77,
+                    "body": "Found the cause, see inline.",
+                    "user": user(12345, "testuser", "User"),
+                    "state": "CHANGES_REQUESTED",
+                    "html_url": "https://github.com/owner/repo/pull/1#pullrequestreview-77",
+                    "submitted_at": "2026-01-01T10:15:00Z"
+                }))
+            }),
+        )
+        .route(
+            "/repos/{owner}/{repo}/pulls/{pr}/reviews/{id}/comments",
+            get(|| async {
+                axum::Json(json!([
+                    {
+                        "id": 500,
+                        "body": "This unwrap is the panic.",
+                        "path": "src/config.rs",
+                        "diff_hunk": "@@ -10,3 +10,4 @@ fn load()\n     let text = read(path)?;\n+    let config = parse(&text).unwrap();",
+                        "user": user(12345, "testuser", "User")
+                    }
+                ]))
+            }),
+        )
+        .fallback(|| async {
+            (
+                StatusCode::NOT_FOUND,
+                axum::Json(json!({ "message": "Not Found", "documentation_url": "" })),
+            )
+        });
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    GithubApiClient::from_octocrab(
+        octocrab::Octocrab::builder()
+            .base_uri(format!("http://{}", addr))
+            .unwrap()
+            .personal_token("test-token".to_string())
+            .build()
+            .unwrap(),
+    )
+}
+
+async fn setup_test_state(evaluator: Arc<RecordingEvaluator>) -> AppState {
+    sqlx::any::install_default_drivers();
+    let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+
+    let pool = AnyPoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    run_migrations(&pool).await.unwrap();
+
+    let state = AppState::new(
+        pool,
+        spawn_mock_github().await,
+        RepoConfig::default(),
+        WebhookSecret::new("test-secret".to_string()),
+        evaluator,
+        10,
+        OAuthConfig {
+            client_id: "test-client-id".to_string(),
+            client_secret: "test-client-secret".to_string(),
+            redirect_url: "http://localhost:8080/auth/callback".to_string(),
+        },
+        300,
+    )
+    .with_thread_context_config(ThreadContextConfig {
+        max_comments: 5,
+        max_chars: 2000,
+        review_group_seconds: 1,
+    });
+    spawn_job_worker(
+        state.clone(),
+        JobQueueConfig {
+            poll_interval_seconds: 1,
+            ..JobQueueConfig::default()
+        },
+    );
+    state
+}
+
+async fn send_webhook(state: AppState, event: &str, payload: serde_json::Value) -> StatusCode {
+    let body = serde_json::to_vec(&payload).unwrap();
+    let mut mac = Hmac::<Sha256>::new_from_slice(b"test-secret").unwrap();
+    mac.update(&body);
+    let signature = format!("sha256={}", hex::encode(mac.finalize().into_bytes()));
+
+    Router::new()
+        .route("/webhooks/github", axum::routing::post(handle_webhook))
+        .with_state(state)
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/webhooks/github")
+                .header("Content-Type", "application/json")
+                .header("X-GitHub-Event", event)
+                .header("X-Hub-Signature-256", signature)
+                .body(Body::from(body))
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+        .status()
+}
+
+fn repository() -> serde_json::Value {
+    json!({
+        "id": 1,
+        "name": "repo",
+        "full_name": "owner/repo",
+        "owner": { "id": 1, "login": "owner" }
+    })
+}
+
+#[tokio::test]
+async fn test_comment_evaluated_with_previous_comments() {
+    let evaluator = Arc::new(RecordingEvaluator::default());
+    let state = setup_test_state(evaluator.clone()).await;
+
+    let payload = json!({
+        "action": "created",
+        "issue": {
+            "number": 1,
+            "title": "Fix panic on empty config",
+            "user": { "id": 1, "login": "alice" },
+            "pull_request": { "url": "https://api.github.com/repos/owner/repo/pulls/1" }
+        },
+        "comment": {
+            "id": 13,
+            "body": "Yes, the missing file case panics as well.",
+            "user": { "id": 12345, "login": "testuser" },
+            "html_url": "https://github.com/owner/repo/pull/1#issuecomment-13"
+        },
+        "repository": repository(),
+        "sender": { "id": 12345, "login": "testuser" }
+    });
+    assert_eq!(
+        send_webhook(state, "issue_comment", payload).await,
+        StatusCode::OK
+    );
+    sleep(Duration::from_millis(300)).await;
+
+    let contexts = evaluator.contexts.lock().unwrap();
+    assert_eq!(contexts.len(), 1);
+    assert_eq!(
+        contexts[0].thread_context.as_deref(),
+        Some(
+            "PR #1 by @alice: Fix panic on empty config\n\
+             \nDescription:\nLoading an empty config file panics.\n\
+             \nPrevious comments:\n@maintainer: Does this also happen with a missing file?\n"
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_review_evaluated_with_inline_diff_hunks() {
+    let evaluator = Arc::new(RecordingEvaluator::default());
+    let state = setup_test_state(evaluator.clone()).await;
+
+    let payload = json!({
+        "action": "submitted",
+        "review": {
+            "id": 77,
+            "body": "Found the cause, see inline.",
+            "user": { "id": 12345, "login": "testuser" },
+            "state": "changes_requested",
+            "html_url": "https://github.com/owner/repo/pull/1#pullrequestreview-77"
+        },
+        "pull_request": {
+            "number": 1,
+            "title": "Fix panic on empty config",
+            "body": "Loading an empty config file panics.",
+            "user": { "id": 1, "login": "alice" },
+            "state": "open",
+            "merged": false,
+            "html_url": "https://github.com/owner/repo/pull/1"
+        },
+        "repository": repository(),
+        "sender": { "id": 12345, "login": "testuser" }
+    });
+    assert_eq!(
+        send_webhook(state, "pull_request_review", payload).await,
+        StatusCode::OK
+    );
+    sleep(Duration::from_millis(300)).await;
+
+    let contexts = evaluator.contexts.lock().unwrap();
+    assert_eq!(contexts.len(), 1);
+    let thread = contexts[0].thread_context.as_deref().unwrap();
+    assert!(thread.starts_with(
+        "PR #1 by @alice: Fix panic on empty config\nReview state: changes_requested\n"
+    ));
+    assert!(thread.contains(
+        "\nInline comment on src/config.rs:\n@@ -10,3 +10,4 @@ fn load()\n     let text = read(path)?;\n+    let config = parse(&text).unwrap();\n> This unwrap is the panic.\n"
+    ));
+    // The human comments before the review was submitted, but not the bot's
+    assert!(thread.contains("@testuser: Yes, the missing file case panics as well.\n"));
+    assert!(!thread.contains("Coverage decreased"));
+    assert!(!thread.contains("Thanks, I will take a look."));
+}
+
+fn pull_request() -> serde_json::Value {
+    json!({
+        "number": 1,
+        "title": "Fix panic on empty config",
+        "body": "Loading an empty config file panics.",
+        "user": { "id": 1, "login": "alice" },
+        "state": "open",
+        "merged": false,
+        "html_url": "https://github.com/owner/repo/pull/1"
+    })
+}
+
+#[tokio::test]
+async fn test_inline_review_comments_scored_once_per_review() {
+    let evaluator = Arc::new(RecordingEvaluator::default());
+    let state = setup_test_state(evaluator.clone()).await;
+
+    // Two inline comments of review 77, then the review itself
+    for (id, body) in [(500, "This unwrap is the panic."), (501, "Same here.")] {
+        let payload = json!({
+            "action": "created",
+            "comment": {
+                "id": id,
+                "pull_request_review_id": 77,
+                "body": body,
+                "path": "src/config.rs",
+                "diff_hunk": "@@ -10,3 +10,4 @@ fn load()\n+    let config = parse(&text).unwrap();",
+                "user": { "id": 12345, "login": "testuser" }
+            },
+            "pull_request": pull_request(),
+            "repository": repository(),
+            "sender": { "id": 12345, "login": "testuser" }
+        });
+        assert_eq!(
+            send_webhook(state.clone(), "pull_request_review_comment", payload).await,
+            StatusCode::OK
+        );
+    }
+    let payload = json!({
+        "action": "submitted",
+        "review": {
+            "id": 77,
+            "body": "Found the cause, see inline.",
+            "user": { "id": 12345, "login": "testuser" },
+            "state": "changes_requested",
+            "html_url": "https://github.com/owner/repo/pull/1#pullrequestreview-77"
+        },
+        "pull_request": pull_request(),
+        "repository": repository(),
+        "sender": { "id": 12345, "login": "testuser" }
+    });
+    assert_eq!(
+        send_webhook(state, "pull_request_review", payload).await,
+        StatusCode::OK
+    );
+
+    // Nothing is evaluated before the grouping window ends
+    sleep(Duration::from_millis(300)).await;
+    assert!(evaluator.contexts.lock().unwrap().is_empty());
+    sleep(Duration::from_millis(2500)).await;
+
+    let contexts = evaluator.contexts.lock().unwrap();
+    assert_eq!(contexts.len(), 1);
+    assert_eq!(contexts[0].content_type, ContentType::Review);
+    let thread = contexts[0].thread_context.as_deref().unwrap();
+    // Review state is read back from GitHub
+    assert!(thread.starts_with(
+        "PR #1 by @alice: Fix panic on empty config\nReview state: changes_requested\n"
+    ));
+    assert!(thread.contains("\nInline comment on src/config.rs:\n"));
+}
+
+/// Create a maintainer-blacklisted contributor with a high score
+async fn blacklist_testuser(state: &AppState) {
+    let contributor = create_contributor(&state.db_pool, 12345, "owner", "repo", 150)
+        .await
+        .unwrap();
+    set_blacklisted(&state.db_pool, contributor.id, true)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_review_by_manually_blacklisted_user_not_evaluated() {
+    let evaluator = Arc::new(RecordingEvaluator::default());
+    let state = setup_test_state(evaluator.clone()).await;
+    blacklist_testuser(&state).await;
+
+    let payload = json!({
+        "action": "submitted",
+        "review": {
+            "id": 77,
+            "body": "Found the cause, see inline.",
+            "user": { "id": 12345, "login": "testuser" },
+            "state": "changes_requested",
+            "html_url": "https://github.com/owner/repo/pull/1#pullrequestreview-77"
+        },
+        "pull_request": pull_request(),
+        "repository": repository(),
+        "sender": { "id": 12345, "login": "testuser" }
+    });
+    assert_eq!(
+        send_webhook(state, "pull_request_review", payload).await,
+        StatusCode::OK
+    );
+    sleep(Duration::from_millis(300)).await;
+
+    assert!(evaluator.contexts.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_review_comment_by_manually_blacklisted_user_not_evaluated() {
+    let evaluator = Arc::new(RecordingEvaluator::default());
+    let state = setup_test_state(evaluator.clone()).await;
+    blacklist_testuser(&state).await;
+
+    // A lone inline comment is scored as its review once the window ends
+    let payload = json!({
+        "action": "created",
+        "comment": {
+            "id": 500,
+            "pull_request_review_id": 77,
+            "body": "This unwrap is the panic.",
+            "path": "src/config.rs",
+            "diff_hunk": "@@ -10,3 +10,4 @@ fn load()\n+    let config = parse(&text).unwrap();",
+            "user": { "id": 12345, "login": "testuser" }
+        },
+        "pull_request": pull_request(),
+        "repository": repository(),
+        "sender": { "id": 12345, "login": "testuser" }
+    });
+    assert_eq!(
+        send_webhook(state, "pull_request_review_comment", payload).await,
+        StatusCode::OK
+    );
+    sleep(Duration::from_millis(2800)).await;
+
+    assert!(evaluator.contexts.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_comment_on_long_thread_sees_latest_comments() {
+    let evaluator = Arc::new(RecordingEvaluator::default());
+    let state = setup_test_state(evaluator.clone()).await;
+
+    let last = LONG_THREAD_COMMENTS - 1;
+    let payload = json!({
+        "action": "created",
+        "issue": {
+            "number": 2,
+            "title": "Support TOML includes",
+            "user": { "id": 1, "login": "alice" },
+            "pull_request": { "url": "https://api.github.com/repos/owner/repo/pulls/2" }
+        },
+        "comment": {
+            "id": 1000 + last,
+            "body": format!("Comment {}", last),
+            "user": { "id": 12345, "login": "testuser" },
+            "html_url": "https://github.com/owner/repo/issues/2#issuecomment-2149"
+        },
+        "repository": repository(),
+        "sender": { "id": 12345, "login": "testuser" }
+    });
+    assert_eq!(
+        send_webhook(state, "issue_comment", payload).await,
+        StatusCode::OK
+    );
+    sleep(Duration::from_millis(300)).await;
+
+    let contexts = evaluator.contexts.lock().unwrap();
+    assert_eq!(contexts.len(), 1);
+    let thread = contexts[0].thread_context.as_deref().unwrap();
+    assert!(
+        thread.ends_with(&format!("@maintainer: Comment {}\n", last - 1)),
+        "{}",
+        thread
+    );
+}
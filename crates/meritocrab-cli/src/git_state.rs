@@ -1,6 +1,6 @@
+use crate::{ContributorState, CreditEvent};
 use anyhow::{Context, Result, bail};
 use meritocrab_core::{RepoConfig, apply_credit, check_blacklist};
-use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
 use std::process::Command;
@@ -9,29 +9,6 @@ const DATA_BRANCH: &str = "meritocrab-data";
 const CONTRIBUTORS_FILE: &str = "credit-data/contributors.json";
 const EVENTS_FILE: &str = "credit-data/events.json";
 
-/// Contributor state in contributors.json
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct ContributorState {
-    username: String,
-    credit: i32,
-    is_blacklisted: bool,
-}
-
-/// Credit event in events.json
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct CreditEvent {
-    contributor_id: u64,
-    event_type: String,
-    delta: i32,
-    credit_before: i32,
-    credit_after: i32,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pr_number: Option<u64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    evaluation_summary: Option<String>,
-    timestamp: String,
-}
-
 /// Initialize git state backend by creating meritocrab-data orphan branch
 pub fn init_git_state(repo_path: &Path) -> Result<()> {
     // Check if branch already exists
@@ -147,6 +124,121 @@ pub fn update_git_state(
     config: &RepoConfig,
     is_override: bool,
     set_blacklisted: Option<bool>,
+) -> Result<()> {
+    let mut summary = None;
+
+    modify_git_state(repo_path, commit_msg, |contributors, events| {
+        // Get current credit or default
+        let contributor_id_str = contributor_id.to_string();
+        let credit_before = contributors
+            .get(&contributor_id_str)
+            .map(|s| s.credit)
+            .unwrap_or(config.starting_credit);
+
+        // Apply the earning limits to credit earned through normal events
+        let (delta, limit_reason) = if is_override {
+            (delta, None)
+        } else {
+            crate::limit_earned_credit(
+                config,
+                event_type,
+                delta,
+                pr_number,
+                events
+                    .iter()
+                    .filter(|event| event.contributor_id == contributor_id)
+                    .map(|event| {
+                        (
+                            event.event_type.as_str(),
+                            event.delta,
+                            event.pr_number,
+                            event.timestamp.as_str(),
+                        )
+                    }),
+            )
+        };
+
+        // Apply credit: either absolute override or delta
+        let credit_after = if is_override {
+            std::cmp::max(0, delta)
+        } else {
+            apply_credit(credit_before, delta)
+        };
+
+        // Determine blacklist status: explicit flag takes priority, else check threshold
+        let is_blacklisted = if let Some(bl) = set_blacklisted {
+            bl
+        } else {
+            check_blacklist(credit_after, config.blacklist_threshold)
+        };
+
+        // Update contributor state
+        contributors.insert(
+            contributor_id_str.clone(),
+            ContributorState {
+                username: username.to_string(),
+                credit: credit_after,
+                is_blacklisted,
+            },
+        );
+
+        // Create credit event
+        let event = CreditEvent {
+            contributor_id,
+            event_type: event_type.to_string(),
+            delta,
+            credit_before,
+            credit_after,
+            pr_number,
+            evaluation_summary: evaluation_summary.map(|s| s.to_string()),
+            limit_reason: limit_reason.clone(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        };
+        events.push(event);
+
+        summary = Some((delta, limit_reason, credit_before, credit_after));
+        Ok(true)
+    })?;
+
+    if let Some((delta, limit_reason, credit_before, credit_after)) = summary {
+        if let Some(reason) = &limit_reason {
+            eprintln!("Limited delta to {}: {}", delta, reason);
+        }
+        eprintln!(
+            "Updated contributor {}: {} -> {} credit (delta: {})",
+            contributor_id, credit_before, credit_after, delta
+        );
+    }
+
+    Ok(())
+}
+
+/// Decay the credit of inactive contributors on the data branch
+///
+/// Returns the number of contributors whose credit decayed; nothing is
+/// committed when there are none.
+pub fn decay_git_state(repo_path: &Path, config: &RepoConfig) -> Result<usize> {
+    let mut decayed = 0;
+
+    modify_git_state(
+        repo_path,
+        "meritocrab: decay credit of inactive contributors",
+        |contributors, events| {
+            decayed = crate::decay_contributors(config, contributors, events, chrono::Utc::now());
+            Ok(decayed > 0)
+        },
+    )?;
+
+    Ok(decayed)
+}
+
+/// Clone the data branch, let `modify` change the state, then commit and push
+///
+/// `modify` returns whether it changed anything; unchanged state is not committed.
+fn modify_git_state(
+    repo_path: &Path,
+    commit_msg: &str,
+    modify: impl FnOnce(&mut HashMap<String, ContributorState>, &mut Vec<CreditEvent>) -> Result<bool>,
 ) -> Result<()> {
     // Create temporary directory for the operation
     let temp_dir = tempfile::TempDir::new().context("Failed to create temp directory")?;
@@ -186,49 +278,9 @@ pub fn update_git_state(
         serde_json::from_str(&json).context("Failed to parse events.json")?
     };
 
-    // Get current credit or default
-    let contributor_id_str = contributor_id.to_string();
-    let credit_before = contributors
-        .get(&contributor_id_str)
-        .map(|s| s.credit)
-        .unwrap_or(config.starting_credit);
-
-    // Apply credit: either absolute override or delta
-    let credit_after = if is_override {
-        std::cmp::max(0, delta)
-    } else {
-        apply_credit(credit_before, delta)
-    };
-
-    // Determine blacklist status: explicit flag takes priority, else check threshold
-    let is_blacklisted = if let Some(bl) = set_blacklisted {
-        bl
-    } else {
-        check_blacklist(credit_after, config.blacklist_threshold)
-    };
-
-    // Update contributor state
-    contributors.insert(
-        contributor_id_str.clone(),
-        ContributorState {
-            username: username.to_string(),
-            credit: credit_after,
-            is_blacklisted,
-        },
-    );
-
-    // Create credit event
-    let event = CreditEvent {
-        contributor_id,
-        event_type: event_type.to_string(),
-        delta,
-        credit_before,
-        credit_after,
-        pr_number,
-        evaluation_summary: evaluation_summary.map(|s| s.to_string()),
-        timestamp: chrono::Utc::now().to_rfc3339(),
-    };
-    events.push(event);
+    if !modify(&mut contributors, &mut events)? {
+        return Ok(());
+    }
 
     // Write updated files
     let contributors_json = serde_json::to_string_pretty(&contributors)?;
@@ -243,11 +295,6 @@ pub fn update_git_state(
     run_git(temp_path, &["commit", "-m", commit_msg])?;
     run_git(temp_path, &["push", "origin", DATA_BRANCH])?;
 
-    eprintln!(
-        "Updated contributor {}: {} -> {} credit (delta: {})",
-        contributor_id, credit_before, credit_after, delta
-    );
-
     Ok(())
 }
 
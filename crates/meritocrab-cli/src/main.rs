@@ -1,9 +1,13 @@
 use anyhow::{Context, Result, bail};
 use clap::{Args, Parser, Subcommand, ValueEnum};
 use meritocrab_core::{
-    EventType, QualityLevel, RepoConfig, apply_credit, calculate_delta_with_config, check_blacklist,
+    EventType, QualityLevel, RecentCredit, RepoConfig, apply_credit, apply_earning_limits,
+    calculate_decay, calculate_delta_with_config, check_blacklist,
+};
+use meritocrab_llm::{
+    CHARS_PER_TOKEN, ContentType, DEFAULT_DIFF_TOKEN_BUDGET, EvalContext, LlmConfig, budget_diff,
+    create_evaluator, parse_unified_diff,
 };
-use meritocrab_llm::{ContentType, EvalContext, LlmConfig, create_evaluator};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -66,6 +70,8 @@ enum CreditCommands {
     Check(CheckArgs),
     /// Update contributor credit state
     Update(UpdateArgs),
+    /// Decay the credit of inactive contributors (per the config's [decay] policy)
+    Decay(DecayArgs),
 }
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
@@ -157,6 +163,25 @@ struct UpdateArgs {
     set_blacklisted: Option<bool>,
 }
 
+#[derive(Args)]
+struct DecayArgs {
+    /// State backend
+    #[arg(long, value_enum, default_value = "file")]
+    state_backend: StateBackend,
+
+    /// State directory path (used with file backend)
+    #[arg(long, default_value = "./credit-data")]
+    state_dir: PathBuf,
+
+    /// Git repository path (used with git backend)
+    #[arg(long, default_value = ".")]
+    repo: PathBuf,
+
+    /// Path to .meritocrab.toml config file
+    #[arg(long)]
+    config: Option<PathBuf>,
+}
+
 /// PR evaluation artifact schema (from DESIGN-github-actions.md Section 3)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
@@ -212,6 +237,9 @@ struct CreditEvent {
     pr_number: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     evaluation_summary: Option<String>,
+    /// Why the earning limits reduced the delta
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    limit_reason: Option<String>,
     timestamp: String,
 }
 
@@ -247,6 +275,9 @@ async fn main() -> Result<()> {
             CreditCommands::Update(args) => {
                 credit_update_command(args)?;
             }
+            CreditCommands::Decay(args) => {
+                credit_decay_command(args)?;
+            }
         },
     }
 
@@ -268,12 +299,18 @@ async fn evaluate_command(input_path: PathBuf, llm_config_str: String) -> Result
     let llm_config: LlmConfig =
         serde_json::from_str(&llm_config_str).context("Failed to parse LLM config JSON")?;
 
-    // Truncate diff_content to 10KB if needed
-    let max_diff_size = 10 * 1024; // 10KB
-    let diff_content = if artifact.diff_content.len() > max_diff_size {
-        &artifact.diff_content[..max_diff_size]
+    // Fit the diff into the LLM token budget, the same way server mode does
+    let diff_files = parse_unified_diff(&artifact.diff_content);
+    let diff_content = if diff_files.is_empty() {
+        // Not a unified diff (e.g. the workflow failed to fetch it); cut the
+        // raw text to the same budget
+        artifact
+            .diff_content
+            .chars()
+            .take(DEFAULT_DIFF_TOKEN_BUDGET * CHARS_PER_TOKEN)
+            .collect()
     } else {
-        &artifact.diff_content
+        budget_diff(&diff_files, DEFAULT_DIFF_TOKEN_BUDGET)
     };
 
     // Create LLM evaluator
@@ -284,18 +321,20 @@ async fn evaluate_command(input_path: PathBuf, llm_config_str: String) -> Result
         content_type: ContentType::PullRequest,
         title: Some(artifact.pr_title.clone()),
         body: artifact.pr_body.clone(),
-        diff_summary: Some(format!(
-            "+{} -{} files:{}",
-            artifact.diff_stats.additions,
-            artifact.diff_stats.deletions,
-            artifact.diff_stats.changed_files
-        )),
+        diff_summary: Some(
+            meritocrab_llm::DiffStats {
+                additions: artifact.diff_stats.additions,
+                deletions: artifact.diff_stats.deletions,
+                changed_files: artifact.diff_stats.changed_files,
+            }
+            .to_string(),
+        ),
         thread_context: None,
     };
 
     // Call LLM evaluator
     let evaluation = evaluator
-        .evaluate(diff_content, &eval_context)
+        .evaluate(&diff_content, &eval_context)
         .await
         .context("LLM evaluation failed")?;
 
@@ -494,12 +533,35 @@ fn credit_update_file_backend(args: &UpdateArgs, config: &RepoConfig) -> Result<
         .map(|s| s.credit)
         .unwrap_or(config.starting_credit);
 
+    // Apply the earning limits to credit earned through normal events
+    let (delta, limit_reason) = if args.r#override {
+        (args.delta, None)
+    } else {
+        limit_earned_credit(
+            config,
+            &args.event_type,
+            args.delta,
+            args.pr_number,
+            events
+                .iter()
+                .filter(|event| event.contributor_id == args.contributor_id)
+                .map(|event| {
+                    (
+                        event.event_type.as_str(),
+                        event.delta,
+                        event.pr_number,
+                        event.timestamp.as_str(),
+                    )
+                }),
+        )
+    };
+
     // Apply credit: either absolute override or delta
     let credit_after = if args.r#override {
         // Override mode: delta value is the absolute credit to set
-        std::cmp::max(0, args.delta)
+        std::cmp::max(0, delta)
     } else {
-        apply_credit(credit_before, args.delta)
+        apply_credit(credit_before, delta)
     };
 
     // Determine blacklist status: explicit flag takes priority, else check threshold
@@ -523,11 +585,12 @@ fn credit_update_file_backend(args: &UpdateArgs, config: &RepoConfig) -> Result<
     let event = CreditEvent {
         contributor_id: args.contributor_id,
         event_type: args.event_type.clone(),
-        delta: args.delta,
+        delta,
         credit_before,
         credit_after,
         pr_number: args.pr_number,
         evaluation_summary: args.evaluation_summary.clone(),
+        limit_reason: limit_reason.clone(),
         timestamp: chrono::Utc::now().to_rfc3339(),
     };
     events.push(event);
@@ -537,9 +600,12 @@ fn credit_update_file_backend(args: &UpdateArgs, config: &RepoConfig) -> Result<
         .context("Failed to write contributors.json")?;
     write_json_atomic(&events_path, &events).context("Failed to write events.json")?;
 
+    if let Some(reason) = &limit_reason {
+        eprintln!("Limited delta {} -> {}: {}", args.delta, delta, reason);
+    }
     eprintln!(
         "Updated contributor {}: {} -> {} credit (delta: {})",
-        args.contributor_id, credit_before, credit_after, args.delta
+        args.contributor_id, credit_before, credit_after, delta
     );
 
     Ok(())
@@ -547,12 +613,17 @@ fn credit_update_file_backend(args: &UpdateArgs, config: &RepoConfig) -> Result<
 
 /// Update credit state using git backend with retry logic
 fn credit_update_git_backend(args: &UpdateArgs, config: &RepoConfig) -> Result<()> {
+    retry_git_conflicts(|| try_update_git_state(args, config))
+}
+
+/// Run a git state operation, retrying with backoff when a concurrent update conflicts
+fn retry_git_conflicts<T>(mut operation: impl FnMut() -> Result<T>) -> Result<T> {
     let max_retries = 3;
     let mut backoff_ms = 1000; // Start with 1 second
 
     for attempt in 1..=max_retries {
-        match try_update_git_state(args, config) {
-            Ok(_) => return Ok(()),
+        match operation() {
+            Ok(value) => return Ok(value),
             Err(e) if attempt < max_retries && is_conflict_error(&e) => {
                 eprintln!(
                     "Conflict detected on attempt {}/{}, retrying after {}ms...",
@@ -618,6 +689,191 @@ fn try_update_git_state(args: &UpdateArgs, config: &RepoConfig) -> Result<()> {
     Ok(())
 }
 
+/// Apply the repo's earning limits to a delta recorded by `credit update`
+///
+/// `history` yields the contributor's recorded events as `(event_type, delta,
+/// pr_number, timestamp)`. Returns the delta to record and, when a limit
+/// reduced it, the reason. Event types other than the scored ones (such as
+/// manual adjustments) are never limited.
+fn limit_earned_credit<'a>(
+    config: &RepoConfig,
+    event_type: &str,
+    delta: i32,
+    pr_number: Option<u64>,
+    history: impl Iterator<Item = (&'a str, i32, Option<u64>, &'a str)>,
+) -> (i32, Option<String>) {
+    let Ok(event_type) = event_type.parse::<EventType>() else {
+        return (delta, None);
+    };
+
+    let history: Vec<RecentCredit> = history
+        .filter_map(|(event_type, delta, pr_number, timestamp)| {
+            Some(RecentCredit {
+                event_type: event_type.parse().ok()?,
+                delta,
+                pr_number: pr_number.map(|n| n as i64),
+                created_at: chrono::DateTime::parse_from_rfc3339(timestamp)
+                    .ok()?
+                    .with_timezone(&chrono::Utc),
+            })
+        })
+        .collect();
+
+    let limited = apply_earning_limits(
+        &config.earning_limits,
+        event_type,
+        delta,
+        pr_number.map(|n| n as i64),
+        &history,
+        chrono::Utc::now(),
+    );
+
+    (limited.delta, limited.capped_by.map(|cap| cap.to_string()))
+}
+
+/// Decay the credit of inactive contributors
+fn credit_decay_command(args: DecayArgs) -> Result<()> {
+    let config = load_repo_config(args.config.as_deref())?;
+    if config.decay.is_none() {
+        eprintln!("No [decay] policy configured, nothing to do");
+        return Ok(());
+    }
+
+    let decayed = match args.state_backend {
+        StateBackend::File => credit_decay_file_backend(&args, &config)?,
+        StateBackend::Git => {
+            retry_git_conflicts(|| git_state::decay_git_state(&args.repo, &config))?
+        }
+    };
+
+    eprintln!("Decayed credit of {} inactive contributors", decayed);
+
+    Ok(())
+}
+
+/// Decay credit state using file backend
+fn credit_decay_file_backend(args: &DecayArgs, config: &RepoConfig) -> Result<usize> {
+    let contributors_path = args.state_dir.join("contributors.json");
+    let events_path = args.state_dir.join("events.json");
+    if !contributors_path.exists() {
+        return Ok(0);
+    }
+
+    let json = std::fs::read_to_string(&contributors_path)
+        .with_context(|| format!("Failed to read {:?}", contributors_path))?;
+    let mut contributors: HashMap<String, ContributorState> =
+        serde_json::from_str(&json).context("Failed to parse contributors.json")?;
+
+    let mut events: Vec<CreditEvent> = if events_path.exists() {
+        let json = std::fs::read_to_string(&events_path)
+            .with_context(|| format!("Failed to read {:?}", events_path))?;
+        serde_json::from_str(&json).context("Failed to parse events.json")?
+    } else {
+        Vec::new()
+    };
+
+    let decayed = decay_contributors(config, &mut contributors, &mut events, chrono::Utc::now());
+    if decayed > 0 {
+        write_json_atomic(&contributors_path, &contributors)
+            .context("Failed to write contributors.json")?;
+        write_json_atomic(&events_path, &events).context("Failed to write events.json")?;
+    }
+
+    Ok(decayed)
+}
+
+/// Apply the config's decay policy to every contributor, recording `decay` events
+///
+/// A contributor's last activity is their latest scored event, or their first
+/// recorded event when they have no scored ones; contributors without any
+/// events are left alone. Returns the number of contributors whose credit decayed.
+fn decay_contributors(
+    config: &RepoConfig,
+    contributors: &mut HashMap<String, ContributorState>,
+    events: &mut Vec<CreditEvent>,
+    now: chrono::DateTime<chrono::Utc>,
+) -> usize {
+    let Some(policy) = &config.decay else {
+        return 0;
+    };
+
+    let parse = |timestamp: &str| {
+        chrono::DateTime::parse_from_rfc3339(timestamp)
+            .ok()
+            .map(|t| t.with_timezone(&chrono::Utc))
+    };
+
+    let mut ids: Vec<&String> = contributors.keys().collect();
+    ids.sort();
+    let mut new_events = Vec::new();
+
+    for id in ids {
+        let state = &contributors[id];
+        let Ok(contributor_id) = id.parse::<u64>() else {
+            continue;
+        };
+
+        let history = events
+            .iter()
+            .filter(|event| event.contributor_id == contributor_id)
+            .filter_map(|event| Some((event.event_type.as_str(), parse(&event.timestamp)?)));
+        let (mut first_seen, mut last_activity, mut last_decay) = (None, None, None);
+        for (event_type, timestamp) in history {
+            first_seen = Some(first_seen.map_or(timestamp, |first| timestamp.min(first)));
+            if event_type == "decay" {
+                last_decay = last_decay.max(Some(timestamp));
+            } else if event_type.parse::<EventType>().is_ok() {
+                last_activity = last_activity.max(Some(timestamp));
+            }
+        }
+        let Some(last_activity) = last_activity.or(first_seen) else {
+            continue;
+        };
+
+        let delta = calculate_decay(
+            policy,
+            state.credit,
+            config.starting_credit,
+            last_activity,
+            last_decay,
+            now,
+        );
+        if delta == 0 {
+            continue;
+        }
+
+        let credit_after = apply_credit(state.credit, delta);
+        eprintln!(
+            "Decayed contributor {}: {} -> {} credit (delta: {})",
+            contributor_id, state.credit, credit_after, delta
+        );
+        new_events.push(CreditEvent {
+            contributor_id,
+            event_type: "decay".to_string(),
+            delta,
+            credit_before: state.credit,
+            credit_after,
+            pr_number: None,
+            evaluation_summary: Some(format!(
+                "Inactive since {}",
+                last_activity.format("%Y-%m-%d")
+            )),
+            limit_reason: None,
+            timestamp: now.to_rfc3339(),
+        });
+    }
+
+    for event in &new_events {
+        if let Some(state) = contributors.get_mut(&event.contributor_id.to_string()) {
+            state.credit = event.credit_after;
+        }
+    }
+    let decayed = new_events.len();
+    events.extend(new_events);
+
+    decayed
+}
+
 /// Load RepoConfig from .meritocrab.toml or use defaults
 fn load_repo_config(config_path: Option<&std::path::Path>) -> Result<RepoConfig> {
     if let Some(path) = config_path {
@@ -1208,6 +1464,130 @@ high = 5
         assert!(state.is_blacklisted); // 10 <= 10 (custom threshold)
     }
 
+    #[test]
+    fn test_credit_update_applies_earning_limits() {
+        use std::fs;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("limits.toml");
+        let mut config = RepoConfig::default();
+        config.earning_limits.daily_cap.comment = Some(2);
+        fs::write(&config_path, toml::to_string(&config).unwrap()).unwrap();
+
+        credit_init_command(InitArgs {
+            state_dir: temp_dir.path().to_path_buf(),
+        })
+        .unwrap();
+
+        for (pr_number, event_type) in [
+            (1, "comment"),
+            (2, "comment"),
+            (3, "comment"),
+            (4, "pr_opened"),
+        ] {
+            credit_update_command(UpdateArgs {
+                state_backend: StateBackend::File,
+                state_dir: temp_dir.path().to_path_buf(),
+                repo: PathBuf::from("."),
+                contributor_id: 777,
+                username: "farmer".to_string(),
+                delta: 1,
+                event_type: event_type.to_string(),
+                pr_number: Some(pr_number),
+                evaluation_summary: None,
+                config: Some(config_path.clone()),
+                r#override: false,
+                set_blacklisted: None,
+            })
+            .unwrap();
+        }
+
+        let events_json = fs::read_to_string(temp_dir.path().join("events.json")).unwrap();
+        let events: Vec<CreditEvent> = serde_json::from_str(&events_json).unwrap();
+        let deltas: Vec<i32> = events.iter().map(|e| e.delta).collect();
+        assert_eq!(deltas, vec![1, 1, 0, 1]);
+        assert!(events[1].limit_reason.is_none());
+        assert_eq!(
+            events[2].limit_reason.as_deref(),
+            Some("Daily credit cap of 2 reached")
+        );
+
+        let contributors_json =
+            fs::read_to_string(temp_dir.path().join("contributors.json")).unwrap();
+        let contributors: HashMap<String, ContributorState> =
+            serde_json::from_str(&contributors_json).unwrap();
+        assert_eq!(contributors["777"].credit, 103);
+    }
+
+    #[test]
+    fn test_decay_contributors() {
+        use chrono::{Duration, Utc};
+
+        let config = RepoConfig {
+            decay: Some(meritocrab_core::DecayPolicy::Linear {
+                after_inactive_days: 30,
+                points_per_day: 1.0,
+            }),
+            ..RepoConfig::default()
+        };
+        let now = Utc::now();
+        let event = |contributor_id: u64, event_type: &str, days_ago: i64| CreditEvent {
+            contributor_id,
+            event_type: event_type.to_string(),
+            delta: 0,
+            credit_before: 100,
+            credit_after: 100,
+            pr_number: None,
+            evaluation_summary: None,
+            limit_reason: None,
+            timestamp: (now - Duration::days(days_ago)).to_rfc3339(),
+        };
+        let contributor = |credit: i32| ContributorState {
+            username: "user".to_string(),
+            credit,
+            is_blacklisted: false,
+        };
+
+        let mut contributors = HashMap::from([
+            ("1".to_string(), contributor(150)), // inactive for 40 days
+            ("2".to_string(), contributor(150)), // recently active
+            ("3".to_string(), contributor(150)), // only a maintainer adjustment 45 days ago
+        ]);
+        let mut events = vec![
+            event(1, "comment", 40),
+            event(2, "pr_opened", 60),
+            event(2, "comment", 1),
+            event(3, "manual_adjustment", 45),
+        ];
+
+        assert_eq!(
+            decay_contributors(&config, &mut contributors, &mut events, now),
+            2
+        );
+        assert_eq!(contributors["1"].credit, 140);
+        assert_eq!(contributors["2"].credit, 150);
+        assert_eq!(contributors["3"].credit, 135);
+
+        let decay: Vec<_> = events.iter().filter(|e| e.event_type == "decay").collect();
+        assert_eq!(decay.len(), 2);
+        assert_eq!(decay[0].contributor_id, 1);
+        assert_eq!(decay[0].delta, -10);
+        assert!(
+            decay[0]
+                .evaluation_summary
+                .as_deref()
+                .unwrap()
+                .starts_with("Inactive since")
+        );
+
+        // Nothing more is due at the same instant
+        assert_eq!(
+            decay_contributors(&config, &mut contributors, &mut events, now),
+            0
+        );
+    }
+
     #[test]
     fn test_atomic_write_creates_valid_json() {
         use tempfile::TempDir;
@@ -1,4 +1,6 @@
+use crate::error::CoreError;
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 
 /// Quality level of a contribution
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -18,6 +20,53 @@ pub enum EventType {
     Comment,
     PrMerged,
     ReviewSubmitted,
+    IssueOpened,
+    IssueComment,
+}
+
+impl EventType {
+    /// All scored event types
+    pub const ALL: [EventType; 6] = [
+        EventType::PrOpened,
+        EventType::Comment,
+        EventType::PrMerged,
+        EventType::ReviewSubmitted,
+        EventType::IssueOpened,
+        EventType::IssueComment,
+    ];
+
+    /// Event type as recorded in credit events
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventType::PrOpened => "pr_opened",
+            EventType::Comment => "comment",
+            EventType::PrMerged => "pr_merged",
+            EventType::ReviewSubmitted => "review_submitted",
+            EventType::IssueOpened => "issue_opened",
+            EventType::IssueComment => "issue_comment",
+        }
+    }
+
+    /// Whether the event is a conversation comment, on a PR or an issue
+    pub fn is_comment(&self) -> bool {
+        matches!(self, EventType::Comment | EventType::IssueComment)
+    }
+}
+
+impl FromStr for EventType {
+    type Err = CoreError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pr_opened" => Ok(EventType::PrOpened),
+            "comment" => Ok(EventType::Comment),
+            "pr_merged" => Ok(EventType::PrMerged),
+            "review_submitted" => Ok(EventType::ReviewSubmitted),
+            "issue_opened" => Ok(EventType::IssueOpened),
+            "issue_comment" => Ok(EventType::IssueComment),
+            _ => Err(CoreError::InvalidEventType(s.to_string())),
+        }
+    }
 }
 
 /// Scoring delta configuration for a specific event type and quality level
@@ -52,6 +101,255 @@ impl Default for ScoringDelta {
     }
 }
 
+/// Auto-apply rule for LLM evaluations
+///
+/// Unset fields fall back to the repo-wide rule, then to `auto_apply_confidence`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AutoApplyRule {
+    /// Minimum confidence to auto-apply a positive (or zero) delta
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub positive_confidence: Option<f64>,
+
+    /// Minimum confidence to auto-apply a negative delta
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub negative_confidence: Option<f64>,
+
+    /// Negative deltas below this value always go to maintainer review
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub review_negative_below: Option<i32>,
+}
+
+/// Auto-apply policy: a repo-wide rule plus optional per-event overrides
+///
+/// ```toml
+/// [auto_apply]
+/// positive_confidence = 0.8
+/// review_negative_below = -10
+///
+/// [auto_apply.comment]
+/// negative_confidence = 0.95
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AutoApplyConfig {
+    /// Minimum confidence to auto-apply a positive (or zero) delta
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub positive_confidence: Option<f64>,
+
+    /// Minimum confidence to auto-apply a negative delta
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub negative_confidence: Option<f64>,
+
+    /// Negative deltas below this value always go to maintainer review
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub review_negative_below: Option<i32>,
+
+    /// Overrides for PR opened events
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pr_opened: Option<AutoApplyRule>,
+
+    /// Overrides for comment events
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<AutoApplyRule>,
+
+    /// Overrides for PR merged events
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pr_merged: Option<AutoApplyRule>,
+
+    /// Overrides for review submitted events
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub review_submitted: Option<AutoApplyRule>,
+
+    /// Overrides for issue opened events
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issue_opened: Option<AutoApplyRule>,
+
+    /// Overrides for issue comment events
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issue_comment: Option<AutoApplyRule>,
+}
+
+impl AutoApplyConfig {
+    /// Get the per-event override for an event type, if any
+    pub fn rule_for(&self, event_type: EventType) -> Option<&AutoApplyRule> {
+        match event_type {
+            EventType::PrOpened => self.pr_opened.as_ref(),
+            EventType::Comment => self.comment.as_ref(),
+            EventType::PrMerged => self.pr_merged.as_ref(),
+            EventType::ReviewSubmitted => self.review_submitted.as_ref(),
+            EventType::IssueOpened => self.issue_opened.as_ref(),
+            EventType::IssueComment => self.issue_comment.as_ref(),
+        }
+    }
+}
+
+/// Maximum positive credit per event type within 24 hours
+///
+/// Unset event types are not capped.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DailyCreditCaps {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pr_opened: Option<i32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<i32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pr_merged: Option<i32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub review_submitted: Option<i32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issue_opened: Option<i32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issue_comment: Option<i32>,
+}
+
+impl DailyCreditCaps {
+    /// Get the cap for an event type, if any
+    pub fn cap_for(&self, event_type: EventType) -> Option<i32> {
+        match event_type {
+            EventType::PrOpened => self.pr_opened,
+            EventType::Comment => self.comment,
+            EventType::PrMerged => self.pr_merged,
+            EventType::ReviewSubmitted => self.review_submitted,
+            EventType::IssueOpened => self.issue_opened,
+            EventType::IssueComment => self.issue_comment,
+        }
+    }
+}
+
+/// Per-contributor limits on earned credit, against comment farming
+///
+/// Only positive deltas are limited; penalties always apply in full. Every
+/// limit is off unless configured.
+///
+/// ```toml
+/// [earning_limits]
+/// comment_cooldown_seconds = 300
+/// same_pr_comment_factor = 0.5
+///
+/// [earning_limits.daily_cap]
+/// comment = 10
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EarningLimits {
+    /// Maximum positive credit per event type within 24 hours
+    #[serde(default)]
+    pub daily_cap: DailyCreditCaps,
+
+    /// Credit multiplier for each earlier consecutive comment on the same PR or issue
+    ///
+    /// With `0.5`, the second comment in a row on a PR earns half, the third
+    /// a quarter, and so on (rounded down).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub same_pr_comment_factor: Option<f64>,
+
+    /// Minimum seconds between comments (on PRs or issues) that earn credit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment_cooldown_seconds: Option<i64>,
+}
+
+/// Credit decay for inactive contributors
+///
+/// After `after_inactive_days` without a scored event, credit above
+/// `starting_credit` decays toward it. Scores at or below `starting_credit`
+/// never change.
+///
+/// ```toml
+/// [decay]
+/// mode = "half_life"
+/// after_inactive_days = 90
+/// half_life_days = 180
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum DecayPolicy {
+    /// Credit above `starting_credit` halves every `half_life_days`
+    HalfLife {
+        after_inactive_days: i64,
+        half_life_days: f64,
+    },
+    /// Credit drops by `points_per_day` until it reaches `starting_credit`
+    Linear {
+        after_inactive_days: i64,
+        points_per_day: f64,
+    },
+}
+
+impl DecayPolicy {
+    /// Days without activity before decay starts
+    pub fn after_inactive_days(&self) -> i64 {
+        match self {
+            DecayPolicy::HalfLife {
+                after_inactive_days,
+                ..
+            }
+            | DecayPolicy::Linear {
+                after_inactive_days,
+                ..
+            } => *after_inactive_days,
+        }
+    }
+}
+
+/// Graduated PR gate for contributors below `pr_threshold`
+///
+/// Each tier covers scores from its minimum up to the next milder tier (or
+/// `pr_threshold`). Tiers are checked from mildest to strictest and unset
+/// tiers are skipped; scores below every configured tier are denied. With no
+/// tiers set, every PR below `pr_threshold` is closed.
+///
+/// ```toml
+/// [gate]
+/// label_min_score = 40     # 40..50: allowed with the "needs-triage" label
+/// draft_min_score = 30     # 30..40: converted to a draft
+/// approval_min_score = 20  # 20..30: kept open until `/credit approve`
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GateTiers {
+    /// Minimum score to allow the PR with `label` added
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label_min_score: Option<i32>,
+    /// Minimum score to convert the PR to a draft instead of closing it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub draft_min_score: Option<i32>,
+    /// Minimum score to keep the PR open pending maintainer approval
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approval_min_score: Option<i32>,
+    /// Label added by the label tier
+    pub label: String,
+}
+
+impl Default for GateTiers {
+    fn default() -> Self {
+        Self {
+            label_min_score: None,
+            draft_min_score: None,
+            approval_min_score: None,
+            label: "needs-triage".to_string(),
+        }
+    }
+}
+
+/// Deltas of event types a repository does not score
+static NO_SCORING: ScoringDelta = ScoringDelta {
+    spam: 0,
+    low: 0,
+    acceptable: 0,
+    high: 0,
+};
+
+fn default_auto_apply_confidence() -> f64 {
+    0.85
+}
+
+fn default_edit_grace_seconds() -> i64 {
+    120
+}
+
 /// Repository configuration for credit scoring
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RepoConfig {
@@ -75,6 +373,42 @@ pub struct RepoConfig {
 
     /// Scoring deltas for review submitted events
     pub review_submitted: ScoringDelta,
+
+    /// Scoring deltas for opened issues (issues are not scored when unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issue_opened: Option<ScoringDelta>,
+
+    /// Scoring deltas for comments on issues (not scored when unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issue_comment: Option<ScoringDelta>,
+
+    /// Grant zero credit for reviews with no body and no inline comments
+    #[serde(default)]
+    pub zero_credit_for_empty_reviews: bool,
+
+    /// Minimum LLM confidence to apply credit without maintainer review
+    #[serde(default = "default_auto_apply_confidence")]
+    pub auto_apply_confidence: f64,
+
+    /// Per-direction and per-event refinements of `auto_apply_confidence`
+    #[serde(default)]
+    pub auto_apply: AutoApplyConfig,
+
+    /// Per-contributor caps on earned credit
+    #[serde(default)]
+    pub earning_limits: EarningLimits,
+
+    /// Credit decay for inactive contributors (off when unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decay: Option<DecayPolicy>,
+
+    /// Graduated handling of PRs below `pr_threshold`
+    #[serde(default)]
+    pub gate: GateTiers,
+
+    /// Edits within this many seconds of posting are not re-evaluated
+    #[serde(default = "default_edit_grace_seconds")]
+    pub edit_grace_seconds: i64,
 }
 
 impl Default for RepoConfig {
@@ -107,19 +441,71 @@ impl Default for RepoConfig {
                 acceptable: 5,
                 high: 5,
             },
+            issue_opened: None,
+            issue_comment: None,
+            zero_credit_for_empty_reviews: false,
+            auto_apply_confidence: default_auto_apply_confidence(),
+            auto_apply: AutoApplyConfig::default(),
+            earning_limits: EarningLimits::default(),
+            decay: None,
+            gate: GateTiers::default(),
+            edit_grace_seconds: default_edit_grace_seconds(),
         }
     }
 }
 
 impl RepoConfig {
     /// Get scoring delta configuration for a specific event type
+    ///
+    /// Issue events the repository does not score get all-zero deltas.
     pub fn get_scoring_delta(&self, event_type: EventType) -> &ScoringDelta {
         match event_type {
             EventType::PrOpened => &self.pr_opened,
             EventType::Comment => &self.comment,
             EventType::PrMerged => &self.pr_merged,
             EventType::ReviewSubmitted => &self.review_submitted,
+            EventType::IssueOpened => self.issue_opened.as_ref().unwrap_or(&NO_SCORING),
+            EventType::IssueComment => self.issue_comment.as_ref().unwrap_or(&NO_SCORING),
+        }
+    }
+
+    /// Whether events of this type are scored at all
+    ///
+    /// PR events always are; issue events only when their deltas are set.
+    pub fn scores(&self, event_type: EventType) -> bool {
+        match event_type {
+            EventType::IssueOpened => self.issue_opened.is_some(),
+            EventType::IssueComment => self.issue_comment.is_some(),
+            _ => true,
+        }
+    }
+
+    /// Confidence required to auto-apply `delta` for an event type
+    ///
+    /// Returns `None` when the delta must always go to maintainer review.
+    /// Each setting is taken from the per-event override if present, then the
+    /// repo-wide `[auto_apply]` rule, then `auto_apply_confidence`.
+    pub fn auto_apply_threshold(&self, event_type: EventType, delta: i32) -> Option<f64> {
+        let rule = self.auto_apply.rule_for(event_type);
+
+        if delta < 0 {
+            let review_below = rule
+                .and_then(|r| r.review_negative_below)
+                .or(self.auto_apply.review_negative_below);
+            if review_below.is_some_and(|limit| delta < limit) {
+                return None;
+            }
         }
+
+        let confidence = if delta < 0 {
+            rule.and_then(|r| r.negative_confidence)
+                .or(self.auto_apply.negative_confidence)
+        } else {
+            rule.and_then(|r| r.positive_confidence)
+                .or(self.auto_apply.positive_confidence)
+        };
+
+        Some(confidence.unwrap_or(self.auto_apply_confidence))
     }
 }
 
@@ -184,4 +570,153 @@ mod tests {
         let review_delta = config.get_scoring_delta(EventType::ReviewSubmitted);
         assert_eq!(review_delta.acceptable, 5);
     }
+
+    #[test]
+    fn test_zero_credit_for_empty_reviews_defaults_off() {
+        // Configs written before the option existed still deserialize
+        let mut value = serde_json::to_value(RepoConfig::default()).unwrap();
+        value
+            .as_object_mut()
+            .unwrap()
+            .remove("zero_credit_for_empty_reviews");
+
+        let config: RepoConfig = serde_json::from_value(value).unwrap();
+        assert!(!config.zero_credit_for_empty_reviews);
+    }
+
+    #[test]
+    fn test_event_type_round_trip() {
+        for event_type in EventType::ALL {
+            assert_eq!(event_type.as_str().parse::<EventType>(), Ok(event_type));
+        }
+        assert!("auto_blacklist".parse::<EventType>().is_err());
+    }
+
+    #[test]
+    fn test_issue_scoring_opt_in() {
+        let config = RepoConfig::default();
+        assert!(config.scores(EventType::PrOpened));
+        assert!(!config.scores(EventType::IssueOpened));
+        assert!(!config.scores(EventType::IssueComment));
+        assert_eq!(
+            config
+                .get_scoring_delta(EventType::IssueComment)
+                .get(QualityLevel::High),
+            0
+        );
+
+        let mut value = serde_json::to_value(&config).unwrap();
+        assert!(value.get("issue_opened").is_none());
+        value["issue_opened"] = serde_json::json!({
+            "spam": -10,
+            "low": 0,
+            "acceptable": 2,
+            "high": 5
+        });
+        let config: RepoConfig = serde_json::from_value(value).unwrap();
+        assert!(config.scores(EventType::IssueOpened));
+        assert!(!config.scores(EventType::IssueComment));
+        assert_eq!(
+            config
+                .get_scoring_delta(EventType::IssueOpened)
+                .get(QualityLevel::High),
+            5
+        );
+    }
+
+    #[test]
+    fn test_earning_limits_default_off() {
+        let mut value = serde_json::to_value(RepoConfig::default()).unwrap();
+        value.as_object_mut().unwrap().remove("earning_limits");
+
+        let config: RepoConfig = serde_json::from_value(value).unwrap();
+        assert_eq!(config.earning_limits, EarningLimits::default());
+        assert_eq!(
+            config.earning_limits.daily_cap.cap_for(EventType::Comment),
+            None
+        );
+    }
+
+    #[test]
+    fn test_decay_policy_from_toml_style_json() {
+        let mut value = serde_json::to_value(RepoConfig::default()).unwrap();
+        assert!(value.get("decay").is_none());
+
+        value["decay"] = serde_json::json!({
+            "mode": "linear",
+            "after_inactive_days": 30,
+            "points_per_day": 0.5
+        });
+        let config: RepoConfig = serde_json::from_value(value).unwrap();
+        assert_eq!(
+            config.decay,
+            Some(DecayPolicy::Linear {
+                after_inactive_days: 30,
+                points_per_day: 0.5
+            })
+        );
+        assert_eq!(config.decay.unwrap().after_inactive_days(), 30);
+    }
+
+    #[test]
+    fn test_auto_apply_threshold_defaults() {
+        // Configs written before the options existed keep the 0.85 threshold
+        let mut value = serde_json::to_value(RepoConfig::default()).unwrap();
+        let object = value.as_object_mut().unwrap();
+        object.remove("auto_apply_confidence");
+        object.remove("auto_apply");
+
+        let config: RepoConfig = serde_json::from_value(value).unwrap();
+        assert_eq!(config.auto_apply_confidence, 0.85);
+        assert_eq!(
+            config.auto_apply_threshold(EventType::PrOpened, 15),
+            Some(0.85)
+        );
+        assert_eq!(
+            config.auto_apply_threshold(EventType::PrOpened, -25),
+            Some(0.85)
+        );
+    }
+
+    #[test]
+    fn test_auto_apply_threshold_per_direction_and_event() {
+        let mut value = serde_json::to_value(RepoConfig::default()).unwrap();
+        value["auto_apply_confidence"] = serde_json::json!(0.9);
+        value["auto_apply"] = serde_json::json!({
+            "positive_confidence": 0.8,
+            "review_negative_below": -10,
+            "comment": {
+                "negative_confidence": 0.95,
+                "review_negative_below": -20
+            }
+        });
+        let config: RepoConfig = serde_json::from_value(value).unwrap();
+
+        // Positive deltas use the repo-wide positive rule
+        assert_eq!(
+            config.auto_apply_threshold(EventType::PrOpened, 15),
+            Some(0.8)
+        );
+        // Negative deltas fall back to auto_apply_confidence
+        assert_eq!(
+            config.auto_apply_threshold(EventType::PrOpened, -5),
+            Some(0.9)
+        );
+        // Large penalties always go to review
+        assert_eq!(config.auto_apply_threshold(EventType::PrOpened, -25), None);
+        assert_eq!(
+            config.auto_apply_threshold(EventType::PrOpened, -10),
+            Some(0.9)
+        );
+        // Per-event overrides win
+        assert_eq!(
+            config.auto_apply_threshold(EventType::Comment, -10),
+            Some(0.95)
+        );
+        assert_eq!(config.auto_apply_threshold(EventType::Comment, -25), None);
+        assert_eq!(
+            config.auto_apply_threshold(EventType::Comment, 3),
+            Some(0.8)
+        );
+    }
 }
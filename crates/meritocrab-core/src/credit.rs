@@ -1,4 +1,5 @@
-use crate::config::{EventType, QualityLevel, RepoConfig};
+use crate::config::{DecayPolicy, EventType, QualityLevel, RepoConfig};
+use chrono::{DateTime, Duration, Utc};
 
 /// Calculate the credit delta for a specific event type and quality level
 ///
@@ -64,6 +65,75 @@ pub fn apply_credit(current_score: i32, delta: i32) -> i32 {
     (current_score + delta).max(0)
 }
 
+/// Calculate the (non-positive) decay delta for an inactive contributor
+///
+/// Decay starts `after_inactive_days` after `last_activity` and is measured
+/// from `last_decay` when decay was applied before, so running this
+/// periodically and applying each result compounds to the same curve. Only
+/// credit above `starting_credit` decays, and never below it.
+///
+/// A result of `0` means nothing is due yet; callers should not record it,
+/// so that fractional decay keeps accumulating from `last_decay`.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::{Duration, Utc};
+/// use meritocrab_core::config::DecayPolicy;
+/// use meritocrab_core::credit::calculate_decay;
+///
+/// let policy = DecayPolicy::HalfLife {
+///     after_inactive_days: 90,
+///     half_life_days: 30.0,
+/// };
+/// let now = Utc::now();
+///
+/// // 120 days inactive: one half-life of decay on the 200 credit above 100
+/// let delta = calculate_decay(&policy, 300, 100, now - Duration::days(120), None, now);
+/// assert_eq!(delta, -100);
+///
+/// // Still within the grace period
+/// assert_eq!(calculate_decay(&policy, 300, 100, now - Duration::days(30), None, now), 0);
+/// ```
+pub fn calculate_decay(
+    policy: &DecayPolicy,
+    current_score: i32,
+    starting_credit: i32,
+    last_activity: DateTime<Utc>,
+    last_decay: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> i32 {
+    let excess = current_score - starting_credit;
+    if excess <= 0 {
+        return 0;
+    }
+
+    let decay_start = last_activity + Duration::days(policy.after_inactive_days());
+    let from = match last_decay {
+        Some(last_decay) if last_decay > decay_start => last_decay,
+        _ => decay_start,
+    };
+    if now <= from {
+        return 0;
+    }
+    let days = (now - from).num_seconds() as f64 / 86_400.0;
+
+    let decayed = match policy {
+        DecayPolicy::HalfLife { half_life_days, .. } => {
+            if *half_life_days <= 0.0 {
+                return 0;
+            }
+            let remaining = excess as f64 * 0.5f64.powf(days / half_life_days);
+            excess - remaining.round() as i32
+        }
+        DecayPolicy::Linear { points_per_day, .. } => {
+            (points_per_day.max(0.0) * days).floor() as i32
+        }
+    };
+
+    -decayed.clamp(0, excess)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -164,12 +234,14 @@ mod tests {
     fn test_calculate_delta_with_custom_config() {
         use crate::config::ScoringDelta;
 
-        let mut config = RepoConfig::default();
-        config.pr_opened = ScoringDelta {
-            spam: -50,
-            low: -10,
-            acceptable: 10,
-            high: 30,
+        let config = RepoConfig {
+            pr_opened: ScoringDelta {
+                spam: -50,
+                low: -10,
+                acceptable: 10,
+                high: 30,
+            },
+            ..RepoConfig::default()
         };
 
         assert_eq!(
@@ -181,4 +253,90 @@ mod tests {
             30
         );
     }
+
+    // Test calculate_decay
+    fn half_life() -> DecayPolicy {
+        DecayPolicy::HalfLife {
+            after_inactive_days: 90,
+            half_life_days: 30.0,
+        }
+    }
+
+    #[test]
+    fn test_calculate_decay_grace_period() {
+        let now = Utc::now();
+        assert_eq!(
+            calculate_decay(&half_life(), 300, 100, now - Duration::days(89), None, now),
+            0
+        );
+    }
+
+    #[test]
+    fn test_calculate_decay_only_above_starting_credit() {
+        let now = Utc::now();
+        let inactive = now - Duration::days(1000);
+        assert_eq!(
+            calculate_decay(&half_life(), 100, 100, inactive, None, now),
+            0
+        );
+        assert_eq!(
+            calculate_decay(&half_life(), 40, 100, inactive, None, now),
+            0
+        );
+        // Long inactivity decays all the way to starting credit, never below
+        assert_eq!(
+            calculate_decay(&half_life(), 300, 100, inactive, None, now),
+            -200
+        );
+    }
+
+    #[test]
+    fn test_calculate_decay_half_life_compounds() {
+        let now = Utc::now();
+        let last_activity = now - Duration::days(150);
+
+        // Two half-lives in one step
+        assert_eq!(
+            calculate_decay(&half_life(), 300, 100, last_activity, None, now),
+            -150
+        );
+
+        // Same curve when applied in two steps
+        let midway = now - Duration::days(30);
+        let first = calculate_decay(&half_life(), 300, 100, last_activity, None, midway);
+        assert_eq!(first, -100);
+        let second = calculate_decay(&half_life(), 200, 100, last_activity, Some(midway), now);
+        assert_eq!(second, -50);
+    }
+
+    #[test]
+    fn test_calculate_decay_linear() {
+        let policy = DecayPolicy::Linear {
+            after_inactive_days: 30,
+            points_per_day: 2.0,
+        };
+        let now = Utc::now();
+
+        assert_eq!(
+            calculate_decay(&policy, 150, 100, now - Duration::days(40), None, now),
+            -20
+        );
+        // Less than a point due since the last decay
+        assert_eq!(
+            calculate_decay(
+                &policy,
+                130,
+                100,
+                now - Duration::days(40),
+                Some(now - Duration::hours(6)),
+                now
+            ),
+            0
+        );
+        // Stops at starting credit
+        assert_eq!(
+            calculate_decay(&policy, 110, 100, now - Duration::days(400), None, now),
+            -10
+        );
+    }
 }
@@ -75,6 +75,16 @@ impl EvaluationState {
         self
     }
 
+    /// Auto-apply if the confidence meets `confidence_threshold`, otherwise stay pending
+    ///
+    /// A `None` threshold means the evaluation always requires maintainer review.
+    pub fn decide(self, confidence_threshold: Option<f64>) -> Self {
+        match confidence_threshold {
+            Some(threshold) if self.can_auto_apply(threshold) => self.auto_apply(),
+            _ => self,
+        }
+    }
+
     /// Approve the evaluation with the proposed delta
     pub fn approve(mut self, maintainer_note: Option<String>) -> Self {
         self.status = EvaluationStatus::Approved;
@@ -157,6 +167,22 @@ mod tests {
         assert!(applied.is_completed());
     }
 
+    #[test]
+    fn test_decide() {
+        let eval =
+            || EvaluationState::new("eval-123".to_string(), 12345, "spam".to_string(), 0.9, -25);
+
+        assert_eq!(
+            eval().decide(Some(0.85)).status,
+            EvaluationStatus::AutoApplied
+        );
+        assert_eq!(eval().decide(Some(0.95)).status, EvaluationStatus::Pending);
+        // No threshold: always queued for review
+        let pending = eval().decide(None);
+        assert_eq!(pending.status, EvaluationStatus::Pending);
+        assert_eq!(pending.get_final_delta(), None);
+    }
+
     #[test]
     fn test_approve() {
         let eval = EvaluationState::new(
@@ -2,11 +2,16 @@ pub mod config;
 pub mod credit;
 pub mod error;
 pub mod evaluation;
+pub mod limits;
 pub mod policy;
 
 // Re-export commonly used types
-pub use config::{EventType, QualityLevel, RepoConfig, ServerConfig};
-pub use credit::{apply_credit, calculate_delta, calculate_delta_with_config};
+pub use config::{
+    AutoApplyConfig, AutoApplyRule, DailyCreditCaps, DecayPolicy, EarningLimits, EventType,
+    GateTiers, QualityLevel, RepoConfig, ServerConfig,
+};
+pub use credit::{apply_credit, calculate_decay, calculate_delta, calculate_delta_with_config};
 pub use error::{CoreError, CoreResult};
 pub use evaluation::{EvaluationState, EvaluationStatus};
-pub use policy::{GateResult, check_blacklist, check_pr_gate};
+pub use limits::{EarningCap, LimitedCredit, RecentCredit, apply_earning_limits};
+pub use policy::{GateResult, check_blacklist, check_pr_gate, check_pr_gate_with_tiers};
@@ -0,0 +1,299 @@
+use crate::config::{EarningLimits, EventType};
+use chrono::{DateTime, Duration, Utc};
+use std::{cmp::Reverse, fmt};
+
+/// Hours of credit history the earning limits look at
+pub const EARNING_WINDOW_HOURS: i64 = 24;
+
+/// A credit event already recorded for the contributor
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecentCredit {
+    pub event_type: EventType,
+    pub delta: i32,
+    pub pr_number: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Limit that reduced a credit delta
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EarningCap {
+    /// The daily cap for the event type was reached
+    DailyCap { cap: i32 },
+    /// Earlier consecutive comments on the same PR or issue reduced the credit
+    SamePrComments { previous_comments: i32 },
+    /// The previous scored comment was too recent
+    Cooldown { remaining_seconds: i64 },
+}
+
+impl fmt::Display for EarningCap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EarningCap::DailyCap { cap } => write!(f, "Daily credit cap of {} reached", cap),
+            EarningCap::SamePrComments { previous_comments } => write!(
+                f,
+                "Diminishing credit after {} consecutive comments on this PR",
+                previous_comments
+            ),
+            EarningCap::Cooldown { remaining_seconds } => write!(
+                f,
+                "Comment cooldown active ({}s remaining)",
+                remaining_seconds
+            ),
+        }
+    }
+}
+
+/// Credit delta after applying the earning limits
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LimitedCredit {
+    /// Delta to apply (`0` when fully capped)
+    pub delta: i32,
+    /// The limit that reduced the delta, if any
+    pub capped_by: Option<EarningCap>,
+}
+
+/// Apply per-contributor earning limits to a proposed credit delta
+///
+/// `history` holds the contributor's credit events from (at least) the last
+/// [`EARNING_WINDOW_HOURS`], in any order; older events are ignored. Negative
+/// deltas are never limited. Limits are applied in order: comment cooldown,
+/// diminishing returns for consecutive comments on the same PR, then the
+/// daily cap for the event type.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::{Duration, Utc};
+/// use meritocrab_core::config::{EarningLimits, EventType};
+/// use meritocrab_core::limits::{RecentCredit, apply_earning_limits};
+///
+/// let mut limits = EarningLimits::default();
+/// limits.daily_cap.comment = Some(3);
+///
+/// let now = Utc::now();
+/// let history = vec![RecentCredit {
+///     event_type: EventType::Comment,
+///     delta: 2,
+///     pr_number: Some(1),
+///     created_at: now - Duration::hours(1),
+/// }];
+///
+/// let limited = apply_earning_limits(&limits, EventType::Comment, 3, Some(2), &history, now);
+/// assert_eq!(limited.delta, 1);
+/// assert!(limited.capped_by.is_some());
+/// ```
+pub fn apply_earning_limits(
+    limits: &EarningLimits,
+    event_type: EventType,
+    delta: i32,
+    pr_number: Option<i64>,
+    history: &[RecentCredit],
+    now: DateTime<Utc>,
+) -> LimitedCredit {
+    let mut limited = LimitedCredit {
+        delta,
+        capped_by: None,
+    };
+    if delta <= 0 {
+        return limited;
+    }
+
+    let window_start = now - Duration::hours(EARNING_WINDOW_HOURS);
+    let mut recent: Vec<&RecentCredit> = history
+        .iter()
+        .filter(|event| event.created_at > window_start && event.created_at <= now)
+        .collect();
+    recent.sort_by_key(|event| Reverse(event.created_at));
+
+    if event_type.is_comment() {
+        if let Some(cooldown) = limits.comment_cooldown_seconds {
+            let last_scored = recent
+                .iter()
+                .find(|event| event.event_type.is_comment() && event.delta > 0);
+            if let Some(last_scored) = last_scored {
+                let elapsed = (now - last_scored.created_at).num_seconds();
+                if elapsed < cooldown {
+                    return LimitedCredit {
+                        delta: 0,
+                        capped_by: Some(EarningCap::Cooldown {
+                            remaining_seconds: cooldown - elapsed,
+                        }),
+                    };
+                }
+            }
+        }
+
+        if let (Some(factor), Some(pr_number)) = (limits.same_pr_comment_factor, pr_number) {
+            let previous_comments = recent
+                .iter()
+                .filter(|event| event.event_type.is_comment())
+                .take_while(|event| event.pr_number == Some(pr_number))
+                .count() as i32;
+            if previous_comments > 0 {
+                let scaled = (limited.delta as f64 * factor.clamp(0.0, 1.0).powi(previous_comments))
+                    .floor() as i32;
+                if scaled < limited.delta {
+                    limited = LimitedCredit {
+                        delta: scaled,
+                        capped_by: Some(EarningCap::SamePrComments { previous_comments }),
+                    };
+                }
+            }
+        }
+    }
+
+    if let Some(cap) = limits.daily_cap.cap_for(event_type) {
+        let earned: i32 = recent
+            .iter()
+            .filter(|event| event.event_type == event_type && event.delta > 0)
+            .map(|event| event.delta)
+            .sum();
+        let remaining = (cap - earned).max(0);
+        if remaining < limited.delta {
+            limited = LimitedCredit {
+                delta: remaining,
+                capped_by: Some(EarningCap::DailyCap { cap }),
+            };
+        }
+    }
+
+    limited
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(delta: i32, pr_number: i64, minutes_ago: i64, now: DateTime<Utc>) -> RecentCredit {
+        RecentCredit {
+            event_type: EventType::Comment,
+            delta,
+            pr_number: Some(pr_number),
+            created_at: now - Duration::minutes(minutes_ago),
+        }
+    }
+
+    #[test]
+    fn test_no_limits_configured() {
+        let now = Utc::now();
+        let history: Vec<_> = (1..=50).map(|i| comment(1, 1, i, now)).collect();
+
+        let limited = apply_earning_limits(
+            &EarningLimits::default(),
+            EventType::Comment,
+            1,
+            Some(1),
+            &history,
+            now,
+        );
+        assert_eq!(limited.delta, 1);
+        assert_eq!(limited.capped_by, None);
+    }
+
+    #[test]
+    fn test_negative_delta_never_limited() {
+        let now = Utc::now();
+        let limits = EarningLimits {
+            comment_cooldown_seconds: Some(3600),
+            ..EarningLimits::default()
+        };
+        let history = vec![comment(1, 1, 1, now)];
+
+        let limited =
+            apply_earning_limits(&limits, EventType::Comment, -10, Some(1), &history, now);
+        assert_eq!(limited.delta, -10);
+        assert_eq!(limited.capped_by, None);
+    }
+
+    #[test]
+    fn test_daily_cap() {
+        let now = Utc::now();
+        let mut limits = EarningLimits::default();
+        limits.daily_cap.comment = Some(5);
+
+        // 4 earned today, 10 more yesterday (outside the window)
+        let mut history: Vec<_> = (1..=4).map(|i| comment(1, i, i * 10, now)).collect();
+        history.push(comment(10, 9, 25 * 60, now));
+
+        let limited = apply_earning_limits(&limits, EventType::Comment, 3, Some(7), &history, now);
+        assert_eq!(limited.delta, 1);
+        assert_eq!(limited.capped_by, Some(EarningCap::DailyCap { cap: 5 }));
+
+        history.push(comment(1, 7, 1, now));
+        let limited = apply_earning_limits(&limits, EventType::Comment, 3, Some(8), &history, now);
+        assert_eq!(limited.delta, 0);
+
+        // Other event types have their own cap
+        let limited =
+            apply_earning_limits(&limits, EventType::PrOpened, 15, Some(8), &history, now);
+        assert_eq!(limited.delta, 15);
+    }
+
+    #[test]
+    fn test_same_pr_diminishing_returns() {
+        let now = Utc::now();
+        let limits = EarningLimits {
+            same_pr_comment_factor: Some(0.5),
+            ..EarningLimits::default()
+        };
+
+        // Two consecutive comments on PR 1 (a comment on PR 2 before them)
+        let history = vec![
+            comment(4, 1, 5, now),
+            comment(2, 1, 10, now),
+            comment(4, 2, 20, now),
+        ];
+
+        let limited = apply_earning_limits(&limits, EventType::Comment, 4, Some(1), &history, now);
+        assert_eq!(limited.delta, 1);
+        assert_eq!(
+            limited.capped_by,
+            Some(EarningCap::SamePrComments {
+                previous_comments: 2
+            })
+        );
+
+        // Switching PRs resets the run
+        let limited = apply_earning_limits(&limits, EventType::Comment, 4, Some(2), &history, now);
+        assert_eq!(limited.delta, 4);
+        assert_eq!(limited.capped_by, None);
+    }
+
+    #[test]
+    fn test_comment_cooldown() {
+        let now = Utc::now();
+        let limits = EarningLimits {
+            comment_cooldown_seconds: Some(300),
+            ..EarningLimits::default()
+        };
+
+        // A capped (zero) comment does not restart the cooldown
+        let history = vec![comment(0, 1, 1, now), comment(1, 1, 3, now)];
+        let limited = apply_earning_limits(&limits, EventType::Comment, 1, Some(1), &history, now);
+        assert_eq!(limited.delta, 0);
+        assert_eq!(
+            limited.capped_by,
+            Some(EarningCap::Cooldown {
+                remaining_seconds: 120
+            })
+        );
+
+        let history = vec![comment(1, 1, 6, now)];
+        let limited = apply_earning_limits(&limits, EventType::Comment, 1, Some(1), &history, now);
+        assert_eq!(limited.delta, 1);
+
+        // Comments on issues share the cooldown with PR comments
+        let history = vec![comment(1, 1, 1, now)];
+        let limited =
+            apply_earning_limits(&limits, EventType::IssueComment, 1, Some(2), &history, now);
+        assert_eq!(limited.delta, 0);
+    }
+
+    #[test]
+    fn test_cap_reason() {
+        assert_eq!(
+            EarningCap::DailyCap { cap: 10 }.to_string(),
+            "Daily credit cap of 10 reached"
+        );
+    }
+}
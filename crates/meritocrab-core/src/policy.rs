@@ -1,3 +1,4 @@
+use crate::config::GateTiers;
 use serde::{Deserialize, Serialize};
 
 /// Result of a PR gate check
@@ -5,6 +6,12 @@ use serde::{Deserialize, Serialize};
 pub enum GateResult {
     /// PR is allowed
     Allow,
+    /// PR is allowed, with a triage label added
+    AllowWithLabel,
+    /// PR is converted to a draft instead of being closed
+    Draft,
+    /// PR stays open until a maintainer approves it with `/credit approve`
+    RequireApproval,
     /// PR is denied
     Deny,
 }
@@ -31,6 +38,49 @@ pub fn check_pr_gate(credit_score: i32, threshold: i32) -> GateResult {
     }
 }
 
+/// Check a PR against the threshold and the graduated gate tiers below it
+///
+/// Scores at or above `threshold` are allowed. Below it, the first tier
+/// (from mildest to strictest) whose minimum score is met applies; scores
+/// below every configured tier are denied.
+///
+/// # Examples
+///
+/// ```
+/// use meritocrab_core::config::GateTiers;
+/// use meritocrab_core::policy::{check_pr_gate_with_tiers, GateResult};
+///
+/// let tiers = GateTiers {
+///     label_min_score: Some(40),
+///     draft_min_score: Some(30),
+///     ..GateTiers::default()
+/// };
+///
+/// assert_eq!(check_pr_gate_with_tiers(60, 50, &tiers), GateResult::Allow);
+/// assert_eq!(check_pr_gate_with_tiers(45, 50, &tiers), GateResult::AllowWithLabel);
+/// assert_eq!(check_pr_gate_with_tiers(30, 50, &tiers), GateResult::Draft);
+/// assert_eq!(check_pr_gate_with_tiers(29, 50, &tiers), GateResult::Deny);
+/// ```
+pub fn check_pr_gate_with_tiers(
+    credit_score: i32,
+    threshold: i32,
+    tiers: &GateTiers,
+) -> GateResult {
+    if credit_score >= threshold {
+        return GateResult::Allow;
+    }
+
+    [
+        (tiers.label_min_score, GateResult::AllowWithLabel),
+        (tiers.draft_min_score, GateResult::Draft),
+        (tiers.approval_min_score, GateResult::RequireApproval),
+    ]
+    .into_iter()
+    .find(|(min_score, _)| min_score.is_some_and(|min_score| credit_score >= min_score))
+    .map(|(_, result)| result)
+    .unwrap_or(GateResult::Deny)
+}
+
 /// Check if a user should be blacklisted based on credit score
 ///
 /// Returns `true` if credit_score <= blacklist_threshold, otherwise `false`.
@@ -87,6 +137,54 @@ mod tests {
         assert_eq!(check_pr_gate(99, 100), GateResult::Deny);
     }
 
+    // Test check_pr_gate_with_tiers
+    #[test]
+    fn test_check_pr_gate_without_tiers_matches_binary_gate() {
+        let tiers = GateTiers::default();
+        assert_eq!(check_pr_gate_with_tiers(50, 50, &tiers), GateResult::Allow);
+        assert_eq!(check_pr_gate_with_tiers(49, 50, &tiers), GateResult::Deny);
+    }
+
+    #[test]
+    fn test_check_pr_gate_all_tiers() {
+        let tiers = GateTiers {
+            label_min_score: Some(40),
+            draft_min_score: Some(30),
+            approval_min_score: Some(20),
+            ..GateTiers::default()
+        };
+
+        assert_eq!(check_pr_gate_with_tiers(50, 50, &tiers), GateResult::Allow);
+        assert_eq!(
+            check_pr_gate_with_tiers(49, 50, &tiers),
+            GateResult::AllowWithLabel
+        );
+        assert_eq!(
+            check_pr_gate_with_tiers(40, 50, &tiers),
+            GateResult::AllowWithLabel
+        );
+        assert_eq!(check_pr_gate_with_tiers(39, 50, &tiers), GateResult::Draft);
+        assert_eq!(
+            check_pr_gate_with_tiers(20, 50, &tiers),
+            GateResult::RequireApproval
+        );
+        assert_eq!(check_pr_gate_with_tiers(19, 50, &tiers), GateResult::Deny);
+    }
+
+    #[test]
+    fn test_check_pr_gate_skips_unset_tiers() {
+        let tiers = GateTiers {
+            approval_min_score: Some(10),
+            ..GateTiers::default()
+        };
+
+        assert_eq!(
+            check_pr_gate_with_tiers(45, 50, &tiers),
+            GateResult::RequireApproval
+        );
+        assert_eq!(check_pr_gate_with_tiers(9, 50, &tiers), GateResult::Deny);
+    }
+
     // Test check_blacklist
     #[test]
     fn test_check_blacklist_at_threshold() {
@@ -0,0 +1,10 @@
+-- Link credit events and evaluations to the pull request they were earned on
+
+ALTER TABLE credit_events ADD COLUMN IF NOT EXISTS pr_number BIGINT;
+ALTER TABLE pending_evaluations ADD COLUMN IF NOT EXISTS pr_number BIGINT;
+
+CREATE INDEX IF NOT EXISTS idx_credit_events_pr ON credit_events(contributor_id, pr_number);
+CREATE INDEX IF NOT EXISTS idx_pending_evaluations_pr ON pending_evaluations(contributor_id, pr_number);
+
+-- A merged PR pays out at most once, even if the webhook is redelivered
+CREATE UNIQUE INDEX IF NOT EXISTS idx_credit_events_pr_merged_once ON credit_events(contributor_id, pr_number) WHERE event_type = 'pr_merged';
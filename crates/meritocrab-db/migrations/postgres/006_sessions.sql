@@ -0,0 +1,12 @@
+-- Maintainer dashboard sessions, shared by every server replica
+--
+-- data holds the serialized tower-sessions record; expiry_date is RFC3339 so
+-- expired sessions can be filtered and pruned with a string comparison.
+
+CREATE TABLE IF NOT EXISTS sessions (
+    id TEXT PRIMARY KEY,
+    data TEXT NOT NULL,
+    expiry_date TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_sessions_expiry ON sessions(expiry_date);
@@ -0,0 +1,4 @@
+-- Why an automated credit change was made (earning caps, decay, empty
+-- reviews, auto-blacklisting), kept apart from maintainer overrides
+
+ALTER TABLE credit_events ADD COLUMN IF NOT EXISTS reason TEXT;
@@ -0,0 +1,10 @@
+-- GitHub App installation each repository was last seen under, so
+-- background tasks without a webhook can act as the right installation
+
+CREATE TABLE IF NOT EXISTS repo_installations (
+    repo_owner TEXT NOT NULL,
+    repo_name TEXT NOT NULL,
+    installation_id BIGINT NOT NULL,
+    updated_at TEXT NOT NULL DEFAULT (to_char(now() AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS"+00:00"')),
+    PRIMARY KEY (repo_owner, repo_name)
+);
@@ -14,8 +14,8 @@ CREATE TABLE IF NOT EXISTS contributors (
     UNIQUE(github_user_id, repo_owner, repo_name)
 );
 
-CREATE INDEX idx_contributors_lookup ON contributors(github_user_id, repo_owner, repo_name);
-CREATE INDEX idx_contributors_blacklist ON contributors(is_blacklisted);
+CREATE INDEX IF NOT EXISTS idx_contributors_lookup ON contributors(github_user_id, repo_owner, repo_name);
+CREATE INDEX IF NOT EXISTS idx_contributors_blacklist ON contributors(is_blacklisted);
 
 -- Credit events table: immutable audit log of all credit changes
 CREATE TABLE IF NOT EXISTS credit_events (
@@ -31,7 +31,7 @@ CREATE TABLE IF NOT EXISTS credit_events (
     FOREIGN KEY (contributor_id) REFERENCES contributors(id) ON DELETE CASCADE
 );
 
-CREATE INDEX idx_credit_events_contributor ON credit_events(contributor_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_credit_events_contributor ON credit_events(contributor_id, created_at);
 
 -- Pending evaluations table: maintainer review queue
 CREATE TABLE IF NOT EXISTS pending_evaluations (
@@ -50,8 +50,8 @@ CREATE TABLE IF NOT EXISTS pending_evaluations (
     FOREIGN KEY (contributor_id) REFERENCES contributors(id) ON DELETE CASCADE
 );
 
-CREATE INDEX idx_pending_evaluations_status ON pending_evaluations(repo_owner, repo_name, status, created_at);
-CREATE INDEX idx_pending_evaluations_contributor ON pending_evaluations(contributor_id);
+CREATE INDEX IF NOT EXISTS idx_pending_evaluations_status ON pending_evaluations(repo_owner, repo_name, status, created_at);
+CREATE INDEX IF NOT EXISTS idx_pending_evaluations_contributor ON pending_evaluations(contributor_id);
 
 -- Repo configs table: cached configuration per repository
 CREATE TABLE IF NOT EXISTS repo_configs (
@@ -64,4 +64,4 @@ CREATE TABLE IF NOT EXISTS repo_configs (
     UNIQUE(owner, repo)
 );
 
-CREATE INDEX idx_repo_configs_lookup ON repo_configs(owner, repo);
+CREATE INDEX IF NOT EXISTS idx_repo_configs_lookup ON repo_configs(owner, repo);
@@ -0,0 +1,22 @@
+-- Durable background jobs (LLM evaluations, delayed PR closes)
+--
+-- status: pending -> running -> completed, or dead once retries are exhausted.
+-- A running job whose lease (locked_until) has expired is picked up again.
+
+CREATE TABLE IF NOT EXISTS jobs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    job_type TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    repo_owner TEXT NOT NULL,
+    repo_name TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    run_at TEXT NOT NULL,
+    locked_until TEXT,
+    last_error TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_jobs_claim ON jobs(status, run_at);
+CREATE INDEX IF NOT EXISTS idx_jobs_repo_status ON jobs(repo_owner, repo_name, status);
@@ -0,0 +1,5 @@
+-- Store the contributor's GitHub login so maintainers can refer to them by @username
+
+ALTER TABLE contributors ADD COLUMN login TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_contributors_login ON contributors(login);
@@ -0,0 +1,7 @@
+-- Optional key that makes a job unique, e.g. one evaluation per PR review
+--
+-- Jobs without a key are never deduplicated (NULLs do not conflict).
+
+ALTER TABLE jobs ADD COLUMN dedupe_key TEXT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_jobs_dedupe_key ON jobs(dedupe_key);
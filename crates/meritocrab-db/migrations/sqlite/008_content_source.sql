@@ -0,0 +1,7 @@
+-- Link credit events and evaluations to the GitHub content they were earned
+-- on (e.g. "issue_comment:123"), so edits and deletions can adjust them
+
+ALTER TABLE credit_events ADD COLUMN source_id TEXT;
+ALTER TABLE pending_evaluations ADD COLUMN source_id TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_credit_events_source ON credit_events(contributor_id, source_id);
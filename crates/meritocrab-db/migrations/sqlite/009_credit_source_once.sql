@@ -0,0 +1,5 @@
+-- Credit each piece of GitHub content at most once, so a re-run evaluation
+-- job cannot apply its credit twice. Edit adjustments, reversals and
+-- maintainer decisions on the same content are not restricted.
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_credit_events_source_once ON credit_events(contributor_id, source_id) WHERE event_type IN ('pr_opened', 'comment', 'review_submitted', 'issue_opened', 'issue_comment');
@@ -0,0 +1,10 @@
+-- A maintainer approval exempts a PR once; repeated /credit approve comments
+-- must not add further exemption events
+
+DELETE FROM credit_events
+WHERE event_type = 'exemption'
+  AND id NOT IN (
+    SELECT MIN(id) FROM credit_events WHERE event_type = 'exemption' GROUP BY contributor_id, pr_number
+  );
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_credit_events_exemption_once ON credit_events(contributor_id, pr_number) WHERE event_type = 'exemption';
@@ -1,6 +1,7 @@
 use crate::error::{DbError, DbResult};
-use crate::models::{Contributor, ContributorRaw};
+use crate::models::{Contributor, ContributorRaw, DecayCandidate, DecayCandidateRaw};
 use chrono::Utc;
+use meritocrab_core::EventType;
 use sqlx::{Any, Pool};
 
 /// Create a new contributor with default credit score
@@ -41,7 +42,7 @@ pub async fn get_contributor(
     repo_name: &str,
 ) -> DbResult<Option<Contributor>> {
     let contributor = sqlx::query_as::<_, ContributorRaw>(
-        "SELECT id, github_user_id, repo_owner, repo_name, credit_score, role, is_blacklisted, created_at, updated_at
+        "SELECT id, github_user_id, login, repo_owner, repo_name, credit_score, role, is_blacklisted, created_at, updated_at
          FROM contributors
          WHERE github_user_id = ? AND repo_owner = ? AND repo_name = ?"
     )
@@ -176,7 +177,7 @@ pub async fn list_contributors_by_repo(
     offset: i64,
 ) -> DbResult<Vec<Contributor>> {
     let contributors = sqlx::query_as::<_, ContributorRaw>(
-        "SELECT id, github_user_id, repo_owner, repo_name, credit_score, role, is_blacklisted, created_at, updated_at
+        "SELECT id, github_user_id, login, repo_owner, repo_name, credit_score, role, is_blacklisted, created_at, updated_at
          FROM contributors
          WHERE repo_owner = ? AND repo_name = ?
          ORDER BY credit_score DESC, updated_at DESC
@@ -217,7 +218,7 @@ pub async fn get_contributor_by_id(
     contributor_id: i64,
 ) -> DbResult<Option<Contributor>> {
     let contributor = sqlx::query_as::<_, ContributorRaw>(
-        "SELECT id, github_user_id, repo_owner, repo_name, credit_score, role, is_blacklisted, created_at, updated_at
+        "SELECT id, github_user_id, login, repo_owner, repo_name, credit_score, role, is_blacklisted, created_at, updated_at
          FROM contributors
          WHERE id = ?"
     )
@@ -229,9 +230,117 @@ pub async fn get_contributor_by_id(
     Ok(contributor)
 }
 
+/// Record a contributor's current GitHub login
+///
+/// Updates every repo row for the user, so renames propagate everywhere.
+/// Returns the number of rows whose login changed.
+pub async fn update_login(pool: &Pool<Any>, github_user_id: i64, login: &str) -> DbResult<u64> {
+    let now_str = Utc::now().to_rfc3339();
+
+    let result = sqlx::query(
+        "UPDATE contributors SET login = ?, updated_at = ?
+         WHERE github_user_id = ? AND (login IS NULL OR login != ?)",
+    )
+    .bind(login)
+    .bind(&now_str)
+    .bind(github_user_id)
+    .bind(login)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Get contributor by GitHub login and repo (case-insensitive, like GitHub)
+pub async fn get_contributor_by_login(
+    pool: &Pool<Any>,
+    login: &str,
+    repo_owner: &str,
+    repo_name: &str,
+) -> DbResult<Option<Contributor>> {
+    let contributor = sqlx::query_as::<_, ContributorRaw>(
+        "SELECT id, github_user_id, login, repo_owner, repo_name, credit_score, role, is_blacklisted, created_at, updated_at
+         FROM contributors
+         WHERE LOWER(login) = LOWER(?) AND repo_owner = ? AND repo_name = ?"
+    )
+    .bind(login)
+    .bind(repo_owner)
+    .bind(repo_name)
+    .fetch_optional(pool)
+    .await?
+    .map(|raw| raw.into());
+
+    Ok(contributor)
+}
+
+/// Find the GitHub user ID last seen with a login in any repository
+pub async fn find_user_id_by_login(pool: &Pool<Any>, login: &str) -> DbResult<Option<i64>> {
+    let row: Option<(i64,)> = sqlx::query_as(
+        "SELECT github_user_id FROM contributors
+         WHERE LOWER(login) = LOWER(?)
+         ORDER BY updated_at DESC
+         LIMIT 1",
+    )
+    .bind(login)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|(id,)| id))
+}
+
+/// List every repository with at least one contributor
+pub async fn list_repos(pool: &Pool<Any>) -> DbResult<Vec<(String, String)>> {
+    let repos = sqlx::query_as(
+        "SELECT DISTINCT repo_owner, repo_name FROM contributors ORDER BY repo_owner, repo_name",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(repos)
+}
+
+/// List contributors of a repo scoring above `min_score`, with the time of
+/// their latest scored event and latest `decay` event
+pub async fn list_decay_candidates(
+    pool: &Pool<Any>,
+    repo_owner: &str,
+    repo_name: &str,
+    min_score: i32,
+) -> DbResult<Vec<DecayCandidate>> {
+    let activity_types = EventType::ALL
+        .iter()
+        .map(|event_type| format!("'{}'", event_type.as_str()))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let query = format!(
+        "SELECT c.id, c.github_user_id, c.login, c.repo_owner, c.repo_name, c.credit_score, c.role, c.is_blacklisted, c.created_at, c.updated_at,
+                (SELECT MAX(e.created_at) FROM credit_events e
+                 WHERE e.contributor_id = c.id AND e.event_type IN ({})) AS last_activity,
+                (SELECT MAX(e.created_at) FROM credit_events e
+                 WHERE e.contributor_id = c.id AND e.event_type = 'decay') AS last_decay
+         FROM contributors c
+         WHERE c.repo_owner = ? AND c.repo_name = ? AND c.credit_score > ?
+         ORDER BY c.id",
+        activity_types
+    );
+
+    let candidates = sqlx::query_as::<_, DecayCandidateRaw>(&query)
+        .bind(repo_owner)
+        .bind(repo_name)
+        .bind(min_score)
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|raw| raw.into())
+        .collect();
+
+    Ok(candidates)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::migrations::run_migrations;
     use sqlx::any::AnyPoolOptions;
 
     async fn setup_test_db() -> Pool<Any> {
@@ -251,8 +360,7 @@ mod tests {
             .expect("Failed to enable foreign keys");
 
         // Run migrations
-        sqlx::query(include_str!("../migrations/001_initial.sql"))
-            .execute(&pool)
+        run_migrations(&pool)
             .await
             .expect("Failed to run migrations");
 
@@ -381,4 +489,106 @@ mod tests {
 
         assert!(updated.is_blacklisted);
     }
+
+    #[tokio::test]
+    async fn test_update_login_and_lookup_by_login() {
+        let pool = setup_test_db().await;
+
+        create_contributor(&pool, 12345, "owner", "repo", 100)
+            .await
+            .expect("Failed to create contributor");
+        create_contributor(&pool, 12345, "owner", "other", 100)
+            .await
+            .expect("Failed to create contributor");
+
+        // First sighting fills the login on every repo row
+        assert_eq!(update_login(&pool, 12345, "octocat").await.unwrap(), 2);
+        // Same login again is a no-op
+        assert_eq!(update_login(&pool, 12345, "octocat").await.unwrap(), 0);
+
+        let contributor = get_contributor_by_login(&pool, "OctoCat", "owner", "repo")
+            .await
+            .expect("Failed to get contributor")
+            .expect("Contributor should be found case-insensitively");
+        assert_eq!(contributor.github_user_id, 12345);
+        assert_eq!(contributor.login.as_deref(), Some("octocat"));
+
+        // Rename
+        update_login(&pool, 12345, "octodog").await.unwrap();
+        assert!(
+            get_contributor_by_login(&pool, "octocat", "owner", "repo")
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(
+            find_user_id_by_login(&pool, "octodog").await.unwrap(),
+            Some(12345)
+        );
+        assert_eq!(find_user_id_by_login(&pool, "nobody").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_list_decay_candidates() {
+        use crate::credit_events::insert_credit_event;
+
+        let pool = setup_test_db().await;
+
+        let active = create_contributor(&pool, 1, "owner", "repo", 100)
+            .await
+            .unwrap();
+        update_credit_score(&pool, active.id, 150).await.unwrap();
+        let idle = create_contributor(&pool, 2, "owner", "repo", 100)
+            .await
+            .unwrap();
+        update_credit_score(&pool, idle.id, 120).await.unwrap();
+        // At starting credit: not a candidate
+        create_contributor(&pool, 3, "owner", "repo", 100)
+            .await
+            .unwrap();
+        create_contributor(&pool, 4, "owner", "other", 100)
+            .await
+            .unwrap();
+
+        insert_credit_event(&pool, active.id, "comment", 5, 145, 150, None, None)
+            .await
+            .unwrap();
+        insert_credit_event(&pool, active.id, "decay", -5, 150, 145, None, None)
+            .await
+            .unwrap();
+        // Maintainer actions are not activity
+        insert_credit_event(
+            &pool,
+            idle.id,
+            "manual_adjustment",
+            20,
+            100,
+            120,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            list_repos(&pool).await.unwrap(),
+            vec![
+                ("owner".to_string(), "other".to_string()),
+                ("owner".to_string(), "repo".to_string())
+            ]
+        );
+
+        let candidates = list_decay_candidates(&pool, "owner", "repo", 100)
+            .await
+            .unwrap();
+        assert_eq!(candidates.len(), 2);
+
+        assert_eq!(candidates[0].contributor.id, active.id);
+        assert!(candidates[0].last_activity > active.created_at);
+        assert!(candidates[0].last_decay.is_some());
+
+        assert_eq!(candidates[1].contributor.id, idle.id);
+        assert_eq!(candidates[1].last_activity, idle.created_at);
+        assert!(candidates[1].last_decay.is_none());
+    }
 }
@@ -1,7 +1,8 @@
-use crate::error::DbResult;
-use crate::models::{CreditEvent, CreditEventRaw};
-use chrono::Utc;
-use sqlx::{Any, Pool};
+use crate::error::{DbError, DbResult};
+use crate::models::{CreditEvent, CreditEventRaw, CreditEventWithOrigin, CreditEventWithOriginRaw};
+use chrono::{DateTime, Utc};
+use meritocrab_core::apply_credit;
+use sqlx::{Any, AnyConnection, Pool};
 
 /// Insert a new credit event (immutable audit log)
 #[allow(clippy::too_many_arguments)]
@@ -14,24 +15,392 @@ pub async fn insert_credit_event(
     credit_after: i32,
     llm_evaluation: Option<String>,
     maintainer_override: Option<String>,
+) -> DbResult<CreditEvent> {
+    insert_credit_event_with_pr(
+        pool,
+        contributor_id,
+        event_type,
+        delta,
+        credit_before,
+        credit_after,
+        llm_evaluation,
+        maintainer_override,
+        None,
+    )
+    .await
+}
+
+/// Insert a new credit event linked to a pull request
+///
+/// Returns `DbError::DuplicateCreditEvent` if the event is already recorded for
+/// this PR (e.g. a `pr_merged` event for a redelivered webhook).
+#[allow(clippy::too_many_arguments)]
+pub async fn insert_credit_event_with_pr(
+    pool: &Pool<Any>,
+    contributor_id: i64,
+    event_type: &str,
+    delta: i32,
+    credit_before: i32,
+    credit_after: i32,
+    llm_evaluation: Option<String>,
+    maintainer_override: Option<String>,
+    pr_number: Option<i64>,
+) -> DbResult<CreditEvent> {
+    let mut conn = pool.acquire().await?;
+
+    insert_event(
+        &mut conn,
+        contributor_id,
+        NewCreditEvent {
+            event_type: event_type.to_string(),
+            llm_evaluation,
+            maintainer_override,
+            reason: None,
+            pr_number,
+            source_id: None,
+        },
+        delta,
+        credit_before,
+        credit_after,
+    )
+    .await
+}
+
+/// Credit event recorded by [`apply_credit_delta`]
+#[derive(Debug, Clone)]
+pub struct NewCreditEvent {
+    pub event_type: String,
+    pub llm_evaluation: Option<String>,
+    pub maintainer_override: Option<String>,
+    pub reason: Option<String>,
+    pub pr_number: Option<i64>,
+    pub source_id: Option<String>,
+}
+
+impl NewCreditEvent {
+    /// Create an event of the given type with no evaluation, override, PR, or source
+    pub fn new(event_type: &str) -> Self {
+        Self {
+            event_type: event_type.to_string(),
+            llm_evaluation: None,
+            maintainer_override: None,
+            reason: None,
+            pr_number: None,
+            source_id: None,
+        }
+    }
+
+    /// Attach the LLM evaluation (JSON) behind this event
+    pub fn with_llm_evaluation(mut self, llm_evaluation: String) -> Self {
+        self.llm_evaluation = Some(llm_evaluation);
+        self
+    }
+
+    /// Attach a maintainer override or note
+    pub fn with_maintainer_override(mut self, maintainer_override: String) -> Self {
+        self.maintainer_override = Some(maintainer_override);
+        self
+    }
+
+    /// Attach the reason for an automated change (e.g. an earning cap or decay)
+    pub fn with_reason(mut self, reason: String) -> Self {
+        self.reason = Some(reason);
+        self
+    }
+
+    /// Link the event to a pull request (at most one event per type and PR)
+    pub fn with_pr_number(mut self, pr_number: Option<i64>) -> Self {
+        self.pr_number = pr_number;
+        self
+    }
+
+    /// Link the event to the GitHub content it was earned on
+    pub fn with_source_id(mut self, source_id: Option<String>) -> Self {
+        self.source_id = source_id;
+        self
+    }
+}
+
+/// Outcome of [`apply_credit_delta`]
+#[derive(Debug, Clone)]
+pub struct AppliedCredit {
+    /// The recorded credit event (carries `credit_before` and `credit_after`)
+    pub event: CreditEvent,
+    /// Whether this change crossed the blacklist threshold and blacklisted the contributor
+    pub auto_blacklisted: bool,
+}
+
+/// Apply a credit delta and record it in the audit log atomically
+///
+/// The score update and the credit event are written in one transaction, so
+/// they can never disagree. The contributor row is locked before its score is
+/// read, and the score is written with a conditional update, so concurrent
+/// changes to the same contributor are serialized instead of losing updates.
+///
+/// When `blacklist_threshold` is set and the new score drops to or below it
+/// (from above it), the contributor is blacklisted and an `auto_blacklist`
+/// event is recorded in the same transaction.
+///
+/// Returns `DbError::DuplicateCreditEvent` (and applies nothing) if the event
+/// is linked to a PR that already has an event of this type, and
+/// `DbError::DuplicateSourceCredit` if the GitHub content it is for was
+/// already credited.
+pub async fn apply_credit_delta(
+    pool: &Pool<Any>,
+    contributor_id: i64,
+    delta: i32,
+    event: NewCreditEvent,
+    blacklist_threshold: Option<i32>,
+) -> DbResult<AppliedCredit> {
+    let mut tx = pool.begin().await?;
+    let credit_before = lock_credit_score(&mut tx, contributor_id).await?;
+    let applied = write_credit(
+        &mut tx,
+        contributor_id,
+        credit_before,
+        delta,
+        event,
+        blacklist_threshold,
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(applied)
+}
+
+/// Set a contributor's credit to an absolute score and record it atomically
+///
+/// Like [`apply_credit_delta`], but the delta is the difference between
+/// `score` and the score read under the row lock, so a change that lands
+/// between the caller reading the contributor and this call is not undone.
+pub async fn set_credit_score(
+    pool: &Pool<Any>,
+    contributor_id: i64,
+    score: i32,
+    event: NewCreditEvent,
+    blacklist_threshold: Option<i32>,
+) -> DbResult<AppliedCredit> {
+    let mut tx = pool.begin().await?;
+    let credit_before = lock_credit_score(&mut tx, contributor_id).await?;
+    let applied = write_credit(
+        &mut tx,
+        contributor_id,
+        credit_before,
+        score - credit_before,
+        event,
+        blacklist_threshold,
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(applied)
+}
+
+/// Apply a delta derived from the contributor's current score, unless it is zero
+///
+/// `delta_for` receives the score read under the row lock, so the delta
+/// cannot be based on a score that changed since the caller last read it.
+/// A zero delta records nothing and returns `None`. Otherwise behaves like
+/// [`apply_credit_delta`].
+pub async fn apply_credit_change(
+    pool: &Pool<Any>,
+    contributor_id: i64,
+    delta_for: impl FnOnce(i32) -> i32,
+    event: NewCreditEvent,
+    blacklist_threshold: Option<i32>,
+) -> DbResult<Option<AppliedCredit>> {
+    let mut tx = pool.begin().await?;
+    let credit_before = lock_credit_score(&mut tx, contributor_id).await?;
+    let delta = delta_for(credit_before);
+    if delta == 0 {
+        tx.rollback().await?;
+        return Ok(None);
+    }
+    let applied = write_credit(
+        &mut tx,
+        contributor_id,
+        credit_before,
+        delta,
+        event,
+        blacklist_threshold,
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(Some(applied))
+}
+
+/// Lock a contributor's row and read its credit score
+async fn lock_credit_score(conn: &mut AnyConnection, contributor_id: i64) -> DbResult<i32> {
+    // Write to the row first: this takes the row lock (the write lock on
+    // SQLite), so a concurrent change waits here rather than reading a score
+    // that is about to change
+    let locked = sqlx::query("UPDATE contributors SET updated_at = ? WHERE id = ?")
+        .bind(Utc::now().to_rfc3339())
+        .bind(contributor_id)
+        .execute(&mut *conn)
+        .await?;
+    if locked.rows_affected() == 0 {
+        return Err(DbError::SqlxError(sqlx::Error::RowNotFound));
+    }
+
+    let (credit_score,): (i32,) =
+        sqlx::query_as("SELECT credit_score FROM contributors WHERE id = ?")
+            .bind(contributor_id)
+            .fetch_one(&mut *conn)
+            .await?;
+
+    Ok(credit_score)
+}
+
+/// Apply a delta to a score locked by [`lock_credit_score`] and record it
+async fn write_credit(
+    conn: &mut AnyConnection,
+    contributor_id: i64,
+    credit_before: i32,
+    delta: i32,
+    event: NewCreditEvent,
+    blacklist_threshold: Option<i32>,
+) -> DbResult<AppliedCredit> {
+    let now_str = Utc::now().to_rfc3339();
+    let credit_after = apply_credit(credit_before, delta);
+
+    let updated = sqlx::query(
+        "UPDATE contributors SET credit_score = ?, updated_at = ? WHERE id = ? AND credit_score = ?",
+    )
+    .bind(credit_after)
+    .bind(&now_str)
+    .bind(contributor_id)
+    .bind(credit_before)
+    .execute(&mut *conn)
+    .await?;
+    if updated.rows_affected() == 0 {
+        // Unreachable while the row lock is held; refuse rather than overwrite
+        return Err(DbError::ConcurrentCreditUpdate(contributor_id));
+    }
+
+    let event = insert_event(
+        conn,
+        contributor_id,
+        event,
+        delta,
+        credit_before,
+        credit_after,
+    )
+    .await?;
+
+    let auto_blacklisted = blacklist_threshold
+        .is_some_and(|threshold| credit_after <= threshold && credit_before > threshold);
+    if auto_blacklisted {
+        sqlx::query("UPDATE contributors SET is_blacklisted = 1, updated_at = ? WHERE id = ?")
+            .bind(&now_str)
+            .bind(contributor_id)
+            .execute(&mut *conn)
+            .await?;
+
+        insert_event(
+            conn,
+            contributor_id,
+            NewCreditEvent::new("auto_blacklist").with_reason(format!(
+                "Auto-blacklisted due to credit dropping to {}",
+                credit_after
+            )),
+            0,
+            credit_after,
+            credit_after,
+        )
+        .await?;
+    }
+
+    Ok(AppliedCredit {
+        event,
+        auto_blacklisted,
+    })
+}
+
+/// Set a contributor's blacklist flag and record it in the audit log atomically
+///
+/// The flag and the (zero-delta) event are written in one transaction, and
+/// the event records the score as it is when the row is locked.
+pub async fn set_blacklisted_with_event(
+    pool: &Pool<Any>,
+    contributor_id: i64,
+    is_blacklisted: bool,
+    event: NewCreditEvent,
+) -> DbResult<CreditEvent> {
+    let mut tx = pool.begin().await?;
+    let now_str = Utc::now().to_rfc3339();
+
+    let updated =
+        sqlx::query("UPDATE contributors SET is_blacklisted = ?, updated_at = ? WHERE id = ?")
+            .bind(if is_blacklisted { 1 } else { 0 })
+            .bind(&now_str)
+            .bind(contributor_id)
+            .execute(&mut *tx)
+            .await?;
+    if updated.rows_affected() == 0 {
+        return Err(DbError::SqlxError(sqlx::Error::RowNotFound));
+    }
+
+    let (credit_score,): (i32,) =
+        sqlx::query_as("SELECT credit_score FROM contributors WHERE id = ?")
+            .bind(contributor_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+    let event = insert_event(
+        &mut tx,
+        contributor_id,
+        event,
+        0,
+        credit_score,
+        credit_score,
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(event)
+}
+
+/// Insert a credit event on an existing connection or transaction
+async fn insert_event(
+    conn: &mut AnyConnection,
+    contributor_id: i64,
+    event: NewCreditEvent,
+    delta: i32,
+    credit_before: i32,
+    credit_after: i32,
 ) -> DbResult<CreditEvent> {
     let now = Utc::now();
     let now_str = now.to_rfc3339();
 
     sqlx::query(
-        "INSERT INTO credit_events (contributor_id, event_type, delta, credit_before, credit_after, llm_evaluation, maintainer_override, created_at)
-         VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
+        "INSERT INTO credit_events (contributor_id, event_type, delta, credit_before, credit_after, llm_evaluation, maintainer_override, reason, pr_number, source_id, created_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
     )
     .bind(contributor_id)
-    .bind(event_type)
+    .bind(&event.event_type)
     .bind(delta)
     .bind(credit_before)
     .bind(credit_after)
-    .bind(&llm_evaluation)
-    .bind(&maintainer_override)
+    .bind(&event.llm_evaluation)
+    .bind(&event.maintainer_override)
+    .bind(&event.reason)
+    .bind(event.pr_number)
+    .bind(&event.source_id)
     .bind(&now_str)
-    .execute(pool)
-    .await?;
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| match (&e, &event.source_id, event.pr_number) {
+        (sqlx::Error::Database(db_err), Some(source_id), _) if db_err.is_unique_violation() => {
+            DbError::DuplicateSourceCredit(contributor_id, source_id.clone())
+        }
+        (sqlx::Error::Database(db_err), None, Some(pr)) if db_err.is_unique_violation() => {
+            DbError::DuplicateCreditEvent(contributor_id, event.event_type.clone(), pr)
+        }
+        _ => DbError::from(e),
+    })?;
 
     // Return a dummy ID (the event is immutable and we don't need the ID for most operations)
     // In a real scenario, we might query back to get the actual ID, but for simplicity we'll use 0
@@ -39,16 +408,68 @@ pub async fn insert_credit_event(
     Ok(CreditEvent {
         id: 0, // Placeholder ID
         contributor_id,
-        event_type: event_type.to_string(),
+        event_type: event.event_type,
         delta,
         credit_before,
         credit_after,
-        llm_evaluation,
-        maintainer_override,
+        llm_evaluation: event.llm_evaluation,
+        maintainer_override: event.maintainer_override,
+        reason: event.reason,
+        pr_number: event.pr_number,
+        source_id: event.source_id,
         created_at: now,
     })
 }
 
+/// Get the most recent credit event of a given type for a contributor's PR
+pub async fn get_pr_credit_event(
+    pool: &Pool<Any>,
+    contributor_id: i64,
+    event_type: &str,
+    pr_number: i64,
+) -> DbResult<Option<CreditEvent>> {
+    let event = sqlx::query_as::<_, CreditEventRaw>(
+        "SELECT id, contributor_id, event_type, delta, credit_before, credit_after, llm_evaluation, maintainer_override, reason, pr_number, source_id, created_at
+         FROM credit_events
+         WHERE contributor_id = ? AND event_type = ? AND pr_number = ?
+         ORDER BY created_at DESC
+         LIMIT 1"
+    )
+    .bind(contributor_id)
+    .bind(event_type)
+    .bind(pr_number)
+    .fetch_optional(pool)
+    .await?
+    .map(|raw| raw.into());
+
+    Ok(event)
+}
+
+/// Check whether a maintainer exempted a PR from the credit gate
+///
+/// Exemptions are `exemption` credit events linked to the PR, recorded by
+/// `/credit approve` for the PR's author.
+pub async fn is_pr_exempt(
+    pool: &Pool<Any>,
+    repo_owner: &str,
+    repo_name: &str,
+    pr_number: i64,
+) -> DbResult<bool> {
+    let count: (i64,) = sqlx::query_as(
+        "SELECT COUNT(*)
+         FROM credit_events e
+         JOIN contributors c ON c.id = e.contributor_id
+         WHERE c.repo_owner = ? AND c.repo_name = ? AND e.event_type = 'exemption' AND e.pr_number = ?",
+    )
+    .bind(repo_owner)
+    .bind(repo_name)
+    .bind(pr_number)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(count.0 > 0)
+}
+
 /// List credit events by contributor with pagination
 pub async fn list_events_by_contributor(
     pool: &Pool<Any>,
@@ -57,7 +478,7 @@ pub async fn list_events_by_contributor(
     offset: i64,
 ) -> DbResult<Vec<CreditEvent>> {
     let events = sqlx::query_as::<_, CreditEventRaw>(
-        "SELECT id, contributor_id, event_type, delta, credit_before, credit_after, llm_evaluation, maintainer_override, created_at
+        "SELECT id, contributor_id, event_type, delta, credit_before, credit_after, llm_evaluation, maintainer_override, reason, pr_number, source_id, created_at
          FROM credit_events
          WHERE contributor_id = ?
          ORDER BY created_at DESC
@@ -75,6 +496,83 @@ pub async fn list_events_by_contributor(
     Ok(events)
 }
 
+/// List a contributor's credit events earned on a piece of GitHub content, oldest first
+///
+/// Their deltas add up to the credit the content currently accounts for.
+pub async fn list_events_by_source(
+    pool: &Pool<Any>,
+    contributor_id: i64,
+    source_id: &str,
+) -> DbResult<Vec<CreditEvent>> {
+    let events = sqlx::query_as::<_, CreditEventRaw>(
+        "SELECT id, contributor_id, event_type, delta, credit_before, credit_after, llm_evaluation, maintainer_override, reason, pr_number, source_id, created_at
+         FROM credit_events
+         WHERE contributor_id = ? AND source_id = ?
+         ORDER BY created_at ASC, id ASC"
+    )
+    .bind(contributor_id)
+    .bind(source_id)
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|raw| raw.into())
+    .collect();
+
+    Ok(events)
+}
+
+/// List a contributor's credit events created after `since`, newest first
+pub async fn list_events_since(
+    pool: &Pool<Any>,
+    contributor_id: i64,
+    since: DateTime<Utc>,
+) -> DbResult<Vec<CreditEvent>> {
+    let events = sqlx::query_as::<_, CreditEventRaw>(
+        "SELECT id, contributor_id, event_type, delta, credit_before, credit_after, llm_evaluation, maintainer_override, reason, pr_number, source_id, created_at
+         FROM credit_events
+         WHERE contributor_id = ? AND created_at > ?
+         ORDER BY created_at DESC"
+    )
+    .bind(contributor_id)
+    .bind(since.to_rfc3339())
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|raw| raw.into())
+    .collect();
+
+    Ok(events)
+}
+
+/// List a contributor's credit events created after `since` with their
+/// original event types, newest first
+///
+/// Resolves what each edit adjustment adjusted in the same query.
+pub async fn list_events_with_origin_since(
+    pool: &Pool<Any>,
+    contributor_id: i64,
+    since: DateTime<Utc>,
+) -> DbResult<Vec<CreditEventWithOrigin>> {
+    let events = sqlx::query_as::<_, CreditEventWithOriginRaw>(
+        "SELECT ce.id, ce.contributor_id, ce.event_type, ce.delta, ce.credit_before, ce.credit_after, ce.llm_evaluation, ce.maintainer_override, ce.reason, ce.pr_number, ce.source_id, ce.created_at,
+                (SELECT o.event_type FROM credit_events o
+                 WHERE o.contributor_id = ce.contributor_id AND o.source_id = ce.source_id
+                 ORDER BY o.id LIMIT 1) AS original_event_type
+         FROM credit_events ce
+         WHERE ce.contributor_id = ? AND ce.created_at > ?
+         ORDER BY ce.created_at DESC"
+    )
+    .bind(contributor_id)
+    .bind(since.to_rfc3339())
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|raw| raw.into())
+    .collect();
+
+    Ok(events)
+}
+
 /// Count total events for a contributor
 pub async fn count_events_by_contributor(pool: &Pool<Any>, contributor_id: i64) -> DbResult<i64> {
     let count: (i64,) =
@@ -99,7 +597,7 @@ pub async fn list_events_by_repo(
     // Build dynamic query based on filters
     let mut query = String::from(
         "SELECT ce.id, ce.contributor_id, ce.event_type, ce.delta, ce.credit_before, ce.credit_after,
-                ce.llm_evaluation, ce.maintainer_override, ce.created_at
+                ce.llm_evaluation, ce.maintainer_override, ce.reason, ce.pr_number, ce.source_id, ce.created_at
          FROM credit_events ce
          JOIN contributors c ON ce.contributor_id = c.id
          WHERE c.repo_owner = ? AND c.repo_name = ?"
@@ -181,16 +679,21 @@ pub async fn count_events_by_repo(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::contributors::create_contributor;
+    use crate::contributors::{create_contributor, get_contributor_by_id};
+    use crate::migrations::run_migrations;
     use sqlx::any::AnyPoolOptions;
 
     async fn setup_test_db() -> Pool<Any> {
+        connect_test_db("sqlite::memory:", 1).await
+    }
+
+    async fn connect_test_db(url: &str, max_connections: u32) -> Pool<Any> {
         // Install the SQLite driver for Any
         sqlx::any::install_default_drivers();
 
         let pool = AnyPoolOptions::new()
-            .max_connections(1)
-            .connect("sqlite::memory:")
+            .max_connections(max_connections)
+            .connect(url)
             .await
             .expect("Failed to create test database pool");
 
@@ -201,8 +704,7 @@ mod tests {
             .expect("Failed to enable foreign keys");
 
         // Run migrations
-        sqlx::query(include_str!("../migrations/001_initial.sql"))
-            .execute(&pool)
+        run_migrations(&pool)
             .await
             .expect("Failed to run migrations");
 
@@ -242,6 +744,97 @@ mod tests {
         assert_eq!(event.maintainer_override, None);
     }
 
+    #[tokio::test]
+    async fn test_list_events_since() {
+        let pool = setup_test_db().await;
+
+        let contributor = create_contributor(&pool, 12345, "owner", "repo", 100)
+            .await
+            .expect("Failed to create contributor");
+
+        insert_credit_event(&pool, contributor.id, "comment", 1, 100, 101, None, None)
+            .await
+            .expect("Failed to insert event");
+
+        let recent = list_events_since(
+            &pool,
+            contributor.id,
+            Utc::now() - chrono::Duration::hours(1),
+        )
+        .await
+        .expect("Failed to list events");
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].event_type, "comment");
+
+        let none = list_events_since(
+            &pool,
+            contributor.id,
+            Utc::now() + chrono::Duration::hours(1),
+        )
+        .await
+        .expect("Failed to list events");
+        assert!(none.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_events_with_origin_since() {
+        let pool = setup_test_db().await;
+
+        let contributor = create_contributor(&pool, 12345, "owner", "repo", 100)
+            .await
+            .expect("Failed to create contributor");
+
+        apply_credit_delta(
+            &pool,
+            contributor.id,
+            3,
+            NewCreditEvent::new("comment").with_source_id(Some("issue_comment:500".to_string())),
+            None,
+        )
+        .await
+        .unwrap();
+        apply_credit_delta(
+            &pool,
+            contributor.id,
+            -2,
+            NewCreditEvent::new("edit_adjustment")
+                .with_source_id(Some("issue_comment:500".to_string())),
+            None,
+        )
+        .await
+        .unwrap();
+        apply_credit_delta(
+            &pool,
+            contributor.id,
+            5,
+            NewCreditEvent::new("review"),
+            None,
+        )
+        .await
+        .unwrap();
+
+        let events = list_events_with_origin_since(
+            &pool,
+            contributor.id,
+            Utc::now() - chrono::Duration::hours(1),
+        )
+        .await
+        .expect("Failed to list events");
+        let origins: Vec<_> = events
+            .iter()
+            .map(|e| {
+                (
+                    e.event.event_type.as_str(),
+                    e.original_event_type.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(origins.len(), 3);
+        assert!(origins.contains(&("edit_adjustment", Some("comment"))));
+        assert!(origins.contains(&("comment", Some("comment"))));
+        assert!(origins.contains(&("review", None)));
+    }
+
     #[tokio::test]
     async fn test_list_events_by_contributor() {
         let pool = setup_test_db().await;
@@ -374,4 +967,529 @@ mod tests {
 
         assert_eq!(events.len(), 0);
     }
+
+    #[tokio::test]
+    async fn test_get_pr_credit_event() {
+        let pool = setup_test_db().await;
+
+        let contributor = create_contributor(&pool, 12345, "owner", "repo", 100)
+            .await
+            .expect("Failed to create contributor");
+
+        insert_credit_event_with_pr(
+            &pool,
+            contributor.id,
+            "pr_opened",
+            15,
+            100,
+            115,
+            Some(r#"{"classification": "high"}"#.to_string()),
+            None,
+            Some(42),
+        )
+        .await
+        .expect("Failed to insert event");
+
+        let event = get_pr_credit_event(&pool, contributor.id, "pr_opened", 42)
+            .await
+            .expect("Failed to get event")
+            .expect("Event not found");
+        assert_eq!(event.pr_number, Some(42));
+        assert_eq!(event.delta, 15);
+
+        // Different PR number has no event
+        let missing = get_pr_credit_event(&pool, contributor.id, "pr_opened", 43)
+            .await
+            .expect("Failed to get event");
+        assert!(missing.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_is_pr_exempt() {
+        let pool = setup_test_db().await;
+
+        let contributor = create_contributor(&pool, 12345, "owner", "repo", 100)
+            .await
+            .expect("Failed to create contributor");
+        create_contributor(&pool, 12345, "owner", "other", 100)
+            .await
+            .expect("Failed to create contributor");
+
+        assert!(!is_pr_exempt(&pool, "owner", "repo", 42).await.unwrap());
+
+        apply_credit_delta(
+            &pool,
+            contributor.id,
+            0,
+            NewCreditEvent::new("exemption").with_pr_number(Some(42)),
+            None,
+        )
+        .await
+        .expect("Failed to record exemption");
+
+        assert!(is_pr_exempt(&pool, "owner", "repo", 42).await.unwrap());
+        // Scoped to the PR and the repository
+        assert!(!is_pr_exempt(&pool, "owner", "repo", 43).await.unwrap());
+        assert!(!is_pr_exempt(&pool, "owner", "other", 42).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_pr_merged_event_is_unique_per_pr() {
+        let pool = setup_test_db().await;
+
+        let contributor = create_contributor(&pool, 12345, "owner", "repo", 100)
+            .await
+            .expect("Failed to create contributor");
+
+        insert_credit_event_with_pr(
+            &pool,
+            contributor.id,
+            "pr_merged",
+            20,
+            100,
+            120,
+            None,
+            None,
+            Some(42),
+        )
+        .await
+        .expect("Failed to insert event");
+
+        // Second merge event for the same PR is rejected
+        let result = insert_credit_event_with_pr(
+            &pool,
+            contributor.id,
+            "pr_merged",
+            20,
+            120,
+            140,
+            None,
+            None,
+            Some(42),
+        )
+        .await;
+        assert!(matches!(
+            result,
+            Err(DbError::DuplicateCreditEvent(_, _, 42))
+        ));
+
+        // Other event types for the same PR are unaffected
+        insert_credit_event_with_pr(
+            &pool,
+            contributor.id,
+            "comment",
+            1,
+            120,
+            121,
+            None,
+            None,
+            Some(42),
+        )
+        .await
+        .expect("Failed to insert comment event");
+        insert_credit_event_with_pr(
+            &pool,
+            contributor.id,
+            "comment",
+            1,
+            121,
+            122,
+            None,
+            None,
+            Some(42),
+        )
+        .await
+        .expect("Failed to insert comment event");
+
+        let count = count_events_by_contributor(&pool, contributor.id)
+            .await
+            .expect("Failed to count events");
+        assert_eq!(count, 3);
+    }
+
+    #[tokio::test]
+    async fn test_apply_credit_delta_updates_score_and_logs_event() {
+        let pool = setup_test_db().await;
+        let contributor = create_contributor(&pool, 12345, "owner", "repo", 100)
+            .await
+            .expect("Failed to create contributor");
+
+        let applied = apply_credit_delta(
+            &pool,
+            contributor.id,
+            15,
+            NewCreditEvent::new("pr_opened")
+                .with_llm_evaluation(r#"{"quality": "high"}"#.to_string())
+                .with_pr_number(Some(7)),
+            None,
+        )
+        .await
+        .expect("Failed to apply credit");
+        assert_eq!(applied.event.credit_before, 100);
+        assert_eq!(applied.event.credit_after, 115);
+        assert_eq!(applied.event.pr_number, Some(7));
+        assert!(!applied.auto_blacklisted);
+
+        // Scores are clamped at zero
+        let applied = apply_credit_delta(
+            &pool,
+            contributor.id,
+            -500,
+            NewCreditEvent::new("manual_adjustment"),
+            None,
+        )
+        .await
+        .expect("Failed to apply credit");
+        assert_eq!(applied.event.credit_before, 115);
+        assert_eq!(applied.event.credit_after, 0);
+
+        let contributor = get_contributor_by_id(&pool, contributor.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(contributor.credit_score, 0);
+        assert_eq!(
+            count_events_by_contributor(&pool, contributor.id)
+                .await
+                .unwrap(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_apply_credit_delta_duplicate_pr_event_applies_nothing() {
+        let pool = setup_test_db().await;
+        let contributor = create_contributor(&pool, 12345, "owner", "repo", 100)
+            .await
+            .expect("Failed to create contributor");
+
+        let merged = || NewCreditEvent::new("pr_merged").with_pr_number(Some(42));
+        apply_credit_delta(&pool, contributor.id, 20, merged(), None)
+            .await
+            .expect("Failed to apply credit");
+
+        let result = apply_credit_delta(&pool, contributor.id, 20, merged(), None).await;
+        assert!(matches!(
+            result,
+            Err(DbError::DuplicateCreditEvent(_, _, 42))
+        ));
+
+        // The rejected event rolled back its score change
+        let contributor = get_contributor_by_id(&pool, contributor.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(contributor.credit_score, 120);
+    }
+
+    #[tokio::test]
+    async fn test_list_events_by_source() {
+        let pool = setup_test_db().await;
+        let contributor = create_contributor(&pool, 12345, "owner", "repo", 100)
+            .await
+            .expect("Failed to create contributor");
+
+        let source = || Some("issue_comment:500".to_string());
+        for (event_type, delta, source_id) in [
+            ("comment", 3, source()),
+            ("comment", 1, Some("issue_comment:501".to_string())),
+            ("edit_adjustment", -13, source()),
+            ("manual_adjustment", 5, None),
+        ] {
+            apply_credit_delta(
+                &pool,
+                contributor.id,
+                delta,
+                NewCreditEvent::new(event_type).with_source_id(source_id),
+                None,
+            )
+            .await
+            .expect("Failed to apply credit");
+        }
+
+        let events = list_events_by_source(&pool, contributor.id, "issue_comment:500")
+            .await
+            .unwrap();
+        assert_eq!(
+            events
+                .iter()
+                .map(|e| (e.event_type.as_str(), e.delta))
+                .collect::<Vec<_>>(),
+            vec![("comment", 3), ("edit_adjustment", -13)]
+        );
+        assert_eq!(events[0].source_id.as_deref(), Some("issue_comment:500"));
+    }
+
+    #[tokio::test]
+    async fn test_set_blacklisted_with_event() {
+        let pool = setup_test_db().await;
+        let contributor = create_contributor(&pool, 12345, "owner", "repo", 80)
+            .await
+            .expect("Failed to create contributor");
+
+        let event = set_blacklisted_with_event(
+            &pool,
+            contributor.id,
+            true,
+            NewCreditEvent::new("blacklist_added")
+                .with_maintainer_override("Manually blacklisted by maintainer".to_string()),
+        )
+        .await
+        .expect("Failed to blacklist");
+        assert_eq!(event.delta, 0);
+        assert_eq!(event.credit_before, 80);
+        assert_eq!(event.credit_after, 80);
+
+        let contributor = get_contributor_by_id(&pool, contributor.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(contributor.is_blacklisted);
+        let events = list_events_by_contributor(&pool, contributor.id, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, "blacklist_added");
+
+        // Missing contributors write nothing
+        let result = set_blacklisted_with_event(
+            &pool,
+            9999,
+            false,
+            NewCreditEvent::new("blacklist_removed"),
+        )
+        .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_source_credited_once() {
+        let pool = setup_test_db().await;
+        let contributor = create_contributor(&pool, 12345, "owner", "repo", 100)
+            .await
+            .expect("Failed to create contributor");
+        let comment = || {
+            NewCreditEvent::new("comment")
+                .with_pr_number(Some(7))
+                .with_source_id(Some("issue_comment:500".to_string()))
+        };
+
+        apply_credit_delta(&pool, contributor.id, 3, comment(), None)
+            .await
+            .expect("Failed to apply credit");
+
+        // A re-run of the same evaluation is rejected and rolled back
+        let result = apply_credit_delta(&pool, contributor.id, 3, comment(), None).await;
+        assert!(matches!(result, Err(DbError::DuplicateSourceCredit(_, _))));
+        let contributor = get_contributor_by_id(&pool, contributor.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(contributor.credit_score, 103);
+
+        // Adjustments of the same content are separate events
+        apply_credit_delta(
+            &pool,
+            contributor.id,
+            -3,
+            NewCreditEvent::new("reversal")
+                .with_pr_number(Some(7))
+                .with_source_id(Some("issue_comment:500".to_string())),
+            None,
+        )
+        .await
+        .expect("Failed to apply reversal");
+    }
+
+    #[tokio::test]
+    async fn test_apply_credit_delta_auto_blacklists_on_crossing() {
+        let pool = setup_test_db().await;
+        let contributor = create_contributor(&pool, 12345, "owner", "repo", 20)
+            .await
+            .expect("Failed to create contributor");
+
+        let applied = apply_credit_delta(
+            &pool,
+            contributor.id,
+            -25,
+            NewCreditEvent::new("pr_opened"),
+            Some(0),
+        )
+        .await
+        .expect("Failed to apply credit");
+        assert!(applied.auto_blacklisted);
+
+        let contributor = get_contributor_by_id(&pool, contributor.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(contributor.is_blacklisted);
+
+        let events = list_events_by_contributor(&pool, contributor.id, 10, 0)
+            .await
+            .unwrap();
+        let blacklist_event = events
+            .iter()
+            .find(|e| e.event_type == "auto_blacklist")
+            .unwrap();
+        assert!(blacklist_event.maintainer_override.is_none());
+        assert!(
+            blacklist_event
+                .reason
+                .as_deref()
+                .unwrap()
+                .starts_with("Auto-blacklisted")
+        );
+
+        // Already below the threshold: no second blacklist event
+        let applied = apply_credit_delta(
+            &pool,
+            contributor.id,
+            -5,
+            NewCreditEvent::new("comment"),
+            Some(0),
+        )
+        .await
+        .expect("Failed to apply credit");
+        assert!(!applied.auto_blacklisted);
+    }
+
+    #[tokio::test]
+    async fn test_apply_credit_delta_concurrent_updates_are_not_lost() {
+        // A file database so the pool really has several connections racing
+        let path = std::env::temp_dir().join(format!(
+            "meritocrab-credit-{}-{}.db",
+            std::process::id(),
+            Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        let url = format!("sqlite://{}?mode=rwc", path.display());
+        let pool = connect_test_db(&url, 8).await;
+
+        let contributor = create_contributor(&pool, 12345, "owner", "repo", 100)
+            .await
+            .expect("Failed to create contributor");
+
+        let mut tasks = Vec::new();
+        for i in 0..50 {
+            let pool = pool.clone();
+            let delta = if i % 2 == 0 { 3 } else { -1 };
+            tasks.push(tokio::spawn(async move {
+                apply_credit_delta(
+                    &pool,
+                    contributor.id,
+                    delta,
+                    NewCreditEvent::new("comment"),
+                    None,
+                )
+                .await
+            }));
+        }
+        for task in tasks {
+            task.await.unwrap().expect("Failed to apply credit");
+        }
+
+        // 25 * 3 - 25 * 1 = +50
+        let contributor = get_contributor_by_id(&pool, contributor.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(contributor.credit_score, 150);
+
+        // The audit log chains without gaps: each event starts where another ended
+        let events = list_events_by_contributor(&pool, contributor.id, 100, 0)
+            .await
+            .unwrap();
+        assert_eq!(events.len(), 50);
+        // (except the first, which starts at 100, and the last, which ends at 150)
+        let mut befores: Vec<i32> = events.iter().map(|e| e.credit_before).collect();
+        let mut afters: Vec<i32> = events.iter().map(|e| e.credit_after).collect();
+        befores.push(150);
+        afters.push(100);
+        befores.sort();
+        afters.sort();
+        assert_eq!(befores, afters);
+
+        pool.close().await;
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_set_credit_score_uses_current_score() {
+        let pool = setup_test_db().await;
+
+        let stale = create_contributor(&pool, 12345, "owner", "repo", 100)
+            .await
+            .expect("Failed to create contributor");
+        // A change lands after the caller read the contributor
+        apply_credit_delta(&pool, stale.id, 15, NewCreditEvent::new("pr_opened"), None)
+            .await
+            .unwrap();
+
+        let applied = set_credit_score(
+            &pool,
+            stale.id,
+            120,
+            NewCreditEvent::new("manual_adjustment"),
+            None,
+        )
+        .await
+        .expect("Failed to set credit");
+
+        assert_eq!(applied.event.credit_before, 115);
+        assert_eq!(applied.event.delta, 5);
+        assert_eq!(applied.event.credit_after, 120);
+        let contributor = get_contributor_by_id(&pool, stale.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(contributor.credit_score, 120);
+    }
+
+    #[tokio::test]
+    async fn test_apply_credit_change_skips_zero_delta() {
+        let pool = setup_test_db().await;
+
+        let contributor = create_contributor(&pool, 12345, "owner", "repo", 100)
+            .await
+            .expect("Failed to create contributor");
+
+        let applied = apply_credit_change(
+            &pool,
+            contributor.id,
+            |score| (100 - score).min(0),
+            NewCreditEvent::new("decay"),
+            None,
+        )
+        .await
+        .expect("Failed to apply credit");
+        assert!(applied.is_none());
+        assert_eq!(
+            count_events_by_contributor(&pool, contributor.id)
+                .await
+                .unwrap(),
+            0
+        );
+
+        apply_credit_delta(
+            &pool,
+            contributor.id,
+            40,
+            NewCreditEvent::new("pr_opened"),
+            None,
+        )
+        .await
+        .unwrap();
+        let applied = apply_credit_change(
+            &pool,
+            contributor.id,
+            |score| (100 - score).min(0),
+            NewCreditEvent::new("decay"),
+            None,
+        )
+        .await
+        .expect("Failed to apply credit")
+        .expect("Nothing applied");
+        assert_eq!(applied.event.delta, -40);
+        assert_eq!(applied.event.credit_after, 100);
+    }
 }
@@ -20,6 +20,21 @@ pub enum DbError {
     #[error("Repo config not found: {0}/{1}")]
     RepoConfigNotFound(String, String),
 
+    #[error("Duplicate credit event: contributor_id={0}, event_type={1}, pr_number={2}")]
+    DuplicateCreditEvent(i64, String, i64),
+
+    #[error("Content already credited: contributor_id={0}, source_id={1}")]
+    DuplicateSourceCredit(i64, String),
+
+    #[error("Concurrent credit update for contributor id={0}")]
+    ConcurrentCreditUpdate(i64),
+
+    #[error("Migration {0} ({1}) failed: {2}")]
+    MigrationFailed(i64, &'static str, #[source] sqlx::Error),
+
+    #[error("Unsupported database backend: {0}")]
+    UnsupportedBackend(String),
+
     #[error("Invalid evaluation status: {0}")]
     InvalidStatus(String),
 }
@@ -25,14 +25,44 @@ pub async fn insert_evaluation(
     llm_classification: String,
     confidence: f64,
     proposed_delta: i32,
+    pr_number: Option<i64>,
+) -> DbResult<PendingEvaluation> {
+    insert_evaluation_with_source(
+        pool,
+        id,
+        contributor_id,
+        repo_owner,
+        repo_name,
+        llm_classification,
+        confidence,
+        proposed_delta,
+        pr_number,
+        None,
+    )
+    .await
+}
+
+/// Insert a new pending evaluation linked to the GitHub content it is for
+#[allow(clippy::too_many_arguments)]
+pub async fn insert_evaluation_with_source(
+    pool: &Pool<Any>,
+    id: String,
+    contributor_id: i64,
+    repo_owner: &str,
+    repo_name: &str,
+    llm_classification: String,
+    confidence: f64,
+    proposed_delta: i32,
+    pr_number: Option<i64>,
+    source_id: Option<String>,
 ) -> DbResult<PendingEvaluation> {
     let now = Utc::now();
     let now_str = now.to_rfc3339();
     let status = status_to_string(&EvaluationStatus::Pending);
 
     sqlx::query(
-        "INSERT INTO pending_evaluations (id, contributor_id, repo_owner, repo_name, llm_classification, confidence, proposed_delta, status, created_at, updated_at)
-         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+        "INSERT INTO pending_evaluations (id, contributor_id, repo_owner, repo_name, llm_classification, confidence, proposed_delta, status, pr_number, source_id, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
     )
     .bind(&id)
     .bind(contributor_id)
@@ -42,6 +72,8 @@ pub async fn insert_evaluation(
     .bind(confidence)
     .bind(proposed_delta)
     .bind(status)
+    .bind(pr_number)
+    .bind(&source_id)
     .bind(&now_str)
     .bind(&now_str)
     .execute(pool)
@@ -58,6 +90,8 @@ pub async fn insert_evaluation(
         status: status.to_string(),
         maintainer_note: None,
         final_delta: None,
+        pr_number,
+        source_id,
         created_at: now,
         updated_at: now,
     })
@@ -66,7 +100,7 @@ pub async fn insert_evaluation(
 /// Get an evaluation by ID
 pub async fn get_evaluation(pool: &Pool<Any>, id: &str) -> DbResult<Option<PendingEvaluation>> {
     let eval = sqlx::query_as::<_, PendingEvaluationRaw>(
-        "SELECT id, contributor_id, repo_owner, repo_name, llm_classification, confidence, proposed_delta, status, maintainer_note, final_delta, created_at, updated_at
+        "SELECT id, contributor_id, repo_owner, repo_name, llm_classification, confidence, proposed_delta, status, maintainer_note, final_delta, pr_number, source_id, created_at, updated_at
          FROM pending_evaluations
          WHERE id = ?"
     )
@@ -78,6 +112,33 @@ pub async fn get_evaluation(pool: &Pool<Any>, id: &str) -> DbResult<Option<Pendi
     Ok(eval)
 }
 
+/// Get the most recent evaluation recorded for a contributor's PR
+///
+/// Only evaluations of the PR itself (`source_id` `pull_request:N`) match;
+/// evaluations of the author's comments and reviews on the PR share its
+/// `pr_number` but are not considered.
+pub async fn get_evaluation_by_pr(
+    pool: &Pool<Any>,
+    contributor_id: i64,
+    pr_number: i64,
+) -> DbResult<Option<PendingEvaluation>> {
+    let eval = sqlx::query_as::<_, PendingEvaluationRaw>(
+        "SELECT id, contributor_id, repo_owner, repo_name, llm_classification, confidence, proposed_delta, status, maintainer_note, final_delta, pr_number, source_id, created_at, updated_at
+         FROM pending_evaluations
+         WHERE contributor_id = ? AND pr_number = ? AND source_id = ?
+         ORDER BY created_at DESC
+         LIMIT 1"
+    )
+    .bind(contributor_id)
+    .bind(pr_number)
+    .bind(format!("pull_request:{}", pr_number))
+    .fetch_optional(pool)
+    .await?
+    .map(|raw| raw.into());
+
+    Ok(eval)
+}
+
 /// List evaluations by repo and status with pagination
 pub async fn list_evaluations_by_repo_and_status(
     pool: &Pool<Any>,
@@ -90,7 +151,7 @@ pub async fn list_evaluations_by_repo_and_status(
     let status_str = status_to_string(status);
 
     let evals = sqlx::query_as::<_, PendingEvaluationRaw>(
-        "SELECT id, contributor_id, repo_owner, repo_name, llm_classification, confidence, proposed_delta, status, maintainer_note, final_delta, created_at, updated_at
+        "SELECT id, contributor_id, repo_owner, repo_name, llm_classification, confidence, proposed_delta, status, maintainer_note, final_delta, pr_number, source_id, created_at, updated_at
          FROM pending_evaluations
          WHERE repo_owner = ? AND repo_name = ? AND status = ?
          ORDER BY created_at DESC
@@ -110,6 +171,42 @@ pub async fn list_evaluations_by_repo_and_status(
     Ok(evals)
 }
 
+/// Count evaluations by repo and status
+pub async fn count_evaluations_by_repo_and_status(
+    pool: &Pool<Any>,
+    repo_owner: &str,
+    repo_name: &str,
+    status: &EvaluationStatus,
+) -> DbResult<i64> {
+    let count: (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM pending_evaluations WHERE repo_owner = ? AND repo_name = ? AND status = ?",
+    )
+    .bind(repo_owner)
+    .bind(repo_name)
+    .bind(status_to_string(status))
+    .fetch_one(pool)
+    .await?;
+
+    Ok(count.0)
+}
+
+/// Count a contributor's evaluations of one piece of GitHub content
+pub async fn count_evaluations_by_source(
+    pool: &Pool<Any>,
+    contributor_id: i64,
+    source_id: &str,
+) -> DbResult<i64> {
+    let count: (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM pending_evaluations WHERE contributor_id = ? AND source_id = ?",
+    )
+    .bind(contributor_id)
+    .bind(source_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(count.0)
+}
+
 /// Update evaluation status to approved
 pub async fn approve_evaluation(
     pool: &Pool<Any>,
@@ -204,6 +301,7 @@ pub async fn auto_apply_evaluation(pool: &Pool<Any>, id: &str) -> DbResult<()> {
 mod tests {
     use super::*;
     use crate::contributors::create_contributor;
+    use crate::migrations::run_migrations;
     use sqlx::any::AnyPoolOptions;
 
     async fn setup_test_db() -> Pool<Any> {
@@ -223,8 +321,7 @@ mod tests {
             .expect("Failed to enable foreign keys");
 
         // Run migrations
-        sqlx::query(include_str!("../migrations/001_initial.sql"))
-            .execute(&pool)
+        run_migrations(&pool)
             .await
             .expect("Failed to run migrations");
 
@@ -248,6 +345,7 @@ mod tests {
             "high_quality".to_string(),
             0.95,
             15,
+            None,
         )
         .await
         .expect("Failed to insert evaluation");
@@ -279,6 +377,7 @@ mod tests {
             "high_quality".to_string(),
             0.95,
             15,
+            None,
         )
         .await
         .expect("Failed to insert evaluation");
@@ -315,6 +414,7 @@ mod tests {
             "high_quality".to_string(),
             0.95,
             15,
+            None,
         )
         .await
         .expect("Failed to insert evaluation");
@@ -328,6 +428,7 @@ mod tests {
             "acceptable".to_string(),
             0.75,
             5,
+            None,
         )
         .await
         .expect("Failed to insert evaluation");
@@ -345,6 +446,26 @@ mod tests {
         .expect("Failed to list evaluations");
 
         assert_eq!(evals.len(), 2);
+
+        let pending = count_evaluations_by_repo_and_status(
+            &pool,
+            "owner",
+            "repo",
+            &EvaluationStatus::Pending,
+        )
+        .await
+        .expect("Failed to count evaluations");
+        assert_eq!(pending, 2);
+
+        let approved = count_evaluations_by_repo_and_status(
+            &pool,
+            "owner",
+            "repo",
+            &EvaluationStatus::Approved,
+        )
+        .await
+        .expect("Failed to count evaluations");
+        assert_eq!(approved, 0);
     }
 
     #[tokio::test]
@@ -364,6 +485,7 @@ mod tests {
             "high_quality".to_string(),
             0.95,
             15,
+            None,
         )
         .await
         .expect("Failed to insert evaluation");
@@ -399,6 +521,7 @@ mod tests {
             "acceptable".to_string(),
             0.75,
             5,
+            None,
         )
         .await
         .expect("Failed to insert evaluation");
@@ -437,6 +560,7 @@ mod tests {
             "high_quality".to_string(),
             0.95,
             15,
+            None,
         )
         .await
         .expect("Failed to insert evaluation");
@@ -453,4 +577,63 @@ mod tests {
         assert_eq!(eval.status, "auto_applied");
         assert_eq!(eval.final_delta, Some(15));
     }
+
+    #[tokio::test]
+    async fn test_get_evaluation_by_pr() {
+        let pool = setup_test_db().await;
+
+        let contributor = create_contributor(&pool, 12345, "owner", "repo", 100)
+            .await
+            .expect("Failed to create contributor");
+
+        insert_evaluation_with_source(
+            &pool,
+            "eval-pr-7".to_string(),
+            contributor.id,
+            "owner",
+            "repo",
+            "Acceptable".to_string(),
+            0.6,
+            5,
+            Some(7),
+            Some("pull_request:7".to_string()),
+        )
+        .await
+        .expect("Failed to insert evaluation");
+
+        // The author's later comment on the same PR is not the PR's evaluation
+        insert_evaluation_with_source(
+            &pool,
+            "eval-comment-7".to_string(),
+            contributor.id,
+            "owner",
+            "repo",
+            "Spam".to_string(),
+            0.6,
+            -10,
+            Some(7),
+            Some("issue_comment:99".to_string()),
+        )
+        .await
+        .expect("Failed to insert evaluation");
+
+        let eval = get_evaluation_by_pr(&pool, contributor.id, 7)
+            .await
+            .expect("Failed to get evaluation")
+            .expect("Evaluation not found");
+        assert_eq!(eval.id, "eval-pr-7");
+        assert_eq!(eval.pr_number, Some(7));
+
+        assert_eq!(
+            count_evaluations_by_source(&pool, contributor.id, "issue_comment:99")
+                .await
+                .unwrap(),
+            1
+        );
+
+        let missing = get_evaluation_by_pr(&pool, contributor.id, 8)
+            .await
+            .expect("Failed to get evaluation");
+        assert!(missing.is_none());
+    }
 }
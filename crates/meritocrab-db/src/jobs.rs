@@ -0,0 +1,537 @@
+use crate::error::DbResult;
+use crate::models::{Job, JobRaw};
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{Any, Pool};
+
+const JOB_COLUMNS: &str = "id, job_type, payload, repo_owner, repo_name, status, attempts, run_at, locked_until, last_error, created_at, updated_at";
+
+/// Lifecycle status of a background job
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobStatus {
+    /// Waiting for `run_at` to pass
+    Pending,
+    /// Claimed by a worker holding a lease
+    Running,
+    /// Finished successfully
+    Completed,
+    /// Retries exhausted or failed permanently (dead-letter)
+    Dead,
+}
+
+impl JobStatus {
+    /// String representation stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Pending => "pending",
+            JobStatus::Running => "running",
+            JobStatus::Completed => "completed",
+            JobStatus::Dead => "dead",
+        }
+    }
+}
+
+/// Enqueue a new job to run at `run_at`
+///
+/// Returns the ID of the new job.
+pub async fn enqueue_job(
+    pool: &Pool<Any>,
+    job_type: &str,
+    payload: &str,
+    repo_owner: &str,
+    repo_name: &str,
+    run_at: DateTime<Utc>,
+) -> DbResult<i64> {
+    let now_str = Utc::now().to_rfc3339();
+
+    let (id,): (i64,) = sqlx::query_as(
+        "INSERT INTO jobs (job_type, payload, repo_owner, repo_name, status, attempts, run_at, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, 0, ?, ?, ?)
+         RETURNING id",
+    )
+    .bind(job_type)
+    .bind(payload)
+    .bind(repo_owner)
+    .bind(repo_name)
+    .bind(JobStatus::Pending.as_str())
+    .bind(run_at.to_rfc3339())
+    .bind(&now_str)
+    .bind(&now_str)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(id)
+}
+
+/// Enqueue a job unless one with the same `dedupe_key` was ever enqueued
+///
+/// Returns the ID of the new job, or `None` if the key is taken. Finished
+/// jobs keep their key, so the work is done at most once.
+pub async fn enqueue_unique_job(
+    pool: &Pool<Any>,
+    job_type: &str,
+    payload: &str,
+    repo_owner: &str,
+    repo_name: &str,
+    run_at: DateTime<Utc>,
+    dedupe_key: &str,
+) -> DbResult<Option<i64>> {
+    let now_str = Utc::now().to_rfc3339();
+
+    let id: Option<(i64,)> = sqlx::query_as(
+        "INSERT INTO jobs (job_type, payload, repo_owner, repo_name, status, attempts, run_at, created_at, updated_at, dedupe_key)
+         VALUES (?, ?, ?, ?, ?, 0, ?, ?, ?, ?)
+         ON CONFLICT (dedupe_key) DO NOTHING
+         RETURNING id",
+    )
+    .bind(job_type)
+    .bind(payload)
+    .bind(repo_owner)
+    .bind(repo_name)
+    .bind(JobStatus::Pending.as_str())
+    .bind(run_at.to_rfc3339())
+    .bind(&now_str)
+    .bind(&now_str)
+    .bind(dedupe_key)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(id.map(|(id,)| id))
+}
+
+/// Get a job by ID
+pub async fn get_job(pool: &Pool<Any>, id: i64) -> DbResult<Option<Job>> {
+    let job =
+        sqlx::query_as::<_, JobRaw>(&format!("SELECT {} FROM jobs WHERE id = ?", JOB_COLUMNS))
+            .bind(id)
+            .fetch_optional(pool)
+            .await?
+            .map(|raw| raw.into());
+
+    Ok(job)
+}
+
+/// Claim the next due job and lease it for `lease`
+///
+/// A job is due when it is pending and its `run_at` has passed, or when it is
+/// running but its lease has expired (the worker holding it died). The claim
+/// is a conditional update, so two workers can never hold the same lease.
+/// Claiming increments the job's attempt counter, which identifies the lease
+/// in later transitions.
+pub async fn claim_next_job(pool: &Pool<Any>, lease: Duration) -> DbResult<Option<Job>> {
+    loop {
+        let now_str = Utc::now().to_rfc3339();
+
+        let candidate: Option<(i64,)> = sqlx::query_as(
+            "SELECT id FROM jobs
+             WHERE (status = 'pending' AND run_at <= ?)
+                OR (status = 'running' AND locked_until < ?)
+             ORDER BY run_at ASC, id ASC
+             LIMIT 1",
+        )
+        .bind(&now_str)
+        .bind(&now_str)
+        .fetch_optional(pool)
+        .await?;
+
+        let Some((id,)) = candidate else {
+            return Ok(None);
+        };
+
+        let locked_until = (Utc::now() + lease).to_rfc3339();
+        let result = sqlx::query(
+            "UPDATE jobs
+             SET status = 'running', attempts = attempts + 1, locked_until = ?, updated_at = ?
+             WHERE id = ?
+               AND ((status = 'pending' AND run_at <= ?)
+                 OR (status = 'running' AND locked_until < ?))",
+        )
+        .bind(&locked_until)
+        .bind(&now_str)
+        .bind(id)
+        .bind(&now_str)
+        .bind(&now_str)
+        .execute(pool)
+        .await?;
+
+        // Another worker won the race; look for the next candidate
+        if result.rows_affected() == 0 {
+            continue;
+        }
+
+        return get_job(pool, id).await;
+    }
+}
+
+/// Extend the lease of a running job to `lease` from now
+///
+/// `attempt` is the job's attempt counter at claim time. Returns `false` if
+/// the lease was lost (it expired and another worker claimed the job).
+pub async fn renew_job_lease(
+    pool: &Pool<Any>,
+    id: i64,
+    attempt: i32,
+    lease: Duration,
+) -> DbResult<bool> {
+    let now_str = Utc::now().to_rfc3339();
+
+    let result = sqlx::query(
+        "UPDATE jobs
+         SET locked_until = ?, updated_at = ?
+         WHERE id = ? AND status = 'running' AND attempts = ?",
+    )
+    .bind((Utc::now() + lease).to_rfc3339())
+    .bind(&now_str)
+    .bind(id)
+    .bind(attempt)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Mark a job as completed
+///
+/// Returns `false` if the lease of `attempt` was lost and nothing changed.
+pub async fn complete_job(pool: &Pool<Any>, id: i64, attempt: i32) -> DbResult<bool> {
+    set_job_status(pool, id, attempt, JobStatus::Completed, None, None).await
+}
+
+/// Release a job back to the queue to be retried at `run_at`
+///
+/// Returns `false` if the lease of `attempt` was lost and nothing changed.
+pub async fn retry_job(
+    pool: &Pool<Any>,
+    id: i64,
+    attempt: i32,
+    run_at: DateTime<Utc>,
+    error: &str,
+) -> DbResult<bool> {
+    set_job_status(
+        pool,
+        id,
+        attempt,
+        JobStatus::Pending,
+        Some(run_at),
+        Some(error),
+    )
+    .await
+}
+
+/// Move a job to the dead-letter status
+///
+/// Returns `false` if the lease of `attempt` was lost and nothing changed.
+pub async fn dead_letter_job(
+    pool: &Pool<Any>,
+    id: i64,
+    attempt: i32,
+    error: &str,
+) -> DbResult<bool> {
+    set_job_status(pool, id, attempt, JobStatus::Dead, None, Some(error)).await
+}
+
+/// Move a running job out of its lease, if `attempt` still holds it
+async fn set_job_status(
+    pool: &Pool<Any>,
+    id: i64,
+    attempt: i32,
+    status: JobStatus,
+    run_at: Option<DateTime<Utc>>,
+    error: Option<&str>,
+) -> DbResult<bool> {
+    let now_str = Utc::now().to_rfc3339();
+
+    let result = sqlx::query(
+        "UPDATE jobs
+         SET status = ?, run_at = COALESCE(?, run_at), locked_until = NULL,
+             last_error = COALESCE(?, last_error), updated_at = ?
+         WHERE id = ? AND status = 'running' AND attempts = ?",
+    )
+    .bind(status.as_str())
+    .bind(run_at.map(|t| t.to_rfc3339()))
+    .bind(error)
+    .bind(&now_str)
+    .bind(id)
+    .bind(attempt)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Put a dead-lettered job back on the queue with a fresh attempt budget
+///
+/// Returns `false` if the job does not exist or is not dead.
+pub async fn requeue_dead_job(pool: &Pool<Any>, id: i64) -> DbResult<bool> {
+    let now_str = Utc::now().to_rfc3339();
+
+    let result = sqlx::query(
+        "UPDATE jobs
+         SET status = 'pending', attempts = 0, run_at = ?, locked_until = NULL, updated_at = ?
+         WHERE id = ? AND status = 'dead'",
+    )
+    .bind(&now_str)
+    .bind(&now_str)
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// List jobs for a repository by status with pagination
+pub async fn list_jobs_by_repo_and_status(
+    pool: &Pool<Any>,
+    repo_owner: &str,
+    repo_name: &str,
+    status: JobStatus,
+    limit: i64,
+    offset: i64,
+) -> DbResult<Vec<Job>> {
+    let jobs = sqlx::query_as::<_, JobRaw>(&format!(
+        "SELECT {} FROM jobs
+         WHERE repo_owner = ? AND repo_name = ? AND status = ?
+         ORDER BY updated_at DESC
+         LIMIT ? OFFSET ?",
+        JOB_COLUMNS
+    ))
+    .bind(repo_owner)
+    .bind(repo_name)
+    .bind(status.as_str())
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|raw| raw.into())
+    .collect();
+
+    Ok(jobs)
+}
+
+/// Count jobs that have not finished yet (pending or running)
+pub async fn count_unfinished_jobs(pool: &Pool<Any>) -> DbResult<i64> {
+    let (count,): (i64,) =
+        sqlx::query_as("SELECT COUNT(*) FROM jobs WHERE status IN ('pending', 'running')")
+            .fetch_one(pool)
+            .await?;
+
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use sqlx::any::AnyPoolOptions;
+
+    async fn setup_test_db() -> Pool<Any> {
+        // Install the SQLite driver for Any
+        sqlx::any::install_default_drivers();
+
+        let pool = AnyPoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database pool");
+
+        // Enable foreign keys
+        sqlx::query("PRAGMA foreign_keys = ON")
+            .execute(&pool)
+            .await
+            .expect("Failed to enable foreign keys");
+
+        // Run migrations
+        run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    async fn enqueue_now(pool: &Pool<Any>) -> i64 {
+        enqueue_job(pool, "evaluate", "{}", "owner", "repo", Utc::now())
+            .await
+            .expect("Failed to enqueue job")
+    }
+
+    #[tokio::test]
+    async fn test_claim_leases_job_once() {
+        let pool = setup_test_db().await;
+        let id = enqueue_now(&pool).await;
+
+        let job = claim_next_job(&pool, Duration::minutes(5))
+            .await
+            .expect("Failed to claim job")
+            .expect("Job should be claimable");
+        assert_eq!(job.id, id);
+        assert_eq!(job.status, "running");
+        assert_eq!(job.attempts, 1);
+        assert!(job.locked_until.is_some());
+
+        // Lease is held, so nothing else is claimable
+        let next = claim_next_job(&pool, Duration::minutes(5))
+            .await
+            .expect("Failed to claim job");
+        assert!(next.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_future_job_not_claimed() {
+        let pool = setup_test_db().await;
+        enqueue_job(
+            &pool,
+            "close_pr",
+            "{}",
+            "owner",
+            "repo",
+            Utc::now() + Duration::minutes(2),
+        )
+        .await
+        .expect("Failed to enqueue job");
+
+        let job = claim_next_job(&pool, Duration::minutes(5))
+            .await
+            .expect("Failed to claim job");
+        assert!(job.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_unique_job_enqueued_once() {
+        let pool = setup_test_db().await;
+        let key = "review:owner/repo:77";
+
+        let id = enqueue_unique_job(&pool, "evaluate", "{}", "owner", "repo", Utc::now(), key)
+            .await
+            .expect("Failed to enqueue job");
+        assert!(id.is_some());
+
+        // The key stays taken after the job finished
+        let job = claim_next_job(&pool, Duration::minutes(5))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(complete_job(&pool, job.id, job.attempts).await.unwrap());
+        let again = enqueue_unique_job(&pool, "evaluate", "{}", "owner", "repo", Utc::now(), key)
+            .await
+            .expect("Failed to enqueue job");
+        assert_eq!(again, None);
+
+        // Jobs without a key are unaffected
+        enqueue_now(&pool).await;
+        enqueue_now(&pool).await;
+    }
+
+    #[tokio::test]
+    async fn test_expired_lease_is_reclaimed() {
+        let pool = setup_test_db().await;
+        let id = enqueue_now(&pool).await;
+
+        // Claim with a lease that has already expired (simulates a crashed worker)
+        claim_next_job(&pool, Duration::seconds(-1))
+            .await
+            .expect("Failed to claim job")
+            .expect("Job should be claimable");
+
+        let job = claim_next_job(&pool, Duration::minutes(5))
+            .await
+            .expect("Failed to claim job")
+            .expect("Expired lease should be reclaimable");
+        assert_eq!(job.id, id);
+        assert_eq!(job.attempts, 2);
+    }
+
+    #[tokio::test]
+    async fn test_lost_lease_cannot_finish_job() {
+        let pool = setup_test_db().await;
+        let id = enqueue_now(&pool).await;
+
+        // The first worker's lease expires while it is still running
+        let stale = claim_next_job(&pool, Duration::seconds(-1))
+            .await
+            .unwrap()
+            .unwrap();
+        let current = claim_next_job(&pool, Duration::minutes(5))
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert!(
+            !renew_job_lease(&pool, id, stale.attempts, Duration::minutes(5))
+                .await
+                .unwrap()
+        );
+        assert!(!complete_job(&pool, id, stale.attempts).await.unwrap());
+        assert!(
+            !retry_job(&pool, id, stale.attempts, Utc::now(), "late")
+                .await
+                .unwrap()
+        );
+        assert!(
+            !dead_letter_job(&pool, id, stale.attempts, "late")
+                .await
+                .unwrap()
+        );
+        assert_eq!(get_job(&pool, id).await.unwrap().unwrap().status, "running");
+
+        // The current holder can renew and finish it
+        assert!(
+            renew_job_lease(&pool, id, current.attempts, Duration::minutes(5))
+                .await
+                .unwrap()
+        );
+        assert!(complete_job(&pool, id, current.attempts).await.unwrap());
+        assert_eq!(
+            get_job(&pool, id).await.unwrap().unwrap().status,
+            "completed"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retry_complete_and_dead_letter() {
+        let pool = setup_test_db().await;
+        let id = enqueue_now(&pool).await;
+
+        claim_next_job(&pool, Duration::minutes(5)).await.unwrap();
+        assert!(
+            retry_job(&pool, id, 1, Utc::now(), "rate limited")
+                .await
+                .expect("Failed to retry job")
+        );
+
+        let job = get_job(&pool, id).await.unwrap().unwrap();
+        assert_eq!(job.status, "pending");
+        assert_eq!(job.last_error.as_deref(), Some("rate limited"));
+        assert!(job.locked_until.is_none());
+
+        claim_next_job(&pool, Duration::minutes(5)).await.unwrap();
+        assert!(
+            dead_letter_job(&pool, id, 2, "gave up")
+                .await
+                .expect("Failed to dead-letter job")
+        );
+
+        let dead = list_jobs_by_repo_and_status(&pool, "owner", "repo", JobStatus::Dead, 10, 0)
+            .await
+            .expect("Failed to list jobs");
+        assert_eq!(dead.len(), 1);
+        assert_eq!(dead[0].last_error.as_deref(), Some("gave up"));
+        assert_eq!(count_unfinished_jobs(&pool).await.unwrap(), 0);
+
+        // Requeue gives it a fresh attempt budget
+        assert!(requeue_dead_job(&pool, id).await.unwrap());
+        assert!(!requeue_dead_job(&pool, id).await.unwrap());
+        let job = claim_next_job(&pool, Duration::minutes(5))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(job.attempts, 1);
+
+        assert!(
+            complete_job(&pool, id, job.attempts)
+                .await
+                .expect("Failed to complete job")
+        );
+        let job = get_job(&pool, id).await.unwrap().unwrap();
+        assert_eq!(job.status, "completed");
+    }
+}
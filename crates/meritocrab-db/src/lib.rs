@@ -2,11 +2,20 @@ pub mod contributors;
 pub mod credit_events;
 pub mod error;
 pub mod evaluations;
+pub mod jobs;
+pub mod migrations;
 pub mod models;
 pub mod pool;
 pub mod repo_configs;
+pub mod repo_installations;
+pub mod sessions;
+pub mod webhook_deliveries;
 
 // Re-export commonly used types
 pub use error::{DbError, DbResult};
-pub use models::{Contributor, CreditEvent, PendingEvaluation, RepoConfig};
-pub use pool::{create_pool, run_migrations};
+pub use migrations::run_migrations;
+pub use models::{
+    Contributor, CreditEvent, CreditEventWithOrigin, DecayCandidate, Job, PendingEvaluation,
+    RepoConfig,
+};
+pub use pool::create_pool;
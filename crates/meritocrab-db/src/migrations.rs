@@ -0,0 +1,608 @@
+//! Versioned schema migrations for SQLite and PostgreSQL
+//!
+//! Each backend has its own migration directory (`migrations/sqlite`,
+//! `migrations/postgres`) with the same version numbers. Applied versions are
+//! recorded in `schema_migrations`; each migration runs in its own transaction
+//! together with its version record, so a failure leaves the database at the
+//! last fully applied version.
+
+use crate::error::{DbError, DbResult};
+use chrono::{DateTime, Utc};
+use sqlx::{Any, Pool};
+use std::collections::HashMap;
+
+/// A single schema migration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Migration {
+    /// Version number (the file's numeric prefix)
+    pub version: i64,
+    /// Human-readable name (the file name without prefix and extension)
+    pub name: &'static str,
+    /// SQL script, possibly containing several statements
+    pub sql: &'static str,
+}
+
+impl Migration {
+    /// Individual statements of the migration script
+    pub fn statements(&self) -> Vec<String> {
+        split_statements(self.sql)
+    }
+}
+
+const SQLITE_MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "initial",
+        sql: include_str!("../migrations/sqlite/001_initial.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "pr_tracking",
+        sql: include_str!("../migrations/sqlite/002_pr_tracking.sql"),
+    },
+    Migration {
+        version: 3,
+        name: "webhook_deliveries",
+        sql: include_str!("../migrations/sqlite/003_webhook_deliveries.sql"),
+    },
+    Migration {
+        version: 4,
+        name: "jobs",
+        sql: include_str!("../migrations/sqlite/004_jobs.sql"),
+    },
+    Migration {
+        version: 5,
+        name: "contributor_login",
+        sql: include_str!("../migrations/sqlite/005_contributor_login.sql"),
+    },
+    Migration {
+        version: 6,
+        name: "sessions",
+        sql: include_str!("../migrations/sqlite/006_sessions.sql"),
+    },
+    Migration {
+        version: 7,
+        name: "job_dedupe_key",
+        sql: include_str!("../migrations/sqlite/007_job_dedupe_key.sql"),
+    },
+    Migration {
+        version: 8,
+        name: "content_source",
+        sql: include_str!("../migrations/sqlite/008_content_source.sql"),
+    },
+    Migration {
+        version: 9,
+        name: "credit_source_once",
+        sql: include_str!("../migrations/sqlite/009_credit_source_once.sql"),
+    },
+    Migration {
+        version: 10,
+        name: "credit_event_reason",
+        sql: include_str!("../migrations/sqlite/010_credit_event_reason.sql"),
+    },
+    Migration {
+        version: 11,
+        name: "repo_installations",
+        sql: include_str!("../migrations/sqlite/011_repo_installations.sql"),
+    },
+    Migration {
+        version: 12,
+        name: "exemption_once",
+        sql: include_str!("../migrations/sqlite/012_exemption_once.sql"),
+    },
+];
+
+const POSTGRES_MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "initial",
+        sql: include_str!("../migrations/postgres/001_initial.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "pr_tracking",
+        sql: include_str!("../migrations/postgres/002_pr_tracking.sql"),
+    },
+    Migration {
+        version: 3,
+        name: "webhook_deliveries",
+        sql: include_str!("../migrations/postgres/003_webhook_deliveries.sql"),
+    },
+    Migration {
+        version: 4,
+        name: "jobs",
+        sql: include_str!("../migrations/postgres/004_jobs.sql"),
+    },
+    Migration {
+        version: 5,
+        name: "contributor_login",
+        sql: include_str!("../migrations/postgres/005_contributor_login.sql"),
+    },
+    Migration {
+        version: 6,
+        name: "sessions",
+        sql: include_str!("../migrations/postgres/006_sessions.sql"),
+    },
+    Migration {
+        version: 7,
+        name: "job_dedupe_key",
+        sql: include_str!("../migrations/postgres/007_job_dedupe_key.sql"),
+    },
+    Migration {
+        version: 8,
+        name: "content_source",
+        sql: include_str!("../migrations/postgres/008_content_source.sql"),
+    },
+    Migration {
+        version: 9,
+        name: "credit_source_once",
+        sql: include_str!("../migrations/postgres/009_credit_source_once.sql"),
+    },
+    Migration {
+        version: 10,
+        name: "credit_event_reason",
+        sql: include_str!("../migrations/postgres/010_credit_event_reason.sql"),
+    },
+    Migration {
+        version: 11,
+        name: "repo_installations",
+        sql: include_str!("../migrations/postgres/011_repo_installations.sql"),
+    },
+    Migration {
+        version: 12,
+        name: "exemption_once",
+        sql: include_str!("../migrations/postgres/012_exemption_once.sql"),
+    },
+];
+
+/// Queries that succeed once a migration has been applied
+///
+/// Used to adopt databases created before `schema_migrations` existed: the
+/// leading versions whose probe succeeds are recorded as applied instead of
+/// being run again.
+const LEGACY_PROBES: &[(i64, &str)] = &[
+    (1, "SELECT id FROM repo_configs LIMIT 1"),
+    (2, "SELECT pr_number FROM pending_evaluations LIMIT 1"),
+    (3, "SELECT delivery_id FROM webhook_deliveries LIMIT 1"),
+    (4, "SELECT id FROM jobs LIMIT 1"),
+    (5, "SELECT login FROM contributors LIMIT 1"),
+];
+
+/// Database backend behind a `sqlx::Any` pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    Sqlite,
+    Postgres,
+}
+
+impl Backend {
+    /// Detect the backend of a pool
+    pub async fn detect(pool: &Pool<Any>) -> DbResult<Self> {
+        let conn = pool.acquire().await?;
+        match conn.backend_name() {
+            "SQLite" => Ok(Backend::Sqlite),
+            "PostgreSQL" => Ok(Backend::Postgres),
+            other => Err(DbError::UnsupportedBackend(other.to_string())),
+        }
+    }
+
+    /// All migrations for this backend, in version order
+    pub fn migrations(&self) -> &'static [Migration] {
+        match self {
+            Backend::Sqlite => SQLITE_MIGRATIONS,
+            Backend::Postgres => POSTGRES_MIGRATIONS,
+        }
+    }
+
+    /// Bind parameter placeholder for the `n`th (1-based) parameter
+    pub(crate) fn placeholder(&self, n: usize) -> String {
+        match self {
+            Backend::Sqlite => "?".to_string(),
+            Backend::Postgres => format!("${}", n),
+        }
+    }
+}
+
+/// Applied state of a single migration
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub name: &'static str,
+    /// Whether the migration has been applied
+    pub applied: bool,
+    /// When it was applied; `None` for pending migrations and for schemas
+    /// created before versioning that have not been adopted yet
+    pub applied_at: Option<DateTime<Utc>>,
+}
+
+/// Report which migrations have been applied, without changing the database
+pub async fn migration_status(pool: &Pool<Any>) -> DbResult<Vec<MigrationStatus>> {
+    let backend = Backend::detect(pool).await?;
+
+    let applied: HashMap<i64, Option<DateTime<Utc>>> = match recorded_migrations(pool).await? {
+        Some(recorded) => recorded
+            .into_iter()
+            .map(|(version, applied_at)| (version, Some(applied_at)))
+            .collect(),
+        None => detect_legacy_versions(pool)
+            .await
+            .into_iter()
+            .map(|version| (version, None))
+            .collect(),
+    };
+
+    Ok(backend
+        .migrations()
+        .iter()
+        .map(|m| MigrationStatus {
+            version: m.version,
+            name: m.name,
+            applied: applied.contains_key(&m.version),
+            applied_at: applied.get(&m.version).copied().flatten(),
+        })
+        .collect())
+}
+
+/// Migrations that `run_migrations` would apply, without changing the database
+pub async fn pending_migrations(pool: &Pool<Any>) -> DbResult<Vec<Migration>> {
+    let backend = Backend::detect(pool).await?;
+    let status = migration_status(pool).await?;
+
+    Ok(backend
+        .migrations()
+        .iter()
+        .zip(status)
+        .filter(|(_, status)| !status.applied)
+        .map(|(m, _)| *m)
+        .collect())
+}
+
+/// Apply all pending migrations
+///
+/// Returns the migrations that were applied. Stops at the first failing
+/// migration and returns its error; earlier migrations stay applied.
+pub async fn run_migrations(pool: &Pool<Any>) -> DbResult<Vec<Migration>> {
+    let backend = Backend::detect(pool).await?;
+
+    if backend == Backend::Sqlite {
+        sqlx::query("PRAGMA foreign_keys = ON")
+            .execute(pool)
+            .await?;
+    }
+
+    let recorded = match recorded_migrations(pool).await? {
+        Some(recorded) => recorded,
+        None => adopt_legacy_schema(pool, backend).await?,
+    };
+
+    let mut applied = Vec::new();
+    for migration in backend.migrations() {
+        if recorded.contains_key(&migration.version) {
+            continue;
+        }
+        apply_migration(pool, backend, migration).await?;
+        applied.push(*migration);
+    }
+
+    Ok(applied)
+}
+
+/// Applied versions from `schema_migrations`, or `None` if the table is missing
+async fn recorded_migrations(pool: &Pool<Any>) -> DbResult<Option<HashMap<i64, DateTime<Utc>>>> {
+    let rows: Vec<(i64, String)> =
+        match sqlx::query_as("SELECT version, applied_at FROM schema_migrations")
+            .fetch_all(pool)
+            .await
+        {
+            Ok(rows) => rows,
+            // The table does not exist until the first migration run
+            Err(sqlx::Error::Database(_)) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+    Ok(Some(
+        rows.into_iter()
+            .map(|(version, applied_at)| {
+                let applied_at = DateTime::parse_from_rfc3339(&applied_at)
+                    .map(|t| t.with_timezone(&Utc))
+                    .unwrap_or_default();
+                (version, applied_at)
+            })
+            .collect(),
+    ))
+}
+
+/// Leading migration versions already present in an unversioned schema
+async fn detect_legacy_versions(pool: &Pool<Any>) -> Vec<i64> {
+    let mut versions = Vec::new();
+    for (version, probe) in LEGACY_PROBES {
+        if sqlx::query(probe).fetch_optional(pool).await.is_err() {
+            break;
+        }
+        versions.push(*version);
+    }
+    versions
+}
+
+/// Create `schema_migrations` and record any versions the schema already has
+async fn adopt_legacy_schema(
+    pool: &Pool<Any>,
+    backend: Backend,
+) -> DbResult<HashMap<i64, DateTime<Utc>>> {
+    let legacy = detect_legacy_versions(pool).await;
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS schema_migrations (
+            version BIGINT PRIMARY KEY,
+            name TEXT NOT NULL,
+            applied_at TEXT NOT NULL
+        )",
+    )
+    .execute(pool)
+    .await?;
+
+    let now = Utc::now();
+    let mut recorded = HashMap::new();
+    for migration in backend.migrations() {
+        if !legacy.contains(&migration.version) {
+            continue;
+        }
+        sqlx::query(&insert_version_sql(backend))
+            .bind(migration.version)
+            .bind(migration.name)
+            .bind(now.to_rfc3339())
+            .execute(pool)
+            .await?;
+        recorded.insert(migration.version, now);
+    }
+
+    Ok(recorded)
+}
+
+async fn apply_migration(
+    pool: &Pool<Any>,
+    backend: Backend,
+    migration: &Migration,
+) -> DbResult<()> {
+    let failed = |e: sqlx::Error| DbError::MigrationFailed(migration.version, migration.name, e);
+
+    let mut tx = pool.begin().await.map_err(failed)?;
+
+    for statement in migration.statements() {
+        sqlx::query(&statement)
+            .execute(&mut *tx)
+            .await
+            .map_err(failed)?;
+    }
+
+    sqlx::query(&insert_version_sql(backend))
+        .bind(migration.version)
+        .bind(migration.name)
+        .bind(Utc::now().to_rfc3339())
+        .execute(&mut *tx)
+        .await
+        .map_err(failed)?;
+
+    tx.commit().await.map_err(failed)?;
+
+    Ok(())
+}
+
+fn insert_version_sql(backend: Backend) -> String {
+    format!(
+        "INSERT INTO schema_migrations (version, name, applied_at) VALUES ({}, {}, {})",
+        backend.placeholder(1),
+        backend.placeholder(2),
+        backend.placeholder(3)
+    )
+}
+
+/// Split a SQL script into individual statements
+///
+/// Semicolons inside string literals, quoted identifiers and comments do not
+/// end a statement. Comments are removed and empty statements are dropped.
+pub fn split_statements(sql: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut chars = sql.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' | '"' => {
+                // Quoted literal or identifier; doubled quotes are escapes
+                current.push(c);
+                while let Some(q) = chars.next() {
+                    current.push(q);
+                    if q == c {
+                        if chars.peek() == Some(&c) {
+                            current.push(chars.next().unwrap());
+                        } else {
+                            break;
+                        }
+                    }
+                }
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                // Line comment
+                for n in chars.by_ref() {
+                    if n == '\n' {
+                        current.push('\n');
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                // Block comment
+                chars.next();
+                let mut prev = '\0';
+                for n in chars.by_ref() {
+                    if prev == '*' && n == '/' {
+                        break;
+                    }
+                    prev = n;
+                }
+                current.push(' ');
+            }
+            ';' => {
+                push_statement(&mut statements, &current);
+                current.clear();
+            }
+            _ => current.push(c),
+        }
+    }
+    push_statement(&mut statements, &current);
+
+    statements
+}
+
+fn push_statement(statements: &mut Vec<String>, statement: &str) {
+    let statement = statement.trim();
+    if !statement.is_empty() {
+        statements.push(statement.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn memory_pool() -> Pool<Any> {
+        // Install the SQLite driver for Any
+        sqlx::any::install_default_drivers();
+
+        sqlx::any::AnyPoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database pool")
+    }
+
+    #[test]
+    fn test_split_statements() {
+        let sql = "-- leading comment; not a statement\n\
+                   CREATE TABLE a (x TEXT DEFAULT 'a;b', \"weird;name\" TEXT);\n\
+                   /* block; comment */\n\
+                   INSERT INTO a (x) VALUES ('it''s; fine');\n\
+                   \n\
+                   SELECT 1";
+
+        let statements = split_statements(sql);
+        assert_eq!(statements.len(), 3);
+        assert_eq!(
+            statements[0],
+            "CREATE TABLE a (x TEXT DEFAULT 'a;b', \"weird;name\" TEXT)"
+        );
+        assert_eq!(statements[1], "INSERT INTO a (x) VALUES ('it''s; fine')");
+        assert_eq!(statements[2], "SELECT 1");
+    }
+
+    #[test]
+    fn test_backends_have_matching_versions() {
+        let versions =
+            |ms: &[Migration]| ms.iter().map(|m| (m.version, m.name)).collect::<Vec<_>>();
+        assert_eq!(versions(SQLITE_MIGRATIONS), versions(POSTGRES_MIGRATIONS));
+        for (i, m) in SQLITE_MIGRATIONS.iter().enumerate() {
+            assert_eq!(m.version, i as i64 + 1);
+            assert!(!m.statements().is_empty());
+        }
+        // Every Postgres statement must be free of SQLite-only syntax
+        for m in POSTGRES_MIGRATIONS {
+            assert!(!m.sql.contains("AUTOINCREMENT"), "{}", m.name);
+            assert!(!m.sql.contains("datetime("), "{}", m.name);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_migrations_applies_full_schema_once() {
+        let pool = memory_pool().await;
+
+        let applied = run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+        assert_eq!(applied.len(), SQLITE_MIGRATIONS.len());
+
+        // Every table from every migration exists
+        for (_, probe) in LEGACY_PROBES {
+            sqlx::query(probe)
+                .fetch_optional(&pool)
+                .await
+                .expect("Migrated table missing");
+        }
+
+        // Second run is a no-op
+        let applied = run_migrations(&pool).await.unwrap();
+        assert!(applied.is_empty());
+        assert!(pending_migrations(&pool).await.unwrap().is_empty());
+
+        let status = migration_status(&pool).await.unwrap();
+        assert!(status.iter().all(|s| s.applied && s.applied_at.is_some()));
+    }
+
+    #[tokio::test]
+    async fn test_status_and_pending_do_not_modify_database() {
+        let pool = memory_pool().await;
+
+        let pending = pending_migrations(&pool).await.unwrap();
+        assert_eq!(pending.len(), SQLITE_MIGRATIONS.len());
+        let status = migration_status(&pool).await.unwrap();
+        assert!(status.iter().all(|s| !s.applied));
+
+        // Nothing was created
+        assert!(recorded_migrations(&pool).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_legacy_schema_is_adopted() {
+        let pool = memory_pool().await;
+
+        // Schema created by the old runner, up to version 3
+        for m in &SQLITE_MIGRATIONS[..3] {
+            for statement in m.statements() {
+                sqlx::query(&statement).execute(&pool).await.unwrap();
+            }
+        }
+
+        let status = migration_status(&pool).await.unwrap();
+        assert_eq!(
+            status.iter().filter(|s| s.applied).count(),
+            3,
+            "{:?}",
+            status
+        );
+
+        let applied = run_migrations(&pool).await.expect("Failed to migrate");
+        let versions: Vec<i64> = applied.iter().map(|m| m.version).collect();
+        let expected: Vec<i64> = (4..=SQLITE_MIGRATIONS.len() as i64).collect();
+        assert_eq!(versions, expected);
+
+        let recorded = recorded_migrations(&pool).await.unwrap().unwrap();
+        assert_eq!(recorded.len(), SQLITE_MIGRATIONS.len());
+    }
+
+    #[tokio::test]
+    async fn test_failed_migration_rolls_back_and_reports_version() {
+        let pool = memory_pool().await;
+        run_migrations(&pool).await.unwrap();
+
+        let broken = Migration {
+            version: 99,
+            name: "broken",
+            sql: "CREATE TABLE half_done (id INTEGER); SELECT * FROM no_such_table;",
+        };
+        let result = apply_migration(&pool, Backend::Sqlite, &broken).await;
+        assert!(matches!(
+            result,
+            Err(DbError::MigrationFailed(99, "broken", _))
+        ));
+
+        // Neither the partial DDL nor the version record survived
+        assert!(
+            sqlx::query("SELECT id FROM half_done")
+                .fetch_optional(&pool)
+                .await
+                .is_err()
+        );
+        let recorded = recorded_migrations(&pool).await.unwrap().unwrap();
+        assert!(!recorded.contains_key(&99));
+    }
+}
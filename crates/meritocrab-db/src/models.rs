@@ -7,6 +7,7 @@ use sqlx::FromRow;
 pub struct Contributor {
     pub id: i64,
     pub github_user_id: i64,
+    pub login: Option<String>,
     pub repo_owner: String,
     pub repo_name: String,
     pub credit_score: i32,
@@ -21,6 +22,7 @@ pub struct Contributor {
 pub(crate) struct ContributorRaw {
     pub id: i64,
     pub github_user_id: i64,
+    pub login: Option<String>,
     pub repo_owner: String,
     pub repo_name: String,
     pub credit_score: i32,
@@ -35,6 +37,7 @@ impl From<ContributorRaw> for Contributor {
         Self {
             id: raw.id,
             github_user_id: raw.github_user_id,
+            login: raw.login,
             repo_owner: raw.repo_owner,
             repo_name: raw.repo_name,
             credit_score: raw.credit_score,
@@ -50,6 +53,46 @@ impl From<ContributorRaw> for Contributor {
     }
 }
 
+/// Contributor whose credit may decay, with their activity timestamps
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecayCandidate {
+    pub contributor: Contributor,
+    /// Latest scored event, or when the contributor was first seen
+    pub last_activity: DateTime<Utc>,
+    /// Latest `decay` event, if any
+    pub last_decay: Option<DateTime<Utc>>,
+}
+
+/// Raw decay candidate from database (with string timestamps)
+#[derive(Debug, Clone, FromRow)]
+pub(crate) struct DecayCandidateRaw {
+    #[sqlx(flatten)]
+    pub contributor: ContributorRaw,
+    pub last_activity: Option<String>,
+    pub last_decay: Option<String>,
+}
+
+impl From<DecayCandidateRaw> for DecayCandidate {
+    fn from(raw: DecayCandidateRaw) -> Self {
+        let contributor = Contributor::from(raw.contributor);
+        let parse = |timestamp: &str| {
+            DateTime::parse_from_rfc3339(timestamp)
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+
+        Self {
+            last_activity: raw
+                .last_activity
+                .as_deref()
+                .map(parse)
+                .unwrap_or(contributor.created_at),
+            last_decay: raw.last_decay.as_deref().map(parse),
+            contributor,
+        }
+    }
+}
+
 /// Credit event database model (immutable audit log)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreditEvent {
@@ -61,6 +104,11 @@ pub struct CreditEvent {
     pub credit_after: i32,
     pub llm_evaluation: Option<String>,
     pub maintainer_override: Option<String>,
+    /// Reason for an automated change (earning cap, decay, auto-blacklist)
+    pub reason: Option<String>,
+    pub pr_number: Option<i64>,
+    /// GitHub content the event was earned on (e.g. `issue_comment:123`)
+    pub source_id: Option<String>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -75,6 +123,9 @@ pub(crate) struct CreditEventRaw {
     pub credit_after: i32,
     pub llm_evaluation: Option<String>,
     pub maintainer_override: Option<String>,
+    pub reason: Option<String>,
+    pub pr_number: Option<i64>,
+    pub source_id: Option<String>,
     pub created_at: String,
 }
 
@@ -89,6 +140,9 @@ impl From<CreditEventRaw> for CreditEvent {
             credit_after: raw.credit_after,
             llm_evaluation: raw.llm_evaluation,
             maintainer_override: raw.maintainer_override,
+            reason: raw.reason,
+            pr_number: raw.pr_number,
+            source_id: raw.source_id,
             created_at: DateTime::parse_from_rfc3339(&raw.created_at)
                 .unwrap()
                 .with_timezone(&Utc),
@@ -96,6 +150,32 @@ impl From<CreditEventRaw> for CreditEvent {
     }
 }
 
+/// Credit event with the type of the first event on the same content
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreditEventWithOrigin {
+    pub event: CreditEvent,
+    /// Type of the contributor's first event with the same `source_id`, which
+    /// is what an `edit_adjustment` adjusted; `None` without a `source_id`
+    pub original_event_type: Option<String>,
+}
+
+/// Raw credit event with origin from database
+#[derive(Debug, Clone, FromRow)]
+pub(crate) struct CreditEventWithOriginRaw {
+    #[sqlx(flatten)]
+    pub event: CreditEventRaw,
+    pub original_event_type: Option<String>,
+}
+
+impl From<CreditEventWithOriginRaw> for CreditEventWithOrigin {
+    fn from(raw: CreditEventWithOriginRaw) -> Self {
+        Self {
+            event: CreditEvent::from(raw.event),
+            original_event_type: raw.original_event_type,
+        }
+    }
+}
+
 /// Pending evaluation database model
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PendingEvaluation {
@@ -109,6 +189,9 @@ pub struct PendingEvaluation {
     pub status: String,
     pub maintainer_note: Option<String>,
     pub final_delta: Option<i32>,
+    pub pr_number: Option<i64>,
+    /// GitHub content the evaluation is for (e.g. `issue_comment:123`)
+    pub source_id: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -126,6 +209,8 @@ pub(crate) struct PendingEvaluationRaw {
     pub status: String,
     pub maintainer_note: Option<String>,
     pub final_delta: Option<i32>,
+    pub pr_number: Option<i64>,
+    pub source_id: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -143,6 +228,8 @@ impl From<PendingEvaluationRaw> for PendingEvaluation {
             status: raw.status,
             maintainer_note: raw.maintainer_note,
             final_delta: raw.final_delta,
+            pr_number: raw.pr_number,
+            source_id: raw.source_id,
             created_at: DateTime::parse_from_rfc3339(&raw.created_at)
                 .unwrap()
                 .with_timezone(&Utc),
@@ -189,3 +276,66 @@ impl From<RepoConfigRaw> for RepoConfig {
         }
     }
 }
+
+/// Background job database model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Job {
+    pub id: i64,
+    pub job_type: String,
+    pub payload: String,
+    pub repo_owner: String,
+    pub repo_name: String,
+    pub status: String,
+    pub attempts: i32,
+    pub run_at: DateTime<Utc>,
+    pub locked_until: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Raw job model from database (with string timestamps)
+#[derive(Debug, Clone, FromRow)]
+pub(crate) struct JobRaw {
+    pub id: i64,
+    pub job_type: String,
+    pub payload: String,
+    pub repo_owner: String,
+    pub repo_name: String,
+    pub status: String,
+    pub attempts: i32,
+    pub run_at: String,
+    pub locked_until: Option<String>,
+    pub last_error: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl From<JobRaw> for Job {
+    fn from(raw: JobRaw) -> Self {
+        Self {
+            id: raw.id,
+            job_type: raw.job_type,
+            payload: raw.payload,
+            repo_owner: raw.repo_owner,
+            repo_name: raw.repo_name,
+            status: raw.status,
+            attempts: raw.attempts,
+            run_at: DateTime::parse_from_rfc3339(&raw.run_at)
+                .unwrap()
+                .with_timezone(&Utc),
+            locked_until: raw.locked_until.map(|t| {
+                DateTime::parse_from_rfc3339(&t)
+                    .unwrap()
+                    .with_timezone(&Utc)
+            }),
+            last_error: raw.last_error,
+            created_at: DateTime::parse_from_rfc3339(&raw.created_at)
+                .unwrap()
+                .with_timezone(&Utc),
+            updated_at: DateTime::parse_from_rfc3339(&raw.updated_at)
+                .unwrap()
+                .with_timezone(&Utc),
+        }
+    }
+}
@@ -107,6 +107,7 @@ pub async fn get_repo_config_raw(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::migrations::run_migrations;
     use sqlx::any::AnyPoolOptions;
     use std::thread;
 
@@ -127,8 +128,7 @@ mod tests {
             .expect("Failed to enable foreign keys");
 
         // Run migrations
-        sqlx::query(include_str!("../migrations/001_initial.sql"))
-            .execute(&pool)
+        run_migrations(&pool)
             .await
             .expect("Failed to run migrations");
 
@@ -0,0 +1,97 @@
+use crate::error::DbResult;
+use chrono::Utc;
+use sqlx::{Any, Pool};
+
+/// Remember the GitHub App installation a repository's webhooks arrive from
+pub async fn record_repo_installation(
+    pool: &Pool<Any>,
+    repo_owner: &str,
+    repo_name: &str,
+    installation_id: i64,
+) -> DbResult<()> {
+    let now_str = Utc::now().to_rfc3339();
+
+    sqlx::query(
+        "INSERT INTO repo_installations (repo_owner, repo_name, installation_id, updated_at) VALUES (?, ?, ?, ?)
+         ON CONFLICT (repo_owner, repo_name) DO UPDATE SET installation_id = excluded.installation_id, updated_at = excluded.updated_at",
+    )
+    .bind(repo_owner)
+    .bind(repo_name)
+    .bind(installation_id)
+    .bind(&now_str)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Get the installation a repository was last seen under, if any
+pub async fn get_repo_installation(
+    pool: &Pool<Any>,
+    repo_owner: &str,
+    repo_name: &str,
+) -> DbResult<Option<i64>> {
+    let row: Option<(i64,)> = sqlx::query_as(
+        "SELECT installation_id FROM repo_installations WHERE repo_owner = ? AND repo_name = ?",
+    )
+    .bind(repo_owner)
+    .bind(repo_name)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|(installation_id,)| installation_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use sqlx::any::AnyPoolOptions;
+
+    async fn setup_test_db() -> Pool<Any> {
+        // Install the SQLite driver for Any
+        sqlx::any::install_default_drivers();
+
+        let pool = AnyPoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database pool");
+
+        // Run migrations
+        run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_record_repo_installation_keeps_latest() {
+        let pool = setup_test_db().await;
+
+        assert_eq!(
+            get_repo_installation(&pool, "owner", "repo").await.unwrap(),
+            None
+        );
+
+        record_repo_installation(&pool, "owner", "repo", 1)
+            .await
+            .expect("Failed to record installation");
+        record_repo_installation(&pool, "owner", "repo", 2)
+            .await
+            .expect("Failed to record installation");
+        record_repo_installation(&pool, "other", "repo", 3)
+            .await
+            .expect("Failed to record installation");
+
+        assert_eq!(
+            get_repo_installation(&pool, "owner", "repo").await.unwrap(),
+            Some(2)
+        );
+        assert_eq!(
+            get_repo_installation(&pool, "other", "repo").await.unwrap(),
+            Some(3)
+        );
+    }
+}
@@ -0,0 +1,226 @@
+//! Dashboard session storage
+//!
+//! Backs the API's `tower_sessions` store on the shared `Pool<Any>`, which
+//! `tower-sessions-sqlx-store` cannot use. `sqlx::Any` passes placeholders
+//! through to the driver unchanged, so every query is rendered for the pool's
+//! backend.
+
+use crate::error::DbResult;
+use crate::migrations::Backend;
+use chrono::{DateTime, Utc};
+use sqlx::{Any, Pool};
+
+/// Render `sql`, written with `?` placeholders, for the pool's backend
+async fn render(pool: &Pool<Any>, sql: &str) -> DbResult<String> {
+    let backend = Backend::detect(pool).await?;
+    let mut rendered = String::with_capacity(sql.len());
+    for (n, part) in sql.split('?').enumerate() {
+        if n > 0 {
+            rendered.push_str(&backend.placeholder(n));
+        }
+        rendered.push_str(part);
+    }
+    Ok(rendered)
+}
+
+/// Insert a new session
+///
+/// Returns `false` without changing anything if a session with the same ID
+/// already exists.
+pub async fn create_session(
+    pool: &Pool<Any>,
+    id: &str,
+    data: &str,
+    expiry_date: DateTime<Utc>,
+) -> DbResult<bool> {
+    let sql = render(
+        pool,
+        "INSERT INTO sessions (id, data, expiry_date) VALUES (?, ?, ?)
+         ON CONFLICT (id) DO NOTHING",
+    )
+    .await?;
+    let result = sqlx::query(&sql)
+        .bind(id)
+        .bind(data)
+        .bind(expiry_date.to_rfc3339())
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Insert or update a session
+pub async fn save_session(
+    pool: &Pool<Any>,
+    id: &str,
+    data: &str,
+    expiry_date: DateTime<Utc>,
+) -> DbResult<()> {
+    let sql = render(
+        pool,
+        "INSERT INTO sessions (id, data, expiry_date) VALUES (?, ?, ?)
+         ON CONFLICT (id) DO UPDATE SET data = excluded.data, expiry_date = excluded.expiry_date",
+    )
+    .await?;
+    sqlx::query(&sql)
+        .bind(id)
+        .bind(data)
+        .bind(expiry_date.to_rfc3339())
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Load the data of a session that has not expired yet
+pub async fn load_session(pool: &Pool<Any>, id: &str) -> DbResult<Option<String>> {
+    let sql = render(
+        pool,
+        "SELECT data FROM sessions WHERE id = ? AND expiry_date > ?",
+    )
+    .await?;
+    let row: Option<(String,)> = sqlx::query_as(&sql)
+        .bind(id)
+        .bind(Utc::now().to_rfc3339())
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.map(|(data,)| data))
+}
+
+/// Delete a session
+pub async fn delete_session(pool: &Pool<Any>, id: &str) -> DbResult<()> {
+    let sql = render(pool, "DELETE FROM sessions WHERE id = ?").await?;
+    sqlx::query(&sql).bind(id).execute(pool).await?;
+
+    Ok(())
+}
+
+/// Delete all expired sessions
+///
+/// Returns the number of sessions removed.
+pub async fn delete_expired_sessions(pool: &Pool<Any>) -> DbResult<u64> {
+    let sql = render(pool, "DELETE FROM sessions WHERE expiry_date <= ?").await?;
+    let result = sqlx::query(&sql)
+        .bind(Utc::now().to_rfc3339())
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use chrono::Duration;
+    use sqlx::any::AnyPoolOptions;
+
+    async fn setup_test_db() -> Pool<Any> {
+        setup_db("sqlite::memory:").await
+    }
+
+    /// Postgres database to run the session tests against, if configured
+    ///
+    /// The database must be disposable: the tests create and delete sessions
+    /// in it.
+    fn postgres_url() -> Option<String> {
+        std::env::var("MERITOCRAB_TEST_POSTGRES_URL").ok()
+    }
+
+    async fn setup_db(url: &str) -> Pool<Any> {
+        // Install the SQLite and Postgres drivers for Any
+        sqlx::any::install_default_drivers();
+
+        let pool = AnyPoolOptions::new()
+            .max_connections(1)
+            .connect(url)
+            .await
+            .expect("Failed to create test database pool");
+
+        // Run migrations
+        run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    #[test]
+    fn test_placeholders_are_rendered_per_backend() {
+        assert_eq!(Backend::Sqlite.placeholder(2), "?");
+        assert_eq!(Backend::Postgres.placeholder(2), "$2");
+    }
+
+    #[tokio::test]
+    async fn test_session_round_trip() {
+        assert_session_round_trip(&setup_test_db().await).await;
+    }
+
+    #[tokio::test]
+    async fn test_expired_sessions_are_hidden_and_pruned() {
+        assert_expired_sessions_are_hidden_and_pruned(&setup_test_db().await).await;
+    }
+
+    #[tokio::test]
+    async fn test_sessions_on_postgres() {
+        let Some(url) = postgres_url() else {
+            eprintln!("MERITOCRAB_TEST_POSTGRES_URL not set; skipping");
+            return;
+        };
+        let pool = setup_db(&url).await;
+        sqlx::query("DELETE FROM sessions")
+            .execute(&pool)
+            .await
+            .expect("Failed to clear sessions");
+
+        assert_session_round_trip(&pool).await;
+        assert_expired_sessions_are_hidden_and_pruned(&pool).await;
+    }
+
+    async fn assert_session_round_trip(pool: &Pool<Any>) {
+        let expiry = Utc::now() + Duration::hours(1);
+
+        assert!(create_session(pool, "s1", "{}", expiry).await.unwrap());
+        // IDs never collide silently
+        assert!(
+            !create_session(pool, "s1", "{\"other\":1}", expiry)
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            load_session(pool, "s1").await.unwrap().as_deref(),
+            Some("{}")
+        );
+
+        save_session(pool, "s1", "{\"user\":1}", expiry)
+            .await
+            .expect("Failed to save session");
+        assert_eq!(
+            load_session(pool, "s1").await.unwrap().as_deref(),
+            Some("{\"user\":1}")
+        );
+
+        delete_session(pool, "s1")
+            .await
+            .expect("Failed to delete session");
+        assert!(load_session(pool, "s1").await.unwrap().is_none());
+    }
+
+    async fn assert_expired_sessions_are_hidden_and_pruned(pool: &Pool<Any>) {
+        save_session(pool, "old", "{}", Utc::now() - Duration::minutes(1))
+            .await
+            .unwrap();
+        save_session(pool, "new", "{}", Utc::now() + Duration::hours(1))
+            .await
+            .unwrap();
+
+        assert!(load_session(pool, "old").await.unwrap().is_none());
+
+        let pruned = delete_expired_sessions(pool)
+            .await
+            .expect("Failed to prune sessions");
+        assert_eq!(pruned, 1);
+        assert!(load_session(pool, "new").await.unwrap().is_some());
+    }
+}
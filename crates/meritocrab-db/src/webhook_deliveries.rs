@@ -0,0 +1,148 @@
+use crate::error::DbResult;
+use chrono::{Duration, Utc};
+use sqlx::{Any, Pool};
+
+/// Record a webhook delivery ID as processed
+///
+/// Returns `true` if the delivery was newly recorded, or `false` if it had
+/// already been recorded (i.e. this is a redelivery).
+pub async fn record_delivery(
+    pool: &Pool<Any>,
+    delivery_id: &str,
+    event_type: Option<&str>,
+) -> DbResult<bool> {
+    let now_str = Utc::now().to_rfc3339();
+
+    let result = sqlx::query(
+        "INSERT INTO webhook_deliveries (delivery_id, event_type, received_at) VALUES (?, ?, ?)
+         ON CONFLICT (delivery_id) DO NOTHING",
+    )
+    .bind(delivery_id)
+    .bind(event_type)
+    .bind(&now_str)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Remove a recorded delivery so a later redelivery is processed again
+///
+/// Used when processing fails after the delivery was recorded.
+pub async fn delete_delivery(pool: &Pool<Any>, delivery_id: &str) -> DbResult<()> {
+    sqlx::query("DELETE FROM webhook_deliveries WHERE delivery_id = ?")
+        .bind(delivery_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Prune delivery IDs older than the retention window
+///
+/// Returns the number of deliveries removed.
+pub async fn prune_deliveries(pool: &Pool<Any>, retention_days: i64) -> DbResult<u64> {
+    let cutoff = (Utc::now() - Duration::days(retention_days)).to_rfc3339();
+
+    let result = sqlx::query("DELETE FROM webhook_deliveries WHERE received_at < ?")
+        .bind(&cutoff)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use sqlx::any::AnyPoolOptions;
+
+    async fn setup_test_db() -> Pool<Any> {
+        // Install the SQLite driver for Any
+        sqlx::any::install_default_drivers();
+
+        let pool = AnyPoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database pool");
+
+        // Enable foreign keys
+        sqlx::query("PRAGMA foreign_keys = ON")
+            .execute(&pool)
+            .await
+            .expect("Failed to enable foreign keys");
+
+        // Run migrations
+        run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_record_delivery_detects_duplicates() {
+        let pool = setup_test_db().await;
+
+        let first = record_delivery(&pool, "delivery-1", Some("pull_request"))
+            .await
+            .expect("Failed to record delivery");
+        assert!(first);
+
+        let second = record_delivery(&pool, "delivery-1", Some("pull_request"))
+            .await
+            .expect("Failed to record delivery");
+        assert!(!second);
+
+        let other = record_delivery(&pool, "delivery-2", None)
+            .await
+            .expect("Failed to record delivery");
+        assert!(other);
+    }
+
+    #[tokio::test]
+    async fn test_delete_delivery_allows_reprocessing() {
+        let pool = setup_test_db().await;
+
+        record_delivery(&pool, "delivery-1", None)
+            .await
+            .expect("Failed to record delivery");
+        delete_delivery(&pool, "delivery-1")
+            .await
+            .expect("Failed to delete delivery");
+
+        let again = record_delivery(&pool, "delivery-1", None)
+            .await
+            .expect("Failed to record delivery");
+        assert!(again);
+    }
+
+    #[tokio::test]
+    async fn test_prune_deliveries() {
+        let pool = setup_test_db().await;
+
+        // Insert an old delivery directly
+        let old = (Utc::now() - Duration::days(30)).to_rfc3339();
+        sqlx::query("INSERT INTO webhook_deliveries (delivery_id, received_at) VALUES (?, ?)")
+            .bind("old-delivery")
+            .bind(&old)
+            .execute(&pool)
+            .await
+            .expect("Failed to insert old delivery");
+
+        record_delivery(&pool, "new-delivery", None)
+            .await
+            .expect("Failed to record delivery");
+
+        let pruned = prune_deliveries(&pool, 7)
+            .await
+            .expect("Failed to prune deliveries");
+        assert_eq!(pruned, 1);
+
+        // Old delivery can be recorded again, new one is still a duplicate
+        assert!(record_delivery(&pool, "old-delivery", None).await.unwrap());
+        assert!(!record_delivery(&pool, "new-delivery", None).await.unwrap());
+    }
+}
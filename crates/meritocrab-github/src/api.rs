@@ -1,9 +1,24 @@
 use crate::{
     error::{GithubError, GithubResult},
-    types::CollaboratorRole,
+    types::{
+        CollaboratorRole, Comment, Issue, PullRequest, PullRequestFile, PullRequestStats, Review,
+        ReviewComment, User,
+    },
 };
 use octocrab::{Octocrab, models::CommentId};
 
+/// Files GitHub returns per page when listing pull request files
+const FILES_PER_PAGE: usize = 100;
+
+/// Pages of pull request files fetched at most (GitHub lists up to 3000 files)
+const MAX_FILE_PAGES: usize = 30;
+
+/// Comments GitHub returns per page when listing issue comments
+const COMMENTS_PER_PAGE: usize = 100;
+
+/// Pages of issue comments fetched at most
+const MAX_COMMENT_PAGES: usize = 10;
+
 /// GitHub API client for repository operations
 pub struct GithubApiClient {
     client: Octocrab,
@@ -22,6 +37,20 @@ impl GithubApiClient {
         Ok(Self { client })
     }
 
+    /// Create new GitHub API client against a custom API base URL
+    pub fn with_api_url(token: String, api_url: &str) -> GithubResult<Self> {
+        let client = Octocrab::builder()
+            .base_uri(api_url)
+            .map_err(|e| GithubError::ApiError(format!("Invalid GitHub API URL: {}", e)))?
+            .personal_token(token)
+            .build()
+            .map_err(|e| {
+                GithubError::ApiError(format!("Failed to create octocrab client: {}", e))
+            })?;
+
+        Ok(Self { client })
+    }
+
     /// Create client from existing octocrab instance
     pub fn from_octocrab(client: Octocrab) -> Self {
         Self { client }
@@ -52,6 +81,31 @@ impl GithubApiClient {
         Ok(())
     }
 
+    /// Reopen a closed pull request
+    ///
+    /// # Arguments
+    /// * `owner` - Repository owner username
+    /// * `repo` - Repository name
+    /// * `pr_number` - Pull request number
+    pub async fn reopen_pull_request(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: u64,
+    ) -> GithubResult<()> {
+        self.client
+            .pulls(owner, repo)
+            .update(pr_number)
+            .state(octocrab::params::pulls::State::Open)
+            .send()
+            .await
+            .map_err(|e| {
+                GithubError::ApiError(format!("Failed to reopen PR #{}: {}", pr_number, e))
+            })?;
+
+        Ok(())
+    }
+
     /// Add a comment to an issue or pull request
     ///
     /// # Arguments
@@ -78,6 +132,120 @@ impl GithubApiClient {
         Ok(comment.id)
     }
 
+    /// Add labels to an issue or pull request
+    ///
+    /// # Arguments
+    /// * `owner` - Repository owner username
+    /// * `repo` - Repository name
+    /// * `issue_number` - Issue or PR number
+    /// * `labels` - Labels to add (created by GitHub if missing)
+    pub async fn add_labels(
+        &self,
+        owner: &str,
+        repo: &str,
+        issue_number: u64,
+        labels: &[String],
+    ) -> GithubResult<()> {
+        self.client
+            .issues(owner, repo)
+            .add_labels(issue_number, labels)
+            .await
+            .map_err(|e| {
+                GithubError::ApiError(format!("Failed to add labels to #{}: {}", issue_number, e))
+            })?;
+
+        Ok(())
+    }
+
+    /// Convert a pull request to a draft
+    ///
+    /// The REST API cannot do this, so the PR's node ID is looked up and the
+    /// `convertPullRequestToDraft` GraphQL mutation is used.
+    ///
+    /// # Arguments
+    /// * `owner` - Repository owner username
+    /// * `repo` - Repository name
+    /// * `pr_number` - Pull request number
+    pub async fn convert_to_draft(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: u64,
+    ) -> GithubResult<()> {
+        self.pull_request_mutation(
+            owner,
+            repo,
+            pr_number,
+            "mutation($id: ID!) { convertPullRequestToDraft(input: { pullRequestId: $id }) { pullRequest { isDraft } } }",
+            &format!("convert PR #{} to draft", pr_number),
+        )
+        .await
+    }
+
+    /// Mark a draft pull request ready for review
+    ///
+    /// Like [`convert_to_draft`](Self::convert_to_draft), this uses GraphQL
+    /// (the `markPullRequestReadyForReview` mutation).
+    ///
+    /// # Arguments
+    /// * `owner` - Repository owner username
+    /// * `repo` - Repository name
+    /// * `pr_number` - Pull request number
+    pub async fn mark_ready_for_review(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: u64,
+    ) -> GithubResult<()> {
+        self.pull_request_mutation(
+            owner,
+            repo,
+            pr_number,
+            "mutation($id: ID!) { markPullRequestReadyForReview(input: { pullRequestId: $id }) { pullRequest { isDraft } } }",
+            &format!("mark PR #{} ready for review", pr_number),
+        )
+        .await
+    }
+
+    /// Run a GraphQL mutation taking the PR's node ID as `$id`
+    ///
+    /// `action` describes the mutation in errors.
+    async fn pull_request_mutation(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: u64,
+        mutation: &str,
+        action: &str,
+    ) -> GithubResult<()> {
+        let route = format!("/repos/{}/{}/pulls/{}", owner, repo, pr_number);
+        let pull_request: serde_json::Value =
+            self.client.get(route, None::<&()>).await.map_err(|e| {
+                GithubError::ApiError(format!("Failed to fetch PR #{}: {}", pr_number, e))
+            })?;
+        let node_id = pull_request["node_id"]
+            .as_str()
+            .ok_or_else(|| GithubError::ApiError(format!("PR #{} has no node ID", pr_number)))?;
+
+        let response: serde_json::Value = self
+            .client
+            .graphql(&serde_json::json!({
+                "query": mutation,
+                "variables": { "id": node_id },
+            }))
+            .await
+            .map_err(|e| GithubError::ApiError(format!("Failed to {}: {}", action, e)))?;
+
+        if let Some(errors) = response.get("errors") {
+            return Err(GithubError::ApiError(format!(
+                "Failed to {}: {}",
+                action, errors
+            )));
+        }
+
+        Ok(())
+    }
+
     /// Check the collaborator role/permission level for a user
     ///
     /// # Arguments
@@ -130,6 +298,283 @@ impl GithubApiClient {
         }
     }
 
+    /// List the inline comments attached to a pull request review
+    ///
+    /// # Arguments
+    /// * `owner` - Repository owner username
+    /// * `repo` - Repository name
+    /// * `pr_number` - Pull request number
+    /// * `review_id` - Review ID
+    pub async fn list_review_comments(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: u64,
+        review_id: u64,
+    ) -> GithubResult<Vec<ReviewComment>> {
+        let route = format!(
+            "/repos/{}/{}/pulls/{}/reviews/{}/comments",
+            owner, repo, pr_number, review_id
+        );
+
+        self.client
+            .get(route, Some(&[("per_page", "100")]))
+            .await
+            .map_err(|e| {
+                GithubError::ApiError(format!(
+                    "Failed to list comments for review {} on PR #{}: {}",
+                    review_id, pr_number, e
+                ))
+            })
+    }
+
+    /// Get a pull request review
+    ///
+    /// # Arguments
+    /// * `owner` - Repository owner username
+    /// * `repo` - Repository name
+    /// * `pr_number` - Pull request number
+    /// * `review_id` - Review ID
+    pub async fn get_review(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: u64,
+        review_id: u64,
+    ) -> GithubResult<Review> {
+        let route = format!(
+            "/repos/{}/{}/pulls/{}/reviews/{}",
+            owner, repo, pr_number, review_id
+        );
+
+        self.client.get(route, None::<&()>).await.map_err(|e| {
+            GithubError::ApiError(format!(
+                "Failed to get review {} on PR #{}: {}",
+                review_id, pr_number, e
+            ))
+        })
+    }
+
+    /// Count the inline comments attached to a pull request review
+    ///
+    /// # Arguments
+    /// * `owner` - Repository owner username
+    /// * `repo` - Repository name
+    /// * `pr_number` - Pull request number
+    /// * `review_id` - Review ID
+    pub async fn count_review_comments(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: u64,
+        review_id: u64,
+    ) -> GithubResult<usize> {
+        let comments = self
+            .list_review_comments(owner, repo, pr_number, review_id)
+            .await?;
+
+        Ok(comments.len())
+    }
+
+    /// Get an issue or pull request, including its description
+    ///
+    /// # Arguments
+    /// * `owner` - Repository owner username
+    /// * `repo` - Repository name
+    /// * `issue_number` - Issue or PR number
+    pub async fn get_issue(
+        &self,
+        owner: &str,
+        repo: &str,
+        issue_number: u64,
+    ) -> GithubResult<Issue> {
+        let route = format!("/repos/{}/{}/issues/{}", owner, repo, issue_number);
+
+        self.client
+            .get(route, None::<&()>)
+            .await
+            .map_err(|e| GithubError::ApiError(format!("Failed to fetch #{}: {}", issue_number, e)))
+    }
+
+    /// List the latest conversation comments of an issue or pull request,
+    /// oldest first
+    ///
+    /// GitHub lists comments oldest first, so on long threads only the last
+    /// `MAX_COMMENT_PAGES` pages are fetched, located from the comment count.
+    /// Comments posted after the count was read are still included.
+    ///
+    /// # Arguments
+    /// * `owner` - Repository owner username
+    /// * `repo` - Repository name
+    /// * `issue_number` - Issue or PR number
+    /// * `comment_count` - The issue's comment count ([`Issue::comments`])
+    pub async fn list_latest_issue_comments(
+        &self,
+        owner: &str,
+        repo: &str,
+        issue_number: u64,
+        comment_count: u64,
+    ) -> GithubResult<Vec<Comment>> {
+        let route = format!("/repos/{}/{}/issues/{}/comments", owner, repo, issue_number);
+        let per_page = COMMENTS_PER_PAGE.to_string();
+        let last_page = (comment_count as usize).div_ceil(COMMENTS_PER_PAGE).max(1);
+        let first_page = last_page.saturating_sub(MAX_COMMENT_PAGES - 1).max(1);
+
+        let mut comments = Vec::new();
+        for page in first_page..=last_page + 1 {
+            let page = page.to_string();
+            let batch: Vec<Comment> = self
+                .client
+                .get(
+                    &route,
+                    Some(&[("per_page", per_page.as_str()), ("page", page.as_str())]),
+                )
+                .await
+                .map_err(|e| {
+                    GithubError::ApiError(format!(
+                        "Failed to list comments of #{}: {}",
+                        issue_number, e
+                    ))
+                })?;
+
+            let last_page = batch.len() < COMMENTS_PER_PAGE;
+            comments.extend(batch);
+            if last_page {
+                break;
+            }
+        }
+
+        Ok(comments)
+    }
+
+    /// List the files changed by a pull request, with their patches
+    ///
+    /// # Arguments
+    /// * `owner` - Repository owner username
+    /// * `repo` - Repository name
+    /// * `pr_number` - Pull request number
+    pub async fn list_pull_request_files(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: u64,
+    ) -> GithubResult<Vec<PullRequestFile>> {
+        let route = format!("/repos/{}/{}/pulls/{}/files", owner, repo, pr_number);
+        let per_page = FILES_PER_PAGE.to_string();
+
+        let mut files = Vec::new();
+        for page in 1..=MAX_FILE_PAGES {
+            let page = page.to_string();
+            let batch: Vec<PullRequestFile> = self
+                .client
+                .get(
+                    &route,
+                    Some(&[("per_page", per_page.as_str()), ("page", page.as_str())]),
+                )
+                .await
+                .map_err(|e| {
+                    GithubError::ApiError(format!(
+                        "Failed to list files of PR #{}: {}",
+                        pr_number, e
+                    ))
+                })?;
+
+            let last_page = batch.len() < FILES_PER_PAGE;
+            files.extend(batch);
+            if last_page {
+                break;
+            }
+        }
+
+        Ok(files)
+    }
+
+    /// Get a pull request, including its state and whether it is a draft
+    ///
+    /// # Arguments
+    /// * `owner` - Repository owner username
+    /// * `repo` - Repository name
+    /// * `pr_number` - Pull request number
+    pub async fn get_pull_request(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: u64,
+    ) -> GithubResult<PullRequest> {
+        let route = format!("/repos/{}/{}/pulls/{}", owner, repo, pr_number);
+
+        self.client
+            .get(route, None::<&()>)
+            .await
+            .map_err(|e| GithubError::ApiError(format!("Failed to fetch PR #{}: {}", pr_number, e)))
+    }
+
+    /// Get the line and file counts of a pull request
+    ///
+    /// # Arguments
+    /// * `owner` - Repository owner username
+    /// * `repo` - Repository name
+    /// * `pr_number` - Pull request number
+    pub async fn get_pull_request_stats(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: u64,
+    ) -> GithubResult<PullRequestStats> {
+        let route = format!("/repos/{}/{}/pulls/{}", owner, repo, pr_number);
+
+        self.client
+            .get(route, None::<&()>)
+            .await
+            .map_err(|e| GithubError::ApiError(format!("Failed to fetch PR #{}: {}", pr_number, e)))
+    }
+
+    /// Get the unified diff of a pull request
+    ///
+    /// GitHub refuses diffs over 20,000 lines or 300 files; use
+    /// [`list_pull_request_files`](Self::list_pull_request_files) for those.
+    ///
+    /// # Arguments
+    /// * `owner` - Repository owner username
+    /// * `repo` - Repository name
+    /// * `pr_number` - Pull request number
+    pub async fn get_pull_request_diff(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: u64,
+    ) -> GithubResult<String> {
+        self.client
+            .pulls(owner, repo)
+            .get_diff(pr_number)
+            .await
+            .map_err(|e| {
+                GithubError::ApiError(format!("Failed to fetch diff of PR #{}: {}", pr_number, e))
+            })
+    }
+
+    /// Look up a GitHub user by login
+    ///
+    /// # Arguments
+    /// * `login` - GitHub username (without `@`)
+    ///
+    /// # Returns
+    /// The user, or `None` if no account has that login
+    pub async fn get_user(&self, login: &str) -> GithubResult<Option<User>> {
+        let route = format!("/users/{}", login);
+
+        match self.client.get::<User, _, ()>(route, None).await {
+            Ok(user) => Ok(Some(user)),
+            Err(octocrab::Error::GitHub { source, .. }) if source.status_code.as_u16() == 404 => {
+                Ok(None)
+            }
+            Err(e) => Err(GithubError::ApiError(format!(
+                "Failed to look up user {}: {}",
+                login, e
+            ))),
+        }
+    }
+
     /// Get file content from repository
     ///
     /// # Arguments
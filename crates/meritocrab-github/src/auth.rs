@@ -1,7 +1,14 @@
 use crate::error::{GithubError, GithubResult};
+use chrono::{DateTime, Utc};
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use octocrab::Octocrab;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+/// Default GitHub REST API base URL
+pub const DEFAULT_API_URL: &str = "https://api.github.com";
+
 /// GitHub App authentication configuration
 #[derive(Clone)]
 pub struct GithubAppAuth {
@@ -31,12 +38,9 @@ impl GithubAppAuth {
     /// Generate a JWT token for GitHub App authentication
     ///
     /// GitHub requires JWTs to be signed with RS256 and have specific claims:
-    /// - iat: issued at time (current time)
+    /// - iat: issued at time (backdated 60 seconds to allow for clock drift)
     /// - exp: expiration time (max 10 minutes from iat)
     /// - iss: issuer (the app ID)
-    ///
-    /// Note: This is a placeholder that returns the necessary structure.
-    /// In production, use a proper JWT library like `jsonwebtoken` to sign with RS256.
     pub fn generate_jwt(&self) -> GithubResult<String> {
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -44,16 +48,17 @@ impl GithubAppAuth {
             .as_secs() as i64;
 
         let claims = JwtClaims {
-            iat: now,
-            exp: now + 600, // 10 minutes (max allowed by GitHub)
+            iat: now - 60,
+            exp: now + 540, // 10 minutes after iat (max allowed by GitHub)
             iss: self.app_id.to_string(),
         };
 
-        // In production, this would use jsonwebtoken crate with RS256
-        // For now, return a placeholder that indicates what needs to be done
-        let jwt_payload = format!("PLACEHOLDER_JWT_FOR_APP_{}_AT_{}", self.app_id, claims.iat);
+        let key = EncodingKey::from_rsa_pem(self.private_key.as_bytes()).map_err(|e| {
+            GithubError::AuthError(format!("Invalid GitHub App private key: {}", e))
+        })?;
 
-        Ok(jwt_payload)
+        jsonwebtoken::encode(&Header::new(Algorithm::RS256), &claims, &key)
+            .map_err(|e| GithubError::AuthError(format!("Failed to sign JWT: {}", e)))
     }
 }
 
@@ -101,33 +106,46 @@ impl InstallationToken {
     }
 }
 
+/// Response of `POST /app/installations/{id}/access_tokens`
+#[derive(Debug, Deserialize)]
+struct AccessTokenResponse {
+    token: String,
+    expires_at: DateTime<Utc>,
+}
+
 /// Installation token manager that handles caching and refreshing
+///
+/// Tokens are cached per installation and refreshed once they are within
+/// five minutes of expiry.
 pub struct InstallationTokenManager {
     auth: GithubAppAuth,
-    cached_token: Option<InstallationToken>,
+    api_url: String,
+    cached_tokens: HashMap<i64, InstallationToken>,
 }
 
 impl InstallationTokenManager {
-    /// Create new installation token manager
+    /// Create new installation token manager against api.github.com
     pub fn new(auth: GithubAppAuth) -> Self {
+        Self::with_api_url(auth, DEFAULT_API_URL)
+    }
+
+    /// Create new installation token manager against a custom API base URL
+    ///
+    /// Used for GitHub Enterprise Server and for tests against a mock GitHub.
+    pub fn with_api_url(auth: GithubAppAuth, api_url: &str) -> Self {
         Self {
             auth,
-            cached_token: None,
+            api_url: api_url.trim_end_matches('/').to_string(),
+            cached_tokens: HashMap::new(),
         }
     }
 
     /// Get a valid installation token, refreshing if necessary
-    ///
-    /// This method would:
-    /// 1. Check if cached token exists and is still valid
-    /// 2. If not, generate a new JWT
-    /// 3. Use JWT to request installation token from GitHub API
-    /// 4. Cache and return the new token
-    pub async fn get_token(&mut self, installation_id: i64) -> GithubResult<String> {
+    pub async fn get_token(&mut self, installation_id: i64) -> GithubResult<InstallationToken> {
         // Check if we have a cached token that's still valid
-        if let Some(ref token) = self.cached_token {
+        if let Some(token) = self.cached_tokens.get(&installation_id) {
             if !token.is_expiring_soon() {
-                return Ok(token.token().to_string());
+                return Ok(token.clone());
             }
         }
 
@@ -135,29 +153,41 @@ impl InstallationTokenManager {
         self.refresh_token(installation_id).await
     }
 
-    /// Refresh the installation token
-    async fn refresh_token(&mut self, installation_id: i64) -> GithubResult<String> {
-        // Generate JWT for app authentication
-        let _jwt = self.auth.generate_jwt()?;
-
-        // In production, this would:
-        // 1. Use the JWT to call GitHub API: POST /app/installations/{installation_id}/access_tokens
-        // 2. Parse the response to get token and expires_at
-        // 3. Cache the token
-        //
-        // For now, return a placeholder
-        let token_value = format!("ghs_installation_token_for_{}", installation_id);
-        let expires_at = SystemTime::now() + Duration::from_secs(3600); // 1 hour
-
-        let token = InstallationToken::new(token_value.clone(), expires_at);
-        self.cached_token = Some(token);
-
-        Ok(token_value)
+    /// Exchange an app JWT for a fresh installation token and cache it
+    async fn refresh_token(&mut self, installation_id: i64) -> GithubResult<InstallationToken> {
+        let jwt = self.auth.generate_jwt()?;
+
+        let client = Octocrab::builder()
+            .base_uri(self.api_url.as_str())
+            .map_err(|e| GithubError::AuthError(format!("Invalid GitHub API URL: {}", e)))?
+            .personal_token(jwt)
+            .build()
+            .map_err(|e| {
+                GithubError::AuthError(format!("Failed to create octocrab client: {}", e))
+            })?;
+
+        let response: AccessTokenResponse = client
+            .post(
+                format!("/app/installations/{}/access_tokens", installation_id),
+                None::<&()>,
+            )
+            .await
+            .map_err(|e| {
+                GithubError::AuthError(format!(
+                    "Failed to create access token for installation {}: {}",
+                    installation_id, e
+                ))
+            })?;
+
+        let token = InstallationToken::new(response.token, response.expires_at.into());
+        self.cached_tokens.insert(installation_id, token.clone());
+
+        Ok(token)
     }
 
-    /// Clear cached token (useful for testing or forcing refresh)
+    /// Clear cached tokens (useful for testing or forcing refresh)
     pub fn clear_cache(&mut self) {
-        self.cached_token = None;
+        self.cached_tokens.clear();
     }
 }
 
@@ -174,11 +204,32 @@ mod tests {
 
     #[test]
     fn test_generate_jwt() {
+        let auth = GithubAppAuth::new(
+            12345,
+            include_str!("../tests/fixtures/test-app-key.pem").to_string(),
+        );
+        let jwt = auth.generate_jwt().expect("Failed to generate JWT");
+
+        // header.claims.signature
+        let parts: Vec<&str> = jwt.split('.').collect();
+        assert_eq!(parts.len(), 3);
+
+        use base64::Engine;
+        let claims = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(parts[1])
+            .unwrap();
+        let claims: JwtClaims = serde_json::from_slice(&claims).unwrap();
+        assert_eq!(claims.iss, "12345");
+        assert_eq!(claims.exp - claims.iat, 600);
+    }
+
+    #[test]
+    fn test_generate_jwt_rejects_invalid_key() {
         let auth = GithubAppAuth::new(12345, "private-key".to_string());
-        let jwt = auth.generate_jwt();
-        assert!(jwt.is_ok());
-        let jwt_str = jwt.unwrap();
-        assert!(jwt_str.contains("12345"));
+        assert!(matches!(
+            auth.generate_jwt(),
+            Err(GithubError::AuthError(_))
+        ));
     }
 
     #[test]
@@ -208,44 +259,4 @@ mod tests {
         let token = InstallationToken::new("token".to_string(), future_time);
         assert!(!token.is_expiring_soon());
     }
-
-    #[tokio::test]
-    async fn test_installation_token_manager() {
-        let auth = GithubAppAuth::new(12345, "private-key".to_string());
-        let mut manager = InstallationTokenManager::new(auth);
-
-        let token = manager.get_token(67890).await;
-        assert!(token.is_ok());
-        assert!(token.unwrap().contains("67890"));
-    }
-
-    #[tokio::test]
-    async fn test_installation_token_manager_caching() {
-        let auth = GithubAppAuth::new(12345, "private-key".to_string());
-        let mut manager = InstallationTokenManager::new(auth);
-
-        // First call should create token
-        let token1 = manager.get_token(67890).await.unwrap();
-
-        // Second call should return cached token
-        let token2 = manager.get_token(67890).await.unwrap();
-
-        assert_eq!(token1, token2);
-    }
-
-    #[tokio::test]
-    async fn test_installation_token_manager_clear_cache() {
-        let auth = GithubAppAuth::new(12345, "private-key".to_string());
-        let mut manager = InstallationTokenManager::new(auth);
-
-        // Get initial token
-        let _token1 = manager.get_token(67890).await.unwrap();
-
-        // Clear cache
-        manager.clear_cache();
-
-        // Should refresh token
-        let token2 = manager.get_token(67890).await.unwrap();
-        assert!(token2.contains("67890"));
-    }
 }
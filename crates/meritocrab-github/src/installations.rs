@@ -0,0 +1,69 @@
+use crate::{
+    api::GithubApiClient,
+    auth::{DEFAULT_API_URL, GithubAppAuth, InstallationTokenManager},
+    error::GithubResult,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Cache of per-installation GitHub API clients
+///
+/// A GitHub App is installed separately on each org or user account, and each
+/// installation has its own short-lived access token. This cache hands out a
+/// client authenticated as the requested installation, rebuilding it whenever
+/// the underlying token is refreshed (five minutes before it expires).
+pub struct InstallationClients {
+    tokens: Mutex<InstallationTokenManager>,
+    clients: Mutex<HashMap<i64, CachedClient>>,
+    api_url: String,
+}
+
+struct CachedClient {
+    token: String,
+    client: Arc<GithubApiClient>,
+}
+
+impl InstallationClients {
+    /// Create a client cache against api.github.com
+    pub fn new(auth: GithubAppAuth) -> Self {
+        Self::with_api_url(auth, DEFAULT_API_URL)
+    }
+
+    /// Create a client cache against a custom API base URL
+    pub fn with_api_url(auth: GithubAppAuth, api_url: &str) -> Self {
+        Self {
+            tokens: Mutex::new(InstallationTokenManager::with_api_url(auth, api_url)),
+            clients: Mutex::new(HashMap::new()),
+            api_url: api_url.to_string(),
+        }
+    }
+
+    /// Get a client authenticated as the given installation
+    pub async fn client(&self, installation_id: i64) -> GithubResult<Arc<GithubApiClient>> {
+        // Holding the token lock serializes refreshes, so concurrent webhooks
+        // for one installation trigger a single token exchange
+        let token = self.tokens.lock().await.get_token(installation_id).await?;
+
+        let mut clients = self.clients.lock().await;
+        if let Some(cached) = clients.get(&installation_id) {
+            if cached.token == token.token() {
+                return Ok(cached.client.clone());
+            }
+        }
+
+        let client = Arc::new(GithubApiClient::with_api_url(
+            token.token().to_string(),
+            &self.api_url,
+        )?);
+        clients.insert(
+            installation_id,
+            CachedClient {
+                token: token.token().to_string(),
+                client: client.clone(),
+            },
+        );
+
+        Ok(client)
+    }
+}
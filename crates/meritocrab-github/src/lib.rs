@@ -1,6 +1,7 @@
 pub mod api;
 pub mod auth;
 pub mod error;
+pub mod installations;
 pub mod types;
 pub mod webhook;
 
@@ -8,8 +9,10 @@ pub mod webhook;
 pub use api::GithubApiClient;
 pub use auth::{GithubAppAuth, InstallationToken, InstallationTokenManager};
 pub use error::{GithubError, GithubResult};
+pub use installations::InstallationClients;
 pub use types::{
-    CollaboratorRole, Comment, IssueCommentEvent, PullRequest, PullRequestEvent,
-    PullRequestReviewEvent, Repository, Review, User,
+    CollaboratorRole, Comment, IssueCommentEvent, IssuesEvent, PullRequest, PullRequestEvent,
+    PullRequestFile, PullRequestReviewCommentEvent, PullRequestReviewEvent, PullRequestStats,
+    PushCommit, PushEvent, Repository, Review, ReviewComment, User,
 };
 pub use webhook::{VerifiedWebhook, WebhookSecret};
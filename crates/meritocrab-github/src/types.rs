@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 /// GitHub user information
@@ -16,6 +17,7 @@ pub struct Repository {
     pub name: String,
     pub full_name: String,
     pub owner: User,
+    pub default_branch: Option<String>,
 }
 
 /// Pull request information
@@ -28,6 +30,12 @@ pub struct PullRequest {
     pub state: String,
     pub merged: Option<bool>,
     pub html_url: String,
+    #[serde(default)]
+    pub draft: bool,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
 }
 
 /// Issue comment information
@@ -37,6 +45,10 @@ pub struct Comment {
     pub body: String,
     pub user: User,
     pub html_url: String,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
 }
 
 /// Pull request review information
@@ -45,17 +57,79 @@ pub struct Review {
     pub id: i64,
     pub body: Option<String>,
     pub user: User,
+    /// Lowercase (`approved`, `changes_requested`, ...): webhooks send it in
+    /// lowercase, the REST API in uppercase
+    #[serde(deserialize_with = "deserialize_lowercase")]
     pub state: String,
     pub html_url: String,
+    #[serde(default)]
+    pub submitted_at: Option<DateTime<Utc>>,
 }
 
-/// Pull request issue information (for comments)
+fn deserialize_lowercase<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    String::deserialize(deserializer).map(|value| value.to_lowercase())
+}
+
+/// File changed by a pull request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PullRequestFile {
+    pub filename: String,
+    pub status: String,
+    pub additions: u64,
+    pub deletions: u64,
+    /// Unified diff hunks, omitted by GitHub for binary and very large files
+    #[serde(default)]
+    pub patch: Option<String>,
+}
+
+/// Size of a pull request's changes
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PullRequestStats {
+    pub additions: u64,
+    pub deletions: u64,
+    pub changed_files: u64,
+    pub commits: u64,
+}
+
+/// Inline comment on a pull request diff
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewComment {
+    pub id: i64,
+    pub body: String,
+    pub path: String,
+    /// Diff lines up to and including the commented line
+    pub diff_hunk: String,
+    pub user: User,
+    #[serde(default)]
+    pub in_reply_to_id: Option<i64>,
+    /// Review the comment was submitted with
+    #[serde(default)]
+    pub pull_request_review_id: Option<i64>,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Issue information (also sent for pull request conversations)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Issue {
     pub number: i64,
     pub title: String,
+    #[serde(default)]
+    pub body: Option<String>,
     pub user: User,
     pub pull_request: Option<PullRequestReference>,
+    /// Number of conversation comments
+    #[serde(default)]
+    pub comments: u64,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
 }
 
 /// Reference to a pull request from an issue
@@ -84,6 +158,25 @@ pub struct IssueCommentEvent {
     pub sender: User,
 }
 
+/// Pull request review comment webhook event (inline diff comments)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PullRequestReviewCommentEvent {
+    pub action: String,
+    pub comment: ReviewComment,
+    pub pull_request: PullRequest,
+    pub repository: Repository,
+    pub sender: User,
+}
+
+/// Issues webhook event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssuesEvent {
+    pub action: String,
+    pub issue: Issue,
+    pub repository: Repository,
+    pub sender: User,
+}
+
 /// Pull request review webhook event
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PullRequestReviewEvent {
@@ -94,6 +187,55 @@ pub struct PullRequestReviewEvent {
     pub sender: User,
 }
 
+/// Commit summary included in a push event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PushCommit {
+    pub id: String,
+    #[serde(default)]
+    pub added: Vec<String>,
+    #[serde(default)]
+    pub removed: Vec<String>,
+    #[serde(default)]
+    pub modified: Vec<String>,
+}
+
+impl PushCommit {
+    /// Check if this commit added, removed, or modified the given path
+    pub fn touches(&self, path: &str) -> bool {
+        self.added
+            .iter()
+            .chain(&self.removed)
+            .chain(&self.modified)
+            .any(|p| p == path)
+    }
+}
+
+/// Push webhook event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PushEvent {
+    #[serde(rename = "ref")]
+    pub git_ref: String,
+    #[serde(default)]
+    pub commits: Vec<PushCommit>,
+    pub repository: Repository,
+    pub sender: User,
+}
+
+impl PushEvent {
+    /// Check if the push targets the repository's default branch
+    pub fn is_default_branch(&self) -> bool {
+        match &self.repository.default_branch {
+            Some(branch) => self.git_ref == format!("refs/heads/{}", branch),
+            None => false,
+        }
+    }
+
+    /// Check if any commit in the push touched the given path
+    pub fn touches(&self, path: &str) -> bool {
+        self.commits.iter().any(|c| c.touches(path))
+    }
+}
+
 /// GitHub collaborator permission level
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -212,6 +354,92 @@ mod tests {
         assert!(event.issue.pull_request.is_some());
     }
 
+    #[test]
+    fn test_parse_review_comment_event() {
+        let json = r#"{
+            "action": "created",
+            "comment": {
+                "id": 500,
+                "pull_request_review_id": 77,
+                "body": "This unwrap panics on empty input.",
+                "path": "src/config.rs",
+                "diff_hunk": "@@ -10,3 +10,4 @@\n+    let config = parse(&text).unwrap();",
+                "user": {
+                    "id": 12345,
+                    "login": "testuser"
+                }
+            },
+            "pull_request": {
+                "number": 1,
+                "title": "Fix config loading",
+                "body": null,
+                "user": {
+                    "id": 1,
+                    "login": "alice"
+                },
+                "state": "open",
+                "html_url": "https://github.com/owner/repo/pull/1"
+            },
+            "repository": {
+                "id": 1,
+                "name": "repo",
+                "full_name": "owner/repo",
+                "owner": {
+                    "id": 1,
+                    "login": "owner"
+                }
+            },
+            "sender": {
+                "id": 12345,
+                "login": "testuser"
+            }
+        }"#;
+
+        let event: PullRequestReviewCommentEvent = serde_json::from_str(json).unwrap();
+        assert_eq!(event.action, "created");
+        assert_eq!(event.comment.pull_request_review_id, Some(77));
+        assert_eq!(event.comment.path, "src/config.rs");
+        assert_eq!(event.pull_request.number, 1);
+    }
+
+    #[test]
+    fn test_parse_issues_event() {
+        let json = r#"{
+            "action": "opened",
+            "issue": {
+                "number": 7,
+                "title": "Crash on empty config",
+                "body": "Steps to reproduce: load an empty file.",
+                "user": {
+                    "id": 12345,
+                    "login": "testuser"
+                }
+            },
+            "repository": {
+                "id": 1,
+                "name": "repo",
+                "full_name": "owner/repo",
+                "owner": {
+                    "id": 1,
+                    "login": "owner"
+                }
+            },
+            "sender": {
+                "id": 12345,
+                "login": "testuser"
+            }
+        }"#;
+
+        let event: IssuesEvent = serde_json::from_str(json).unwrap();
+        assert_eq!(event.action, "opened");
+        assert_eq!(event.issue.number, 7);
+        assert_eq!(
+            event.issue.body.as_deref(),
+            Some("Steps to reproduce: load an empty file.")
+        );
+        assert!(event.issue.pull_request.is_none());
+    }
+
     #[test]
     fn test_parse_pull_request_review_event() {
         let json = r#"{
@@ -261,6 +489,89 @@ mod tests {
         assert_eq!(event.pull_request.number, 123);
     }
 
+    #[test]
+    fn test_review_state_is_lowercased() {
+        // As the REST API returns it
+        let json = r#"{
+            "id": 789,
+            "body": "",
+            "user": { "id": 12345, "login": "testuser" },
+            "state": "CHANGES_REQUESTED",
+            "html_url": "https://github.com/owner/repo/pull/123#pullrequestreview-789"
+        }"#;
+
+        let review: Review = serde_json::from_str(json).unwrap();
+        assert_eq!(review.state, "changes_requested");
+    }
+
+    #[test]
+    fn test_parse_push_event() {
+        let json = r#"{
+            "ref": "refs/heads/main",
+            "commits": [
+                {
+                    "id": "abc123",
+                    "added": [],
+                    "removed": [],
+                    "modified": ["README.md"]
+                },
+                {
+                    "id": "def456",
+                    "added": [],
+                    "removed": [],
+                    "modified": [".meritocrab.toml"]
+                }
+            ],
+            "repository": {
+                "id": 1,
+                "name": "repo",
+                "full_name": "owner/repo",
+                "owner": {
+                    "id": 1,
+                    "login": "owner"
+                },
+                "default_branch": "main"
+            },
+            "sender": {
+                "id": 12345,
+                "login": "testuser"
+            }
+        }"#;
+
+        let event: PushEvent = serde_json::from_str(json).unwrap();
+        assert_eq!(event.git_ref, "refs/heads/main");
+        assert_eq!(event.commits.len(), 2);
+        assert!(event.is_default_branch());
+        assert!(event.touches(".meritocrab.toml"));
+        assert!(!event.touches("src/main.rs"));
+    }
+
+    #[test]
+    fn test_push_event_non_default_branch() {
+        let json = r#"{
+            "ref": "refs/heads/feature",
+            "commits": [],
+            "repository": {
+                "id": 1,
+                "name": "repo",
+                "full_name": "owner/repo",
+                "owner": {
+                    "id": 1,
+                    "login": "owner"
+                },
+                "default_branch": "main"
+            },
+            "sender": {
+                "id": 12345,
+                "login": "testuser"
+            }
+        }"#;
+
+        let event: PushEvent = serde_json::from_str(json).unwrap();
+        assert!(!event.is_default_branch());
+        assert!(!event.touches(".meritocrab.toml"));
+    }
+
     #[test]
     fn test_collaborator_role_has_write_access() {
         assert!(CollaboratorRole::Admin.has_write_access());
@@ -0,0 +1,184 @@
+/// Integration tests for installation token exchange against a mock GitHub
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    routing::{get, post},
+};
+use chrono::{Duration, Utc};
+use meritocrab_github::{
+    GithubAppAuth, GithubError, InstallationClients, InstallationTokenManager,
+};
+use serde_json::{Value, json};
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+const TEST_APP_KEY: &str = include_str!("fixtures/test-app-key.pem");
+
+#[derive(Clone)]
+struct MockGithub {
+    /// Number of access tokens issued so far
+    issued: Arc<AtomicUsize>,
+    /// Lifetime of issued tokens in seconds
+    token_lifetime: i64,
+}
+
+/// Issue `ghs_<installation>_<n>` tokens to callers presenting an app JWT
+async fn create_access_token(
+    State(mock): State<MockGithub>,
+    Path(installation_id): Path<i64>,
+    headers: HeaderMap,
+) -> (StatusCode, Json<Value>) {
+    let authorization = headers
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    let is_jwt = authorization
+        .strip_prefix("Bearer ")
+        .is_some_and(|jwt| jwt.split('.').count() == 3);
+
+    if !is_jwt {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(
+                json!({ "message": "A JSON web token could not be decoded", "documentation_url": "" }),
+            ),
+        );
+    }
+    if installation_id == 404 {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "message": "Not Found", "documentation_url": "" })),
+        );
+    }
+
+    let n = mock.issued.fetch_add(1, Ordering::SeqCst) + 1;
+    let expires_at = Utc::now() + Duration::seconds(mock.token_lifetime);
+    (
+        StatusCode::CREATED,
+        Json(json!({
+            "token": format!("ghs_{}_{}", installation_id, n),
+            "expires_at": expires_at.to_rfc3339(),
+            "permissions": {}
+        })),
+    )
+}
+
+/// Echo the token a request was authenticated with as the user's login
+async fn get_user(headers: HeaderMap) -> Json<Value> {
+    let token = headers
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .unwrap_or_default()
+        .to_string();
+    Json(json!({ "id": 1, "login": token, "type": "User" }))
+}
+
+async fn spawn_mock_github(token_lifetime: i64) -> (String, Arc<AtomicUsize>) {
+    let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+
+    let issued = Arc::new(AtomicUsize::new(0));
+    let app = Router::new()
+        .route(
+            "/app/installations/{id}/access_tokens",
+            post(create_access_token),
+        )
+        .route("/users/{login}", get(get_user))
+        .with_state(MockGithub {
+            issued: issued.clone(),
+            token_lifetime,
+        });
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    (format!("http://{}", addr), issued)
+}
+
+fn test_auth() -> GithubAppAuth {
+    GithubAppAuth::new(12345, TEST_APP_KEY.to_string())
+}
+
+#[tokio::test]
+async fn test_token_exchange_is_cached() {
+    let (api_url, issued) = spawn_mock_github(3600).await;
+    let mut manager = InstallationTokenManager::with_api_url(test_auth(), &api_url);
+
+    let token1 = manager.get_token(67890).await.unwrap();
+    assert_eq!(token1.token(), "ghs_67890_1");
+    assert!(!token1.is_expiring_soon());
+
+    // Second call is served from the cache
+    let token2 = manager.get_token(67890).await.unwrap();
+    assert_eq!(token2.token(), "ghs_67890_1");
+    assert_eq!(issued.load(Ordering::SeqCst), 1);
+
+    // Other installations get their own token
+    let other = manager.get_token(11111).await.unwrap();
+    assert_eq!(other.token(), "ghs_11111_2");
+
+    // Clearing the cache forces a new exchange
+    manager.clear_cache();
+    let token3 = manager.get_token(67890).await.unwrap();
+    assert_eq!(token3.token(), "ghs_67890_3");
+}
+
+#[tokio::test]
+async fn test_token_refreshed_before_expiry() {
+    // Tokens expire in two minutes, inside the five-minute refresh window
+    let (api_url, issued) = spawn_mock_github(120).await;
+    let mut manager = InstallationTokenManager::with_api_url(test_auth(), &api_url);
+
+    let token1 = manager.get_token(67890).await.unwrap();
+    let token2 = manager.get_token(67890).await.unwrap();
+    assert_ne!(token1.token(), token2.token());
+    assert_eq!(issued.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_token_exchange_failure() {
+    let (api_url, _issued) = spawn_mock_github(3600).await;
+    let mut manager = InstallationTokenManager::with_api_url(test_auth(), &api_url);
+
+    let result = manager.get_token(404).await;
+    assert!(matches!(result, Err(GithubError::AuthError(_))));
+}
+
+#[tokio::test]
+async fn test_installation_clients_use_installation_tokens() {
+    let (api_url, issued) = spawn_mock_github(3600).await;
+    let clients = InstallationClients::with_api_url(test_auth(), &api_url);
+
+    let client_a = clients.client(1).await.unwrap();
+    let client_b = clients.client(2).await.unwrap();
+
+    // Each client authenticates as its own installation
+    let user = client_a.get_user("anyone").await.unwrap().unwrap();
+    assert_eq!(user.login, "ghs_1_1");
+    let user = client_b.get_user("anyone").await.unwrap().unwrap();
+    assert_eq!(user.login, "ghs_2_2");
+
+    // Clients are reused while their token is valid
+    let again = clients.client(1).await.unwrap();
+    assert!(Arc::ptr_eq(&client_a, &again));
+    assert_eq!(issued.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_installation_client_rebuilt_after_refresh() {
+    let (api_url, _issued) = spawn_mock_github(120).await;
+    let clients = InstallationClients::with_api_url(test_auth(), &api_url);
+
+    let first = clients.client(1).await.unwrap();
+    let second = clients.client(1).await.unwrap();
+    assert!(!Arc::ptr_eq(&first, &second));
+
+    let user = second.get_user("anyone").await.unwrap().unwrap();
+    assert_eq!(user.login, "ghs_1_2");
+}
@@ -0,0 +1,428 @@
+//! Fitting pull request diffs into an LLM token budget
+//!
+//! Source files are shown first, then docs and configuration; lockfiles and
+//! generated files are only listed. A file whose patch does not fit in full
+//! is reduced to its hunk headers with per-hunk line counts, and files that
+//! do not fit at all are counted at the end.
+
+use std::fmt;
+
+/// Default number of tokens a pull request diff may use in the prompt
+pub const DEFAULT_DIFF_TOKEN_BUDGET: usize = 4000;
+
+/// Rough number of characters per token used to estimate prompt size
+pub const CHARS_PER_TOKEN: usize = 4;
+
+/// Room kept for the trailing count of files that were not shown
+const NOT_SHOWN_RESERVE: usize = 32;
+
+/// Longest hunk header kept in a hunk summary
+const MAX_HUNK_HEADER_CHARS: usize = 120;
+
+/// Lockfiles, listed but never shown
+const LOCKFILES: &[&str] = &[
+    "Cargo.lock",
+    "package-lock.json",
+    "npm-shrinkwrap.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "bun.lockb",
+    "poetry.lock",
+    "Pipfile.lock",
+    "uv.lock",
+    "Gemfile.lock",
+    "composer.lock",
+    "go.sum",
+    "flake.lock",
+    "mix.lock",
+    "pubspec.lock",
+    "Podfile.lock",
+];
+
+/// Path fragments of vendored or build output directories
+const GENERATED_DIRS: &[&str] = &[
+    "vendor/",
+    "node_modules/",
+    "dist/",
+    "__generated__/",
+    "__snapshots__/",
+];
+
+/// File name suffixes of generated files
+const GENERATED_SUFFIXES: &[&str] = &[
+    ".lock", ".min.js", ".min.css", ".map", ".pb.go", "_pb2.py", ".pb.rs", ".snap", ".svg",
+];
+
+/// File extensions of docs, configuration and data
+const OTHER_EXTENSIONS: &[&str] = &[
+    "md", "markdown", "rst", "txt", "adoc", "json", "yaml", "yml", "toml", "ini", "cfg", "xml",
+    "csv",
+];
+
+/// Changes to one file of a pull request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileDiff {
+    pub path: String,
+    pub additions: u64,
+    pub deletions: u64,
+    /// Unified diff hunks (`None` for binary files or patches GitHub omits)
+    pub patch: Option<String>,
+}
+
+/// How much of the budget a file deserves, most important first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FileKind {
+    /// Code, including tests
+    Source,
+    /// Docs, configuration and data files
+    Other,
+    /// Lockfiles, vendored and generated files
+    Generated,
+}
+
+/// Totals of a pull request's changes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiffStats {
+    pub additions: u64,
+    pub deletions: u64,
+    pub changed_files: u64,
+}
+
+impl DiffStats {
+    /// Add up the changes of the given files
+    pub fn from_files(files: &[FileDiff]) -> Self {
+        Self {
+            additions: files.iter().map(|file| file.additions).sum(),
+            deletions: files.iter().map(|file| file.deletions).sum(),
+            changed_files: files.len() as u64,
+        }
+    }
+}
+
+impl fmt::Display for DiffStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "+{} -{} in {} {}",
+            self.additions,
+            self.deletions,
+            self.changed_files,
+            if self.changed_files == 1 {
+                "file"
+            } else {
+                "files"
+            }
+        )
+    }
+}
+
+/// Classify a file by how useful its diff is for judging a contribution
+pub fn classify_path(path: &str) -> FileKind {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    let in_generated_dir = GENERATED_DIRS
+        .iter()
+        .any(|dir| path.starts_with(dir) || path.contains(&format!("/{}", dir)));
+
+    if LOCKFILES.contains(&name)
+        || in_generated_dir
+        || GENERATED_SUFFIXES
+            .iter()
+            .any(|suffix| name.ends_with(suffix))
+        || name.contains(".generated.")
+    {
+        return FileKind::Generated;
+    }
+
+    match name.rsplit_once('.') {
+        Some((_, extension)) if OTHER_EXTENSIONS.contains(&extension.to_lowercase().as_str()) => {
+            FileKind::Other
+        }
+        None if name.eq_ignore_ascii_case("LICENSE") || name.eq_ignore_ascii_case("README") => {
+            FileKind::Other
+        }
+        _ => FileKind::Source,
+    }
+}
+
+/// Split a unified diff (`git diff` / GitHub `.diff` output) into files
+///
+/// Line counts come from the hunks, so a diff truncated mid-file still
+/// yields the part that is present.
+pub fn parse_unified_diff(diff: &str) -> Vec<FileDiff> {
+    let mut files = Vec::new();
+    let mut current: Option<(FileDiff, Vec<&str>)> = None;
+
+    for line in diff.lines() {
+        if let Some(paths) = line.strip_prefix("diff --git ") {
+            files.extend(current.take().map(finish_file));
+            let path = paths
+                .rsplit_once(" b/")
+                .map(|(_, path)| path)
+                .unwrap_or(paths);
+            current = Some((
+                FileDiff {
+                    path: path.to_string(),
+                    additions: 0,
+                    deletions: 0,
+                    patch: None,
+                },
+                Vec::new(),
+            ));
+            continue;
+        }
+
+        let Some((file, hunks)) = current.as_mut() else {
+            continue;
+        };
+        if hunks.is_empty() && !line.starts_with("@@") {
+            // File header: renames and `+++ b/path` name the new path
+            if let Some(path) = line
+                .strip_prefix("+++ b/")
+                .or_else(|| line.strip_prefix("rename to "))
+            {
+                file.path = path.to_string();
+            }
+            continue;
+        }
+
+        if line.starts_with('+') {
+            file.additions += 1;
+        } else if line.starts_with('-') {
+            file.deletions += 1;
+        }
+        hunks.push(line);
+    }
+
+    files.extend(current.map(finish_file));
+    files
+}
+
+fn finish_file((mut file, hunks): (FileDiff, Vec<&str>)) -> FileDiff {
+    if !hunks.is_empty() {
+        file.patch = Some(hunks.join("\n"));
+    }
+    file
+}
+
+/// Render the files of a diff within a token budget
+///
+/// Files are ordered by [`FileKind`] and keep their order within a kind.
+/// Each file is shown in full if it fits, otherwise as a hunk summary, and
+/// room is kept for a one-line entry for every later file so large source
+/// files cannot hide the rest of the change.
+pub fn budget_diff(files: &[FileDiff], token_budget: usize) -> String {
+    let budget = (token_budget * CHARS_PER_TOKEN).saturating_sub(NOT_SHOWN_RESERVE);
+
+    let mut ordered: Vec<(FileKind, &FileDiff)> = files
+        .iter()
+        .map(|file| (classify_path(&file.path), file))
+        .collect();
+    ordered.sort_by_key(|(kind, _)| *kind);
+
+    let listings: Vec<String> = ordered
+        .iter()
+        .map(|(kind, file)| file_listing(file, *kind))
+        .collect();
+    let mut reserved: usize = listings.iter().map(String::len).sum();
+
+    let mut out = String::new();
+    let mut not_shown = 0;
+    for ((kind, file), listing) in ordered.iter().zip(&listings) {
+        reserved -= listing.len();
+        let available = budget.saturating_sub(out.len() + reserved);
+
+        let section = match (&file.patch, kind) {
+            (Some(patch), FileKind::Source | FileKind::Other) => {
+                let full = format!("{}\n{}\n", file_header(file), patch);
+                if full.len() <= available {
+                    full
+                } else {
+                    summarize_hunks(file, patch, available).unwrap_or_else(|| listing.clone())
+                }
+            }
+            _ => listing.clone(),
+        };
+
+        if out.len() + section.len() > budget {
+            not_shown += 1;
+            continue;
+        }
+        out.push_str(&section);
+    }
+
+    if not_shown > 0 {
+        out.push_str(&format!("... {} more files not shown\n", not_shown));
+    }
+    out
+}
+
+fn file_header(file: &FileDiff) -> String {
+    format!(
+        "File: {} (+{} -{})",
+        file.path, file.additions, file.deletions
+    )
+}
+
+/// One-line entry for a file whose patch is not shown
+fn file_listing(file: &FileDiff, kind: FileKind) -> String {
+    let reason = match (kind, &file.patch) {
+        (FileKind::Generated, _) => "lockfile or generated, not shown",
+        (_, None) => "binary or too large, no patch",
+        _ => "not shown",
+    };
+    format!("{} [{}]\n", file_header(file), reason)
+}
+
+/// Hunk headers of a patch with their line counts, within `available` chars
+fn summarize_hunks(file: &FileDiff, patch: &str, available: usize) -> Option<String> {
+    let mut hunks: Vec<(String, u64, u64)> = Vec::new();
+    for line in patch.lines() {
+        if line.starts_with("@@") {
+            let header: String = line.chars().take(MAX_HUNK_HEADER_CHARS).collect();
+            hunks.push((header, 0, 0));
+        } else if let Some((_, additions, deletions)) = hunks.last_mut() {
+            if line.starts_with('+') {
+                *additions += 1;
+            } else if line.starts_with('-') {
+                *deletions += 1;
+            }
+        }
+    }
+
+    let more = |count: usize| format!("  ... {} more hunks\n", count);
+    let mut summary = format!("{} [hunks summarized]\n", file_header(file));
+    if summary.len() + more(hunks.len()).len() > available {
+        return None;
+    }
+    for (shown, (header, additions, deletions)) in hunks.iter().enumerate() {
+        let line = format!("  {} (+{} -{})\n", header, additions, deletions);
+        let left_after = hunks.len() - shown - 1;
+        let trailer = if left_after > 0 {
+            more(left_after).len()
+        } else {
+            0
+        };
+        if summary.len() + line.len() + trailer > available {
+            summary.push_str(&more(hunks.len() - shown));
+            break;
+        }
+        summary.push_str(&line);
+    }
+    Some(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, patch: Option<&str>) -> FileDiff {
+        let patch = patch.map(str::to_string);
+        let count = |prefix: char| {
+            patch
+                .as_deref()
+                .map(|p| p.lines().filter(|l| l.starts_with(prefix)).count() as u64)
+                .unwrap_or(0)
+        };
+        FileDiff {
+            path: path.to_string(),
+            additions: count('+'),
+            deletions: count('-'),
+            patch,
+        }
+    }
+
+    #[test]
+    fn test_classify_path() {
+        assert_eq!(classify_path("src/main.rs"), FileKind::Source);
+        assert_eq!(classify_path("tests/api_test.py"), FileKind::Source);
+        assert_eq!(classify_path("README.md"), FileKind::Other);
+        assert_eq!(classify_path("config/app.TOML"), FileKind::Other);
+        assert_eq!(classify_path("Cargo.lock"), FileKind::Generated);
+        assert_eq!(classify_path("web/package-lock.json"), FileKind::Generated);
+        assert_eq!(classify_path("vendor/lib/x.go"), FileKind::Generated);
+        assert_eq!(classify_path("assets/app.min.js"), FileKind::Generated);
+        assert_eq!(
+            classify_path("api/schema.generated.ts"),
+            FileKind::Generated
+        );
+    }
+
+    #[test]
+    fn test_parse_unified_diff() {
+        let diff = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,3 +1,4 @@ mod a;
+ mod b;
+-mod c;
++mod d;
++mod e;
+diff --git a/logo.png b/logo.png
+Binary files a/logo.png and b/logo.png differ
+diff --git a/old.rs b/new.rs
+similarity index 100%
+rename from old.rs
+rename to new.rs
+";
+        let files = parse_unified_diff(diff);
+        assert_eq!(files.len(), 3);
+        assert_eq!(files[0].path, "src/lib.rs");
+        assert_eq!((files[0].additions, files[0].deletions), (2, 1));
+        assert!(files[0].patch.as_deref().unwrap().starts_with("@@ -1,3"));
+        assert_eq!(files[1].path, "logo.png");
+        assert_eq!(files[1].patch, None);
+        assert_eq!(files[2].path, "new.rs");
+
+        assert_eq!(
+            DiffStats::from_files(&files).to_string(),
+            "+2 -1 in 3 files"
+        );
+    }
+
+    #[test]
+    fn test_small_diff_shown_in_full() {
+        let files = vec![
+            file("Cargo.lock", Some("@@ -1 +1 @@\n-a\n+b")),
+            file("src/lib.rs", Some("@@ -1 +1 @@\n-old\n+new")),
+        ];
+
+        let rendered = budget_diff(&files, DEFAULT_DIFF_TOKEN_BUDGET);
+        assert_eq!(
+            rendered,
+            "File: src/lib.rs (+1 -1)\n@@ -1 +1 @@\n-old\n+new\n\
+             File: Cargo.lock (+1 -1) [lockfile or generated, not shown]\n"
+        );
+    }
+
+    #[test]
+    fn test_large_file_summarized_without_hiding_others() {
+        let mut big = String::from("@@ -1,2 +1,400 @@ fn big()\n");
+        big.push_str(&"+line of new code\n".repeat(400));
+        big.push_str("@@ -50,3 +450,3 @@ fn other()\n-gone\n+here");
+        let files = vec![
+            file("src/big.rs", Some(&big)),
+            file("docs/guide.md", Some("@@ -1 +1 @@\n-x\n+y")),
+        ];
+
+        let rendered = budget_diff(&files, 100);
+        assert!(rendered.len() <= 400);
+        assert!(rendered.contains("File: src/big.rs (+401 -1) [hunks summarized]"));
+        assert!(rendered.contains("  @@ -1,2 +1,400 @@ fn big() (+400 -0)"));
+        assert!(rendered.contains("  @@ -50,3 +450,3 @@ fn other() (+1 -1)"));
+        assert!(rendered.contains("File: docs/guide.md (+1 -1)\n@@ -1 +1 @@"));
+    }
+
+    #[test]
+    fn test_files_over_budget_are_counted() {
+        let files: Vec<_> = (0..50)
+            .map(|i| file(&format!("src/module_{}.rs", i), Some("@@ -1 +1 @@\n-a\n+b")))
+            .collect();
+
+        let rendered = budget_diff(&files, 50);
+        assert!(rendered.len() <= 200);
+        assert!(rendered.starts_with("File: src/module_0.rs"));
+        assert!(rendered.ends_with("more files not shown\n"));
+    }
+}
@@ -1,5 +1,6 @@
 pub mod claude;
 pub mod config;
+pub mod diff;
 pub mod factory;
 pub mod mock;
 pub mod openai;
@@ -9,6 +10,10 @@ pub mod traits;
 // Re-export main types for convenience
 pub use claude::ClaudeEvaluator;
 pub use config::LlmConfig;
+pub use diff::{
+    CHARS_PER_TOKEN, DEFAULT_DIFF_TOKEN_BUDGET, DiffStats, FileDiff, budget_diff,
+    parse_unified_diff,
+};
 pub use factory::create_evaluator;
 pub use mock::MockEvaluator;
 pub use openai::OpenAiEvaluator;
@@ -31,6 +31,7 @@ pub fn build_user_prompt(content: &str, context: &EvalContext) -> String {
         ContentType::PullRequest => build_pr_prompt(content, context),
         ContentType::Comment => build_comment_prompt(content, context),
         ContentType::Review => build_review_prompt(content, context),
+        ContentType::Issue => build_issue_prompt(content, context),
     }
 }
 
@@ -82,6 +83,25 @@ fn build_review_prompt(content: &str, context: &EvalContext) -> String {
     prompt
 }
 
+/// Build prompt for issue evaluation
+fn build_issue_prompt(content: &str, context: &EvalContext) -> String {
+    let mut prompt = String::from("Evaluate this issue:\n\n");
+
+    if let Some(title) = &context.title {
+        prompt.push_str(&format!("Title: {}\n\n", title));
+    }
+
+    prompt.push_str(&format!("Issue:\n{}\n\n", content));
+    prompt.push_str(
+        "For bug reports, weigh whether the issue gives reproduction steps, expected and \
+         actual behavior, and version or environment details. For feature requests, weigh \
+         whether it explains the use case. Vague, duplicated, or off-topic issues are low.\n\n",
+    );
+    prompt.push_str("Provide your evaluation as JSON.");
+
+    prompt
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -145,4 +165,21 @@ mod tests {
         assert!(prompt.contains("PR about feature X"));
         assert!(prompt.contains("Looks good to me"));
     }
+
+    #[test]
+    fn test_build_issue_prompt() {
+        let context = EvalContext {
+            content_type: ContentType::Issue,
+            title: Some("Crash on empty config".to_string()),
+            body: "Steps to reproduce: ...".to_string(),
+            diff_summary: None,
+            thread_context: None,
+        };
+
+        let prompt = build_user_prompt("Steps to reproduce: ...", &context);
+        assert!(prompt.contains("Evaluate this issue"));
+        assert!(prompt.contains("Crash on empty config"));
+        assert!(prompt.contains("Steps to reproduce: ..."));
+        assert!(prompt.contains("reproduction steps"));
+    }
 }
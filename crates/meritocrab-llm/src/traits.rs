@@ -7,7 +7,7 @@ use serde::{Deserialize, Serialize};
 pub struct EvalContext {
     /// Type of content being evaluated
     pub content_type: ContentType,
-    /// Title (for PRs)
+    /// Title (for PRs and issues)
     pub title: Option<String>,
     /// Body text
     pub body: String,
@@ -24,6 +24,7 @@ pub enum ContentType {
     PullRequest,
     Comment,
     Review,
+    Issue,
 }
 
 /// Result of LLM evaluation
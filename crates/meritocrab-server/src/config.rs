@@ -1,8 +1,10 @@
 use config::{Config, ConfigError, Environment, File};
+use meritocrab_api::{JobQueueConfig, NotificationConfig, RateLimitConfig, ThreadContextConfig};
 use meritocrab_core::{RepoConfig, ServerConfig};
 use meritocrab_llm::LlmConfig;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
+use tower_sessions::cookie::SameSite;
 
 /// Complete application configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,12 +17,41 @@ pub struct AppConfig {
     pub llm: LlmConfig,
     #[serde(default = "default_max_concurrent_llm_evals")]
     pub max_concurrent_llm_evals: usize,
+    /// How long processed webhook delivery IDs are kept for deduplication
+    #[serde(default = "default_webhook_delivery_retention_days")]
+    pub webhook_delivery_retention_days: i64,
+    /// Seconds between credit decay runs (repos opt in with `[decay]`)
+    #[serde(default = "default_credit_decay_interval_seconds")]
+    pub credit_decay_interval_seconds: u64,
+    /// Background job queue (LLM evaluations, delayed PR closes)
+    #[serde(default)]
+    pub jobs: JobQueueConfig,
+    /// Maintainer dashboard sessions and cookies
+    #[serde(default)]
+    pub session: SessionConfig,
+    /// Rate limits on the webhook endpoint, admin API and `/credit me`
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+    /// Outbound notifications on credit and blacklist changes
+    #[serde(default)]
+    pub notifications: NotificationConfig,
+    /// How much of a comment's thread goes into its evaluation
+    #[serde(default)]
+    pub thread_context: ThreadContextConfig,
 }
 
 fn default_max_concurrent_llm_evals() -> usize {
     10
 }
 
+fn default_webhook_delivery_retention_days() -> i64 {
+    7
+}
+
+fn default_credit_decay_interval_seconds() -> u64 {
+    3600
+}
+
 /// Database configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DatabaseConfig {
@@ -28,6 +59,73 @@ pub struct DatabaseConfig {
     pub max_connections: u32,
 }
 
+/// `SameSite` policy for the session cookie
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CookieSameSite {
+    Strict,
+    Lax,
+    None,
+}
+
+impl From<CookieSameSite> for SameSite {
+    fn from(same_site: CookieSameSite) -> Self {
+        match same_site {
+            CookieSameSite::Strict => SameSite::Strict,
+            CookieSameSite::Lax => SameSite::Lax,
+            CookieSameSite::None => SameSite::None,
+        }
+    }
+}
+
+/// Maintainer dashboard session configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionConfig {
+    /// Only send the session cookie over HTTPS
+    #[serde(default = "default_cookie_secure")]
+    pub cookie_secure: bool,
+    /// `SameSite` policy; `lax` keeps the OAuth callback redirect working
+    #[serde(default = "default_cookie_same_site")]
+    pub cookie_same_site: CookieSameSite,
+    /// Cookie domain (defaults to the host that set the cookie)
+    #[serde(default)]
+    pub cookie_domain: Option<String>,
+    /// Sessions expire after this many hours without activity
+    #[serde(default = "default_session_inactivity_hours")]
+    pub inactivity_hours: i64,
+    /// How often expired sessions are deleted from the database
+    #[serde(default = "default_session_cleanup_interval_seconds")]
+    pub cleanup_interval_seconds: u64,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            cookie_secure: default_cookie_secure(),
+            cookie_same_site: default_cookie_same_site(),
+            cookie_domain: None,
+            inactivity_hours: default_session_inactivity_hours(),
+            cleanup_interval_seconds: default_session_cleanup_interval_seconds(),
+        }
+    }
+}
+
+fn default_cookie_secure() -> bool {
+    true
+}
+
+fn default_cookie_same_site() -> CookieSameSite {
+    CookieSameSite::Lax
+}
+
+fn default_session_inactivity_hours() -> i64 {
+    24
+}
+
+fn default_session_cleanup_interval_seconds() -> u64 {
+    3600
+}
+
 /// GitHub configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GithubConfig {
@@ -95,7 +193,8 @@ impl AppConfig {
             .set_default("credit.review_submitted.spam", 0)?
             .set_default("credit.review_submitted.low", 0)?
             .set_default("credit.review_submitted.acceptable", 5)?
-            .set_default("credit.review_submitted.high", 5)?;
+            .set_default("credit.review_submitted.high", 5)?
+            .set_default("credit.zero_credit_for_empty_reviews", false)?;
 
         // Try to load config.toml if it exists
         let builder = if Path::new("config.toml").exists() {
@@ -136,4 +235,21 @@ mod tests {
         assert_eq!(db_config.url, "sqlite://test.db");
         assert_eq!(db_config.max_connections, 10);
     }
+
+    #[test]
+    fn test_session_config_defaults() {
+        let config: SessionConfig = serde_json::from_str("{}").unwrap();
+        assert!(config.cookie_secure);
+        assert_eq!(config.cookie_same_site, CookieSameSite::Lax);
+        assert!(config.cookie_domain.is_none());
+        assert_eq!(config.inactivity_hours, 24);
+
+        let config: SessionConfig = serde_json::from_str(
+            r#"{"cookie_secure": false, "cookie_same_site": "strict", "cookie_domain": "example.com"}"#,
+        )
+        .unwrap();
+        assert!(!config.cookie_secure);
+        assert_eq!(SameSite::from(config.cookie_same_site), SameSite::Strict);
+        assert_eq!(config.cookie_domain.as_deref(), Some("example.com"));
+    }
 }
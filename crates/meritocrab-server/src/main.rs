@@ -1,31 +1,54 @@
 mod config;
+mod migrate;
+mod tasks;
 
 use axum::{
     Router, middleware,
     routing::{get, post},
 };
+use clap::{Parser, Subcommand};
 use config::AppConfig;
 use meritocrab_api::{
-    AppState, OAuthConfig, admin_handlers, auth_middleware, handle_webhook, health,
-    init_server_start_time, oauth,
+    AppState, DbSessionStore, Notifier, OAuthConfig, RateLimiters, SelfServiceLimiter,
+    admin_handlers, auth_middleware, handle_webhook, health, init_server_start_time, oauth,
+    spawn_job_worker,
 };
 use meritocrab_db::run_migrations;
-use meritocrab_github::{GithubApiClient, GithubAppAuth, InstallationTokenManager, WebhookSecret};
+use meritocrab_github::{GithubAppAuth, InstallationClients, WebhookSecret};
 use meritocrab_llm::create_evaluator;
 use sqlx::any::AnyPoolOptions;
 use std::fs;
-use tower_sessions::{Expiry, MemoryStore, SessionManagerLayer};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tower_sessions::{Expiry, SessionManagerLayer};
 use tracing::{error, info};
 
+#[derive(Parser)]
+#[command(name = "meritocrab-server")]
+#[command(about = "Server for the Meritocrab reputation system")]
+#[command(version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Commands>,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Apply pending database migrations and exit
+    Migrate(migrate::MigrateArgs),
+}
+
 #[tokio::main]
 async fn main() {
+    let cli = Cli::parse();
+
     // Initialize tracing
     tracing_subscriber::fmt::init();
 
     // Initialize server start time for health endpoint
     init_server_start_time();
 
-    // Install SQLite driver for sqlx::Any
+    // Install SQLite and PostgreSQL drivers for sqlx::Any
     sqlx::any::install_default_drivers();
 
     // Load configuration
@@ -55,12 +78,33 @@ async fn main() {
         }
     };
 
-    // Run database migrations
-    if let Err(e) = run_migrations(&db_pool).await {
-        error!("Failed to run database migrations: {}", e);
-        std::process::exit(1);
+    if let Some(Commands::Migrate(args)) = cli.command {
+        if let Err(e) = migrate::run(&db_pool, args).await {
+            error!("Migration failed: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // Run database migrations; a failed migration stops startup
+    match run_migrations(&db_pool).await {
+        Ok(applied) => {
+            for migration in &applied {
+                info!(
+                    "Applied migration {:03}_{}",
+                    migration.version, migration.name
+                );
+            }
+            info!("Database migrations completed successfully");
+        }
+        Err(e) => {
+            error!("Failed to run database migrations: {}", e);
+            std::process::exit(1);
+        }
     }
-    info!("Database migrations completed successfully");
+
+    // Prune old webhook delivery IDs in the background
+    tasks::spawn_delivery_pruner(db_pool.clone(), config.webhook_delivery_retention_days);
 
     // Load GitHub App private key
     let private_key = match fs::read_to_string(&config.github.private_key_path) {
@@ -77,15 +121,18 @@ async fn main() {
     // Create GitHub App authentication
     let github_auth = GithubAppAuth::new(config.github.app_id as i64, private_key);
 
-    // Create installation token manager
-    let mut token_manager = InstallationTokenManager::new(github_auth);
+    // Create per-installation GitHub client cache
+    let api_url = config
+        .github
+        .api_url
+        .as_deref()
+        .unwrap_or(meritocrab_github::auth::DEFAULT_API_URL);
+    let installation_clients = Arc::new(InstallationClients::with_api_url(github_auth, api_url));
 
-    // Get installation token
-    let token = match token_manager
-        .get_token(config.github.installation_id as i64)
-        .await
-    {
-        Ok(token) => token,
+    // Exchange a token for the default installation up front so misconfiguration fails fast
+    let default_installation_id = config.github.installation_id as i64;
+    let github_client = match installation_clients.client(default_installation_id).await {
+        Ok(client) => client,
         Err(e) => {
             error!(
                 "Failed to get GitHub installation token for installation {}: {}",
@@ -99,15 +146,6 @@ async fn main() {
         config.github.installation_id
     );
 
-    // Create GitHub API client
-    let github_client = match GithubApiClient::new(token) {
-        Ok(client) => client,
-        Err(e) => {
-            error!("Failed to create GitHub API client: {}", e);
-            std::process::exit(1);
-        }
-    };
-
     // Create webhook secret
     let webhook_secret = WebhookSecret::new(config.github.webhook_secret.clone());
 
@@ -128,13 +166,57 @@ async fn main() {
         redirect_url: config.github.oauth_redirect_url.clone(),
     };
 
-    // Create session store (using in-memory for simplicity)
-    let session_store = MemoryStore::default();
-    let session_layer = SessionManagerLayer::new(session_store)
-        .with_expiry(Expiry::OnInactivity(time::Duration::hours(24)));
+    // Sessions are stored in the database so they survive restarts and are
+    // shared between replicas
+    let session_store = DbSessionStore::new(db_pool.clone());
+    let mut session_layer = SessionManagerLayer::new(session_store)
+        .with_secure(config.session.cookie_secure)
+        .with_same_site(config.session.cookie_same_site.into())
+        .with_expiry(Expiry::OnInactivity(time::Duration::hours(
+            config.session.inactivity_hours,
+        )));
+    if let Some(domain) = config.session.cookie_domain.clone() {
+        session_layer = session_layer.with_domain(domain);
+    }
+    tasks::spawn_session_cleanup(
+        db_pool.clone(),
+        std::time::Duration::from_secs(config.session.cleanup_interval_seconds),
+    );
+
+    // Create rate limiters
+    let rate_limiters = if config.rate_limit.enabled {
+        match RateLimiters::new(config.rate_limit.clone()) {
+            Ok(rate_limiters) => {
+                let cleanup = rate_limiters.clone();
+                tasks::spawn_rate_limiter_cleanup(move || cleanup.retain_recent());
+                Some(rate_limiters)
+            }
+            Err(e) => {
+                error!("Failed to create rate limiters: {}", e);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        info!("Rate limiting disabled");
+        None
+    };
+
+    // `/credit me` replies are limited even when a proxy rate limits HTTP
+    let self_service_limiter = match SelfServiceLimiter::new(config.rate_limit.self_service.clone())
+    {
+        Ok(limiter) => {
+            let cleanup = limiter.clone();
+            tasks::spawn_rate_limiter_cleanup(move || cleanup.retain_recent());
+            limiter
+        }
+        Err(e) => {
+            error!("Failed to create /credit me rate limiter: {}", e);
+            std::process::exit(1);
+        }
+    };
 
     // Create application state
-    let app_state = AppState::new(
+    let mut app_state = AppState::new(
         db_pool,
         github_client,
         config.credit,
@@ -143,10 +225,36 @@ async fn main() {
         config.max_concurrent_llm_evals,
         oauth_config,
         300, // config cache TTL in seconds (5 minutes)
+    )
+    .with_installation_clients(installation_clients, default_installation_id)
+    .with_thread_context_config(config.thread_context.clone())
+    .with_self_service_limiter(self_service_limiter);
+    if let Some(rate_limiters) = rate_limiters.clone() {
+        app_state = app_state.with_rate_limiters(rate_limiters);
+    }
+    if !config.notifications.targets.is_empty() {
+        let targets = config.notifications.targets.len();
+        match Notifier::new(config.notifications.clone()) {
+            Ok(notifier) => {
+                info!("Delivering notifications to {} targets", targets);
+                app_state = app_state.with_notifier(notifier);
+            }
+            Err(e) => {
+                error!("Failed to create notifier: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    // Start the background job worker (resumes jobs left over from a previous run)
+    spawn_job_worker(app_state.clone(), config.jobs.clone());
+    tasks::spawn_credit_decay(
+        app_state.clone(),
+        std::time::Duration::from_secs(config.credit_decay_interval_seconds),
     );
 
     // Build admin API router (protected)
-    let admin_routes = Router::new()
+    let mut admin_routes = Router::new()
         .route(
             "/api/repos/:owner/:repo/evaluations",
             get(admin_handlers::list_evaluations),
@@ -175,15 +283,29 @@ async fn main() {
             "/api/repos/:owner/:repo/events",
             get(admin_handlers::list_credit_events),
         )
-        .route_layer(middleware::from_fn_with_state(
-            app_state.clone(),
-            auth_middleware::require_maintainer,
-        ));
+        .route(
+            "/api/repos/:owner/:repo/jobs",
+            get(admin_handlers::list_jobs),
+        )
+        .route(
+            "/api/repos/:owner/:repo/jobs/:id/retry",
+            post(admin_handlers::retry_dead_job),
+        );
+    let mut webhook_routes = Router::new().route("/webhooks/github", post(handle_webhook));
+    if let Some(rate_limiters) = &rate_limiters {
+        // Runs inside require_maintainer, which identifies the user to key on
+        admin_routes = admin_routes.route_layer(rate_limiters.admin_rate_limiter());
+        webhook_routes = webhook_routes.route_layer(rate_limiters.webhook_rate_limiter());
+    }
+    let admin_routes = admin_routes.route_layer(middleware::from_fn_with_state(
+        app_state.clone(),
+        auth_middleware::require_maintainer,
+    ));
 
     // Build Axum router
     let app = Router::new()
         .route("/health", get(health))
-        .route("/webhooks/github", post(handle_webhook))
+        .merge(webhook_routes)
         .route("/auth/github", get(oauth::github_auth))
         .route("/auth/callback", get(oauth::github_callback))
         .route("/auth/logout", post(oauth::logout))
@@ -204,10 +326,14 @@ async fn main() {
     info!("Server listening on http://{}", addr);
 
     // Run server with graceful shutdown
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await
-        .expect("Server error");
+    // Peer addresses are needed to rate limit webhooks by source IP
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal())
+    .await
+    .expect("Server error");
 
     info!("Server shutdown complete");
 }
@@ -0,0 +1,81 @@
+use clap::{Args, Subcommand};
+use meritocrab_db::{
+    DbResult,
+    migrations::{migration_status, pending_migrations, run_migrations},
+};
+use sqlx::{Any, Pool};
+
+/// Arguments for `meritocrab-server migrate`
+#[derive(Args)]
+pub struct MigrateArgs {
+    #[command(subcommand)]
+    command: Option<MigrateCommand>,
+
+    /// Print the statements of pending migrations without applying them
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[derive(Subcommand)]
+enum MigrateCommand {
+    /// Show which migrations have been applied
+    Status,
+}
+
+/// Run the `migrate` subcommand against the configured database
+pub async fn run(db_pool: &Pool<Any>, args: MigrateArgs) -> DbResult<()> {
+    match args.command {
+        Some(MigrateCommand::Status) => print_status(db_pool).await,
+        None if args.dry_run => print_pending(db_pool).await,
+        None => apply(db_pool).await,
+    }
+}
+
+async fn print_status(db_pool: &Pool<Any>) -> DbResult<()> {
+    println!("{:<8} {:<24} Applied at", "Version", "Name");
+    for status in migration_status(db_pool).await? {
+        let applied_at = match (status.applied, status.applied_at) {
+            (true, Some(at)) => at.to_rfc3339(),
+            (true, None) => "present (not yet recorded)".to_string(),
+            (false, _) => "pending".to_string(),
+        };
+        println!(
+            "{:<8} {:<24} {}",
+            format!("{:03}", status.version),
+            status.name,
+            applied_at
+        );
+    }
+
+    Ok(())
+}
+
+async fn print_pending(db_pool: &Pool<Any>) -> DbResult<()> {
+    let pending = pending_migrations(db_pool).await?;
+    if pending.is_empty() {
+        println!("-- No pending migrations");
+        return Ok(());
+    }
+
+    for migration in pending {
+        println!("-- {:03}_{}", migration.version, migration.name);
+        for statement in migration.statements() {
+            println!("{};", statement);
+        }
+        println!();
+    }
+
+    Ok(())
+}
+
+async fn apply(db_pool: &Pool<Any>) -> DbResult<()> {
+    let applied = run_migrations(db_pool).await?;
+    if applied.is_empty() {
+        println!("Database is up to date");
+    }
+    for migration in applied {
+        println!("Applied {:03}_{}", migration.version, migration.name);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use chrono::Utc;
+use meritocrab_api::{AppState, apply_credit_decay};
+use meritocrab_db::{sessions::delete_expired_sessions, webhook_deliveries::prune_deliveries};
+use sqlx::{Any, Pool};
+use std::time::Duration;
+use tracing::{error, info};
+
+/// How often the webhook delivery retention job runs
+const DELIVERY_PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Spawn a background task that periodically prunes old webhook delivery IDs
+///
+/// Delivery IDs only need to live long enough to catch GitHub redeliveries,
+/// so anything older than `retention_days` is removed every hour.
+pub fn spawn_delivery_pruner(db_pool: Pool<Any>, retention_days: i64) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(DELIVERY_PRUNE_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            match prune_deliveries(&db_pool, retention_days).await {
+                Ok(0) => {}
+                Ok(pruned) => info!("Pruned {} expired webhook delivery IDs", pruned),
+                Err(e) => error!("Failed to prune webhook delivery IDs: {}", e),
+            }
+        }
+    });
+}
+
+/// Spawn a background task that periodically deletes expired dashboard sessions
+pub fn spawn_session_cleanup(db_pool: Pool<Any>, cleanup_interval: Duration) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(cleanup_interval);
+
+        loop {
+            interval.tick().await;
+
+            match delete_expired_sessions(&db_pool).await {
+                Ok(0) => {}
+                Ok(deleted) => info!("Deleted {} expired sessions", deleted),
+                Err(e) => error!("Failed to delete expired sessions: {}", e),
+            }
+        }
+    });
+}
+
+/// Spawn a background task that forgets rate limiter keys with full buckets
+///
+/// `retain_recent` is the limiter's own cleanup (`RateLimiters::retain_recent`
+/// or `SelfServiceLimiter::retain_recent`).
+pub fn spawn_rate_limiter_cleanup(retain_recent: impl Fn() + Send + 'static) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(60));
+
+        loop {
+            interval.tick().await;
+            retain_recent();
+        }
+    });
+}
+
+/// Spawn a background task that periodically decays the credit of inactive contributors
+pub fn spawn_credit_decay(state: AppState, decay_interval: Duration) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(decay_interval);
+
+        loop {
+            interval.tick().await;
+
+            match apply_credit_decay(&state, Utc::now()).await {
+                Ok(0) => {}
+                Ok(decayed) => info!("Decayed credit of {} inactive contributors", decayed),
+                Err(e) => error!("Failed to apply credit decay: {}", e),
+            }
+        }
+    });
+}